@@ -0,0 +1,587 @@
+# English UI messages: the reference catalog that every translation starts from.
+# Messages missing from a translation fall back to these.
+
+## Startup and scanning
+
+warning = Warning: { $message }
+# $kind: config, scan, read, write, deletion, terminal, schedule, serve or usage
+error-report = Error [{ $kind }]: { $error }
+error-title-config = Configuration problem
+error-title-scan = Scan failed
+error-title-read = Cannot read input
+error-title-write = Cannot save output
+error-title-deletion = Deletion failed
+error-title-terminal = Terminal problem
+error-title-schedule = Scheduling failed
+error-title-serve = Server failed
+error-title-usage = Cannot continue
+error-incomplete = Not everything could be deleted; see the failures listed above
+error-press-any-key = Press any key to exit
+error-scan-panicked = The scan stopped unexpectedly
+error-current-dir = Cannot determine current directory: { $error }
+error-path-missing = Path does not exist: { $path }
+error-no-mounts = No local filesystems found to scan
+error-no-drives = No fixed drives found to scan (--all-drives is for Windows; use --all-mounts elsewhere)
+error-resume-multiple-roots = --resume works with a single --path
+# $view: clutter, downloads, summary or report
+error-display = Error displaying { $view }: { $error }
+warning-access = Warning: Cannot access { $path }: { $error }
+warning-checkpoint-save = Warning: Cannot save scan checkpoint: { $error }
+warning-history = Warning: Cannot update the scan history: { $error }
+warning-no-allocated-sizes = Warning: This scan has no allocated sizes; showing apparent sizes
+scan-complete = ✓ Scan complete! Found { $count } directories
+history-compared = Compared with the scan of { $root } from { $age }
+scan-root-complete = ✓ Scanned { $root }: found { $count } directories
+scan-alias = ↪ { $path } is the same directory as { $original } (bind mount or firmlink); counted once
+bookmark-using = Scanning bookmark { $name }: { $path }
+bookmark-none-chosen = No bookmark chosen; nothing scanned.
+bookmark-title = 🔖 Bookmarks
+key-scan-bookmark = Scan
+mounts-title = 💽 Disks
+mounts-current-dir = Current directory
+mounts-column-mount = Mounted on
+mounts-column-type = Type
+mounts-column-size = Size
+mounts-column-free = Free
+mounts-column-used = Used
+mounts-column-device = Device
+key-scan-mount = Scan
+mounts-none-chosen = No disk chosen; nothing scanned.
+profile-selected = Profile { $name } selected { $count } { $count ->
+        [one] directory
+       *[other] directories
+    } ({ $size })
+target-free-selected = { $free } is free; selected { $count } safe temp { $count ->
+        [one] directory
+       *[other] directories
+    } ({ $size }) to free the { $needed } still needed
+target-free-short = The safe temp directories fall { $missing } short of the target; select more in interactive mode
+target-free-reached = { $free } is already free, which meets the target of { $target }; nothing selected
+error-free-space = Cannot tell how much space is free on the volume of { $path }
+marked-active = { $count } temporary directories modified within { $window } are marked as in use
+
+## Loading and saving scans
+
+loaded-entries = Loaded { $count } entries from { $file }
+loaded-scan-of = {"  "}Scan of { $root } on { $host } ({ $age })
+unknown-host = unknown host
+session-dropped = {"  "}{ $count } directories no longer exist and were dropped
+filtered-temp = Filtered to { $count } temporary { $count ->
+        [one] directory
+       *[other] directories
+    }
+refresh-vanished = {"  "}vanished: { $path }
+refresh-changed = {"  "}changed:  { $path } ({ $old } -> { $new })
+refresh-summary = Refreshed { $count } entries: { $vanished } vanished, { $changed } changed size
+ncdu-imported = Imported { $count } directories from ncdu export { $file }
+du-imported = Imported { $count } directories from { $file }
+ncdu-exported = ncdu export saved to { $file } (open with: ncdu -f { $file })
+results-saved = Results saved to { $file }
+merged-scans = Merged { $scans } scans into { $output } ({ $entries } entries, { $duplicates } duplicates dropped)
+
+## Subcommands
+
+clutter-none = No empty directories or broken symlinks under { $root }
+clutter-kept = Found { $summary }; nothing removed.
+error-downloads-dir = Cannot determine the Downloads directory; pass --dir
+downloads-deleted = Deleted
+downloads-moved = Moved to { $archive }:
+downloads-untouched = Nothing deleted or moved.
+policy-scanning = Policy { $name }: scanning { $path }
+policy-matched = Policy { $name }: { $count } { $count ->
+        [one] directory
+       *[other] directories
+    } ({ $size }) match
+policy-match = {"  "}{ $size }  { $path }
+policy-read-only = Read-only mode: policy { $name } only reports what it matches
+serve-listening = Serving the JSON API on http://{ $addr }/api ({ $deletion }); stop with Ctrl-C
+serve-deletion-off = deleting disabled, set { $variable } to enable it
+serve-deletion-read-only = read-only, deleting disabled
+serve-deletion-on = deleting enabled with the bearer token
+schedule-install-prompt = Install this schedule into { $target }? [y/N]{" "}
+schedule-not-installed = Nothing installed.
+schedule-written = Wrote { $path }
+schedule-enabled = Enabled { $timer }; see it with: systemctl --user list-timers
+schedule-cron-installed = Added the line to your crontab; see it with: crontab -l
+prune-found = { $dir }: { $count } { $count ->
+        [one] file
+       *[other] files
+    } not modified within { $age } ({ $size })
+prune-prompt = Delete these { $count } files ({ $size })? [y/N]{" "}
+prune-nothing = Nothing pruned.
+prune-read-only = Read-only mode: nothing is pruned
+prune-tracked = Skipping { $dir }: it holds { $count } git-tracked files (--allow-tracked prunes it anyway)
+prune-done = Pruned { $count } files ({ $size }) from { $dir }, and { $dirs } subdirectories they left empty
+pending-marked = ⏳ Marked for deletion: { $path } → { $pending }
+pending-mark-failed = ✗ Failed to mark { $path }: { $reason }
+pending-summary = { $count } { $count ->
+    [one] directory renamed; `purge-pending` deletes it
+   *[other] directories renamed; `purge-pending` deletes them
+} after { $date }. Rename back to keep.
+pending-item = { $path } ({ $size }, after { $date })
+pending-waiting = Still in their grace period ({ $count }):
+pending-due = Grace period over ({ $count }):
+pending-nothing-due = Nothing to purge.
+pending-read-only = Read-only mode: nothing is purged
+pending-dry-run = Dry run: nothing purged.
+pending-prompt = Delete these { $count } directories ({ $size })? [y/N]{" "}
+
+## Summary and interactive mode
+
+# Continuation lines keep their indentation with {"  "}
+summary-text =
+    Summary:
+    {"  "}Total directories: { $dirs }
+    {"  "}Total files: { $files }
+    {"  "}Total size: { $size }
+interactive-empty = No directories to display in interactive mode.
+interactive-launching = Launching interactive mode...
+session-saved-resume = Session saved to { $path }; resume with --session { $path }
+overrides-saved = Saved classification overrides to { $path }
+error-save-overrides = Error saving overrides: { $error }
+
+## Deletion
+
+skip-foreign = Skipping { $path } (scanned on another machine)
+skip-active = Skipping { $path } (modified recently, may be in use)
+skip-tracked = Skipping { $path } ({ $count } git-tracked files; use --allow-tracked to delete anyway)
+warning-git-status = Warning: Cannot check git status of { $path }: { $error }
+open-file-holder = ⚠ { $path } is in use by { $process } (pid { $pid })
+warning-open-files = Warning: Cannot check for open files: { $error }
+nothing-selected = No directories selected for deletion.
+dry-run-banner = === DRY RUN: nothing will be deleted ===
+dry-run-would-delete = Would delete { $count } directories:
+deleting-unconfirmed = Deleting { $count } directories without confirmation (--yes):
+deletion-cancelled = Deletion cancelled.
+deletion-cancelling = Cancelling after the current directory...
+warning-ctrlc = Warning: Cannot install Ctrl-C handler: { $error }
+deleting = Deleting... press Ctrl-C to stop after the current directory.
+error-trash-dir = Cannot determine the user data directory for the trash
+error-smtp-missing = --email-report needs a mail server: add an [smtp] table to the config
+error-data-dir = Cannot determine the user data and config directories
+trash-moved = Moved { $count } { $count ->
+        [one] directory
+       *[other] directories
+    } to the trash in { $dir }; `disk-cleanup-tool undo` restores them
+trash-moved-recycle-bin = Moved { $count } { $count ->
+        [one] directory
+       *[other] directories
+    } to the Recycle Bin; restore them from there
+trash-moved-desktop = Moved { $count } { $count ->
+        [one] directory
+       *[other] directories
+    } to the trash; `disk-cleanup-tool undo` or your file manager restores them
+undo-restored = ✓ Restored { $path }
+undo-failed = ✗ Cannot restore { $path }: { $reason }
+undo-recycle-bin = ! { $path } is in the Recycle Bin; undo cannot restore it, restore it from Explorer (Restore)
+undo-summary = Restored { $restored } of { $total } directories ({ $size })
+
+## Categories
+
+category-normal = Normal
+category-temp = Temp
+category-build-output = Build output
+category-dependencies = Dependencies
+category-virtual-env = Virtual env
+category-ide-metadata = IDE metadata
+category-os-cache = OS cache
+category-ml-models = ML models
+category-app-caches = App caches
+safety-normal = Not flagged; review manually before deleting
+safety-temp = Usually safe: temporary files and caches are recreated on demand
+safety-build-output = Safe: regenerated by the next build
+safety-dependencies = Safe: re-downloaded by the package manager
+safety-virtual-env = Caution: the environment must be recreated before use
+safety-ide-metadata = Caution: editor settings and workspace state are lost
+safety-os-cache = Safe: recreated by the operating system
+safety-ml-models = Caution: large downloads that may be slow to fetch again
+safety-app-caches = Safe once the app is closed: pages and media load again, logins and settings are kept
+
+## Interactive mode
+
+app-name = Disk Cleanup Tool
+interactive-title = Interactive Mode
+interactive-filter = (≥1 MB)
+label-total = Total
+label-size = Size
+label-selected = Selected
+count-dirs = { $count } dirs
+count-files = { $count } files
+in-use = in use
+tab-all = All
+tab-this-scan = (this scan)
+tab-title = { $label } ({ $size })
+tab-title-selected = { $label } ({ $size }, { $selected } selected)
+column-type = Type
+column-path = Path
+column-size = Size
+column-growth = Since last
+column-allocation-difference = On disk
+column-files = Files
+column-modified = Modified
+column-parent-share = % of parent
+column-temp-share = % of temp
+column-rebuild = Rebuild
+column-safety = Safety
+column-owner = Owner
+column-file-types = File types
+column-name = Name
+column-on-disk = On disk
+column-hint = How to shrink it
+column-count = Count
+list-directories = Directories
+list-groups = Grouped by name
+list-images = Large disk images
+list-files = Largest files
+list-tree = Tree
+list-details = Details
+list-session-log = Session log
+log-empty = Nothing done yet
+list-children = Contents
+tree-no-children = No listed subdirectories
+file-types-counting = counting…
+file-types-none = no files
+file-types-no-extension = (no extension)
+changed-since-scan = changed since the scan
+live-updates-failed = Live size updates are off: { $error }
+image-qcow2 = QEMU disk
+image-vmdk = VMware disk
+image-vdi = VirtualBox disk
+image-vhd = Hyper-V disk
+image-iso = ISO image
+image-docker-desktop = Docker Desktop disk
+image-wsl = WSL disk
+image-hint-qcow2 = Sparsify: qemu-img convert -O qcow2 old.qcow2 new.qcow2
+image-hint-vmdk = Defragment and compact it in the VM's disk settings
+image-hint-vdi = Zero free space in the guest, then VBoxManage modifymedium --compact
+image-hint-vhd = Optimize-VHD -Mode Full with the VM stopped
+image-hint-iso = Installer image: delete it if you can download it again
+image-hint-docker-desktop = docker system prune, then Clean / Purge data in Docker Desktop's troubleshooting settings
+image-hint-wsl = Compact WSL disk: wsl --shutdown, then Optimize-VHD or diskpart compact vdisk
+summary-images = { $count ->
+    [one] 1 large disk image takes { $size }; press i in interactive mode
+   *[other] { $count } large disk images take { $size }; press i in interactive mode
+}
+synced-warning = Synced by { $service }: deleting here also deletes it in the cloud and on your other devices
+locked-warning = No permission to delete { $path }; it needs sudo or the owner's account
+access-deletable = You can delete this
+explain-title = Why was this flagged? (any key to close)
+breakdown-title = { $path } by subdirectory (any key to close)
+breakdown-rest = (files and smaller directories)
+jump-title = Jump to path
+jump-hint = Tab: complete | Enter: jump | Esc: cancel
+jump-more = ... and { $count } more
+key-or = or
+key-navigate = Navigate
+key-toggle = Toggle
+key-select-all = Select all
+key-select-suggested = Suggest
+key-clear = Clear
+key-explain = Explain
+key-breakdown = Breakdown
+key-reclassify = Temp/normal, category
+key-page = Page
+key-jump = Jump
+key-jump-path = Go to path
+key-directories = Directories
+key-group = Group by name
+key-images = Disk images
+key-files = Largest files
+key-two-pane = Two panes
+key-list = List
+key-switch-pane = Switch pane
+key-expand = Expand/collapse
+key-next-root = Next root
+key-save-session = Save session
+key-session-log = Log
+key-delete-selected = Delete selected
+key-quit = Quit
+key-commands = Commands
+commands-title = Commands
+commands-hint = ↑/↓: choose | Enter: run | Esc: cancel
+commands-none = No command matches
+command-sort-size = Sort by size
+command-sort-files = Sort by file count
+command-sort-safety = Sort by safety score
+command-sort-growth = Sort by growth since the last scan
+command-temp-only = Temp directories only (toggle)
+command-export-selection = Export selected paths to a file
+command-units-binary = Units: binary (1 KB = 1024 B)
+command-units-si = Units: SI (1 kB = 1000 B)
+command-units-bytes = Units: exact bytes
+command-size-basis = Sizes: apparent/allocated on disk (toggle)
+command-reclassify = Toggle temp/normal
+command-next-category = Next category
+command-group-view = Group by name (toggle)
+command-two-pane-view = Two-pane view (toggle)
+command-images-view = Large disk images view (toggle)
+status-sorted-size = Sorted by size
+status-sorted-files = Sorted by file count
+status-sorted-safety = Sorted by safety score, safest first
+status-sorted-growth = Sorted by growth since the last scan, biggest growers first
+status-temp-only = Showing only temp directories
+status-temp-only-off = Showing all directories
+status-size-apparent = Showing apparent sizes
+status-size-allocated = Showing sizes allocated on disk; the On disk column is allocated minus apparent size
+status-no-allocated-sizes = This scan has no allocated sizes
+status-selection-exported = Exported { $count } selected paths to { $path }
+status-export-error = Cannot export selection: { $error }
+status-selected = Selected { $count } dirs ({ $size })
+status-selected-files = Selected { $count } dirs and { $files } files ({ $size })
+status-nothing-selected = Nothing selected; press Space to select directories
+status-in-use = { $path } was modified recently and may be in use
+status-in-use-skipped = , { $count } in use skipped
+status-no-session-file = No session file location; start with --session FILE
+status-session-saved = Session saved to { $path } ({ $count } selected)
+status-session-error = Cannot save session: { $error }
+status-nothing-to-group = No temp directories to group
+status-no-images = No disk images of 1 GB or more found
+status-images-not-selectable = Disk images aren't selected here; Enter goes to the directory they are in
+status-files-searching = Looking for the largest files…
+status-no-files = No files found in these directories
+status-jump-none = No listed directory matches { $path }
+status-reclassified = { $path } is now { $category } (saved on exit)
+status-selection-cleared = Selection cleared
+status-suggested = Selected { $count } suggested deletions ({ $size }); review them, then press d
+status-no-suggestions = No suggested deletions here: none is a temp directory in a project, untouched for 30 days, with a safety score of 70 or more
+
+## Scan progress and summary
+
+scan-title = Scanning Filesystem
+scan-scanning = Scanning directories...
+scan-current = Current
+scan-current-path = Current Path
+scan-press = Press
+scan-to-cancel = to cancel
+scan-cancelled = Scan cancelled by user.
+telemetry-elapsed = scanned in { $time }
+telemetry-cpu = CPU { $time }
+telemetry-peak-memory = peak memory { $size }
+telemetry-read = read { $size }
+telemetry-read-ops = read { $size } ({ $ops } block reads)
+telemetry-block-reads = { $ops } block reads
+telemetry-rate = { $rate } dirs/s
+telemetry-progress-elapsed = Elapsed
+telemetry-progress-memory = Memory
+telemetry-progress-read = Read
+summary-title = Scan Summary
+summary-root = Root
+summary-total-dirs = Total directories
+summary-temp-dirs = Temp directories
+summary-temp-size = Temp size
+summary-by-age = By age
+summary-filesystem = Filesystem
+summary-incomplete = { $count ->
+    [one] 1 item could not be read; sizes above it are lower bounds
+   *[other] { $count } items could not be read; sizes above them are lower bounds
+}
+summary-estimated = Sizes marked ≈ are estimates from a sample of the files (--estimate)
+summary-top-size = Top { $count } Largest Directories
+summary-top-files = Top { $count } Directories by File Count
+summary-top-safety = Top { $count } Safest Deletions
+summary-top-growth = Top { $count } Biggest Growers
+key-scroll = Scroll
+key-interactive = Interactive mode
+key-exit = Exit
+
+## Reports and explanations
+
+report-reclaimable = Reclaimable space under { $root }: { $size } in { $count } directories
+report-by-category = By category:
+report-by-type = By type:
+report-type-line = { $name } ({ $category }): { $size } across { $count } { $count ->
+        [one] directory
+       *[other] directories
+    }
+report-most-files = Most files:
+report-directory = Directory
+column-inodes = Inodes
+report-no-ages = No file ages recorded (rescan, or load a scan file saved by this version)
+report-by-age = By last-modified age:
+report-by-age-top-level = By age per top-level directory:
+age-under-1m = < 1 month
+age-1-6m = 1-6 months
+age-6-12m = 6-12 months
+age-over-1y = > 1 year
+report-no-owners = No ownership information (scan a Unix filesystem, or load a JSON scan)
+report-by-user = By user
+report-by-group = By group
+inodes-used = { $used } of { $total } inodes used ({ $share }%)
+rebuild-cheap = cheap to regenerate
+rebuild-slow = slow to regenerate
+rebuild-irreplaceable = irreplaceable
+rebuild-cheap-short = cheap
+rebuild-slow-short = slow
+rebuild-irreplaceable-short = irreplaceable
+# Explanation lines are aligned on the value column
+explain-category = Category: { $name } ({ $category })
+explain-label = Label:    { $label }
+explain-rule = Rule:     { $rule }
+explain-safety = Safety:   { $note }
+explain-rebuild = Rebuild:  { $cost }
+explain-inside = Inside:   { $path } ({ $category }); scans report it as part of that directory
+
+## Classification reasons
+
+reason-override = override set in interactive mode
+reason-config-rule = config path rule '{ $pattern }'
+reason-backend-rule = rule '{ $pattern }' from { $backend }
+reason-builtin-rule = built-in path rule '{ $pattern }'
+reason-sibling = (project context: '{ $sibling }' found next to it)
+reason-builtin-name = built-in directory name '{ $name }'
+reason-builtin-glob = built-in name pattern '{ $pattern }'
+reason-no-match = no rule matched
+
+## Deletion screens
+
+deletion-complete = Deletion complete:
+deletion-report-complete = ✓ Deletion Complete
+deletion-report-cancelled = ⏹ Deletion Cancelled
+deletion-successful = Successfully deleted:{" "}
+deletion-files = Files among them:{" "}
+deletion-failed = Failed:{" "}
+deletion-cancelled-count = Cancelled:{" "}
+deletion-freed = Space freed:{" "}
+deletion-not-deleted = Cancelled, not deleted
+deletion-busy = { $reason } (in use, can be retried)
+deletion-retryable = { $count } of them in use, close the programs using them and retry
+deletion-retrying = Retrying { $count } failed deletions...
+key-retry-failed = Retry failed ({ $count })
+deletion-results-title = {" "}Results ({ $position }/{ $count }){" "}
+deletion-directories-title = {" "}Directories ({ $position }/{ $count }){" "}
+key-close = Close
+key-cancel = Cancel
+strong-reason-size = Selection includes more than { $size }
+strong-reason-non-temp = Selection includes { $count } non-temp { $count ->
+        [one] directory
+       *[other] directories
+    }
+strong-reason-both = Selection includes more than { $size } and { $count } non-temp { $count ->
+        [one] directory
+       *[other] directories
+    }
+deletion-total-size = Total size to be freed:{" "}
+deletion-confirmation = DELETION CONFIRMATION
+deletion-about-to-delete = You are about to delete { $count } directories:
+deletion-cannot-undo = This action cannot be undone!
+deletion-type-strong = Type DELETE or the number of directories ({ $count }) to confirm deletion:{" "}
+deletion-type-yes = Type 'yes' to confirm deletion:{" "}
+deletion-directories = Directories to delete:{" "}
+deletion-mismatch = (does not match, try again)
+deletion-type = Type{" "}
+deletion-press-enter = {" "}and press Enter:{" "}
+deletion-confirm = Confirm deletion
+deletion-left-untouched = ⏹ Cancelled: { $count } directories left untouched
+deletion-deleted = ✓ Deleted: { $path }
+deletion-trashed = ✓ Moved to trash: { $path }
+deletion-emptied = ✓ Emptied: { $path }
+deletion-progress = Deleting { $path }: { $percent }% ({ $removed }/{ $entries } entries)
+deletion-stopped-partway = ⏹ Stopped partway through { $path } after freeing { $size }
+deletion-mode-remove = delete
+deletion-mode-empty = empty, keep directory
+deletion-mode-empty-gitkeep = empty, keep directory and .gitkeep
+deletion-needs-sudo = 🔒 no permission, needs sudo
+deletion-read-only = 🔒 read-only filesystem, will fail
+deletion-will-fail = { $count } of them will fail, see the notes above
+key-deletion-mode = Delete/empty this one
+deletion-delete-failed = ✗ Failed to delete { $path }: { $reason }
+
+## Clutter and downloads
+
+clutter-summary = { $dirs } empty directory trees and { $links } broken symlinks
+clutter-removed = Removed { $dirs } empty directory trees and { $links } broken symlinks
+clutter-found = Found { $summary }
+clutter-title = 🧹 Empty Directories & Broken Symlinks
+clutter-under = Under { $root }:{" "}
+clutter-list-title = {" "}Clutter{" "}
+clutter-confirm = Remove all { $summary }?{" "}
+key-yes = Yes
+key-other-no = any other key: No
+key-clean-up-all = Clean up all
+age-today = today
+age-days = { $count ->
+        [one] { $count } day
+       *[other] { $count } days
+    }
+age-months = { $count } months
+age-years = { $count } years
+downloads-done = { $verb } { $count } items ({ $size })
+downloads-selected = { $selected } of { $count } items selected ({ $size })
+downloads-title = 📥 Downloads Triage: { $dir }
+downloads-header = { $count } items, { $size }  |  Selected:{" "}
+downloads-oldest-first = {" "}Oldest first{" "}
+downloads-largest-first = {" "}Largest first{" "}
+downloads-delete = Delete { $count } items ({ $size })?{" "}
+downloads-move = Move to { $archive } { $count } items ({ $size })?{" "}
+key-older-than = Older than { $age }
+key-none = None
+key-sort = Sort
+key-delete = Delete
+key-archive = Archive
+
+## Modification times
+
+modified-just-now = just now
+modified-minutes = { $count }m ago
+modified-hours = { $count }h ago
+modified-days = { $count }d ago
+modified-months = { $count }mo ago
+modified-years = { $count }y ago
+
+## Read-only mode
+
+error-read-only-yes = --yes deletes without asking, which read-only mode does not allow
+error-read-only-path-list = --delete-from-file deletes, which read-only mode does not allow
+error-read-only-apply-selection = --apply-selection deletes, which read-only mode does not allow
+path-list-loaded = Read { $count } paths from { $file }
+selection-loaded = { $count } rows are marked in the selected column
+path-list-rejected = Not deleting { $path }: { $reason }
+path-list-relative = not an absolute path
+path-list-missing = does not exist
+path-list-symlink = a symbolic link
+path-list-not-directory = not a directory
+path-list-root = a filesystem root
+path-list-system = a system directory
+path-list-home = it is your home directory or contains it
+read-only-badge = [read-only]
+status-read-only = Read-only mode: deletion is disabled
+
+## Hooks
+
+hook-pre-failed = Deletion cancelled: { $error }
+
+## Notifications
+
+notify-scan-finished = Scan finished: { $count } directories, { $size }
+notify-deletion-finished = Deleted { $count } directories and freed { $size }{ $failed ->
+        [0] {""}
+       *[other] ; { $failed } failed
+    }
+
+## Metrics
+
+metrics-written = Metrics written to { $file }
+
+## Posting results
+
+results-posted = Results posted to { $url }
+email-sent = Report emailed to { $to }
+email-subject = Disk cleanup report: { $root } on { $host }
+email-body-scan = Scan of { $root } on { $host } at { $time }
+email-body-size = Total: { $size } in { $directories } directories ({ $files } files)
+email-body-temp = Temp directories: { $count } ({ $size })
+email-body-errors = Unreadable: { $count } (sizes are lower bounds)
+email-body-dry-run = Dry run: nothing was deleted
+email-body-deleted = Deleted: { $count } ({ $size } freed)
+email-body-failed = Failed to delete ({ $count }):
+
+## Audit journal
+
+audit-select = Selected { $path }
+audit-deselect = Unselected { $path }
+audit-select-many = Selected { $count } directories
+audit-deselect-many = Unselected { $count } directories
+audit-reclassify = Marked { $path } as { $category }
+audit-delete = Deleted { $path }
+audit-delete-failed = Failed to delete { $path }: { $reason }
+audit-saved = Session actions appended to { $path }
@@ -0,0 +1,187 @@
+use crate::i18n::t;
+use crate::scanner::DirectoryEntry;
+use crate::utils::format_size;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// Upper bounds of all but the last bucket: 1 month, 6 months, 1 year
+const BUCKET_LIMITS: [Duration; 3] = [
+    Duration::from_secs(30 * DAY),
+    Duration::from_secs(182 * DAY),
+    Duration::from_secs(365 * DAY),
+];
+
+/// "< 1 month", "1-6 months", "6-12 months" and "> 1 year" in the UI language
+pub fn bucket_labels() -> [String; 4] {
+    [t!("age-under-1m"), t!("age-1-6m"), t!("age-6-12m"), t!("age-over-1y")]
+}
+
+/// Names of the optional CSV columns holding each bucket
+pub const BUCKET_COLUMNS: [&str; 4] = ["bytes_under_1m", "bytes_1_6m", "bytes_6_12m", "bytes_over_1y"];
+
+/// Bytes by last-modified age of the files counted in a directory, relative to when it was
+/// scanned
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct AgeBuckets(pub [u64; 4]);
+
+impl AgeBuckets {
+    /// Add one file of `bytes` (its size as counted by the scan); files without a
+    /// modification time, or one in the future, count as new
+    pub fn record(&mut self, metadata: &Metadata, bytes: u64, now: SystemTime) {
+        self.record_modified(metadata.modified().ok(), bytes, now);
+    }
+
+    /// [`AgeBuckets::record`] for a file whose modification time is already known
+    pub fn record_modified(&mut self, modified: Option<SystemTime>, bytes: u64, now: SystemTime) {
+        let age = modified.and_then(|modified| now.duration_since(modified).ok()).unwrap_or_default();
+        self.0[bucket(age)] += bytes;
+    }
+
+    pub fn add(&mut self, other: &AgeBuckets) {
+        for (bytes, more) in self.0.iter_mut().zip(other.0) {
+            *bytes += more;
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.0.iter().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+}
+
+fn bucket(age: Duration) -> usize {
+    BUCKET_LIMITS.iter().position(|limit| age < *limit).unwrap_or(BUCKET_LIMITS.len())
+}
+
+/// Age buckets for the whole scan and for each top-level directory under the root
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AgeReport {
+    pub overall: AgeBuckets,
+    /// Largest first; files directly in the root only count towards `overall`
+    pub top_level: Vec<(PathBuf, AgeBuckets)>,
+}
+
+pub fn age_report(entries: &[DirectoryEntry], root: &Path) -> AgeReport {
+    let mut report = AgeReport::default();
+    let mut top_level: HashMap<PathBuf, AgeBuckets> = HashMap::new();
+    for entry in entries.iter().filter(|e| !e.ages.is_empty()) {
+        report.overall.add(&entry.ages);
+        let first = entry.path.strip_prefix(root).ok().and_then(|rel| rel.components().next());
+        if let Some(first) = first {
+            top_level.entry(root.join(first)).or_default().add(&entry.ages);
+        }
+    }
+    report.top_level = top_level.into_iter().collect();
+    report
+        .top_level
+        .sort_by(|(a_path, a), (b_path, b)| b.total().cmp(&a.total()).then_with(|| a_path.cmp(b_path)));
+    report
+}
+
+/// `1.20 GB (12%)` for one bucket of `buckets`
+pub fn format_bucket(buckets: &AgeBuckets, index: usize) -> String {
+    let total = buckets.total();
+    let share = (buckets.0[index] * 100).checked_div(total).unwrap_or(0);
+    format!("{} ({}%)", format_size(buckets.0[index]), share)
+}
+
+/// "By age" section for the report, listing at most `top` top-level directories
+pub fn format_age_report(report: &AgeReport, top: usize) -> String {
+    let mut out = String::new();
+    if report.overall.is_empty() {
+        let _ = writeln!(out, "\n{}", t!("report-no-ages"));
+        return out;
+    }
+
+    let _ = writeln!(out, "\n{}", t!("report-by-age"));
+    let labels = bucket_labels();
+    for (index, label) in labels.iter().enumerate() {
+        let _ = writeln!(out, "  {:<12} {:>18}", label, format_bucket(&report.overall, index));
+    }
+
+    let _ = writeln!(out, "\n{}", t!("report-by-age-top-level"));
+    let _ = writeln!(
+        out,
+        "  {:>11} {:>11} {:>11} {:>11}  {}",
+        labels[0],
+        labels[1],
+        labels[2],
+        labels[3],
+        t!("report-directory")
+    );
+    for (path, buckets) in report.top_level.iter().take(top) {
+        let _ = writeln!(
+            out,
+            "  {:>11} {:>11} {:>11} {:>11}  {}",
+            format_size(buckets.0[0]),
+            format_size(buckets.0[1]),
+            format_size(buckets.0[2]),
+            format_size(buckets.0[3]),
+            path.display()
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buckets() {
+        assert_eq!(bucket(Duration::ZERO), 0);
+        assert_eq!(bucket(Duration::from_secs(29 * DAY)), 0);
+        assert_eq!(bucket(Duration::from_secs(30 * DAY)), 1);
+        assert_eq!(bucket(Duration::from_secs(200 * DAY)), 2);
+        assert_eq!(bucket(Duration::from_secs(365 * DAY)), 3);
+        assert_eq!(bucket(Duration::from_secs(3650 * DAY)), 3);
+
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), "abcd").unwrap();
+        let metadata = temp_file.path().metadata().unwrap();
+        let mut ages = AgeBuckets::default();
+        ages.record(&metadata, 4, SystemTime::now() + Duration::from_secs(400 * DAY));
+        ages.record(&metadata, 4, SystemTime::now());
+        assert_eq!(ages, AgeBuckets([4, 0, 0, 4]));
+    }
+
+    #[test]
+    fn test_age_report() {
+        let entry = |path: &str, ages| DirectoryEntry {
+            path: PathBuf::from(path),
+            ages: AgeBuckets(ages),
+            ..Default::default()
+        };
+        let entries = vec![
+            entry("/data", [5, 0, 0, 0]),
+            entry("/data/old", [0, 0, 0, 100]),
+            entry("/data/old/deeper", [0, 0, 50, 200]),
+            entry("/data/new", [10, 20, 0, 0]),
+            entry("/data/empty", [0, 0, 0, 0]),
+        ];
+
+        let report = age_report(&entries, Path::new("/data"));
+        assert_eq!(report.overall, AgeBuckets([15, 20, 50, 300]));
+        assert_eq!(
+            report.top_level,
+            vec![
+                (PathBuf::from("/data/old"), AgeBuckets([0, 0, 50, 300])),
+                (PathBuf::from("/data/new"), AgeBuckets([10, 20, 0, 0])),
+            ]
+        );
+
+        let text = format_age_report(&report, 10);
+        assert!(text.contains("  > 1 year     "));
+        assert!(text.contains(" 300 B (77%)\n"));
+        assert!(text.lines().last().unwrap().ends_with("/data/new"));
+    }
+}
@@ -0,0 +1,804 @@
+use crate::audit;
+use crate::classifier::Classifier;
+use crate::cli::OpenFilesCheck;
+use crate::csv_handler::ScanMetadata;
+use crate::deletion::{self, DeletionMode, DeletionReport};
+use crate::email;
+use crate::error::AppError;
+use crate::hooks;
+use crate::i18n::t;
+use crate::interactive::InteractiveSession;
+use crate::notifications;
+use crate::open_files;
+use crate::path_list;
+use crate::pending;
+use crate::push;
+use crate::report;
+use crate::scan_file::{self, ScanFormat};
+use crate::scanner::{self, DirectoryEntry, EntryType};
+use crate::summary_ui::{self, SummaryAction};
+use crate::trash::{self, TrashBatch};
+use crate::utils::{self, RebuildCost, SortKey};
+use crate::vcs;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
+
+/// What is reported once the run is over
+pub struct RunResults {
+    pub summary: report::RunSummary,
+    pub metadata: ScanMetadata,
+    /// Entries to include with `--post-entries`
+    pub entries: Option<Vec<DirectoryEntry>>,
+    pub summary_json: bool,
+    /// How `--summary-json` prints it (`--format`)
+    pub summary_format: ScanFormat,
+    pub post_url: Option<String>,
+    pub post_headers: Vec<(String, String)>,
+    pub email: Option<EmailReport>,
+}
+
+impl RunResults {
+    /// `--post-results` sends the summary to an endpoint, `--email-report` mails it;
+    /// `--summary-json` prints it as one JSON object, the last line on stdout, or as a TOML or
+    /// YAML document with `--format`
+    pub fn finish(&self) {
+        if let Some(url) = &self.post_url {
+            let payload = push::ResultsPayload::new(&self.metadata, &self.summary, self.entries.as_deref());
+            match push::post_results(url, &self.post_headers, &payload) {
+                Ok(_) => println!("{}", t!("results-posted", url = url.clone())),
+                Err(e) => eprintln!("{}", t!("warning", message = e.to_string())),
+            }
+        }
+        if let Some(report) = &self.email {
+            let message = email::Message {
+                to: report.to.clone(),
+                subject: email::report_subject(&self.metadata),
+                body: email::report_body(&self.metadata, &self.summary),
+                attachment: Some(email::Attachment {
+                    name: "scan.csv".to_string(),
+                    content_type: "text/csv".to_string(),
+                    data: report.csv.clone(),
+                }),
+            };
+            match email::send(&report.smtp, &message) {
+                Ok(_) => println!("{}", t!("email-sent", to = report.to.join(", "))),
+                Err(e) => eprintln!("{}", t!("warning", message = e.to_string())),
+            }
+        }
+        if self.summary_json {
+            match self.summary_format {
+                ScanFormat::Csv | ScanFormat::Json => println!("{}", self.summary.to_json()),
+                format => match scan_file::to_string(&self.summary, format) {
+                    Ok(text) => print!("{}", text),
+                    Err(e) => eprintln!("{}", t!("warning", message = e.to_string())),
+                },
+            }
+        }
+    }
+}
+
+/// Where `--email-report` sends the run's summary, and the scan attached to it
+pub struct EmailReport {
+    pub to: Vec<String>,
+    pub smtp: email::SmtpConfig,
+    /// The scan as a CSV file, as it was before anything was deleted
+    pub csv: Vec<u8>,
+}
+
+/// Checks and switches applied between selecting directories and deleting them
+pub struct DeletionOptions {
+    /// Directories from merged scans of other machines
+    pub foreign: HashSet<PathBuf>,
+    pub active_since: Option<SystemTime>,
+    pub allow_tracked: bool,
+    pub open_files: Option<OpenFilesCheck>,
+    /// Delete without asking (`--yes`)
+    pub yes: bool,
+    pub dry_run: bool,
+    /// Never delete anything (`--read-only`)
+    pub read_only: bool,
+    /// Selections larger than this need a typed confirmation
+    pub large_deletion_threshold: u64,
+    /// Directories recounted at once for the confirmation (`--size-jobs`)
+    pub size_jobs: usize,
+    pub hooks: hooks::Hooks,
+    /// Desktop notification for deletions that take longer than this
+    pub notify_after: Option<Duration>,
+    /// Move directories here instead of deleting them (`--trash`)
+    pub trash: Option<trash::Trash>,
+    /// Rename directories for `purge-pending` to delete this much later (`--grace-period`)
+    pub grace_period: Option<Duration>,
+}
+
+/// How interactive mode is opened, again after each deletion
+pub struct InteractiveSettings {
+    pub sort: SortKey,
+    pub classifier: Classifier,
+    pub root_path: PathBuf,
+    pub roots: Vec<PathBuf>,
+    pub two_pane: bool,
+    /// Preselect the suggested directories; only in the first session
+    pub suggested_selection: bool,
+    pub read_only: bool,
+    pub session_file: Option<PathBuf>,
+    pub scan_metadata: ScanMetadata,
+    pub watch: bool,
+    /// Where classification changes made with 't' / 'T' are saved
+    pub overrides_path: Option<PathBuf>,
+}
+
+/// Directories the user agreed to delete, and how
+pub struct Confirmed {
+    paths: Vec<PathBuf>,
+    modes: HashMap<PathBuf, DeletionMode>,
+    total_size: u64,
+}
+
+/// What came of asking to delete a selection
+pub enum Confirmation {
+    Confirmed(Confirmed),
+    /// The user said no
+    Cancelled,
+    /// Nothing to ask about: everything was filtered out, a dry run or a failed hook
+    Skipped,
+}
+
+/// A finished deletion, and what retrying its busy paths needs
+pub struct Deletion<'a> {
+    report: DeletionReport,
+    modes: HashMap<PathBuf, DeletionMode>,
+    batch: Option<TrashBatch<'a>>,
+    /// Date the directories were marked for, when they were renamed rather than deleted
+    pending_until: Option<SystemTime>,
+}
+
+/// A screen of the run after the scan, or its end. Each one hands over to the next and
+/// screens can lead back: leaving the deletion report returns to the summary.
+pub enum Stage<'a> {
+    /// The overview of the scan; opens interactive mode or ends the run
+    Summary,
+    /// Browse and select; hands the selection over for confirmation
+    Interactive,
+    /// Safety checks and confirmation of a selection; saying no returns to interactive mode
+    Confirm(Vec<PathBuf>),
+    /// Delete what was confirmed
+    Delete(Confirmed),
+    /// What the deletion did; busy paths can be retried from here
+    Report(Box<Deletion<'a>>),
+    Done,
+}
+
+/// The screens of a run after the scan
+pub struct App<'a> {
+    /// Everything scanned, minus what has been deleted since
+    entries: Vec<DirectoryEntry>,
+    settings: InteractiveSettings,
+    options: &'a DeletionOptions,
+    results: RunResults,
+    /// Kept while confirming, so saying no returns to the same selection
+    session: Option<InteractiveSession>,
+    /// Actions of finished sessions and deletions, for the audit journal
+    records: Vec<audit::Record>,
+}
+
+impl<'a> App<'a> {
+    pub fn new(
+        entries: Vec<DirectoryEntry>,
+        settings: InteractiveSettings,
+        options: &'a DeletionOptions,
+        results: RunResults,
+    ) -> Self {
+        Self { entries, settings, options, results, session: None, records: Vec::new() }
+    }
+
+    /// Go from `stage` until the run is done, then write the audit journal and report the
+    /// results
+    pub fn run(mut self, mut stage: Stage<'a>) -> Result<(), AppError> {
+        loop {
+            stage = match stage {
+                Stage::Summary => self.summary(),
+                Stage::Interactive => self.interactive()?,
+                Stage::Confirm(paths) => {
+                    let (costs, normal) = match &self.session {
+                        Some(session) => (session.rebuild_costs(), session.normal_paths()),
+                        None => (HashMap::new(), HashSet::new()),
+                    };
+                    match confirm(paths, &costs, &normal, self.options) {
+                        Confirmation::Confirmed(confirmed) => Stage::Delete(confirmed),
+                        Confirmation::Cancelled => Stage::Interactive,
+                        Confirmation::Skipped => Stage::Done,
+                    }
+                }
+                Stage::Delete(confirmed) => Stage::Report(Box::new(delete(confirmed, self.options)?)),
+                Stage::Report(mut deletion) => {
+                    review(&mut deletion);
+                    self.forget(&deletion.report);
+                    Stage::Summary
+                }
+                Stage::Done => break,
+            };
+        }
+
+        if let Some(session) = self.session.take() {
+            self.records.extend_from_slice(session.session_log());
+        }
+        if let (false, Some(journal)) = (self.records.is_empty(), audit::AuditJournal::default_location()) {
+            match journal.append(&self.records) {
+                Ok(_) => println!("{}", t!("audit-saved", path = journal.path().display().to_string())),
+                Err(e) => eprintln!("{}", t!("warning", message = e.to_string())),
+            }
+        }
+        self.results.finish();
+        Ok(())
+    }
+
+    fn summary(&mut self) -> Stage<'a> {
+        if self.entries.is_empty() {
+            return Stage::Done;
+        }
+        let root_path = &self.settings.root_path;
+        match summary_ui::show_summary(&self.entries, root_path, self.settings.sort) {
+            Ok(SummaryAction::LaunchInteractive) => Stage::Interactive,
+            Ok(SummaryAction::Continue) => Stage::Done,
+            Err(e) => {
+                eprintln!("{}", t!("error-display", view = "summary", error = e.to_string()));
+                // Fallback to text summary
+                if let Some(root) = scanner::find_root(&self.entries, root_path) {
+                    println!(
+                        "\n{}",
+                        t!(
+                            "summary-text",
+                            dirs = self.entries.len(),
+                            files = root.cumulative_file_count,
+                            size = utils::format_size(root.shown_size())
+                        )
+                    );
+                }
+                Stage::Done
+            }
+        }
+    }
+
+    fn interactive(&mut self) -> Result<Stage<'a>, AppError> {
+        if self.entries.is_empty() {
+            println!("\n{}", t!("interactive-empty"));
+            return Ok(Stage::Done);
+        }
+        let settings = &mut self.settings;
+        let entries = &self.entries;
+        let session = self.session.get_or_insert_with(|| {
+            println!("\n{}", t!("interactive-launching"));
+            let session = InteractiveSession::new(entries.clone(), settings.sort)
+                .with_classifier(settings.classifier.clone(), settings.root_path.clone())
+                .with_roots(&settings.roots)
+                .with_two_pane(settings.two_pane)
+                .with_suggested_selection(settings.suggested_selection)
+                .with_read_only(settings.read_only)
+                .with_session_file(settings.session_file.clone(), settings.scan_metadata.clone())
+                .with_live_updates(&settings.roots, settings.watch);
+            settings.suggested_selection = false;
+            session
+        });
+
+        let result = session.run();
+
+        if let Some(path) = session.saved_session() {
+            println!("{}", t!("session-saved-resume", path = path.display().to_string()));
+        }
+
+        // Persist classification changes made with 't' / 'T', and keep them for later sessions
+        if let Some(overrides) = session.changed_overrides() {
+            settings.classifier = settings.classifier.clone().with_overrides(overrides.clone());
+            if let Some(path) = &settings.overrides_path {
+                match overrides.save(path) {
+                    Ok(_) => println!("{}", t!("overrides-saved", path = path.display().to_string())),
+                    Err(e) => eprintln!("{}", t!("error-save-overrides", error = e.to_string())),
+                }
+            }
+        }
+
+        let selected = result?;
+        if selected.is_empty() {
+            println!("{}", t!("nothing-selected"));
+            return Ok(Stage::Done);
+        }
+        Ok(Stage::Confirm(selected))
+    }
+
+    /// Count a finished deletion and drop what it removed, so the next screen shows what is
+    /// left; the session that selected it is closed and a later one starts afresh
+    fn forget(&mut self, report: &DeletionReport) {
+        self.results.summary.add_deletion(report);
+        let mut files = HashMap::new();
+        if let Some(session) = self.session.take() {
+            self.records.extend_from_slice(session.session_log());
+            files = session.selected_file_sizes();
+            // Reclassified and recounted while the session was open
+            let index: HashMap<&Path, usize> =
+                self.entries.iter().enumerate().map(|(i, e)| (e.path.as_path(), i)).collect();
+            let updated: Vec<(usize, DirectoryEntry)> = session
+                .entries()
+                .iter()
+                .filter_map(|e| index.get(e.path.as_path()).map(|&i| (i, e.clone())))
+                .collect();
+            for (i, entry) in updated {
+                self.entries[i] = entry;
+            }
+        }
+        self.records.extend(audit::deletion_records(report));
+        forget_deleted(&mut self.entries, &report.successful, &files);
+    }
+}
+
+/// Take `deleted` out of `entries`: directories that are gone go with everything listed
+/// below them, emptied ones stay with nothing in them, and the directories above lose what
+/// they held. Deleted files, sized in `files`, are taken out of the directories above them.
+pub fn forget_deleted(entries: &mut Vec<DirectoryEntry>, deleted: &[PathBuf], files: &HashMap<PathBuf, u64>) {
+    for path in deleted {
+        let Some(entry) = entries.iter_mut().find(|e| &e.path == path) else {
+            if let Some(&size) = files.get(path) {
+                for above in entries.iter_mut().filter(|e| path.starts_with(&e.path)) {
+                    if path.parent() == Some(above.path.as_path()) {
+                        above.size_bytes = above.size_bytes.saturating_sub(size);
+                        above.file_count = above.file_count.saturating_sub(1);
+                    }
+                    above.cumulative_size_bytes = above.cumulative_size_bytes.saturating_sub(size);
+                    above.cumulative_allocated_bytes = above.cumulative_allocated_bytes.saturating_sub(size);
+                    above.cumulative_file_count = above.cumulative_file_count.saturating_sub(1);
+                }
+            }
+            continue;
+        };
+        let (size, files) = (entry.cumulative_size_bytes, entry.cumulative_file_count);
+        let allocated = entry.cumulative_allocated_bytes;
+        entry.size_bytes = 0;
+        entry.file_count = 0;
+        entry.cumulative_size_bytes = 0;
+        entry.cumulative_allocated_bytes = 0;
+        entry.cumulative_file_count = 0;
+        entry.selected = false;
+        for above in entries.iter_mut().filter(|e| path.starts_with(&e.path) && &e.path != path) {
+            above.cumulative_size_bytes = above.cumulative_size_bytes.saturating_sub(size);
+            above.cumulative_allocated_bytes = above.cumulative_allocated_bytes.saturating_sub(allocated);
+            above.cumulative_file_count = above.cumulative_file_count.saturating_sub(files);
+        }
+        let exists = path.exists();
+        entries.retain(|e| !e.path.starts_with(path) || (exists && &e.path == path));
+    }
+}
+
+/// The Ctrl-C flag for deletions; the handler can only be installed once per process
+pub fn cancel_flag() -> &'static AtomicBool {
+    static CANCEL: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+    CANCEL.get_or_init(|| {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let handler_cancel = Arc::clone(&cancel);
+        if let Err(e) = ctrlc::set_handler(move || {
+            handler_cancel.store(true, Ordering::SeqCst);
+            eprintln!("\n{}", t!("deletion-cancelling"));
+        }) {
+            eprintln!("{}", t!("warning-ctrlc", error = e.to_string()));
+        }
+        cancel
+    })
+}
+
+/// What `recheck` found about a directory, or a check it couldn't run
+pub enum Recheck<'a> {
+    /// Modified since `active_since`; left out
+    Active(&'a Path),
+    /// Holds this many git-tracked files; left out
+    Tracked(&'a Path, usize),
+    /// Kept, since its git status is unknown
+    GitStatusFailed(&'a Path, &'a io::Error),
+    /// A process has files open under the directory; left out with `--open-files block`
+    InUse(&'a open_files::OpenFileHolder),
+    OpenFilesFailed(&'a io::Error),
+}
+
+/// The checks repeated right before deleting, for everything that deletes: leaves out of
+/// `paths` the directories modified since `active_since`, holding git-tracked files unless
+/// `allow_tracked`, and in use by a process with `--open-files block`, telling `report`
+/// about each
+pub fn recheck(
+    paths: &mut Vec<PathBuf>,
+    active_since: Option<SystemTime>,
+    allow_tracked: bool,
+    open_files: Option<OpenFilesCheck>,
+    mut report: impl FnMut(Recheck),
+) {
+    // A build may have started since the scan
+    if let Some(since) = active_since {
+        paths.retain(|path| {
+            let active = scanner::modified_since(path, since);
+            if active {
+                report(Recheck::Active(path));
+            }
+            !active
+        });
+    }
+
+    // Temp-looking directories can still hold unique, version-controlled work
+    if !allow_tracked {
+        paths.retain(|path| match vcs::tracked_file_count(path) {
+            Ok(0) => true,
+            Ok(count) => {
+                report(Recheck::Tracked(path, count));
+                false
+            }
+            Err(e) => {
+                report(Recheck::GitStatusFailed(path, &e));
+                true
+            }
+        });
+    }
+
+    if let Some(mode) = open_files {
+        match open_files::find_holders(paths) {
+            Ok(holders) => {
+                holders.iter().for_each(|holder| report(Recheck::InUse(holder)));
+                if mode == OpenFilesCheck::Block {
+                    paths.retain(|path| !holders.iter().any(|h| &h.path == path));
+                }
+            }
+            Err(e) => report(Recheck::OpenFilesFailed(&e)),
+        }
+    }
+}
+
+/// Run the safety checks on the selected directories and confirm what is left.
+/// `normal` holds the directories classified as non-temp, which need a typed confirmation.
+pub fn confirm(
+    mut selected_paths: Vec<PathBuf>,
+    costs: &HashMap<PathBuf, RebuildCost>,
+    normal: &HashSet<PathBuf>,
+    options: &DeletionOptions,
+) -> Confirmation {
+    // Interactive mode never hands over a selection in read-only mode; this is the backstop
+    if options.read_only {
+        return Confirmation::Skipped;
+    }
+    selected_paths.retain(|path| {
+        let remote = options.foreign.contains(path);
+        if remote {
+            println!("{}", t!("skip-foreign", path = path.display().to_string()));
+        }
+        !remote
+    });
+
+    recheck(&mut selected_paths, options.active_since, options.allow_tracked, options.open_files, |event| match event {
+        Recheck::Active(path) => println!("{}", t!("skip-active", path = path.display().to_string())),
+        Recheck::Tracked(path, count) => {
+            println!("{}", t!("skip-tracked", path = path.display().to_string(), count = count))
+        }
+        Recheck::GitStatusFailed(path, e) => {
+            eprintln!("{}", t!("warning-git-status", path = path.display().to_string(), error = e.to_string()))
+        }
+        Recheck::InUse(holder) => println!(
+            "{}",
+            t!(
+                "open-file-holder",
+                path = holder.path.display().to_string(),
+                process = holder.process_name.clone(),
+                pid = holder.pid
+            )
+        ),
+        Recheck::OpenFilesFailed(e) => eprintln!("{}", t!("warning-open-files", error = e.to_string())),
+    });
+
+    if selected_paths.is_empty() {
+        println!("{}", t!("nothing-selected"));
+        return Confirmation::Skipped;
+    }
+
+    if options.dry_run {
+        println!("\n{}", t!("dry-run-banner"));
+        println!("{}", t!("dry-run-would-delete", count = selected_paths.len()));
+        let total_size = deletion::selection_size(&selected_paths, options.size_jobs);
+        deletion::print_deletion_plan(&selected_paths, costs, &HashMap::new(), total_size);
+        return Confirmation::Skipped;
+    }
+
+    // Confirm deletion; scripted runs skip the prompt but still log what gets deleted
+    let total_size = deletion::selection_size(&selected_paths, options.size_jobs);
+    let mut modes = HashMap::new();
+    let confirmed = if options.yes {
+        println!("\n{}", t!("deleting-unconfirmed", count = selected_paths.len()));
+        deletion::print_deletion_plan(&selected_paths, costs, &modes, total_size);
+        true
+    } else {
+        let non_temp = selected_paths.iter().filter(|path| normal.contains(*path)).count();
+        let strong = deletion::strong_confirmation_reason(total_size, options.large_deletion_threshold, non_temp);
+        let permanent = options.trash.is_none() && options.grace_period.is_none();
+        deletion::confirm_deletion(&selected_paths, costs, &mut modes, total_size, strong.as_deref(), permanent)
+    };
+    if !confirmed {
+        println!("{}", t!("deletion-cancelled"));
+        return Confirmation::Cancelled;
+    }
+
+    if let Some(command) = &options.hooks.pre_deletion {
+        if let Err(e) = hooks::run_hook(command, &hooks::HookSummary::before(&selected_paths, total_size)) {
+            eprintln!("{}", t!("hook-pre-failed", error = e.to_string()));
+            return Confirmation::Skipped;
+        }
+    }
+    Confirmation::Confirmed(Confirmed { paths: selected_paths, modes, total_size })
+}
+
+/// Delete what was confirmed, then notify and run the post-deletion hook
+pub fn delete<'a>(confirmed: Confirmed, options: &'a DeletionOptions) -> Result<Deletion<'a>, AppError> {
+    let Confirmed { paths, modes, total_size } = confirmed;
+    // Ctrl-C stops the batch after the directory currently being deleted
+    let cancel = cancel_flag();
+    cancel.store(false, Ordering::SeqCst);
+    println!("{}", t!("deleting"));
+
+    let started = Instant::now();
+    let mut batch = options.trash.as_ref().map(trash::Trash::batch);
+    // Marked directories are renamed whole, whatever their mode; nothing is emptied yet
+    let pending_until = options.grace_period.map(|grace| SystemTime::now() + grace);
+    let report = match pending_until {
+        Some(due) => pending::mark(&paths, due, cancel),
+        None => deletion::delete_directories_cancellable(&paths, &modes, cancel, batch.as_mut())?,
+    };
+    if let (Some(trash), Some(batch)) = (&options.trash, &batch) {
+        if batch.moved() > 0 {
+            println!("{}", trash.moved_message(batch.moved()));
+        }
+    }
+    if let Some(due) = pending_until {
+        if !report.successful.is_empty() {
+            println!("{}", t!("pending-summary", count = report.successful.len(), date = pending::format_date(due)));
+        }
+    }
+    let message = t!(
+        "notify-deletion-finished",
+        count = report.successful.len(),
+        size = utils::format_size(report.total_freed_bytes),
+        failed = report.failed.len()
+    );
+    notifications::notify_if_long(started.elapsed(), options.notify_after, &message);
+    if let Some(command) = &options.hooks.post_deletion {
+        let summary = hooks::HookSummary::after(&paths, total_size, &report);
+        if let Err(e) = hooks::run_hook(command, &summary) {
+            eprintln!("{}", t!("warning", message = e.to_string()));
+        }
+    }
+    Ok(Deletion { report, modes, batch, pending_until })
+}
+
+/// Show the deletion report until it is closed; busy paths can be retried from it once
+/// whatever holds them is closed
+fn review(deletion: &mut Deletion) {
+    loop {
+        match deletion.report.show_report() {
+            Ok(true) => {
+                println!("{}", t!("deletion-retrying", count = deletion.report.retryable.len()));
+                let cancel = cancel_flag();
+                cancel.store(false, Ordering::SeqCst);
+                let retryable = deletion.report.retryable.clone();
+                let retry = match deletion.pending_until {
+                    Some(due) => Ok(pending::mark(&retryable, due, cancel)),
+                    None => deletion::delete_directories_cancellable(&retryable, &deletion.modes, cancel, deletion.batch.as_mut()),
+                };
+                match retry {
+                    Ok(retry) => deletion.report.merge_retry(retry),
+                    Err(e) => AppError::from(e).print(),
+                }
+            }
+            Ok(false) => break,
+            Err(e) => {
+                eprintln!("{}", t!("error-display", view = "report", error = e.to_string()));
+                // Fallback to text report
+                deletion.report.print_summary();
+                break;
+            }
+        }
+    }
+}
+
+/// Check, confirm and delete a selection outside the screens (path lists, policies,
+/// scripted runs). Returns the deletion's results, if anything was deleted.
+pub fn delete_selected(
+    selected_paths: Vec<PathBuf>,
+    costs: &HashMap<PathBuf, RebuildCost>,
+    normal: &HashSet<PathBuf>,
+    options: &DeletionOptions,
+) -> Result<Option<DeletionReport>, AppError> {
+    let Confirmation::Confirmed(confirmed) = confirm(selected_paths, costs, normal, options) else {
+        return Ok(None);
+    };
+    let mut deletion = delete(confirmed, options)?;
+    if options.yes {
+        deletion.report.print_summary();
+    } else {
+        review(&mut deletion);
+    }
+    Ok(Some(deletion.report))
+}
+
+/// The selection stored in a loaded scan or session, without directories in use
+pub fn selected_paths(entries: &[DirectoryEntry]) -> Vec<PathBuf> {
+    entries.iter().filter(|e| e.selected && !e.active).map(|e| e.path.clone()).collect()
+}
+
+/// Delete directories named outside the tool, by `--delete-from-file` or an edited
+/// selection (`--apply-selection`): protected paths are refused as for any path list, and
+/// the rest go through the usual checks and confirmation. `entries` give each path's rebuild
+/// cost and type; non-temp ones need the typed confirmation. Returns the deletion's results,
+/// if anything was deleted, and whether everything listed was.
+pub fn delete_listed(
+    listed: &[PathBuf],
+    entries: &[DirectoryEntry],
+    options: &DeletionOptions,
+) -> Result<(Option<DeletionReport>, bool), AppError> {
+    let (paths, rejected) = path_list::validate(listed, dirs::home_dir().as_deref());
+    for rejected in &rejected {
+        eprintln!(
+            "{}",
+            t!("path-list-rejected", path = rejected.path.display().to_string(), reason = rejected.reason.clone())
+        );
+    }
+    let costs: HashMap<PathBuf, RebuildCost> = entries.iter().map(|e| (e.path.clone(), e.rebuild_cost())).collect();
+    let normal = entries.iter().filter(|e| e.entry_type == EntryType::Normal).map(|e| e.path.clone()).collect();
+    let report = delete_selected(paths, &costs, &normal, options)?;
+    let complete = rejected.is_empty() && report.as_ref().is_none_or(|report| report.failed.is_empty());
+    Ok((report, complete))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv_handler;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Scripted deletion with every safety check at its default
+    fn scripted() -> DeletionOptions {
+        DeletionOptions {
+            foreign: HashSet::new(),
+            active_since: None,
+            allow_tracked: false,
+            open_files: None,
+            yes: true,
+            dry_run: false,
+            read_only: false,
+            large_deletion_threshold: 0,
+            size_jobs: 1,
+            hooks: hooks::Hooks::default(),
+            notify_after: None,
+            trash: None,
+            grace_period: None,
+        }
+    }
+
+    fn make_dir(path: &Path) {
+        fs::create_dir_all(path).unwrap();
+        fs::write(path.join("file"), "data").unwrap();
+    }
+
+    #[test]
+    fn test_apply_selection() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = fs::canonicalize(temp_dir.path()).unwrap();
+        let (a, b, c) = (root.join("a/node_modules"), root.join("b/target"), root.join("c/node_modules"));
+        for dir in [&a, &b, &c] {
+            make_dir(dir);
+        }
+        let link = root.join("link");
+        std::os::unix::fs::symlink(&c, &link).unwrap();
+        fs::write(root.join("notes.txt"), "").unwrap();
+
+        // Rows marked in a spreadsheet: two deletable directories, one unmarked, and a
+        // symlink, a file, a missing and a relative path that are refused
+        let row = |path: &Path, mark: &str| format!("{},1,4,1,4,normal,{}\n", path.display(), mark);
+        let csv = [
+            "path,files,size_bytes,cumulative_files,cumulative_size_bytes,type,selected\n".to_string(),
+            row(&a, "y"),
+            row(&b, "TRUE"),
+            row(&c, ""),
+            row(&link, "x"),
+            row(&root.join("notes.txt"), "yes"),
+            row(&root.join("missing"), "1"),
+            row(Path::new("relative/target"), "1"),
+        ]
+        .concat();
+        let (entries, _) = csv_handler::read_csv_from(csv.as_bytes()).unwrap();
+        let listed = selected_paths(&entries);
+        assert_eq!(listed.len(), 6);
+
+        let (report, complete) = delete_listed(&listed, &entries, &scripted()).unwrap();
+        assert!(!complete);
+        assert_eq!(report.unwrap().successful, [a.clone(), b.clone()]);
+        assert!(!a.exists() && !b.exists());
+        assert!(c.join("file").exists() && link.exists() && root.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_dry_run_deletes_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = fs::canonicalize(temp_dir.path()).unwrap().join("app/node_modules");
+        make_dir(&cache);
+
+        let options = DeletionOptions { dry_run: true, ..scripted() };
+        let (report, complete) = delete_listed(std::slice::from_ref(&cache), &[], &options).unwrap();
+        assert!(report.is_none() && complete);
+        assert!(cache.join("file").exists());
+    }
+
+    #[test]
+    fn test_yes_keeps_safety_checks() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = fs::canonicalize(temp_dir.path()).unwrap();
+        let free = root.join("free/node_modules");
+        let link = root.join("link");
+        let repo = root.join("repo");
+        let tracked = repo.join("build");
+        for dir in [&free, &tracked] {
+            make_dir(dir);
+        }
+        std::os::unix::fs::symlink(&free, &link).unwrap();
+        let git = |args: &[&str]| std::process::Command::new("git").arg("-C").arg(&repo).args(args).output().is_ok_and(|o| o.status.success());
+        let with_git = git(&["init", "-q"]) && git(&["add", "build"]);
+
+        // Normal directories past a zero threshold would need the typed confirmation, which
+        // --yes skips; the protected-path and git checks still refuse
+        let listed = if with_git { vec![free.clone(), link.clone(), tracked.clone()] } else { vec![free.clone(), link.clone()] };
+        let (report, complete) = delete_listed(&listed, &[], &scripted()).unwrap();
+        assert!(!complete);
+        assert_eq!(report.unwrap().successful, vec![free.clone()]);
+        assert!(link.symlink_metadata().is_ok());
+        assert!(!with_git || tracked.join("file").exists());
+
+        // And so does the check for directories in use
+        let busy = root.join("busy/target");
+        make_dir(&busy);
+        let options = DeletionOptions { active_since: SystemTime::now().checked_sub(Duration::from_secs(3600)), ..scripted() };
+        let (report, _) = delete_listed(std::slice::from_ref(&busy), &[], &options).unwrap();
+        assert!(report.is_none());
+        assert!(busy.join("file").exists());
+    }
+
+    fn entry(path: &Path, size: u64, files: u64) -> DirectoryEntry {
+        DirectoryEntry {
+            path: path.to_path_buf(),
+            cumulative_size_bytes: size,
+            cumulative_file_count: files,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_forget_deleted() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let emptied = root.join("app/logs");
+        fs::create_dir_all(&emptied).unwrap();
+        let gone = root.join("app/node_modules");
+        let mut entries = vec![
+            entry(root, 1000, 100),
+            entry(&root.join("app"), 900, 90),
+            entry(&gone, 500, 50),
+            entry(&gone.join("react"), 200, 20),
+            entry(&emptied, 100, 10),
+            entry(&emptied.join("old"), 60, 6),
+            entry(&root.join("docs"), 100, 10),
+        ];
+
+        let video = root.join("docs/talk.mp4");
+        let files = HashMap::from([(video.clone(), 80)]);
+        forget_deleted(&mut entries, &[gone.clone(), emptied.clone(), root.join("unlisted"), video], &files);
+
+        let left: Vec<(&Path, u64, u64)> =
+            entries.iter().map(|e| (e.path.as_path(), e.cumulative_size_bytes, e.cumulative_file_count)).collect();
+        assert_eq!(
+            left,
+            [(root, 320, 39), (root.join("app").as_path(), 300, 30), (emptied.as_path(), 0, 0), (root.join("docs").as_path(), 20, 9)]
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use crate::deletion::DeletionReport;
+use crate::i18n::t;
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AuditError {
+    #[error("Cannot write the audit journal {path}: {source}")]
+    IoError { path: PathBuf, source: io::Error },
+}
+
+/// Something done in an interactive session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Action {
+    Select { path: PathBuf },
+    Deselect { path: PathBuf },
+    /// Several directories selected at once: all, the suggested ones or a name group
+    SelectMany { count: usize },
+    /// Several directories unselected at once
+    DeselectMany { count: usize },
+    /// Classified by hand with 't' / 'T', remembered as an override
+    Reclassify { path: PathBuf, category: String },
+    Delete { path: PathBuf },
+    DeleteFailed { path: PathBuf, reason: String },
+}
+
+impl Action {
+    /// One line for the session log pane
+    pub fn describe(&self) -> String {
+        let path = |path: &Path| path.display().to_string();
+        match self {
+            Action::Select { path: p } => t!("audit-select", path = path(p)),
+            Action::Deselect { path: p } => t!("audit-deselect", path = path(p)),
+            Action::SelectMany { count } => t!("audit-select-many", count = *count),
+            Action::DeselectMany { count } => t!("audit-deselect-many", count = *count),
+            Action::Reclassify { path: p, category } => t!("audit-reclassify", path = path(p), category = category.clone()),
+            Action::Delete { path: p } => t!("audit-delete", path = path(p)),
+            Action::DeleteFailed { path: p, reason } => t!("audit-delete-failed", path = path(p), reason = reason.clone()),
+        }
+    }
+}
+
+/// One line of the audit journal: who did what, when
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Record {
+    /// RFC 3339, to the second
+    pub time: String,
+    pub user: String,
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+impl Record {
+    pub fn now(action: Action) -> Self {
+        Self {
+            time: humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+            user: std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default(),
+            action,
+        }
+    }
+
+    /// `HH:MM:SS  what happened`, for the session log pane
+    pub fn describe(&self) -> String {
+        let clock = self.time.get(11..19).unwrap_or(&self.time);
+        format!("{}  {}", clock, self.action.describe())
+    }
+}
+
+/// What a deletion run did, as records for the journal
+pub fn deletion_records(report: &DeletionReport) -> Vec<Record> {
+    let deleted = report.successful.iter().map(|path| Action::Delete { path: path.clone() });
+    let failed = report.failed.iter().map(|(path, reason)| Action::DeleteFailed { path: path.clone(), reason: reason.clone() });
+    deleted.chain(failed).map(Record::now).collect()
+}
+
+/// Append-only JSON Lines log of the actions taken in interactive sessions, for handover
+/// notes on shared machines: `<data dir>/disk-cleanup-tool/audit.jsonl`
+#[derive(Debug, Clone)]
+pub struct AuditJournal {
+    path: PathBuf,
+}
+
+impl AuditJournal {
+    pub fn default_location() -> Option<Self> {
+        Some(Self::new(dirs::data_dir()?.join("disk-cleanup-tool").join("audit.jsonl")))
+    }
+
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, records: &[Record]) -> Result<(), AuditError> {
+        let io_error = |source| AuditError::IoError { path: self.path.clone(), source };
+        let mut lines = String::new();
+        for record in records {
+            lines.push_str(&serde_json::to_string(record).map_err(|e| io_error(e.into()))?);
+            lines.push('\n');
+        }
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path).map_err(io_error)?;
+        // One write for the whole session, so sessions ending at once don't interleave
+        file.write_all(lines.as_bytes()).map_err(io_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_append_records() {
+        let temp_dir = TempDir::new().unwrap();
+        let journal = AuditJournal::new(temp_dir.path().join("state/audit.jsonl"));
+        let report = DeletionReport {
+            successful: vec![PathBuf::from("/p/a/node_modules")],
+            failed: vec![(PathBuf::from("/p/b/target"), "Permission denied".to_string())],
+            cancelled: Vec::new(),
+            total_freed_bytes: 1500,
+            deleted_files: 0,
+            retryable: Vec::new(),
+        };
+        let mut records = vec![
+            Record::now(Action::Select { path: PathBuf::from("/p/a/node_modules") }),
+            Record::now(Action::Reclassify { path: PathBuf::from("/p/b/target"), category: "Build output".to_string() }),
+        ];
+        records.extend(deletion_records(&report));
+        journal.append(&records).unwrap();
+        journal.append(&records[..1]).unwrap();
+
+        let content = fs::read_to_string(journal.path()).unwrap();
+        let lines: Vec<serde_json::Value> = content.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0]["action"], "select");
+        assert_eq!(lines[1]["category"], "Build output");
+        assert_eq!(lines[2]["action"], "delete");
+        assert_eq!(lines[3]["reason"], "Permission denied");
+        assert!(lines[0]["time"].as_str().unwrap().ends_with('Z'));
+
+        assert!(records[0].describe().contains("/p/a/node_modules"));
+        assert_eq!(&records[0].describe()[2..3], ":");
+    }
+}
@@ -0,0 +1,281 @@
+use crate::cli::CliArgs;
+use crate::i18n::t;
+use crate::utils::expand_home;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame, Terminal,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BookmarkError {
+    #[error("No bookmarks configured; add a [bookmarks.NAME] table with a path to the config")]
+    NoneConfigured,
+
+    #[error("Unknown bookmark '{0}' (available: {1})")]
+    Unknown(String, String),
+
+    #[error("Cannot show the bookmark picker: {0}")]
+    Picker(#[from] io::Error),
+}
+
+/// A frequently cleaned location, from the `[bookmarks]` table of the config, with
+/// defaults for the flags it is usually scanned with.
+///
+/// ```toml
+/// [bookmarks.work-repos]
+/// path = "~/code"
+/// temp_only = true
+/// keep = ["vendor"]
+///
+/// [bookmarks.downloads]
+/// path = "~/Downloads"
+/// interactive = true
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Bookmark {
+    /// Directory to scan; `~/` expands to the home directory
+    pub path: PathBuf,
+
+    /// As `--temp-only`
+    #[serde(default)]
+    pub temp_only: bool,
+
+    /// As `--interactive`
+    #[serde(default)]
+    pub interactive: bool,
+
+    /// As `--read-only`
+    #[serde(default)]
+    pub read_only: bool,
+
+    /// As `--skip-active`, e.g. "2h"
+    #[serde(default, deserialize_with = "crate::config::deserialize_duration")]
+    pub skip_active: Option<Duration>,
+
+    /// As `--temp-dir`
+    #[serde(default)]
+    pub temp_dirs: Vec<String>,
+
+    /// As `--keep`
+    #[serde(default)]
+    pub keep: Vec<String>,
+}
+
+impl Bookmark {
+    /// The bookmarked directory, with `~/` expanded
+    pub fn root(&self) -> PathBuf {
+        expand_home(&self.path)
+    }
+
+    /// Scan the bookmarked directory, adding the bookmark's flags to those given on the
+    /// command line; a duration given on the command line wins
+    pub fn apply(&self, args: &mut CliArgs) {
+        args.path = vec![self.root()];
+        args.temp_only |= self.temp_only;
+        args.interactive |= self.interactive;
+        args.read_only |= self.read_only;
+        args.skip_active = args.skip_active.or(self.skip_active);
+        args.temp_dirs.extend(self.temp_dirs.iter().cloned());
+        args.keep.extend(self.keep.iter().cloned());
+    }
+
+    /// The bookmark's flags as they would be typed, e.g. "--temp-only --keep vendor"
+    pub fn flags(&self) -> String {
+        let mut flags = Vec::new();
+        for (set, flag) in [
+            (self.temp_only, "--temp-only"),
+            (self.interactive, "--interactive"),
+            (self.read_only, "--read-only"),
+        ] {
+            if set {
+                flags.push(flag.to_string());
+            }
+        }
+        if let Some(window) = self.skip_active {
+            flags.push(format!("--skip-active {}", humantime::format_duration(window)));
+        }
+        flags.extend(self.temp_dirs.iter().map(|name| format!("--temp-dir {}", name)));
+        flags.extend(self.keep.iter().map(|name| format!("--keep {}", name)));
+        flags.join(" ")
+    }
+}
+
+/// The bookmark called `name`
+pub fn find<'a>(bookmarks: &'a BTreeMap<String, Bookmark>, name: &str) -> Result<&'a Bookmark, BookmarkError> {
+    if bookmarks.is_empty() {
+        return Err(BookmarkError::NoneConfigured);
+    }
+    bookmarks.get(name).ok_or_else(|| {
+        let available = bookmarks.keys().map(String::as_str).collect::<Vec<_>>().join(", ");
+        BookmarkError::Unknown(name.to_string(), available)
+    })
+}
+
+/// Let the user pick a bookmark on a start screen; `None` if they quit without choosing
+pub fn choose(bookmarks: &BTreeMap<String, Bookmark>) -> Result<Option<(&str, &Bookmark)>, BookmarkError> {
+    if bookmarks.is_empty() {
+        return Err(BookmarkError::NoneConfigured);
+    }
+    let choices: Vec<(&str, &Bookmark)> = bookmarks.iter().map(|(name, b)| (name.as_str(), b)).collect();
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let result = run_picker(&mut terminal, &choices);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    Ok(result?.map(|index| choices[index]))
+}
+
+fn run_picker(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, choices: &[(&str, &Bookmark)]) -> io::Result<Option<usize>> {
+    let mut selected = 0usize;
+    loop {
+        terminal.draw(|f| render_picker(f, choices, selected))?;
+
+        if event::poll(std::time::Duration::from_millis(100))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
+                    KeyCode::Enter => return Ok(Some(selected)),
+                    KeyCode::Up | KeyCode::Char('k') => selected = selected.saturating_sub(1),
+                    KeyCode::Down | KeyCode::Char('j') => selected = (selected + 1).min(choices.len() - 1),
+                    // 1-9 pick directly
+                    KeyCode::Char(c @ '1'..='9') => {
+                        let index = c as usize - '1' as usize;
+                        if index < choices.len() {
+                            return Ok(Some(index));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn render_picker(f: &mut Frame, choices: &[(&str, &Bookmark)], selected: usize) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Min(0),    // List
+            Constraint::Length(3), // Footer
+        ])
+        .split(f.area());
+
+    let header = Paragraph::new(Line::from(Span::styled(
+        t!("bookmark-title"),
+        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+    )))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Cyan)));
+    f.render_widget(header, chunks[0]);
+
+    let name_width = choices.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0);
+    let items: Vec<ListItem> = choices
+        .iter()
+        .enumerate()
+        .map(|(i, (name, bookmark))| {
+            let number = if i < 9 { format!("{} ", i + 1) } else { "  ".to_string() };
+            ListItem::new(Line::from(vec![
+                Span::styled(number, Style::default().fg(Color::DarkGray)),
+                Span::styled(format!("{:<width$}  ", name, width = name_width), Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(bookmark.root().display().to_string()),
+                Span::styled(format!("  {}", bookmark.flags()), Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::White)))
+        .highlight_style(Style::default().bg(Color::DarkGray))
+        .highlight_symbol("▶ ");
+    let mut state = ListState::default().with_selected(Some(selected));
+    f.render_stateful_widget(list, chunks[1], &mut state);
+
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled("↑/↓", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  |  ", t!("key-navigate"))),
+        Span::styled("Enter", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  |  ", t!("key-scan-bookmark"))),
+        Span::styled("q", Style::default().fg(Color::Green)),
+        Span::raw(format!(": {}", t!("key-quit"))),
+    ]))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::White)));
+    f.render_widget(footer, chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn bookmarks() -> BTreeMap<String, Bookmark> {
+        let toml = r#"
+[work-repos]
+path = "~/code"
+temp_only = true
+skip_active = "2h"
+keep = ["vendor"]
+
+[downloads]
+path = "/data/downloads"
+"#;
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn test_apply_bookmark() {
+        let bookmarks = bookmarks();
+        let work = find(&bookmarks, "work-repos").unwrap();
+        assert_eq!(work.flags(), "--temp-only --skip-active 2h --keep vendor");
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(work.root(), home.join("code"));
+        }
+
+        let mut args = CliArgs::parse_from(["disk-cleanup-tool", "--keep", "dist", "--skip-active", "10m"]);
+        work.apply(&mut args);
+        assert_eq!(args.path, vec![work.root()]);
+        assert!(args.temp_only);
+        assert!(!args.interactive);
+        assert_eq!(args.keep, vec!["dist", "vendor"]);
+        // The command line wins
+        assert_eq!(args.skip_active, Some(Duration::from_secs(600)));
+
+        let downloads = find(&bookmarks, "downloads").unwrap();
+        assert_eq!(downloads.root(), PathBuf::from("/data/downloads"));
+        assert_eq!(downloads.flags(), "");
+    }
+
+    #[test]
+    fn test_unknown_bookmark() {
+        let bookmarks = bookmarks();
+        assert_eq!(find(&bookmarks, "photos").unwrap_err().to_string(), "Unknown bookmark 'photos' (available: downloads, work-repos)");
+        assert!(matches!(find(&BTreeMap::new(), "photos"), Err(BookmarkError::NoneConfigured)));
+    }
+}
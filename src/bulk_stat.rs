@@ -0,0 +1,411 @@
+use crate::ages::AgeBuckets;
+use crate::owners::{self, OwnerUsage};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Totals of everything below a directory, as the scanner keeps them for a temp directory
+#[derive(Debug, Default)]
+pub struct TreeSize {
+    pub files: u64,
+    pub bytes: u64,
+    /// Bytes allocated on disk to the files
+    pub allocated: u64,
+    /// Files, directories and other inodes below the directory, not counting itself
+    pub inodes: u64,
+    /// Directories that couldn't be read and files that couldn't be looked at
+    pub errors: u64,
+    pub owners: Vec<OwnerUsage>,
+    pub ages: AgeBuckets,
+}
+
+impl TreeSize {
+    #[cfg_attr(not(any(target_os = "linux", target_os = "macos")), allow(dead_code))]
+    fn add_file(&mut self, bytes: u64, allocated: u64, (uid, gid): (u32, u32), modified: Option<SystemTime>, now: SystemTime) {
+        self.files += 1;
+        self.bytes += bytes;
+        self.allocated += allocated;
+        owners::record_owner(&mut self.owners, uid, gid, bytes);
+        self.ages.record_modified(modified, bytes, now);
+    }
+}
+
+/// Size everything below `dir` the way the scanner sizes a temp directory, reading whole
+/// directories at a time instead of looking at each file on its own: Linux reads entries
+/// with `getdents64` into a large buffer and looks at files with `statx` relative to the
+/// open directory, macOS gets names, types, sizes and owners of a buffer full of entries
+/// from one `getattrlistbulk` call. Symbolic links aren't followed.
+///
+/// `visit` is called after each directory with the number of files found in it, and
+/// stops the walk by returning false. `None` where the platform has no bulk call or `dir`
+/// can't be opened, to walk it file by file instead.
+pub fn size_tree(dir: &Path, now: SystemTime, visit: &mut dyn FnMut(u64) -> bool) -> Option<TreeSize> {
+    platform::size_tree(dir, now, visit)
+}
+
+/// Seconds and nanoseconds since the epoch, which may be before it
+#[cfg_attr(not(any(target_os = "linux", target_os = "macos")), allow(dead_code))]
+fn system_time(seconds: i64, nanoseconds: u32) -> Option<SystemTime> {
+    let epoch = SystemTime::UNIX_EPOCH;
+    match u64::try_from(seconds) {
+        Ok(seconds) => epoch.checked_add(Duration::new(seconds, nanoseconds)),
+        Err(_) => epoch.checked_sub(Duration::from_secs(seconds.unsigned_abs()))?.checked_add(Duration::from_nanos(nanoseconds.into())),
+    }
+}
+
+/// Type and name of each record in a buffer filled by `getdents64`, including `.` and `..`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn dirents(buffer: &[u8]) -> impl Iterator<Item = (u8, &std::ffi::CStr)> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        // struct linux_dirent64 { u64 d_ino; i64 d_off; u16 d_reclen; u8 d_type; char d_name[]; }
+        let record = buffer.get(offset..)?;
+        let length = usize::from(u16::from_ne_bytes(record.get(16..18)?.try_into().ok()?));
+        let name = std::ffi::CStr::from_bytes_until_nul(record.get(19..length)?).ok()?;
+        offset += length;
+        Some((record[18], name))
+    })
+}
+
+/// One entry of a `getattrlistbulk` buffer
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+#[derive(Debug, PartialEq, Eq)]
+struct BulkEntry<'a> {
+    name: &'a [u8],
+    /// `VREG`, `VDIR`, `VLNK`, ...
+    object_type: u32,
+    modified: Option<SystemTime>,
+    owner: (u32, u32),
+    flags: u32,
+    allocated: u64,
+    size: u64,
+}
+
+/// The attributes the macOS bulk path asks for of one entry, at the start of `record`:
+/// the record length, the set of returned attributes, then name (as an offset and length
+/// from where it is referenced), object type, modification time, owner, group, flags,
+/// allocated size and data length, in that order. `FSOPT_PACK_INVAL_ATTRS` keeps the layout fixed by zeroing
+/// the attributes an entry doesn't have, like the data length of a directory.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn bulk_entry(record: &[u8]) -> Option<BulkEntry<'_>> {
+    let u32_at = |offset: usize| Some(u32::from_ne_bytes(record.get(offset..offset + 4)?.try_into().ok()?));
+    let i64_at = |offset: usize| Some(i64::from_ne_bytes(record.get(offset..offset + 8)?.try_into().ok()?));
+    let length = u32_at(0)? as usize;
+    let record = record.get(..length)?;
+
+    let name_start = 24usize.checked_add_signed(i32::from_ne_bytes(record.get(24..28)?.try_into().ok()?) as isize)?;
+    let name = record.get(name_start..name_start + u32_at(28)? as usize)?;
+    Some(BulkEntry {
+        name: name.strip_suffix(b"\0").unwrap_or(name),
+        object_type: u32_at(32)?,
+        modified: system_time(i64_at(36)?, u32::try_from(i64_at(44)?).unwrap_or(0)),
+        owner: (u32_at(52)?, u32_at(56)?),
+        flags: u32_at(60)?,
+        allocated: u64::try_from(i64_at(64)?).unwrap_or(0),
+        size: u64::try_from(i64_at(72)?).unwrap_or(0),
+    })
+}
+
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+mod platform {
+    use super::{dirents, system_time, TreeSize};
+    use std::ffi::{CStr, OsStr};
+    use std::fs::File;
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::io::AsRawFd;
+    use std::path::{Path, PathBuf};
+    use std::time::SystemTime;
+
+    /// Bytes of directory entries read per call
+    const BUFFER: usize = 64 * 1024;
+    const DT_UNKNOWN: u8 = 0;
+    const DT_DIR: u8 = 4;
+    const DT_REG: u8 = 8;
+
+    pub fn size_tree(dir: &Path, now: SystemTime, visit: &mut dyn FnMut(u64) -> bool) -> Option<TreeSize> {
+        let root = File::open(dir).ok()?;
+        // Kernels before 4.11 have no statx
+        if stat(&root, c"", libc::AT_EMPTY_PATH).is_err_and(|e| e.raw_os_error() == Some(libc::ENOSYS)) {
+            return None;
+        }
+
+        let mut tree = TreeSize::default();
+        // Directories are opened when their turn comes, so only one is open at a time
+        let mut pending: Vec<(PathBuf, Option<File>)> = vec![(dir.to_path_buf(), Some(root))];
+        // u64s keep the records 8-byte aligned, as the kernel writes them
+        let mut buffer = vec![0u64; BUFFER / 8];
+        while let Some((path, opened)) = pending.pop() {
+            let directory = match opened.map_or_else(|| File::open(&path), Ok) {
+                Ok(directory) => directory,
+                Err(_) => {
+                    tree.errors += 1;
+                    continue;
+                }
+            };
+            let files_before = tree.files;
+            loop {
+                // SAFETY: `buffer` is writable for BUFFER bytes
+                let read = unsafe { libc::syscall(libc::SYS_getdents64, directory.as_raw_fd(), buffer.as_mut_ptr(), BUFFER) };
+                if read <= 0 {
+                    tree.errors += u64::from(read < 0);
+                    break;
+                }
+                // SAFETY: the kernel filled the first `read` bytes of `buffer`
+                let filled = unsafe { std::slice::from_raw_parts(buffer.as_ptr().cast::<u8>(), read as usize) };
+                for (kind, name) in dirents(filled).filter(|(_, name)| !matches!(name.to_bytes(), b"." | b"..")) {
+                    tree.inodes += 1;
+                    let child = || path.join(OsStr::from_bytes(name.to_bytes()));
+                    match kind {
+                        DT_DIR => pending.push((child(), None)),
+                        // Some filesystems leave the type for statx to tell
+                        DT_REG | DT_UNKNOWN => match stat(&directory, name, libc::AT_SYMLINK_NOFOLLOW) {
+                            Ok(stat) => match u32::from(stat.stx_mode) & libc::S_IFMT {
+                                libc::S_IFREG => tree.add_file(
+                                    stat.stx_size,
+                                    stat.stx_blocks * 512,
+                                    (stat.stx_uid, stat.stx_gid),
+                                    system_time(stat.stx_mtime.tv_sec, stat.stx_mtime.tv_nsec),
+                                    now,
+                                ),
+                                libc::S_IFDIR => pending.push((child(), None)),
+                                _ => {}
+                            },
+                            Err(_) => tree.errors += 1,
+                        },
+                        _ => {}
+                    }
+                }
+            }
+            if !visit(tree.files - files_before) {
+                break;
+            }
+        }
+        Some(tree)
+    }
+
+    /// Type, size, allocation, owner and modification time of `name` in `directory`
+    fn stat(directory: &File, name: &CStr, flags: libc::c_int) -> io::Result<libc::statx> {
+        let mask = libc::STATX_TYPE | libc::STATX_SIZE | libc::STATX_BLOCKS | libc::STATX_UID | libc::STATX_GID | libc::STATX_MTIME;
+        // SAFETY: a zeroed statx is valid; the kernel overwrites what it fills in
+        let mut stat: libc::statx = unsafe { std::mem::zeroed() };
+        // SAFETY: `name` is NUL-terminated and `stat` a writable statx
+        match unsafe { libc::statx(directory.as_raw_fd(), name.as_ptr(), flags, mask, &mut stat) } {
+            0 => Ok(stat),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{bulk_entry, TreeSize};
+    use std::ffi::OsStr;
+    use std::fs::File;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::io::AsRawFd;
+    use std::path::{Path, PathBuf};
+    use std::time::SystemTime;
+
+    /// Bytes of attributes fetched per call
+    const BUFFER: usize = 64 * 1024;
+    const VREG: u32 = 1;
+    const VDIR: u32 = 2;
+    /// Evicted by a File Provider (iCloud Drive, OneDrive, Dropbox), so it takes no space
+    const SF_DATALESS: u32 = 0x4000_0000;
+
+    pub fn size_tree(dir: &Path, now: SystemTime, visit: &mut dyn FnMut(u64) -> bool) -> Option<TreeSize> {
+        let root = File::open(dir).ok()?;
+        // The layout `bulk_entry` reads
+        let mut attributes = libc::attrlist {
+            bitmapcount: libc::ATTR_BIT_MAP_COUNT,
+            reserved: 0,
+            commonattr: libc::ATTR_CMN_RETURNED_ATTRS
+                | libc::ATTR_CMN_NAME
+                | libc::ATTR_CMN_OBJTYPE
+                | libc::ATTR_CMN_MODTIME
+                | libc::ATTR_CMN_OWNERID
+                | libc::ATTR_CMN_GRPID
+                | libc::ATTR_CMN_FLAGS,
+            volattr: 0,
+            dirattr: 0,
+            fileattr: libc::ATTR_FILE_ALLOCSIZE | libc::ATTR_FILE_DATALENGTH,
+            forkattr: 0,
+        };
+
+        let mut tree = TreeSize::default();
+        // Directories are opened when their turn comes, so only one is open at a time
+        let mut pending: Vec<(PathBuf, Option<File>)> = vec![(dir.to_path_buf(), Some(root))];
+        // u64s keep the records aligned
+        let mut buffer = vec![0u64; BUFFER / 8];
+        while let Some((path, opened)) = pending.pop() {
+            let directory = match opened.map_or_else(|| File::open(&path), Ok) {
+                Ok(directory) => directory,
+                Err(_) => {
+                    tree.errors += 1;
+                    continue;
+                }
+            };
+            let files_before = tree.files;
+            loop {
+                // SAFETY: `attributes` is a valid attrlist and `buffer` writable for BUFFER bytes
+                let count = unsafe {
+                    libc::getattrlistbulk(
+                        directory.as_raw_fd(),
+                        (&mut attributes as *mut libc::attrlist).cast(),
+                        buffer.as_mut_ptr().cast(),
+                        BUFFER,
+                        u64::from(libc::FSOPT_PACK_INVAL_ATTRS),
+                    )
+                };
+                if count <= 0 {
+                    tree.errors += u64::from(count < 0);
+                    break;
+                }
+                // SAFETY: the buffer is fully initialized, whatever the call filled in
+                let filled = unsafe { std::slice::from_raw_parts(buffer.as_ptr().cast::<u8>(), BUFFER) };
+                let mut offset = 0;
+                for _ in 0..count {
+                    let Some(entry) = filled.get(offset..).and_then(bulk_entry) else {
+                        tree.errors += 1;
+                        break;
+                    };
+                    offset += u32::from_ne_bytes(filled[offset..offset + 4].try_into().unwrap_or_default()) as usize;
+                    tree.inodes += 1;
+                    match entry.object_type {
+                        VREG => {
+                            let bytes = if entry.flags & SF_DATALESS != 0 { 0 } else { entry.size };
+                            tree.add_file(bytes, entry.allocated, entry.owner, entry.modified, now);
+                        }
+                        VDIR => pending.push((path.join(OsStr::from_bytes(entry.name)), None)),
+                        _ => {}
+                    }
+                }
+            }
+            if !visit(tree.files - files_before) {
+                break;
+            }
+        }
+        Some(tree)
+    }
+}
+
+#[cfg(not(any(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")), target_os = "macos")))]
+mod platform {
+    use super::TreeSize;
+    use std::path::Path;
+    use std::time::SystemTime;
+
+    pub fn size_tree(_dir: &Path, _now: SystemTime, _visit: &mut dyn FnMut(u64) -> bool) -> Option<TreeSize> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+    use walkdir::WalkDir;
+
+    #[test]
+    fn test_size_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("node_modules");
+        fs::create_dir_all(root.join("lodash/fp")).unwrap();
+        fs::create_dir_all(root.join("empty")).unwrap();
+        fs::write(root.join("index.js"), "x".repeat(1000)).unwrap();
+        fs::write(root.join("lodash/lodash.js"), "x".repeat(5000)).unwrap();
+        fs::write(root.join("lodash/fp/map.js"), "x".repeat(20)).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(root.join("lodash"), root.join("link")).unwrap();
+
+        let now = SystemTime::now();
+        let mut visited = Vec::new();
+        let Some(tree) = size_tree(&root, now, &mut |files| {
+            visited.push(files);
+            true
+        }) else {
+            // No bulk path on this platform
+            return;
+        };
+        assert_eq!((tree.files, tree.bytes, tree.errors), (3, 6020, 0));
+        let allocated = ["index.js", "lodash/lodash.js", "lodash/fp/map.js"]
+            .iter()
+            .map(|file| crate::cloud_sync::allocated_size(&fs::metadata(root.join(file)).unwrap()))
+            .sum::<u64>();
+        assert_eq!(tree.allocated, allocated);
+        // The same as walking it entry by entry, the link counted but not followed
+        assert_eq!(tree.inodes, WalkDir::new(&root).into_iter().skip(1).count() as u64);
+        assert_eq!(tree.owners.iter().map(|o| o.bytes).sum::<u64>(), 6020);
+        assert_eq!(tree.ages.0[0], 6020);
+        visited.sort();
+        assert_eq!(visited, [0, 1, 1, 1]);
+
+        // Stopping early
+        let mut directories = 0;
+        let tree = size_tree(&root, now, &mut |_| {
+            directories += 1;
+            false
+        })
+        .unwrap();
+        assert_eq!((directories, tree.files), (1, 1));
+        assert!(size_tree(&root.join("missing"), now, &mut |_| true).is_none());
+    }
+
+    #[test]
+    fn test_dirents() {
+        let mut buffer = Vec::new();
+        for (kind, name) in [(4u8, "."), (8, "index.js"), (4, "lodash")] {
+            // Records are padded to 8 bytes
+            let length = (19 + name.len() + 1).next_multiple_of(8);
+            let mut record = vec![0u8; length];
+            record[16..18].copy_from_slice(&(length as u16).to_ne_bytes());
+            record[18] = kind;
+            record[19..19 + name.len()].copy_from_slice(name.as_bytes());
+            buffer.extend(record);
+        }
+        let read: Vec<(u8, &str)> = dirents(&buffer).map(|(kind, name)| (kind, name.to_str().unwrap())).collect();
+        assert_eq!(read, [(4, "."), (8, "index.js"), (4, "lodash")]);
+        assert_eq!(dirents(&buffer[..10]).count(), 0);
+    }
+
+    #[test]
+    fn test_bulk_entry() {
+        let mut record = vec![0u8; 80];
+        record.extend(b"index.js\0\0\0\0");
+        let length = record.len() as u32;
+        record[0..4].copy_from_slice(&length.to_ne_bytes());
+        // The name is 56 bytes on from its reference at 24, 9 bytes with the NUL
+        record[24..28].copy_from_slice(&56i32.to_ne_bytes());
+        record[28..32].copy_from_slice(&9u32.to_ne_bytes());
+        record[32..36].copy_from_slice(&1u32.to_ne_bytes());
+        record[36..44].copy_from_slice(&1_700_000_000i64.to_ne_bytes());
+        record[52..56].copy_from_slice(&501u32.to_ne_bytes());
+        record[56..60].copy_from_slice(&20u32.to_ne_bytes());
+        record[64..72].copy_from_slice(&4096i64.to_ne_bytes());
+        record[72..80].copy_from_slice(&1234i64.to_ne_bytes());
+        record.extend([0xff; 8]);
+
+        let entry = bulk_entry(&record).unwrap();
+        assert_eq!(
+            entry,
+            BulkEntry {
+                name: b"index.js",
+                object_type: 1,
+                modified: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+                owner: (501, 20),
+                flags: 0,
+                allocated: 4096,
+                size: 1234,
+            }
+        );
+        // A record claiming more than there is
+        assert!(bulk_entry(&record[..60]).is_none());
+    }
+
+    #[test]
+    fn test_system_time() {
+        assert_eq!(system_time(1, 500), Some(SystemTime::UNIX_EPOCH + Duration::new(1, 500)));
+        assert_eq!(system_time(-2, 500_000_000), Some(SystemTime::UNIX_EPOCH - Duration::from_millis(1500)));
+    }
+}
@@ -0,0 +1,190 @@
+use crate::ages::AgeBuckets;
+use crate::owners::OwnerUsage;
+use crate::scanner::EntryType;
+use crate::utils;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CheckpointError {
+    #[error("No scan checkpoint at {path}: {source}")]
+    IoError {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid scan checkpoint {path}: {source}")]
+    JsonError {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+
+    #[error("The checkpoint is for a scan of {found}, not {expected}; run with --path {found}")]
+    RootMismatch { found: PathBuf, expected: PathBuf },
+}
+
+/// Partial results of an interrupted scan.
+///
+/// The scanner walks directories in file-name order, so walk order matches path order:
+/// every directory that sorts before `position` and is not one of its ancestors has been
+/// counted completely, as have the files sorting before it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScanCheckpoint {
+    /// Absolute scan root
+    pub root: PathBuf,
+    /// Last directory the walk entered; `None` once the walk is finished
+    pub position: Option<PathBuf>,
+    /// Direct file count and size of each directory seen so far
+    pub dirs: Vec<(PathBuf, u64, u64, EntryType)>,
+    pub labels: Vec<(PathBuf, String)>,
+    pub modified: Vec<(PathBuf, SystemTime)>,
+    /// Per-owner usage of each directory seen so far
+    #[serde(default)]
+    pub owners: Vec<(PathBuf, Vec<OwnerUsage>)>,
+    #[serde(default)]
+    pub ages: Vec<(PathBuf, AgeBuckets)>,
+    #[serde(default)]
+    pub inodes: Vec<(PathBuf, u64)>,
+    /// Bytes allocated to the files found in each directory so far
+    #[serde(default)]
+    pub allocated: Vec<(PathBuf, u64)>,
+    /// Unreadable entries found in each directory so far
+    #[serde(default)]
+    pub errors: Vec<(PathBuf, u64)>,
+    /// Directories found to be another path to an already counted directory
+    #[serde(default)]
+    pub aliases: Vec<(PathBuf, PathBuf)>,
+    /// Temp directories found by the walk, sized as a whole afterwards
+    pub temp_dirs: Vec<PathBuf>,
+    /// How many of `temp_dirs` have been sized
+    pub temp_sized: usize,
+}
+
+impl ScanCheckpoint {
+    /// Default location for scans of `root`: `<data dir>/disk-cleanup-tool/scan-checkpoint-<hash>.json`,
+    /// keyed on the canonical root so concurrent scans of different roots keep separate files
+    pub fn default_path(root: &Path) -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("disk-cleanup-tool").join(file_name(root)))
+    }
+
+    /// Load the checkpoint at `path`, which must belong to a scan of `root`
+    pub fn load(path: &Path, root: &Path) -> Result<ScanCheckpoint, CheckpointError> {
+        let content = fs::read(path).map_err(|source| CheckpointError::IoError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let checkpoint: ScanCheckpoint = serde_json::from_slice(&content).map_err(|source| CheckpointError::JsonError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        if utils::canonical_path(&checkpoint.root) != utils::canonical_path(root) {
+            return Err(CheckpointError::RootMismatch {
+                found: checkpoint.root,
+                expected: root.to_path_buf(),
+            });
+        }
+        Ok(checkpoint)
+    }
+
+    /// Write the checkpoint next to `path` and rename it into place, so an interruption
+    /// while saving never leaves a truncated file behind
+    pub fn save(&self, path: &Path) -> Result<(), CheckpointError> {
+        let io_error = |source| CheckpointError::IoError {
+            path: path.to_path_buf(),
+            source,
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let content = serde_json::to_vec(self).map_err(|source| CheckpointError::JsonError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let partial = path.with_extension("json.partial");
+        fs::write(&partial, content).map_err(io_error)?;
+        fs::rename(&partial, path).map_err(io_error)
+    }
+}
+
+/// Hash of the canonical `root`, naming the files kept per root. FNV-1a rather than
+/// `DefaultHasher`, whose output may change between Rust releases and would orphan the
+/// files after an upgrade.
+pub fn root_hash(root: &Path) -> u64 {
+    // Every spelling of a root shares one checkpoint
+    utils::canonical_path(root)
+        .as_os_str()
+        .as_encoded_bytes()
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Checkpoint file name for scans of `root`
+fn file_name(root: &Path) -> String {
+    format!("scan-checkpoint-{:016x}.json", root_hash(root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_checkpoint_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("state").join("scan-checkpoint.json");
+        let checkpoint = ScanCheckpoint {
+            root: PathBuf::from("/data"),
+            position: Some(PathBuf::from("/data/b")),
+            dirs: vec![
+                (PathBuf::from("/data"), 1, 10, EntryType::Normal),
+                (PathBuf::from("/data/a/node_modules"), 5, 500, EntryType::DependencyCache),
+            ],
+            labels: vec![],
+            modified: vec![(PathBuf::from("/data"), SystemTime::UNIX_EPOCH)],
+            owners: vec![(PathBuf::from("/data"), vec![OwnerUsage { uid: 1000, gid: 1000, files: 1, bytes: 10 }])],
+            ages: vec![(PathBuf::from("/data"), AgeBuckets([0, 0, 10, 0]))],
+            inodes: vec![(PathBuf::from("/data"), 1)],
+            errors: vec![(PathBuf::from("/data/b"), 2)],
+            allocated: vec![(PathBuf::from("/data"), 4096)],
+            aliases: vec![(PathBuf::from("/data/mnt"), PathBuf::from("/data/a"))],
+            temp_dirs: vec![PathBuf::from("/data/a/node_modules")],
+            temp_sized: 0,
+        };
+
+        checkpoint.save(&path).unwrap();
+        assert!(!path.with_extension("json.partial").exists());
+        assert_eq!(ScanCheckpoint::load(&path, Path::new("/data")).unwrap(), checkpoint);
+
+        let result = ScanCheckpoint::load(&path, Path::new("/elsewhere"));
+        assert!(matches!(result, Err(CheckpointError::RootMismatch { .. })));
+        let result = ScanCheckpoint::load(&temp_dir.path().join("missing.json"), Path::new("/data"));
+        assert!(matches!(result, Err(CheckpointError::IoError { .. })));
+    }
+
+    #[test]
+    fn test_checkpoint_per_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let (a, b) = (temp_dir.path().join("a"), temp_dir.path().join("b"));
+        fs::create_dir_all(&a).unwrap();
+        fs::create_dir_all(&b).unwrap();
+
+        // Different roots get different files; spellings of one root share a file
+        assert_ne!(file_name(&a), file_name(&b));
+        assert_eq!(file_name(&a), file_name(&b.join("..").join("a")));
+        assert!(file_name(&a).starts_with("scan-checkpoint-") && file_name(&a).ends_with(".json"));
+
+        let path_a = temp_dir.path().join(file_name(&a));
+        let path_b = temp_dir.path().join(file_name(&b));
+        ScanCheckpoint { root: a.clone(), ..Default::default() }.save(&path_a).unwrap();
+        ScanCheckpoint { root: b.clone(), ..Default::default() }.save(&path_b).unwrap();
+        assert_eq!(ScanCheckpoint::load(&path_a, &a).unwrap().root, a);
+        assert_eq!(ScanCheckpoint::load(&path_b, &b.join("..").join("b")).unwrap().root, b);
+
+        // A checkpoint copied to the wrong root's file is still refused on resume
+        fs::copy(&path_a, &path_b).unwrap();
+        assert!(matches!(ScanCheckpoint::load(&path_b, &b), Err(CheckpointError::RootMismatch { .. })));
+    }
+}
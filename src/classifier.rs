@@ -0,0 +1,967 @@
+use crate::config::PathRule;
+use crate::i18n::t;
+use crate::overrides::Overrides;
+use crate::scanner::EntryType;
+use crate::utils::{
+    classify_directory, classify_directory_ignore_case, BUILTIN_PATH_RULES, JETBRAINS_CACHE_ROOTS, JETBRAINS_PRODUCTS,
+    TEMP_DIRECTORY_GLOBS, VSCODE_CONFIG_ROOTS, VSCODE_PRODUCTS,
+};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use thiserror::Error;
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+#[derive(Debug, Error)]
+pub enum ClassifierError {
+    #[error("Invalid pattern '{pattern}': {message}")]
+    InvalidPattern { pattern: String, message: String },
+}
+
+/// How directory names and paths are compared with the rules, from the `[matching]` table
+/// of the config. Defaults follow the platform's usual filesystem: exact on Linux, ignoring
+/// case on macOS and Windows, and normalizing Unicode on macOS (which stores names
+/// decomposed, so `café` typed in a rule wouldn't match `café` on disk).
+///
+/// ```toml
+/// [matching]
+/// ignore_case = true
+/// normalize_unicode = true
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MatchOptions {
+    /// `Build`, `TEMP` and `Node_Modules` match `build`, `temp` and `node_modules`
+    pub ignore_case: bool,
+    /// Compare names and patterns in Unicode normalization form C
+    pub normalize_unicode: bool,
+}
+
+// Only derivable on platforms where both defaults are false
+#[allow(clippy::derivable_impls)]
+impl Default for MatchOptions {
+    fn default() -> Self {
+        Self {
+            ignore_case: cfg!(any(target_os = "macos", target_os = "windows")),
+            normalize_unicode: cfg!(target_os = "macos"),
+        }
+    }
+}
+
+impl MatchOptions {
+    /// Exact, byte-for-byte matching
+    #[cfg(test)]
+    pub fn exact() -> Self {
+        Self {
+            ignore_case: false,
+            normalize_unicode: false,
+        }
+    }
+
+    /// `text` in normalization form C when normalizing; unchanged otherwise
+    pub(crate) fn normalize<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.normalize_unicode && !is_nfc(text) {
+            Cow::Owned(text.nfc().collect())
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+
+    fn normalize_path<'a>(&self, path: &'a Path) -> Cow<'a, Path> {
+        match path.to_str().map(|text| self.normalize(text)) {
+            Some(Cow::Owned(text)) => Cow::Owned(PathBuf::from(text)),
+            _ => Cow::Borrowed(path),
+        }
+    }
+}
+
+/// Directory classifier combining the built-in temp names and glob patterns with
+/// per-run additions and exclusions. All patterns are compiled once on construction.
+#[derive(Debug, Clone)]
+pub struct Classifier {
+    builtin_globs: GlobSet,
+    builtin_glob_types: Vec<EntryType>,
+    builtin_path_rules: PathRuleSet,
+    extra: GlobSet,
+    extra_patterns: Vec<String>,
+    keep: GlobSet,
+    keep_patterns: Vec<String>,
+    path_rules: PathRuleSet,
+    backends: Vec<Arc<dyn ClassifierBackend>>,
+    overrides: Overrides,
+    options: MatchOptions,
+}
+
+/// An additional source of path rules, consulted after the config's `[[rules]]` and before
+/// the built-in rules. Rule files (see `rule_files`) are the shipped implementation; a
+/// backend that doesn't recognize a directory returns `None` and the next one is asked.
+pub trait ClassifierBackend: fmt::Debug + Send + Sync {
+    /// Shown by `explain` as where the matching rule came from
+    fn name(&self) -> &str;
+
+    /// The rule deciding the directory at `path`, already normalized as the classifier's
+    /// match options say; a `Normal` category pins the directory as normal
+    fn find_rule(&self, path: &Path) -> Option<&PathRule>;
+
+    /// The same backend, with its patterns compiled for `options`
+    fn with_match_options(&self, options: MatchOptions) -> Result<Arc<dyn ClassifierBackend>, ClassifierError>;
+}
+
+/// A named set of path rules, such as one loaded from a rule file
+#[derive(Debug, Clone)]
+pub struct RuleSetBackend {
+    name: String,
+    rules: PathRuleSet,
+}
+
+impl RuleSetBackend {
+    pub fn new(name: String, rules: &[PathRule], options: MatchOptions) -> Result<Self, ClassifierError> {
+        Ok(Self {
+            name,
+            rules: PathRuleSet::build(rules, options)?,
+        })
+    }
+}
+
+impl ClassifierBackend for RuleSetBackend {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn find_rule(&self, path: &Path) -> Option<&PathRule> {
+        self.rules.find(path)
+    }
+
+    fn with_match_options(&self, options: MatchOptions) -> Result<Arc<dyn ClassifierBackend>, ClassifierError> {
+        Ok(Arc::new(RuleSetBackend::new(self.name.clone(), &self.rules.rules, options)?))
+    }
+}
+
+/// The rule that decided how a directory is classified
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchReason {
+    /// Set for this exact path in interactive mode
+    Override,
+    /// Excluded with `--keep`
+    Keep { pattern: String },
+    /// `[[rules]]` entry from the config file
+    ConfigRule(PathRule),
+    /// Rule of a classifier backend, such as a rule file
+    Backend { backend: String, rule: PathRule },
+    /// Built-in location-based rule
+    BuiltinPathRule(PathRule),
+    /// Built-in exact directory name
+    BuiltinName(String),
+    /// Built-in name glob such as `*.egg-info`
+    BuiltinGlob(String),
+    /// Added with `--temp-dir`
+    Extra { pattern: String },
+    /// No rule matched
+    NoMatch,
+}
+
+impl fmt::Display for MatchReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let describe_rule = |f: &mut fmt::Formatter<'_>, rule_text: String, rule: &PathRule| {
+            write!(f, "{}", rule_text)?;
+            if let Some(sibling) = &rule.sibling {
+                write!(f, " {}", t!("reason-sibling", sibling = sibling.as_str()))?;
+            }
+            Ok(())
+        };
+
+        match self {
+            MatchReason::Override => write!(f, "{}", t!("reason-override")),
+            MatchReason::Keep { pattern } => write!(f, "--keep '{}'", pattern),
+            MatchReason::ConfigRule(rule) => {
+                describe_rule(f, t!("reason-config-rule", pattern = rule.path_pattern.as_str()), rule)
+            }
+            MatchReason::Backend { backend, rule } => describe_rule(
+                f,
+                t!("reason-backend-rule", pattern = rule.path_pattern.as_str(), backend = backend.as_str()),
+                rule,
+            ),
+            MatchReason::BuiltinPathRule(rule) => {
+                describe_rule(f, t!("reason-builtin-rule", pattern = rule.path_pattern.as_str()), rule)
+            }
+            MatchReason::BuiltinName(name) => write!(f, "{}", t!("reason-builtin-name", name = name.as_str())),
+            MatchReason::BuiltinGlob(pattern) => {
+                write!(f, "{}", t!("reason-builtin-glob", pattern = pattern.as_str()))
+            }
+            MatchReason::Extra { pattern } => write!(f, "--temp-dir '{}'", pattern),
+            MatchReason::NoMatch => write!(f, "{}", t!("reason-no-match")),
+        }
+    }
+}
+
+/// Why a directory was (or wasn't) flagged, as reported by `explain`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub path: PathBuf,
+    pub entry_type: EntryType,
+    pub label: Option<String>,
+    pub reason: MatchReason,
+}
+
+/// Compiled path rules, matched in declaration order
+#[derive(Debug, Clone)]
+struct PathRuleSet {
+    globs: GlobSet,
+    rules: Vec<PathRule>,
+}
+
+impl PathRuleSet {
+    fn build(rules: &[PathRule], options: MatchOptions) -> Result<Self, ClassifierError> {
+        let mut builder = GlobSetBuilder::new();
+        for rule in rules {
+            let pattern = expand_path_pattern(&rule.path_pattern);
+            let glob = GlobBuilder::new(&options.normalize(&pattern))
+                .literal_separator(true)
+                .case_insensitive(options.ignore_case)
+                .build()
+                .map_err(|e| ClassifierError::InvalidPattern {
+                    pattern: rule.path_pattern.clone(),
+                    message: e.kind().to_string(),
+                })?;
+            builder.add(glob);
+        }
+        let globs = builder.build().map_err(|e| ClassifierError::InvalidPattern {
+            pattern: rules.iter().map(|r| r.path_pattern.as_str()).collect::<Vec<_>>().join(", "),
+            message: e.to_string(),
+        })?;
+
+        Ok(Self {
+            globs,
+            rules: rules.to_vec(),
+        })
+    }
+
+    /// First matching rule; a `Normal` category means the rule pins the directory as normal.
+    /// `path` must already be normalized for matching.
+    fn find(&self, path: &Path) -> Option<&PathRule> {
+        for idx in self.globs.matches(path) {
+            let rule = &self.rules[idx];
+            let sibling_present = match (&rule.sibling, path.parent()) {
+                (None, _) => true,
+                (Some(sibling), Some(parent)) => parent.join(sibling).exists(),
+                (Some(_), None) => false,
+            };
+            if sibling_present {
+                return Some(rule);
+            }
+        }
+        None
+    }
+}
+
+impl Classifier {
+    /// `extra` names are classified as temp, `keep` names are never classified as temp.
+    /// Both accept exact names or glob patterns such as `cmake-build-*`.
+    pub fn new(extra: Vec<String>, keep: Vec<String>) -> Result<Self, ClassifierError> {
+        let options = MatchOptions::default();
+        let builtin_patterns: Vec<&str> = TEMP_DIRECTORY_GLOBS.iter().map(|(p, _)| *p).collect();
+
+        Ok(Self {
+            builtin_globs: build_glob_set(&builtin_patterns, options)?,
+            builtin_glob_types: TEMP_DIRECTORY_GLOBS.iter().map(|(_, t)| *t).collect(),
+            builtin_path_rules: PathRuleSet::build(&builtin_path_rules(), options)?,
+            extra: build_glob_set(&extra, options)?,
+            extra_patterns: extra,
+            keep: build_glob_set(&keep, options)?,
+            keep_patterns: keep,
+            path_rules: PathRuleSet::build(&[], options)?,
+            backends: Vec::new(),
+            overrides: Overrides::default(),
+            options,
+        })
+    }
+
+    /// Add path-based rules from the config; these are evaluated before the built-in rules
+    pub fn with_path_rules(mut self, rules: &[PathRule]) -> Result<Self, ClassifierError> {
+        self.path_rules = PathRuleSet::build(rules, self.options)?;
+        Ok(self)
+    }
+
+    /// Consult `backend` after the config's path rules and the backends added before it
+    pub fn with_backend(mut self, backend: Arc<dyn ClassifierBackend>) -> Result<Self, ClassifierError> {
+        self.backends.push(backend.with_match_options(self.options)?);
+        Ok(self)
+    }
+
+    /// Compare names and paths with the rules according to `options`; recompiles every pattern
+    pub fn with_match_options(mut self, options: MatchOptions) -> Result<Self, ClassifierError> {
+        if options == self.options {
+            return Ok(self);
+        }
+        let builtin_patterns: Vec<&str> = TEMP_DIRECTORY_GLOBS.iter().map(|(p, _)| *p).collect();
+        self.builtin_globs = build_glob_set(&builtin_patterns, options)?;
+        self.builtin_path_rules = PathRuleSet::build(&self.builtin_path_rules.rules, options)?;
+        self.extra = build_glob_set(&self.extra_patterns, options)?;
+        self.keep = build_glob_set(&self.keep_patterns, options)?;
+        self.path_rules = PathRuleSet::build(&self.path_rules.rules, options)?;
+        self.backends = self
+            .backends
+            .iter()
+            .map(|backend| backend.with_match_options(options))
+            .collect::<Result<_, _>>()?;
+        self.options = options;
+        Ok(self)
+    }
+
+    /// Per-path overrides; these take priority over every other rule
+    pub fn with_overrides(mut self, overrides: Overrides) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    pub fn overrides(&self) -> &Overrides {
+        &self.overrides
+    }
+
+    pub fn set_override(&mut self, path: PathBuf, entry_type: EntryType) {
+        self.overrides.set(path, entry_type);
+    }
+
+    /// Classify a directory by its absolute path: per-path overrides and `--keep` first,
+    /// then configured path rules, backends such as rule files, built-in path rules, and
+    /// finally the name-based rules
+    pub fn classify_path(&self, path: &Path) -> Option<EntryType> {
+        if let Some(entry_type) = self.overrides.get(path) {
+            return Some(entry_type).filter(|t| t.is_temp());
+        }
+        let name = path.file_name()?.to_string_lossy();
+        if self.keep.is_match(self.options.normalize(&name).as_ref()) {
+            return None;
+        }
+
+        if let Some(rule) = self.find_path_rule(path) {
+            return if rule.category.is_temp() { Some(rule.category) } else { None };
+        }
+
+        self.classify(&name)
+    }
+
+    /// Label of the path rule that classifies this directory, if it has one
+    pub fn label_path(&self, path: &Path) -> Option<String> {
+        if self.overrides.get(path).is_some() {
+            return None;
+        }
+        self.find_path_rule(path).and_then(|rule| rule.label.clone())
+    }
+
+    /// Classify a directory like `classify_path` and report which rule decided it
+    pub fn explain(&self, path: &Path) -> Explanation {
+        let (entry_type, label, reason) = self.explain_match(path);
+        Explanation {
+            path: path.to_path_buf(),
+            entry_type: entry_type.unwrap_or_default(),
+            label,
+            reason,
+        }
+    }
+
+    fn explain_match(&self, path: &Path) -> (Option<EntryType>, Option<String>, MatchReason) {
+        if let Some(entry_type) = self.overrides.get(path) {
+            return (Some(entry_type).filter(|t| t.is_temp()), None, MatchReason::Override);
+        }
+        let Some(name) = path.file_name().map(|n| n.to_string_lossy()) else {
+            return (None, None, MatchReason::NoMatch);
+        };
+        let name = self.options.normalize(&name).into_owned();
+        if let Some(&idx) = self.keep.matches(&name).first() {
+            let pattern = self.keep_patterns[idx].clone();
+            return (None, None, MatchReason::Keep { pattern });
+        }
+
+        let path = self.options.normalize_path(path);
+        let rule_match = self
+            .path_rules
+            .find(&path)
+            .map(|rule| (rule, MatchReason::ConfigRule(rule.clone())))
+            .or_else(|| {
+                self.backends.iter().find_map(|backend| {
+                    backend.find_rule(&path).map(|rule| {
+                        let backend = backend.name().to_string();
+                        (rule, MatchReason::Backend { backend, rule: rule.clone() })
+                    })
+                })
+            })
+            .or_else(|| {
+                self.builtin_path_rules
+                    .find(&path)
+                    .map(|rule| (rule, MatchReason::BuiltinPathRule(rule.clone())))
+            });
+        if let Some((rule, reason)) = rule_match {
+            let entry_type = Some(rule.category).filter(|c| c.is_temp());
+            return (entry_type, rule.label.clone(), reason);
+        }
+
+        if let Some(entry_type) = self.classify_name(&name) {
+            return (Some(entry_type), None, MatchReason::BuiltinName(name));
+        }
+        if let Some(&idx) = self.builtin_globs.matches(&name).first() {
+            let pattern = TEMP_DIRECTORY_GLOBS[idx].0.to_string();
+            return (Some(self.builtin_glob_types[idx]), None, MatchReason::BuiltinGlob(pattern));
+        }
+        if let Some(&idx) = self.extra.matches(&name).first() {
+            let pattern = self.extra_patterns[idx].clone();
+            return (Some(EntryType::Temp), None, MatchReason::Extra { pattern });
+        }
+        (None, None, MatchReason::NoMatch)
+    }
+
+    fn find_path_rule(&self, path: &Path) -> Option<&PathRule> {
+        let path = self.options.normalize_path(path);
+        self.path_rules
+            .find(&path)
+            .or_else(|| self.backends.iter().find_map(|backend| backend.find_rule(&path)))
+            .or_else(|| self.builtin_path_rules.find(&path))
+    }
+
+    /// Built-in exact names; `name` must already be normalized
+    fn classify_name(&self, name: &str) -> Option<EntryType> {
+        if self.options.ignore_case {
+            classify_directory_ignore_case(name)
+        } else {
+            classify_directory(name)
+        }
+    }
+
+    /// Classify a directory by name, returning `None` for normal directories
+    pub fn classify(&self, name: &str) -> Option<EntryType> {
+        let name = self.options.normalize(name);
+        let name = name.as_ref();
+        if self.keep.is_match(name) {
+            return None;
+        }
+        if let Some(entry_type) = self.classify_name(name) {
+            return Some(entry_type);
+        }
+        if let Some(&idx) = self.builtin_globs.matches(name).first() {
+            return Some(self.builtin_glob_types[idx]);
+        }
+        if self.extra.is_match(name) {
+            return Some(EntryType::Temp);
+        }
+        None
+    }
+
+    /// Whether any per-run additions, exclusions, path rules, backends or per-path overrides
+    /// are configured
+    pub fn has_overrides(&self) -> bool {
+        !self.extra.is_empty()
+            || !self.keep.is_empty()
+            || !self.path_rules.globs.is_empty()
+            || !self.backends.is_empty()
+            || !self.overrides.is_empty()
+    }
+}
+
+impl Default for Classifier {
+    fn default() -> Self {
+        Classifier::new(Vec::new(), Vec::new()).expect("built-in patterns are valid")
+    }
+}
+
+/// Built-in path rules, plus the per-product IDE caches and the module cache of every
+/// `$GOPATH` entry when it is set
+fn builtin_path_rules() -> Vec<PathRule> {
+    let mut rules: Vec<PathRule> = BUILTIN_PATH_RULES
+        .iter()
+        .map(|rule| PathRule {
+            path_pattern: rule.pattern.to_string(),
+            category: rule.category,
+            sibling: rule.sibling.map(|s| s.to_string()),
+            label: rule.label.map(|s| s.to_string()),
+        })
+        .collect();
+
+    let generated = |path_pattern: String, category: EntryType, label: String| PathRule {
+        path_pattern,
+        category,
+        sibling: None,
+        label: Some(label),
+    };
+    for (prefix, product) in JETBRAINS_PRODUCTS {
+        let label = format!("{} caches", product);
+        for root in JETBRAINS_CACHE_ROOTS {
+            rules.push(generated(format!("{}/{}*", root, prefix), EntryType::Temp, label.clone()));
+        }
+        // Before 2020.1 everything lived in `~/.PyCharm2019.3`, with the caches in `system`
+        rules.push(generated(format!("~/.{}*/system", prefix), EntryType::Temp, label));
+    }
+    for (dir, product) in VSCODE_PRODUCTS {
+        for root in VSCODE_CONFIG_ROOTS {
+            let label = format!("{} workspace storage", product);
+            rules.push(generated(format!("{}/{}/User/workspaceStorage", root, dir), EntryType::IdeMetadata, label));
+            let label = format!("{} extension downloads", product);
+            rules.push(generated(format!("{}/{}/CachedExtensionVSIXs", root, dir), EntryType::AppCache, label));
+        }
+    }
+
+    if let Some(gopath) = std::env::var_os("GOPATH") {
+        for dir in std::env::split_paths(&gopath) {
+            if dir.is_absolute() {
+                rules.push(PathRule {
+                    path_pattern: escape_glob(&dir.join("pkg").join("mod").to_string_lossy()),
+                    category: EntryType::DependencyCache,
+                    sibling: None,
+                    label: Some("Go module cache".to_string()),
+                });
+            }
+        }
+    }
+
+    rules
+}
+
+/// Escape glob metacharacters so a literal path can be used as a pattern
+fn escape_glob(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '{' | '}') {
+            escaped.push('[');
+            escaped.push(c);
+            escaped.push(']');
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// Expand `~/` to the home directory and anchor relative patterns at any depth
+fn expand_path_pattern(pattern: &str) -> String {
+    if let Some(rest) = pattern.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return format!("{}/{}", home.to_string_lossy().trim_end_matches('/'), rest);
+        }
+    }
+    if pattern.starts_with('/') || pattern.starts_with("**") {
+        pattern.to_string()
+    } else {
+        format!("**/{}", pattern)
+    }
+}
+
+/// Compile directory name patterns, matched as `options` says
+pub(crate) fn build_glob_set<S: AsRef<str>>(patterns: &[S], options: MatchOptions) -> Result<GlobSet, ClassifierError> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = pattern.as_ref();
+        let glob = GlobBuilder::new(&options.normalize(pattern))
+            .case_insensitive(options.ignore_case)
+            .build()
+            .map_err(|e| ClassifierError::InvalidPattern {
+            pattern: pattern.to_string(),
+            message: e.kind().to_string(),
+        })?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|e| ClassifierError::InvalidPattern {
+        pattern: patterns.iter().map(|p| p.as_ref()).collect::<Vec<_>>().join(", "),
+        message: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_matches_builtin() {
+        let classifier = Classifier::default();
+        assert_eq!(classifier.classify("node_modules"), Some(EntryType::DependencyCache));
+        assert_eq!(classifier.classify("src"), None);
+        assert!(!classifier.has_overrides());
+    }
+
+    #[test]
+    fn test_builtin_globs() {
+        let classifier = Classifier::default();
+        assert_eq!(classifier.classify("mypkg.egg-info"), Some(EntryType::BuildOutput));
+        assert_eq!(classifier.classify("App.app.dSYM"), Some(EntryType::BuildOutput));
+        assert_eq!(classifier.classify("cmake-build-debug"), Some(EntryType::BuildOutput));
+        assert_eq!(classifier.classify("egg-info"), None);
+        assert_eq!(classifier.classify("cmake-build"), None);
+    }
+
+    #[test]
+    fn test_extra_and_keep() {
+        let classifier = Classifier::new(
+            vec!["generated".to_string()],
+            vec!["dist".to_string()],
+        )
+        .unwrap();
+        assert_eq!(classifier.classify("generated"), Some(EntryType::Temp));
+        assert_eq!(classifier.classify("dist"), None);
+        assert!(classifier.classify("target").is_some());
+        assert!(classifier.has_overrides());
+    }
+
+    #[test]
+    fn test_glob_overrides() {
+        let classifier = Classifier::new(
+            vec!["*.tmpdir".to_string()],
+            vec!["cmake-build-*".to_string()],
+        )
+        .unwrap();
+        assert!(classifier.classify("scratch.tmpdir").is_some());
+        assert!(classifier.classify("cmake-build-release").is_none());
+    }
+
+    #[test]
+    fn test_match_options() {
+        let exact = Classifier::new(vec!["Generated".to_string()], vec![])
+            .and_then(|c| c.with_match_options(MatchOptions::exact()))
+            .unwrap();
+        assert_eq!(exact.classify("Node_Modules"), None);
+        assert_eq!(exact.classify("generated"), None);
+
+        let ignore_case = MatchOptions {
+            ignore_case: true,
+            ..MatchOptions::exact()
+        };
+        let classifier = Classifier::new(vec!["Generated".to_string()], vec!["BUILD".to_string()])
+            .and_then(|c| c.with_path_rules(&[rule("/srv/**/Artifacts", EntryType::BuildOutput, None)]))
+            .and_then(|c| c.with_match_options(ignore_case))
+            .unwrap();
+        assert_eq!(classifier.classify("Node_Modules"), Some(EntryType::DependencyCache));
+        assert_eq!(classifier.classify("TEMP"), Some(EntryType::Temp));
+        assert_eq!(classifier.classify("CMake-Build-Debug"), Some(EntryType::BuildOutput));
+        assert_eq!(classifier.classify("generated"), Some(EntryType::Temp));
+        assert_eq!(classifier.classify("build"), None);
+        assert_eq!(classifier.classify_path(Path::new("/srv/app/artifacts")), Some(EntryType::BuildOutput));
+
+        // "café" typed in a rule (composed) matches a decomposed name on disk
+        let decomposed = "cafe\u{301}";
+        let classifier = Classifier::new(vec!["caf\u{e9}".to_string()], vec![]).unwrap();
+        let exact = classifier.clone().with_match_options(MatchOptions::exact()).unwrap();
+        assert_eq!(exact.classify(decomposed), None);
+        let normalize = MatchOptions {
+            normalize_unicode: true,
+            ..MatchOptions::exact()
+        };
+        let normalized = classifier.with_match_options(normalize).unwrap();
+        assert_eq!(normalized.classify(decomposed), Some(EntryType::Temp));
+        assert_eq!(normalized.explain(&Path::new("/p").join(decomposed)).entry_type, EntryType::Temp);
+    }
+
+    #[test]
+    fn test_keep_wins_over_extra() {
+        let classifier = Classifier::new(vec!["out".to_string()], vec!["out".to_string()]).unwrap();
+        assert!(classifier.classify("out").is_none());
+    }
+
+    fn rule(path_pattern: &str, category: EntryType, sibling: Option<&str>) -> PathRule {
+        PathRule {
+            path_pattern: path_pattern.to_string(),
+            category,
+            sibling: sibling.map(|s| s.to_string()),
+            label: None,
+        }
+    }
+
+    #[test]
+    fn test_path_rules() {
+        let classifier = Classifier::default()
+            .with_path_rules(&[
+                rule("/data/Library/Caches/*", EntryType::OsCache, None),
+                rule("photos/build", EntryType::Normal, None),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            classifier.classify_path(Path::new("/data/Library/Caches/com.apple.Safari")),
+            Some(EntryType::OsCache)
+        );
+        // `*` does not cross directory boundaries
+        assert_eq!(classifier.classify_path(Path::new("/data/Library/Caches/a/b")), None);
+        // Relative patterns match at any depth and `normal` overrides the name rule
+        assert_eq!(classifier.classify_path(Path::new("/home/me/photos/build")), None);
+        assert_eq!(
+            classifier.classify_path(Path::new("/home/me/app/build")),
+            Some(EntryType::BuildOutput)
+        );
+    }
+
+    #[test]
+    fn test_path_rule_sibling() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let ios = temp_dir.path().join("ios");
+        let other = temp_dir.path().join("other");
+        std::fs::create_dir_all(ios.join("Pods")).unwrap();
+        std::fs::create_dir_all(other.join("Pods")).unwrap();
+        std::fs::write(ios.join("Podfile"), "").unwrap();
+
+        let classifier = Classifier::default()
+            .with_path_rules(&[rule("**/Pods", EntryType::DependencyCache, Some("Podfile"))])
+            .unwrap();
+
+        assert_eq!(classifier.classify_path(&ios.join("Pods")), Some(EntryType::DependencyCache));
+        assert_eq!(classifier.classify_path(&other.join("Pods")), None);
+    }
+
+    #[test]
+    fn test_explain() {
+        let classifier = Classifier::new(vec!["gen*".to_string()], vec!["dist".to_string()])
+            .unwrap()
+            .with_path_rules(&[rule("photos/build", EntryType::Normal, None)])
+            .unwrap();
+
+        let explanation = classifier.explain(Path::new("/home/me/app/node_modules"));
+        assert_eq!(explanation.entry_type, EntryType::DependencyCache);
+        assert_eq!(explanation.reason, MatchReason::BuiltinName("node_modules".to_string()));
+
+        let explanation = classifier.explain(Path::new("/home/me/app/mypkg.egg-info"));
+        assert_eq!(explanation.reason, MatchReason::BuiltinGlob("*.egg-info".to_string()));
+
+        let explanation = classifier.explain(Path::new("/home/me/app/generated"));
+        assert_eq!(explanation.entry_type, EntryType::Temp);
+        assert_eq!(explanation.reason, MatchReason::Extra { pattern: "gen*".to_string() });
+
+        let explanation = classifier.explain(Path::new("/home/me/app/dist"));
+        assert_eq!(explanation.entry_type, EntryType::Normal);
+        assert_eq!(explanation.reason, MatchReason::Keep { pattern: "dist".to_string() });
+
+        let explanation = classifier.explain(Path::new("/home/me/photos/build"));
+        assert_eq!(explanation.entry_type, EntryType::Normal);
+        assert!(matches!(explanation.reason, MatchReason::ConfigRule(_)));
+        assert_eq!(explanation.reason.to_string(), "config path rule 'photos/build'");
+
+        let explanation = classifier.explain(Path::new("/home/me/app/src"));
+        assert_eq!(explanation.reason, MatchReason::NoMatch);
+    }
+
+    #[test]
+    fn test_explain_project_context() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let game = temp_dir.path().join("game");
+        std::fs::create_dir_all(game.join("Library")).unwrap();
+        std::fs::create_dir_all(game.join("ProjectSettings")).unwrap();
+
+        let explanation = Classifier::default().explain(&game.join("Library"));
+        assert_eq!(explanation.entry_type, EntryType::BuildOutput);
+        assert_eq!(explanation.label.as_deref(), Some("Unity Library"));
+        assert!(matches!(explanation.reason, MatchReason::BuiltinPathRule(_)));
+        assert!(explanation.reason.to_string().contains("'ProjectSettings' found next to it"));
+    }
+
+    #[test]
+    fn test_per_path_overrides() {
+        let mut overrides = Overrides::default();
+        overrides.set(PathBuf::from("/home/me/photos/build"), EntryType::Normal);
+        overrides.set(PathBuf::from("/home/me/scratch"), EntryType::Temp);
+        let mut classifier = Classifier::default().with_overrides(overrides);
+
+        assert!(classifier.has_overrides());
+        assert_eq!(classifier.classify_path(Path::new("/home/me/photos/build")), None);
+        assert_eq!(classifier.classify_path(Path::new("/home/me/scratch")), Some(EntryType::Temp));
+        // Only the exact path is affected
+        assert_eq!(
+            classifier.classify_path(Path::new("/home/me/app/build")),
+            Some(EntryType::BuildOutput)
+        );
+        assert_eq!(classifier.explain(Path::new("/home/me/photos/build")).reason, MatchReason::Override);
+
+        classifier.set_override(PathBuf::from("/home/me/photos/build"), EntryType::ModelCache);
+        assert_eq!(
+            classifier.classify_path(Path::new("/home/me/photos/build")),
+            Some(EntryType::ModelCache)
+        );
+        assert_eq!(classifier.overrides().paths.len(), 2);
+    }
+
+    #[test]
+    fn test_expand_path_pattern() {
+        assert_eq!(expand_path_pattern("/abs/*"), "/abs/*");
+        assert_eq!(expand_path_pattern("**/Pods"), "**/Pods");
+        assert_eq!(expand_path_pattern("Library/Caches/*"), "**/Library/Caches/*");
+        if let Some(home) = dirs::home_dir() {
+            assert!(expand_path_pattern("~/.cache/**").starts_with(home.to_string_lossy().as_ref()));
+        }
+    }
+
+    #[test]
+    fn test_builtin_path_rules() {
+        let classifier = Classifier::default();
+
+        assert_eq!(
+            classifier.classify_path(Path::new("/opt/miniconda3/pkgs")),
+            Some(EntryType::DependencyCache)
+        );
+        assert_eq!(
+            classifier.classify_path(Path::new("/home/me/anaconda3/envs/torch")),
+            Some(EntryType::VirtualEnv)
+        );
+        assert_eq!(classifier.classify_path(Path::new("/home/me/code/pkgs")), None);
+
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(
+                classifier.classify_path(&home.join("go/pkg/mod")),
+                Some(EntryType::DependencyCache)
+            );
+            assert_eq!(
+                classifier.classify_path(&home.join(".cache/go-build")),
+                Some(EntryType::BuildOutput)
+            );
+        }
+    }
+
+    #[test]
+    fn test_ml_cache_rules() {
+        let Some(home) = dirs::home_dir() else { return };
+        let classifier = Classifier::default();
+
+        // Container directories stay normal so their children are listed individually
+        assert_eq!(classifier.classify_path(&home.join(".cache")), None);
+        assert_eq!(classifier.classify_path(&home.join(".cache/huggingface")), None);
+        assert_eq!(classifier.classify_path(&home.join(".cache/huggingface/hub")), None);
+        assert_eq!(
+            classifier.classify_path(&home.join(".cache/huggingface/hub/models--bert-base-uncased")),
+            Some(EntryType::ModelCache)
+        );
+        assert_eq!(
+            classifier.classify_path(&home.join(".cache/huggingface/datasets")),
+            Some(EntryType::ModelCache)
+        );
+        assert_eq!(classifier.classify_path(&home.join(".cache/torch")), Some(EntryType::ModelCache));
+        assert_eq!(classifier.classify_path(&home.join(".keras")), Some(EntryType::ModelCache));
+        assert_eq!(
+            classifier.classify_path(&home.join("tensorflow_datasets")),
+            Some(EntryType::ModelCache)
+        );
+        assert_eq!(
+            classifier.classify_path(&home.join(".cache/pip")),
+            Some(EntryType::DependencyCache)
+        );
+        assert_eq!(classifier.classify_path(&home.join(".cache/fontconfig")), Some(EntryType::Temp));
+        // `.cache` elsewhere is still a temp directory by name
+        assert_eq!(
+            classifier.classify_path(Path::new("/srv/app/.cache")),
+            Some(EntryType::Temp)
+        );
+    }
+
+    #[test]
+    fn test_app_cache_rules() {
+        let Some(home) = dirs::home_dir() else { return };
+        let classifier = Classifier::default();
+        let classify = |path: &str| classifier.classify_path(&home.join(path));
+
+        assert_eq!(classify(".cache/mozilla"), Some(EntryType::AppCache));
+        assert_eq!(classify(".cache/google-chrome"), Some(EntryType::AppCache));
+        assert_eq!(classify("Library/Caches/com.apple.Safari"), Some(EntryType::AppCache));
+        assert_eq!(classify("Library/Caches/Google/Chrome"), Some(EntryType::AppCache));
+        assert_eq!(classify(".config/google-chrome/Default/Cache"), Some(EntryType::AppCache));
+        assert_eq!(classify(".config/google-chrome/Profile 1/Service Worker/CacheStorage"), Some(EntryType::AppCache));
+        assert_eq!(classify("AppData/Local/Microsoft/Edge/User Data/Default/Code Cache"), Some(EntryType::AppCache));
+        assert_eq!(classify("AppData/Local/Mozilla/Firefox/Profiles/abcd.default-release/cache2"), Some(EntryType::AppCache));
+        assert_eq!(classify(".config/Slack/Cache"), Some(EntryType::AppCache));
+        assert_eq!(classify("Library/Application Support/Code/CachedData"), Some(EntryType::AppCache));
+        assert_eq!(classify("AppData/Roaming/discord/GPUCache"), Some(EntryType::AppCache));
+        // The profiles themselves hold history, logins and extensions
+        assert_eq!(classify(".config/google-chrome"), None);
+        assert_eq!(classify(".config/google-chrome/Default"), None);
+        assert_eq!(classify(".config/Slack"), None);
+        assert_eq!(classify(".config/Code/User"), None);
+    }
+
+    #[test]
+    fn test_ide_cache_rules() {
+        let Some(home) = dirs::home_dir() else { return };
+        let classifier = Classifier::default();
+        let classify = |path: &str| classifier.classify_path(&home.join(path));
+        let label = |path: &str| classifier.label_path(&home.join(path));
+
+        // Every product and version is its own entry
+        assert_eq!(classify(".cache/JetBrains"), None);
+        assert_eq!(classify(".cache/JetBrains/PyCharm2024.1"), Some(EntryType::Temp));
+        assert_eq!(label(".cache/JetBrains/PyCharm2024.1").as_deref(), Some("PyCharm caches"));
+        assert_eq!(label(".cache/JetBrains/PyCharmCE2023.3").as_deref(), Some("PyCharm Community caches"));
+        assert_eq!(label("Library/Caches/JetBrains/IntelliJIdea2024.2").as_deref(), Some("IntelliJ IDEA caches"));
+        assert_eq!(label("AppData/Local/JetBrains/Rider2024.1").as_deref(), Some("Rider caches"));
+        assert_eq!(label(".GoLand2019.3/system").as_deref(), Some("GoLand caches"));
+        assert_eq!(label(".cache/Google/AndroidStudio2024.1").as_deref(), Some("Android Studio caches"));
+        // Toolbox installs the IDEs themselves next to the caches on Windows
+        assert_eq!(classify("AppData/Local/JetBrains/Toolbox"), None);
+        // Settings stay
+        assert_eq!(classify(".config/JetBrains/PyCharm2024.1"), None);
+        assert_eq!(classify(".GoLand2019.3/config"), None);
+
+        assert_eq!(classify(".config/Code/User/workspaceStorage"), Some(EntryType::IdeMetadata));
+        assert_eq!(
+            label("Library/Application Support/Code - Insiders/User/workspaceStorage").as_deref(),
+            Some("VS Code Insiders workspace storage")
+        );
+        assert_eq!(classify("AppData/Roaming/Code/CachedExtensionVSIXs"), Some(EntryType::AppCache));
+        assert_eq!(label(".config/VSCodium/CachedExtensionVSIXs").as_deref(), Some("VSCodium extension downloads"));
+        assert_eq!(classify(".config/Code/User"), None);
+    }
+
+    #[test]
+    fn test_mobile_and_unity_rules() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let unity = temp_dir.path().join("game");
+        let android = temp_dir.path().join("android/app");
+        let ios = temp_dir.path().join("ios");
+        for dir in ["Library", "Temp", "Logs", "ProjectSettings"] {
+            std::fs::create_dir_all(unity.join(dir)).unwrap();
+        }
+        std::fs::create_dir_all(android.join("build")).unwrap();
+        std::fs::write(android.join("build.gradle.kts"), "").unwrap();
+        std::fs::create_dir_all(ios.join("Pods")).unwrap();
+        std::fs::write(ios.join("Podfile"), "").unwrap();
+
+        let classifier = Classifier::default();
+
+        assert_eq!(classifier.classify_path(&unity.join("Library")), Some(EntryType::BuildOutput));
+        assert_eq!(classifier.label_path(&unity.join("Library")).as_deref(), Some("Unity Library"));
+        assert_eq!(classifier.classify_path(&unity.join("Temp")), Some(EntryType::Temp));
+        assert_eq!(classifier.label_path(&unity.join("Logs")).as_deref(), Some("Unity Logs"));
+        // Without ProjectSettings, a Library directory is just a directory
+        assert_eq!(classifier.classify_path(&temp_dir.path().join("Library")), None);
+
+        assert_eq!(classifier.label_path(&android.join("build")).as_deref(), Some("Gradle build"));
+        assert_eq!(classifier.label_path(&ios.join("Pods")).as_deref(), Some("CocoaPods"));
+        assert_eq!(
+            classifier.classify_path(&ios.join("Pods")),
+            Some(EntryType::DependencyCache)
+        );
+        // Plain name matches have no label
+        assert_eq!(classifier.label_path(&temp_dir.path().join("node_modules")), None);
+
+        if let Some(home) = dirs::home_dir() {
+            let derived = home.join("Library/Developer/Xcode/DerivedData");
+            assert_eq!(classifier.classify_path(&derived), Some(EntryType::BuildOutput));
+            assert_eq!(classifier.label_path(&derived).as_deref(), Some("Xcode DerivedData"));
+            assert_eq!(
+                classifier.classify_path(&home.join("Android/Sdk/system-images/android-34")),
+                Some(EntryType::DependencyCache)
+            );
+        }
+    }
+
+    #[test]
+    fn test_config_rules_take_priority_over_builtin() {
+        let classifier = Classifier::default()
+            .with_path_rules(&[rule("**/miniconda3/pkgs", EntryType::Normal, None)])
+            .unwrap();
+        assert_eq!(classifier.classify_path(Path::new("/opt/miniconda3/pkgs")), None);
+    }
+
+    #[test]
+    fn test_escape_glob() {
+        assert_eq!(escape_glob("/home/me/go[1]/pkg/mod"), "/home/me/go[[]1[]]/pkg/mod");
+        assert_eq!(escape_glob("/plain/path"), "/plain/path");
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let result = Classifier::new(vec!["[unclosed".to_string()], Vec::new());
+        assert!(matches!(result, Err(ClassifierError::InvalidPattern { .. })));
+    }
+}
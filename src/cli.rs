@@ -1,22 +1,129 @@
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
+use crate::scan_file::ScanFormat;
+use crate::schedule::{Frequency, ScheduleFormat};
+use crate::utils::{SizeBasis, SizeUnits, SortKey};
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(name = "disk-cleanup-tool")]
 #[command(about = "Analyze and clean up disk space by identifying temporary directories", long_about = None)]
 pub struct CliArgs {
-    /// Directory path to analyze (defaults to current directory)
+    /// Directory path to analyze (defaults to current directory). Repeat to scan several roots,
+    /// which interactive mode shows as tabs
     #[arg(short, long)]
-    pub path: Option<PathBuf>,
+    pub path: Vec<PathBuf>,
 
-    /// Output CSV file path
+    /// Scan every local filesystem, each on its own and without crossing into other mounts
+    /// (pseudo and network filesystems are skipped); results are merged with a mount column
+    #[arg(long, conflicts_with_all = ["path", "bookmark", "resume"])]
+    pub all_mounts: bool,
+
+    /// Windows: scan every fixed drive (C:, D:, ...) on its own; results are merged with a
+    /// drive column, as for --all-mounts
+    #[arg(long, conflicts_with_all = ["path", "bookmark", "resume", "all_mounts"])]
+    pub all_drives: bool,
+
+    /// With --all-mounts, scan the filesystems at the same time instead of one after the
+    /// other (no progress screen); faster when they are on separate disks
+    #[arg(long, requires = "all_mounts")]
+    pub parallel_mounts: bool,
+
+    /// Save the scan to this file (CSV, or JSON/TOML/YAML with a .json, .toml or .yaml extension;
+    /// add .gz or .zst to compress).
+    /// {date}, {time}, {hostname} and {root} in the name are filled in, e.g. "scan-{hostname}-{date}.csv"
     #[arg(short, long)]
     pub output_csv: Option<PathBuf>,
 
-    /// Input CSV file path to load previous analysis
+    /// Write --output-csv and --summary-json in this format whatever the file extension, e.g.
+    /// yaml for Ansible or a k8s ConfigMap; the summary is JSON by default
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub format: Option<ScanFormat>,
+
+    /// Scan a location bookmarked in the config (`[bookmarks.NAME]`) with its default flags;
+    /// without a name, pick one from a list
+    #[arg(long, value_name = "NAME", num_args = 0..=1, conflicts_with = "path")]
+    pub bookmark: Option<Option<String>>,
+
+    /// Run a cleanup profile from the config (`[profiles.NAME]`): scan its paths, select what
+    /// it cleans, then review it interactively or, with --yes or `action = "delete"`, delete it
+    #[arg(long, value_name = "NAME", conflicts_with = "bookmark")]
+    pub profile: Option<String>,
+
+    /// Load a previous scan (CSV, JSON or TOML, optionally .gz/.zst compressed) instead of scanning
     #[arg(short, long)]
     pub input_csv: Option<PathBuf>,
 
+    /// Load an ncdu JSON export (`ncdu -o FILE`, or '-' for stdin) instead of scanning
+    #[arg(long, value_name = "FILE", conflicts_with = "input_csv")]
+    pub import_ncdu: Option<PathBuf>,
+
+    /// Load `du -ab` output or an ncdu JSON export ('-' for stdin) instead of scanning, e.g.
+    /// from a machine this tool isn't installed on
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["input_csv", "import_ncdu"])]
+    pub input_du: Option<PathBuf>,
+
+    /// Also save the scan in ncdu's JSON format, for browsing with `ncdu -f FILE` (placeholders
+    /// as for --output-csv)
+    #[arg(long, value_name = "FILE")]
+    pub export_ncdu: Option<PathBuf>,
+
+    /// Continue an interrupted scan from its last checkpoint (saved every minute while scanning)
+    #[arg(long, conflicts_with_all = ["input_csv", "import_ncdu", "input_du", "session"])]
+    pub resume: bool,
+
+    /// With --input-csv or --session: re-check that each directory still exists and recount its size
+    #[arg(long)]
+    pub refresh: bool,
+
+    /// Write Prometheus gauges (bytes per category, temp bytes, scan duration, run time) for
+    /// node_exporter's textfile collector, e.g. /var/lib/node_exporter/textfile/disk_cleanup.prom
+    #[arg(long, value_name = "FILE")]
+    pub metrics_out: Option<PathBuf>,
+
+    /// At the end of the run, POST the JSON summary (as printed by --summary-json, plus host,
+    /// root and scan time) to this URL
+    #[arg(long, value_name = "URL")]
+    pub post_results: Option<String>,
+
+    /// With --post-results: also send every directory of the scan
+    #[arg(long, requires = "post_results")]
+    pub post_entries: bool,
+
+    /// Extra HTTP header for --post-results, e.g. 'Authorization: Bearer TOKEN' (repeatable;
+    /// also `[post_headers]` in the config file)
+    #[arg(long = "post-header", value_name = "HEADER", value_parser = crate::push::parse_header)]
+    pub post_headers: Vec<(String, String)>,
+
+    /// At the end of the run, mail the summary with the scan attached as CSV to this address
+    /// (repeatable), through the mail server in the `[smtp]` table of the config
+    #[arg(long, value_name = "ADDRESS")]
+    pub email_report: Vec<String>,
+
+    /// Serve a JSON API on this address (e.g. 127.0.0.1:8080) instead of running once: clients
+    /// start scans of --path, fetch the latest results and, when DISK_CLEANUP_SERVE_TOKEN is
+    /// set, delete directories from them with that bearer token
+    #[arg(long, value_name = "ADDR", conflicts_with_all = ["input_csv", "import_ncdu", "input_du", "session", "interactive"])]
+    pub serve: Option<String>,
+
+    /// Resume a saved interactive session ('s' saves it); defaults to a session file in the user data directory
+    #[arg(long, value_name = "FILE", num_args = 0..=1, conflicts_with_all = ["input_csv", "import_ncdu", "input_du"])]
+    pub session: Option<Option<PathBuf>>,
+
+    /// Delete the directories listed in FILE, one path per line (e.g. exported from the command
+    /// palette), after checking them against the protected paths and confirming as usual
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["input_csv", "import_ncdu", "input_du", "session", "serve", "resume", "all_mounts", "all_drives", "interactive"]
+    )]
+    pub delete_from_file: Option<PathBuf>,
+
+    /// Delete the rows marked in the `selected` column of the --input-csv file (`true`, `1`,
+    /// `yes` or `x`, e.g. edited in a spreadsheet), after checking them like --delete-from-file
+    /// and confirming as usual
+    #[arg(long, requires = "input_csv", conflicts_with_all = ["interactive", "du", "serve"])]
+    pub apply_selection: bool,
+
     /// Show only temporary directories (node_modules, .venv, etc.)
     #[arg(short, long)]
     pub temp_only: bool,
@@ -24,6 +131,288 @@ pub struct CliArgs {
     /// Launch interactive mode for selection and deletion
     #[arg(long)]
     pub interactive: bool,
+
+    /// Start interactive mode in the two-pane view: directory tree on the left, the highlighted
+    /// directory's details and contents on the right ('v' switches views)
+    #[arg(long)]
+    pub two_pane: bool,
+
+    /// Don't watch the scanned directories while interactive mode is open; by default,
+    /// directories that change (a build, a download) are recounted and marked with ↻
+    #[arg(long)]
+    pub no_watch: bool,
+
+    /// Don't compare with the last scan of the same root, nor record this scan for the next
+    /// comparison
+    #[arg(long)]
+    pub no_history: bool,
+
+    /// Select these directories when interactive mode starts, to review before deleting:
+    /// 'safe' picks the suggested deletions, as 'p' does
+    #[arg(long, value_name = "WHICH")]
+    pub auto_select: Option<AutoSelect>,
+
+    /// Select a small set of the safest temp directories that brings the free space on the scanned
+    /// volume up to SIZE (e.g. '50G'), then review them interactively, or delete them with --yes
+    #[arg(long, value_name = "SIZE", value_parser = crate::utils::parse_size_arg, conflicts_with_all = ["auto_select", "profile", "du", "serve", "apply_selection"])]
+    pub target_free: Option<u64>,
+
+    /// Additional directory name or glob (e.g. 'cmake-build-*') to treat as temporary (repeatable)
+    #[arg(long = "temp-dir", value_name = "NAME")]
+    pub temp_dirs: Vec<String>,
+
+    /// Directory name or glob to keep as normal for this run (repeatable)
+    #[arg(long = "keep", value_name = "NAME")]
+    pub keep: Vec<String>,
+
+    /// Load classification rules from this TOML or JSON rule file (repeatable), on top of the
+    /// rule files in ~/.config/disk-cleanup-tool/rules
+    #[arg(long = "rules", value_name = "FILE")]
+    pub rule_files: Vec<PathBuf>,
+
+    /// Match directory names and rules ignoring case (`Build`, `Node_Modules`), e.g. on a
+    /// case-insensitive network share; on by default on macOS and Windows
+    #[arg(long)]
+    pub ignore_case: bool,
+
+    /// Leave cloud-synced folders (OneDrive, Dropbox, iCloud Drive, Google Drive) out of the
+    /// scan entirely (also `skip_sync_folders = true` in the config); without it, directories
+    /// in them get a ☁ badge in interactive mode
+    #[arg(long, visible_alias = "exclude-synced")]
+    pub skip_sync_folders: bool,
+
+    /// Count filesystem snapshot directories (.snapshots, .zfs, .snapshot), which scans
+    /// skip by default because they share their data with the live files
+    #[arg(long)]
+    pub include_snapshots: bool,
+
+    /// Count data shared between reflinked or cloned files (btrfs, XFS) once; Linux only and
+    /// slower, as every file's extents are read (also `dedupe_extents = true` in the config)
+    #[arg(long)]
+    pub dedupe_extents: bool,
+
+    /// Stop descending into a directory once the files counted in it pass SIZE (e.g. '20GB')
+    /// and leave it out, for when only mid-sized clutter matters; saves walking huge trees
+    #[arg(long, value_name = "SIZE", value_parser = crate::utils::parse_size_arg)]
+    pub skip_larger_than: Option<u64>,
+
+    /// Leave directories smaller than SIZE (e.g. '100MB') out of the results
+    #[arg(long, value_name = "SIZE", value_parser = crate::utils::parse_size_arg)]
+    pub only_larger_than: Option<u64>,
+
+    /// Approximate sizes for a quick first pass over huge trees: in directories with more
+    /// than 100 files, only every tenth further file is looked up and the others are counted
+    /// at the average size; estimated sizes are marked with ≈
+    #[arg(long)]
+    pub estimate: bool,
+
+    /// Config file (defaults to ~/.config/disk-cleanup-tool/config.toml if present)
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Exclude temp directories modified within this window (e.g. '10m', '2h') as in use
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+    pub skip_active: Option<std::time::Duration>,
+
+    /// How many selected directories to recount at once before confirming a deletion; lower
+    /// it for network shares or spinning disks
+    #[arg(long, value_name = "N", default_value_t = 8)]
+    pub size_jobs: usize,
+
+    /// Check for processes with open files under the selected directories before deleting
+    #[arg(long, value_name = "MODE")]
+    pub open_files: Option<OpenFilesCheck>,
+
+    /// Allow deleting directories that contain git-tracked files
+    #[arg(long)]
+    pub allow_tracked: bool,
+
+    /// Delete without asking for confirmation. Without --interactive no UI is shown and the
+    /// selection saved in the session (--session / --input-csv) is deleted
+    #[arg(short = 'y', long, visible_alias = "force")]
+    pub yes: bool,
+
+    /// Move deleted directories to a trash folder in the user data directory instead of
+    /// removing them, so `undo` can restore the last batch. The space is only freed once
+    /// the trash folder is emptied.
+    #[arg(long)]
+    pub trash: bool,
+
+    /// Rename selected directories to <name>.pending-delete-<date> next to where they are,
+    /// that date being this long from now (e.g. '7days'), instead of deleting them, so others
+    /// sharing the volume can object; `purge-pending` removes them once the date has passed
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration, conflicts_with = "trash")]
+    pub grace_period: Option<std::time::Duration>,
+
+    /// Show what would be deleted, after all safety checks, without deleting anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Analyze only: disable deletion, cleanup and archiving everywhere (also `read_only = true`
+    /// in the config file)
+    #[arg(long)]
+    pub read_only: bool,
+
+    /// Show a desktop notification when a scan or deletion takes longer than this (e.g. '30s')
+    /// and the terminal is in the background (also `notify_after` in the config file)
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+    pub notify_after: Option<std::time::Duration>,
+
+    /// At the end, print one JSON object with the run's totals (size, directories, temp space,
+    /// bytes freed, failures) for wrapper scripts; a TOML or YAML document with --format
+    #[arg(long)]
+    pub summary_json: bool,
+
+    /// Print `du`-style "size<TAB>path" lines, in 1K blocks like `du` (bytes with --units
+    /// bytes), instead of opening the summary; other messages go to stderr
+    #[arg(long, conflicts_with_all = ["interactive", "session", "serve", "summary_json", "delete_from_file"])]
+    pub du: bool,
+
+    /// With --du: sizes like `du -h` ("4.0K", "12G"), or `du --si` with --units si
+    #[arg(long, requires = "du")]
+    pub human_readable: bool,
+
+    /// Rank directories by total size, by file count to find what is eating inodes, by
+    /// safety score to see the safest deletions first, or by growth since the last scan
+    #[arg(long, value_enum, default_value_t = SortKey::Size)]
+    pub sort: SortKey,
+
+    /// UI language, e.g. 'en' (defaults to the LC_ALL / LC_MESSAGES / LANG environment)
+    #[arg(long, value_name = "LANG")]
+    pub lang: Option<String>,
+
+    /// Size units: binary (1 KB = 1024 B), si (1 kB = 1000 B) or exact bytes
+    #[arg(long, value_enum, default_value_t = SizeUnits::Binary)]
+    pub units: SizeUnits,
+
+    /// Sizes shown and sorted by: apparent (what the files hold) or allocated (the disk blocks
+    /// they take, lower for sparse and compressed files)
+    #[arg(long, value_enum, value_name = "BASIS", default_value_t = SizeBasis::Apparent)]
+    pub size_basis: SizeBasis,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutoSelect {
+    /// Temp directories in a project, untouched for 30 days and with a safety score of at
+    /// least 70
+    Safe,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpenFilesCheck {
+    /// List the processes but still allow deleting
+    Warn,
+    /// Leave directories with open files out of the deletion
+    Block,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Show which rule classifies a directory and how safe it is to delete
+    Explain {
+        /// Directory to explain
+        path: PathBuf,
+    },
+    /// Print a breakdown of reclaimable space by category without entering interactive mode
+    Report {
+        /// Also save the report to this file; {date}, {time}, {hostname} and {root} are filled in
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Number of largest directories listed per type
+        #[arg(long, default_value_t = 3)]
+        examples: usize,
+
+        /// Also show how many bytes each user and group owns under the root
+        #[arg(long)]
+        by_owner: bool,
+    },
+    /// Find empty directory trees and broken symlinks, which never show up in size rankings,
+    /// and remove them in one go ('d' in the view, or --yes)
+    Clutter,
+    /// Triage the Downloads folder: list its items by age and size, then delete old ones or
+    /// move them to an archive folder. With --yes, acts on everything older than --older-than
+    Downloads {
+        /// Folder to triage (defaults to the user's Downloads directory)
+        #[arg(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+
+        /// Select items not modified within this window (e.g. '30days', '6months')
+        #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration, default_value = "90days")]
+        older_than: std::time::Duration,
+
+        /// Where archived items go (defaults to an Archive folder inside the Downloads folder)
+        #[arg(long, value_name = "DIR")]
+        archive: Option<PathBuf>,
+
+        /// With --yes: move old items to the archive instead of deleting them
+        #[arg(long)]
+        move_to_archive: bool,
+    },
+    /// Delete the files inside directories (e.g. caches) that were not modified within
+    /// --older-than, keeping the directories themselves; asks first unless --yes
+    Prune {
+        /// Directories to prune
+        #[arg(required = true, value_name = "DIR")]
+        dirs: Vec<PathBuf>,
+
+        /// Delete files not modified within this window (e.g. '30days', '6months')
+        #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+        older_than: std::time::Duration,
+    },
+    /// Enforce the retention policies of a policy file: scan each policy's paths, then delete,
+    /// trash or report the directories it matches, without prompts
+    ApplyPolicy {
+        /// Policy file with one or more [[policies]] tables
+        file: PathBuf,
+    },
+    /// Print a systemd service and timer (or a crontab line) that runs a cleanup profile or
+    /// policy file regularly, appending its output to a log; --install sets it up after asking
+    Schedule {
+        /// Cleanup profile from the config to run (as with --profile NAME --yes)
+        #[arg(long, value_name = "NAME", required_unless_present = "policy", conflicts_with = "policy")]
+        profile: Option<String>,
+
+        /// Policy file to apply (as with apply-policy FILE)
+        #[arg(long, value_name = "FILE")]
+        policy: Option<PathBuf>,
+
+        /// How often to run
+        #[arg(long, value_enum, default_value_t = Frequency::Daily)]
+        every: Frequency,
+
+        /// Time of day to run (hourly runs use only the minutes)
+        #[arg(long, value_name = "HH:MM", default_value = "03:00", value_parser = crate::schedule::parse_time)]
+        at: (u8, u8),
+
+        /// systemd user units or a crontab entry
+        #[arg(long, value_enum, default_value_t = ScheduleFormat::Systemd)]
+        format: ScheduleFormat,
+
+        /// Write the units to ~/.config/systemd/user and enable the timer, or add the line to
+        /// your crontab, after confirming (or right away with --yes)
+        #[arg(long)]
+        install: bool,
+    },
+    /// Restore the directories moved by the most recent `--trash` deletion; run again to go
+    /// one batch further back
+    Undo,
+    /// Delete the directories under --path that --grace-period marked for deletion and
+    /// whose date has passed; asks first unless --yes
+    PurgePending,
+    /// Combine scan files (e.g. from several machines) into one, tagging each entry with its source
+    Merge {
+        /// Scan files to combine (CSV or JSON, optionally compressed)
+        #[arg(required = true, num_args = 2..)]
+        inputs: Vec<PathBuf>,
+
+        /// Merged scan file to write (format and compression follow the extension)
+        #[arg(short, long, value_name = "FILE")]
+        output: PathBuf,
+    },
 }
 
 pub fn parse_args() -> CliArgs {
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::fs::{self, Metadata};
+use std::path::{Path, PathBuf};
+
+/// Bytes a file takes on the local disk: zero for cloud-sync placeholders whose contents
+/// are only online (OneDrive/Dropbox/iCloud "online-only" files), the file length otherwise
+pub fn local_size(metadata: &Metadata) -> u64 {
+    if is_placeholder(metadata) {
+        0
+    } else {
+        metadata.len()
+    }
+}
+
+/// Bytes allocated to the file on the local disk: less than its length when it is sparse or
+/// compressed by the filesystem, a little more for the unused end of its last block
+#[cfg(unix)]
+pub fn allocated_size(metadata: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks() * 512
+}
+
+/// Windows reports no allocation in the metadata, so this is the local size
+#[cfg(not(unix))]
+pub fn allocated_size(metadata: &Metadata) -> u64 {
+    local_size(metadata)
+}
+
+/// macOS marks files evicted by a File Provider (iCloud Drive, OneDrive, Dropbox) as dataless
+#[cfg(target_os = "macos")]
+pub fn is_placeholder(metadata: &Metadata) -> bool {
+    use std::os::macos::fs::MetadataExt;
+    const SF_DATALESS: u32 = 0x4000_0000;
+    metadata.st_flags() & SF_DATALESS != 0
+}
+
+/// Windows cloud files are reparse points whose contents are fetched on access
+#[cfg(windows)]
+pub fn is_placeholder(metadata: &Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_OFFLINE: u32 = 0x1000;
+    const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x4_0000;
+    const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x40_0000;
+    metadata.file_attributes()
+        & (FILE_ATTRIBUTE_OFFLINE | FILE_ATTRIBUTE_RECALL_ON_OPEN | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS)
+        != 0
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+pub fn is_placeholder(_metadata: &Metadata) -> bool {
+    false
+}
+
+/// Files sync clients keep at the root of their folder, which find it under any name:
+/// Dropbox's `.dropbox` and `.dropbox.cache`, and the OneDrive client's marker
+const SYNC_MARKERS: [(&str, &str); 3] = [
+    (".dropbox", "Dropbox"),
+    (".dropbox.cache", "Dropbox"),
+    (".849C9593-D756-4E56-8D6E-42412F2A707B", "OneDrive"),
+];
+
+/// Root of a cloud-synced folder, skipped entirely with `--skip-sync-folders`: OneDrive
+/// (including "OneDrive - Company"), Dropbox, iCloud Drive, Google Drive, and the macOS
+/// `~/Library/CloudStorage` and `~/Library/Mobile Documents` containers
+pub fn is_sync_folder(path: &Path) -> bool {
+    sync_service(path).is_some()
+}
+
+/// The service that syncs the folder `path` is the root of, by its name or the marker files
+/// its client leaves there
+pub fn sync_service(path: &Path) -> Option<&'static str> {
+    let name = path.file_name().and_then(|n| n.to_str())?;
+    let in_library = path.parent().and_then(Path::file_name).is_some_and(|parent| parent == "Library");
+    let by_name = match name {
+        "OneDrive" => Some("OneDrive"),
+        "Dropbox" => Some("Dropbox"),
+        "iCloud Drive" | "iCloudDrive" => Some("iCloud Drive"),
+        "Google Drive" => Some("Google Drive"),
+        "CloudStorage" if in_library => Some("CloudStorage"),
+        "Mobile Documents" if in_library => Some("iCloud Drive"),
+        _ if name.starts_with("OneDrive - ") => Some("OneDrive"),
+        _ if name.starts_with("Dropbox (") && name.ends_with(')') => Some("Dropbox"),
+        _ if name.starts_with("GoogleDrive-") => Some("Google Drive"),
+        _ => None,
+    };
+    by_name.or_else(|| {
+        SYNC_MARKERS
+            .iter()
+            .find(|(marker, _)| fs::symlink_metadata(path.join(marker)).is_ok())
+            .map(|&(_, service)| service)
+    })
+}
+
+/// Which entries live in a synced folder, where deleting also deletes in the cloud and on
+/// the user's other devices. Remembers each directory it looked at, so labelling a long
+/// list checks every ancestor once.
+#[derive(Debug, Default)]
+pub struct SyncFolders {
+    known: HashMap<PathBuf, Option<&'static str>>,
+}
+
+impl SyncFolders {
+    /// Service syncing `path`, which is its sync root or lies below one
+    pub fn service_of(&mut self, path: &Path) -> Option<&'static str> {
+        path.ancestors().find_map(|dir| {
+            *self.known.entry(dir.to_path_buf()).or_insert_with(|| sync_service(dir))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_sync_folder() {
+        assert!(is_sync_folder(Path::new("/home/me/Dropbox")));
+        assert!(is_sync_folder(Path::new("/home/me/Dropbox (Acme)")));
+        assert!(is_sync_folder(Path::new("C:/Users/me/OneDrive - Acme Corp")));
+        assert!(is_sync_folder(Path::new("/Users/me/Library/CloudStorage")));
+        assert!(is_sync_folder(Path::new("/Users/me/Library/Mobile Documents")));
+        assert!(!is_sync_folder(Path::new("/srv/CloudStorage")));
+        assert!(!is_sync_folder(Path::new("/home/me/OneDriveBackup")));
+        assert!(!is_sync_folder(Path::new("/home/me/projects")));
+    }
+
+    #[test]
+    fn test_sync_markers() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let custom = temp_dir.path().join("Work files");
+        std::fs::create_dir_all(custom.join("project/node_modules")).unwrap();
+        assert_eq!(sync_service(&custom), None);
+        std::fs::write(custom.join(".dropbox"), "").unwrap();
+        assert_eq!(sync_service(&custom), Some("Dropbox"));
+
+        let mut folders = SyncFolders::default();
+        assert_eq!(folders.service_of(&custom.join("project/node_modules")), Some("Dropbox"));
+        assert_eq!(folders.service_of(Path::new("/Users/me/Library/CloudStorage/GoogleDrive-me@example.com/x")), Some("Google Drive"));
+        assert_eq!(folders.service_of(temp_dir.path()), None);
+    }
+
+    #[test]
+    fn test_local_size() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), "hello").unwrap();
+        let metadata = std::fs::metadata(temp_file.path()).unwrap();
+        assert!(!is_placeholder(&metadata));
+        assert_eq!(local_size(&metadata), 5);
+    }
+}
@@ -0,0 +1,351 @@
+use crate::i18n::t;
+use crossterm::{
+    event::{self, Event, KeyCode},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame, Terminal,
+};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Version-control metadata keeps empty directories on purpose (e.g. `.git/refs/tags`)
+const VCS_DIRS: [&str; 3] = [".git", ".hg", ".svn"];
+
+/// Zero-byte leftovers that never show up in a size-ranked list
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Clutter {
+    /// Topmost directories that contain nothing but (possibly nested) empty directories
+    pub empty_dirs: Vec<PathBuf>,
+    /// Symlinks whose target does not exist
+    pub broken_symlinks: Vec<PathBuf>,
+}
+
+impl Clutter {
+    pub fn is_empty(&self) -> bool {
+        self.empty_dirs.is_empty() && self.broken_symlinks.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        t!("clutter-summary", dirs = self.empty_dirs.len(), links = self.broken_symlinks.len())
+    }
+}
+
+/// Find empty directory trees and dangling symlinks under `root` (never `root` itself).
+/// Unreadable directories and VCS metadata count as content, so they are never reported.
+pub fn find_clutter(root: &Path) -> Clutter {
+    // Directories known to hold a file, symlink or something unreadable, directly or below
+    let non_empty: RefCell<HashSet<PathBuf>> = RefCell::new(HashSet::new());
+    let mark_parent = |path: &Path| {
+        if let Some(parent) = path.parent() {
+            non_empty.borrow_mut().insert(parent.to_path_buf());
+        }
+    };
+
+    let mut clutter = Clutter::default();
+    let mut empty_dirs = Vec::new();
+    // Children come before their directory, so a directory's status is final when it is seen.
+    // `filter_entry` cannot prune here: it only sees a directory after its contents
+    for entry in WalkDir::new(root).contents_first(true).sort_by_file_name() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                if let Some(path) = e.path() {
+                    non_empty.borrow_mut().insert(path.to_path_buf());
+                    mark_parent(path);
+                }
+                continue;
+            }
+        };
+        let path = entry.path();
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        if relative.components().any(|c| VCS_DIRS.iter().any(|name| c.as_os_str() == *name)) {
+            if entry.file_type().is_dir() && VCS_DIRS.iter().any(|name| entry.file_name() == *name) {
+                mark_parent(path);
+            }
+            continue;
+        }
+        if entry.file_type().is_dir() {
+            if non_empty.borrow().contains(path) {
+                mark_parent(path);
+            } else if path != root {
+                empty_dirs.push(path.to_path_buf());
+            }
+        } else {
+            if entry.path_is_symlink() && fs::metadata(path).is_err() {
+                clutter.broken_symlinks.push(path.to_path_buf());
+            }
+            mark_parent(path);
+        }
+    }
+
+    // Only report the top of each empty tree
+    let empty: HashSet<&Path> = empty_dirs.iter().map(PathBuf::as_path).collect();
+    clutter.empty_dirs = empty_dirs
+        .iter()
+        .filter(|dir| !dir.parent().is_some_and(|parent| empty.contains(parent)))
+        .cloned()
+        .collect();
+    clutter.empty_dirs.sort();
+    clutter
+}
+
+/// What [`clean_up`] removed
+#[derive(Debug, Default)]
+pub struct CleanupReport {
+    pub removed_dirs: usize,
+    pub removed_symlinks: usize,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl CleanupReport {
+    pub fn print_summary(&self) {
+        println!("{}", t!("clutter-removed", dirs = self.removed_dirs, links = self.removed_symlinks));
+        for (path, reason) in &self.failed {
+            println!("  ✗ {}: {}", path.display(), reason);
+        }
+    }
+}
+
+/// Remove the clutter, re-checking each item first: a symlink whose target reappeared is
+/// kept, and directories are removed bottom-up with `remove_dir`, which refuses to delete
+/// anything that gained content since the scan
+pub fn clean_up(clutter: &Clutter) -> CleanupReport {
+    let mut report = CleanupReport::default();
+    for link in &clutter.broken_symlinks {
+        let still_broken = fs::symlink_metadata(link).is_ok_and(|m| m.file_type().is_symlink()) && fs::metadata(link).is_err();
+        if !still_broken {
+            report.failed.push((link.clone(), "no longer a broken symlink".to_string()));
+            continue;
+        }
+        match fs::remove_file(link) {
+            Ok(()) => report.removed_symlinks += 1,
+            Err(e) => report.failed.push((link.clone(), e.to_string())),
+        }
+    }
+    for dir in &clutter.empty_dirs {
+        match remove_empty_tree(dir) {
+            Ok(()) => report.removed_dirs += 1,
+            Err(e) => report.failed.push((dir.clone(), e.to_string())),
+        }
+    }
+    report
+}
+
+fn remove_empty_tree(dir: &Path) -> io::Result<()> {
+    for entry in WalkDir::new(dir).contents_first(true) {
+        let entry = entry?;
+        if !entry.file_type().is_dir() {
+            return Err(io::Error::other(format!("{} is not empty", entry.path().display())));
+        }
+        fs::remove_dir(entry.path())?;
+    }
+    Ok(())
+}
+
+pub fn print_clutter(clutter: &Clutter) {
+    println!("{}", t!("clutter-found", summary = clutter.summary()));
+    for dir in &clutter.empty_dirs {
+        println!("  📂 {}", dir.display());
+    }
+    for link in &clutter.broken_symlinks {
+        let target = fs::read_link(link).map(|t| t.display().to_string()).unwrap_or_default();
+        println!("  🔗 {} -> {}", link.display(), target);
+    }
+}
+
+/// Browse the clutter; returns true if the user chose to clean it all up, which
+/// `read_only` rules out
+pub fn show_clutter(clutter: &Clutter, root: &Path, read_only: bool) -> io::Result<bool> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let result = run_clutter_ui(&mut terminal, clutter, root, read_only);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+fn run_clutter_ui(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    clutter: &Clutter,
+    root: &Path,
+    read_only: bool,
+) -> io::Result<bool> {
+    let total = clutter.empty_dirs.len() + clutter.broken_symlinks.len();
+    let mut scroll_offset = 0usize;
+    let mut confirming = false;
+
+    loop {
+        terminal.draw(|f| render_clutter(f, clutter, root, scroll_offset, confirming, read_only))?;
+
+        if event::poll(std::time::Duration::from_millis(100))? {
+            if let Event::Key(key) = event::read()? {
+                if confirming {
+                    match key.code {
+                        KeyCode::Char('y') | KeyCode::Char('Y') => return Ok(true),
+                        _ => confirming = false,
+                    }
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+                    KeyCode::Char('d') if total > 0 && !read_only => confirming = true,
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        scroll_offset = scroll_offset.saturating_sub(1);
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        scroll_offset = scroll_offset.saturating_add(1).min(total.saturating_sub(1));
+                    }
+                    KeyCode::PageUp => {
+                        scroll_offset = scroll_offset.saturating_sub(10);
+                    }
+                    KeyCode::PageDown => {
+                        scroll_offset = scroll_offset.saturating_add(10).min(total.saturating_sub(1));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn render_clutter(f: &mut Frame, clutter: &Clutter, root: &Path, scroll_offset: usize, confirming: bool, read_only: bool) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4), // Header
+            Constraint::Min(0),    // List
+            Constraint::Length(3), // Footer
+        ])
+        .split(f.area());
+
+    let header = Paragraph::new(vec![
+        Line::from(Span::styled(
+            t!("clutter-title"),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(vec![
+            Span::raw(t!("clutter-under", root = root.display().to_string())),
+            Span::styled(clutter.summary(), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+        ]),
+    ])
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Cyan)));
+    f.render_widget(header, chunks[0]);
+
+    let dirs = clutter.empty_dirs.iter().map(|dir| {
+        ListItem::new(Line::from(vec![
+            Span::raw("  📂 "),
+            Span::styled(dir.display().to_string(), Style::default().fg(Color::White)),
+        ]))
+    });
+    let links = clutter.broken_symlinks.iter().map(|link| {
+        let target = fs::read_link(link).map(|t| t.display().to_string()).unwrap_or_default();
+        ListItem::new(Line::from(vec![
+            Span::raw("  🔗 "),
+            Span::styled(link.display().to_string(), Style::default().fg(Color::White)),
+            Span::styled(format!(" -> {}", target), Style::default().fg(Color::Red)),
+        ]))
+    });
+    let list_height = chunks[1].height.saturating_sub(2) as usize;
+    let items: Vec<ListItem> = dirs.chain(links).skip(scroll_offset).take(list_height).collect();
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::White))
+            .title(t!("clutter-list-title")),
+    );
+    f.render_widget(list, chunks[1]);
+
+    let footer = if confirming {
+        Line::from(vec![
+            Span::styled(
+                t!("clutter-confirm", summary = clutter.summary()),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+            Span::raw(format!(": {}  |  {}", t!("key-yes"), t!("key-other-no"))),
+        ])
+    } else {
+        let clean_keys = if read_only {
+            vec![Span::styled(format!("{}  |  ", t!("read-only-badge")), Style::default().fg(Color::Yellow))]
+        } else {
+            vec![
+                Span::styled("d", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::raw(format!(": {}  |  ", t!("key-clean-up-all"))),
+            ]
+        };
+        Line::from([vec![
+            Span::styled("↑/↓", Style::default().fg(Color::Cyan)),
+            Span::raw(format!(": {}  |  ", t!("key-scroll"))),
+        ], clean_keys, vec![
+            Span::styled("q", Style::default().fg(Color::Green)),
+            Span::raw(format!(": {}", t!("key-quit"))),
+        ]].concat())
+    };
+    let footer = Paragraph::new(footer)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::White)));
+    f.render_widget(footer, chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_and_clean_up_clutter() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("old/a/b")).unwrap();
+        fs::create_dir_all(root.join("old/c")).unwrap();
+        fs::create_dir_all(root.join("project/src")).unwrap();
+        fs::write(root.join("project/src/main.rs"), "fn main() {}").unwrap();
+        fs::create_dir_all(root.join("project/empty")).unwrap();
+        fs::create_dir_all(root.join("repo/.git/refs/tags")).unwrap();
+        std::os::unix::fs::symlink(root.join("missing"), root.join("project/dangling")).unwrap();
+        std::os::unix::fs::symlink(root.join("project/src"), root.join("project/ok")).unwrap();
+
+        let clutter = find_clutter(root);
+        assert_eq!(clutter.empty_dirs, vec![root.join("old"), root.join("project/empty")]);
+        assert_eq!(clutter.broken_symlinks, vec![root.join("project/dangling")]);
+        assert!(!clutter.is_empty());
+
+        // Content that shows up after the scan is never deleted
+        fs::write(root.join("old/c/new.txt"), "new").unwrap();
+        let report = clean_up(&clutter);
+        assert_eq!(report.removed_symlinks, 1);
+        assert_eq!(report.removed_dirs, 1);
+        assert_eq!(report.failed.len(), 1);
+        assert!(root.join("old/c/new.txt").exists());
+        assert!(!root.join("project/empty").exists());
+        assert!(fs::symlink_metadata(root.join("project/dangling")).is_err());
+        assert!(root.join("project/ok").exists());
+        assert!(root.join("repo/.git/refs/tags").exists());
+    }
+
+    #[test]
+    fn test_empty_root_is_not_reported() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(find_clutter(temp_dir.path()).is_empty());
+    }
+}
@@ -0,0 +1,310 @@
+use crate::i18n::t;
+use crate::scanner::DirectoryEntry;
+use crate::utils::{format_size, size_units, SizeUnits};
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::{Duration, SystemTime};
+
+/// Share (0.0..=1.0) of each entry's cumulative size in its parent's cumulative size.
+/// Entries whose parent isn't in `entries` (e.g. the scan root) have no share.
+pub fn parent_shares(entries: &[DirectoryEntry]) -> HashMap<PathBuf, f64> {
+    let sizes: HashMap<&std::path::Path, u64> = entries
+        .iter()
+        .map(|e| (e.path.as_path(), e.shown_size()))
+        .collect();
+
+    entries
+        .iter()
+        .filter_map(|e| {
+            let parent_size = *sizes.get(e.path.parent()?)?;
+            let share = if parent_size == 0 {
+                0.0
+            } else {
+                e.shown_size() as f64 / parent_size as f64
+            };
+            Some((e.path.clone(), share))
+        })
+        .collect()
+}
+
+/// "42.0%", or "-" when there is no parent in the scan
+pub fn format_share(share: Option<f64>) -> String {
+    match share {
+        Some(share) => format!("{:.1}%", share * 100.0),
+        None => "-".to_string(),
+    }
+}
+
+/// "▲ 1.20 GB" for growth since the last scan, "▼ 300.00 MB" for a shrink, "=" for no
+/// change and "-" when the last scan didn't list the directory
+pub fn format_growth(growth: Option<i64>) -> String {
+    match growth {
+        Some(0) => "=".to_string(),
+        Some(bytes) if bytes > 0 => format!("▲ {}", format_size(bytes as u64)),
+        Some(bytes) => format!("▼ {}", format_size(bytes.unsigned_abs())),
+        None => "-".to_string(),
+    }
+}
+
+/// Red for growth, green for a shrink
+pub fn growth_color(growth: Option<i64>) -> Color {
+    match growth {
+        Some(bytes) if bytes > 0 => Color::Red,
+        Some(bytes) if bytes < 0 => Color::Green,
+        _ => Color::DarkGray,
+    }
+}
+
+/// "-3.20 GB" where sparse or compressed files take less space on disk than they hold,
+/// "+12.00 KB" where partly filled blocks take more, "=" when both sizes match
+pub fn format_allocation_difference(difference: i64) -> String {
+    match difference {
+        0 => "=".to_string(),
+        bytes if bytes > 0 => format!("+{}", format_size(bytes as u64)),
+        bytes => format!("-{}", format_size(bytes.unsigned_abs())),
+    }
+}
+
+/// Green for space saved on disk, gray for block overhead
+pub fn allocation_difference_color(difference: i64) -> Color {
+    if difference < 0 {
+        Color::Green
+    } else {
+        Color::DarkGray
+    }
+}
+
+/// Width of the size column; exact byte counts need more room
+pub fn size_width() -> u16 {
+    match size_units() {
+        SizeUnits::Bytes => 16,
+        SizeUnits::Binary | SizeUnits::Si => 10,
+    }
+}
+
+/// Width of the inline size bar
+pub const BAR_WIDTH: usize = 10;
+
+/// ncdu-style bar for a share, using eighth blocks for sub-cell precision: "████▍     "
+pub fn share_bar(share: f64, width: usize) -> String {
+    const PARTIAL: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+    let eighths = (share.clamp(0.0, 1.0) * (width * 8) as f64).round() as usize;
+    let full = eighths / 8;
+    let mut bar = "█".repeat(full);
+    if full < width {
+        bar.push(PARTIAL[eighths % 8]);
+        bar.push_str(&" ".repeat(width - full - 1));
+    }
+    bar
+}
+
+/// "42.0% ███▍      " for table cells, or just "-" without a parent
+pub fn format_share_with_bar(share: Option<f64>) -> String {
+    match share {
+        Some(share) => format!("{:>6} {}", format_share(Some(share)), share_bar(share, BAR_WIDTH)),
+        None => format!("{:>6}", "-"),
+    }
+}
+
+/// Colors for the size column (`palette` in config.toml)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Palette {
+    /// Green, yellow, red
+    #[default]
+    Heat,
+    /// Blue, yellow, vermillion (Okabe-Ito), which red-green colorblind users can tell apart
+    Colorblind,
+}
+
+/// Palette used by [`heat_color`], set once from the config
+static PALETTE: AtomicU8 = AtomicU8::new(Palette::Heat as u8);
+
+pub fn set_palette(palette: Palette) {
+    PALETTE.store(palette as u8, Ordering::Relaxed);
+}
+
+fn palette() -> Palette {
+    match PALETTE.load(Ordering::Relaxed) {
+        x if x == Palette::Colorblind as u8 => Palette::Colorblind,
+        _ => Palette::Heat,
+    }
+}
+
+/// Color for `size` on a gradient from small to `max`, so the biggest entries stand out
+pub fn heat_color(size: u64, max: u64) -> Color {
+    heat_color_with(size, max, palette())
+}
+
+pub fn heat_color_with(size: u64, max: u64, palette: Palette) -> Color {
+    let stops: [(u8, u8, u8); 3] = match palette {
+        Palette::Heat => [(80, 200, 80), (230, 210, 60), (230, 60, 50)],
+        Palette::Colorblind => [(86, 180, 233), (240, 228, 66), (213, 94, 0)],
+    };
+    let share = if max == 0 { 0.0 } else { (size as f64 / max as f64).clamp(0.0, 1.0) };
+    let (from, to, t) = if share < 0.5 {
+        (stops[0], stops[1], share * 2.0)
+    } else {
+        (stops[1], stops[2], share * 2.0 - 1.0)
+    };
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    Color::Rgb(mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+/// Age relative to `now`, e.g. "just now", "5m ago", "3d ago"
+pub fn format_age(modified: Option<SystemTime>, now: SystemTime) -> String {
+    let Some(modified) = modified else {
+        return "-".to_string();
+    };
+    let secs = now.duration_since(modified).unwrap_or(Duration::ZERO).as_secs();
+    match secs {
+        0..=59 => t!("modified-just-now"),
+        60..=3599 => t!("modified-minutes", count = secs / 60),
+        3600..=86_399 => t!("modified-hours", count = secs / 3600),
+        86_400..=2_591_999 => t!("modified-days", count = secs / 86_400),
+        2_592_000..=31_535_999 => t!("modified-months", count = secs / 2_592_000),
+        _ => t!("modified-years", count = secs / 31_536_000),
+    }
+}
+
+/// Shorten a path to `width` characters by replacing its start with "…", keeping the
+/// more informative end (`…/project/node_modules`)
+pub fn truncate_path(path: &str, width: usize) -> String {
+    let len = path.chars().count();
+    if len <= width {
+        return path.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let tail: String = path.chars().skip(len - (width - 1)).collect();
+    format!("…{}", tail)
+}
+
+/// Path column text: the truncated path, prefixed with "⚠ " when part of the directory
+/// couldn't be read and its sizes are only a lower bound
+pub fn format_entry_path(entry: &DirectoryEntry, width: usize) -> String {
+    let path = entry.path.display().to_string();
+    if entry.incomplete {
+        format!("⚠ {}", truncate_path(&path, width.saturating_sub(2)))
+    } else {
+        truncate_path(&path, width)
+    }
+}
+
+/// Size column text: the cumulative size, prefixed with "≈ " when `--estimate` extrapolated
+/// part of it
+pub fn format_entry_size(entry: &DirectoryEntry) -> String {
+    let size = format_size(entry.shown_size());
+    if entry.estimated {
+        format!("≈ {}", size)
+    } else {
+        size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, size: u64) -> DirectoryEntry {
+        DirectoryEntry {
+            path: PathBuf::from(path),
+            cumulative_size_bytes: size,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parent_shares() {
+        let entries = vec![entry("/p", 400), entry("/p/a", 300), entry("/p/b", 100), entry("/p/empty", 0)];
+        let shares = parent_shares(&entries);
+
+        assert_eq!(shares.get(&PathBuf::from("/p")), None);
+        assert_eq!(shares[&PathBuf::from("/p/a")], 0.75);
+        assert_eq!(shares[&PathBuf::from("/p/b")], 0.25);
+        assert_eq!(shares[&PathBuf::from("/p/empty")], 0.0);
+        assert_eq!(format_share(Some(0.75)), "75.0%");
+        assert_eq!(format_share(None), "-");
+    }
+
+    #[test]
+    fn test_share_bar() {
+        assert_eq!(share_bar(0.0, 4), "    ");
+        assert_eq!(share_bar(1.0, 4), "████");
+        assert_eq!(share_bar(0.5, 4), "██  ");
+        assert_eq!(share_bar(0.25 + 1.0 / 32.0, 4), "█▏  ");
+        assert_eq!(share_bar(2.0, 4), "████");
+        for step in 0..=100 {
+            assert_eq!(share_bar(step as f64 / 100.0, BAR_WIDTH).chars().count(), BAR_WIDTH);
+        }
+        assert_eq!(format_share_with_bar(Some(0.5)), " 50.0% █████     ");
+        assert_eq!(format_share_with_bar(None), "     -");
+    }
+
+    #[test]
+    fn test_heat_color() {
+        assert_eq!(heat_color_with(0, 100, Palette::Heat), Color::Rgb(80, 200, 80));
+        assert_eq!(heat_color_with(50, 100, Palette::Heat), Color::Rgb(230, 210, 60));
+        assert_eq!(heat_color_with(100, 100, Palette::Heat), Color::Rgb(230, 60, 50));
+        assert_eq!(heat_color_with(100, 100, Palette::Colorblind), Color::Rgb(213, 94, 0));
+        // Nothing scanned yet, or a size past the maximum, stays on the scale
+        assert_eq!(heat_color_with(5, 0, Palette::Heat), Color::Rgb(80, 200, 80));
+        assert_eq!(heat_color_with(200, 100, Palette::Heat), Color::Rgb(230, 60, 50));
+        assert_eq!(heat_color_with(25, 100, Palette::Heat), Color::Rgb(155, 205, 70));
+    }
+
+    #[test]
+    fn test_format_age() {
+        let now = SystemTime::now();
+        let ago = |secs| Some(now - Duration::from_secs(secs));
+        assert_eq!(format_age(ago(5), now), "just now");
+        assert_eq!(format_age(ago(300), now), "5m ago");
+        assert_eq!(format_age(ago(7200), now), "2h ago");
+        assert_eq!(format_age(ago(3 * 86_400), now), "3d ago");
+        assert_eq!(format_age(ago(400 * 86_400), now), "1y ago");
+        assert_eq!(format_age(None, now), "-");
+        // Clock skew: a future timestamp is "just now"
+        assert_eq!(format_age(Some(now + Duration::from_secs(60)), now), "just now");
+    }
+
+    #[test]
+    fn test_format_growth() {
+        assert_eq!(format_growth(Some(2048)), format!("▲ {}", format_size(2048)));
+        assert_eq!(format_growth(Some(-2048)), format!("▼ {}", format_size(2048)));
+        assert_eq!(format_growth(Some(0)), "=");
+        assert_eq!(format_growth(None), "-");
+        assert_eq!(growth_color(Some(1)), Color::Red);
+        assert_eq!(growth_color(Some(-1)), Color::Green);
+    }
+
+    #[test]
+    fn test_format_allocation_difference() {
+        assert_eq!(format_allocation_difference(-2048), format!("-{}", format_size(2048)));
+        assert_eq!(format_allocation_difference(4096), format!("+{}", format_size(4096)));
+        assert_eq!(format_allocation_difference(0), "=");
+        assert_eq!(allocation_difference_color(-1), Color::Green);
+        assert_eq!(allocation_difference_color(1), Color::DarkGray);
+    }
+
+    #[test]
+    fn test_truncate_path() {
+        assert_eq!(truncate_path("/short", 10), "/short");
+        assert_eq!(truncate_path("/home/me/projects/app/node_modules", 17), "…app/node_modules");
+        assert_eq!(truncate_path("/home/me/projects/app/node_modules", 16).chars().count(), 16);
+        assert_eq!(truncate_path("/abc", 0), "");
+
+        let mut entry = entry("/home/me/projects/app/node_modules", 0);
+        assert_eq!(format_entry_path(&entry, 17), "…app/node_modules");
+        entry.incomplete = true;
+        assert_eq!(format_entry_path(&entry, 17), "⚠ …p/node_modules");
+
+        entry.cumulative_size_bytes = 2048;
+        assert_eq!(format_entry_size(&entry), format_size(2048));
+        entry.estimated = true;
+        assert_eq!(format_entry_size(&entry), format!("≈ {}", format_size(2048)));
+    }
+}
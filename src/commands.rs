@@ -0,0 +1,128 @@
+use crate::i18n::t;
+use crate::utils::{SizeUnits, SortKey};
+use crossterm::event::KeyCode;
+
+/// What a command palette entry does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// The same as pressing this key
+    Key(KeyCode),
+    SortBy(SortKey),
+    /// Show only temp directories, or everything again
+    ToggleTempOnly,
+    /// Write the selected paths to a file, one per line
+    ExportSelection,
+    Units(SizeUnits),
+}
+
+/// An entry in the ':' command palette
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Command {
+    /// Message id of its name
+    pub id: &'static str,
+    /// Key that does the same outside the palette, shown next to the name
+    pub key: Option<&'static str>,
+    pub action: Action,
+}
+
+impl Command {
+    pub fn name(&self) -> String {
+        t!(self.id)
+    }
+}
+
+const fn command(id: &'static str, key: Option<&'static str>, action: Action) -> Command {
+    Command { id, key, action }
+}
+
+/// Every command, in the order listed when nothing is typed
+pub const COMMANDS: &[Command] = &[
+    command("command-sort-size", None, Action::SortBy(SortKey::Size)),
+    command("command-sort-files", None, Action::SortBy(SortKey::Files)),
+    command("command-sort-safety", None, Action::SortBy(SortKey::Safety)),
+    command("command-sort-growth", None, Action::SortBy(SortKey::Growth)),
+    command("command-temp-only", None, Action::ToggleTempOnly),
+    command("command-export-selection", None, Action::ExportSelection),
+    command("command-units-binary", None, Action::Units(SizeUnits::Binary)),
+    command("command-units-si", None, Action::Units(SizeUnits::Si)),
+    command("command-units-bytes", None, Action::Units(SizeUnits::Bytes)),
+    command("key-select-all", Some("a"), Action::Key(KeyCode::Char('a'))),
+    command("key-clear", Some("c"), Action::Key(KeyCode::Char('c'))),
+    command("key-select-suggested", Some("p"), Action::Key(KeyCode::Char('p'))),
+    command("key-session-log", Some("L"), Action::Key(KeyCode::Char('L'))),
+    command("key-explain", Some("e"), Action::Key(KeyCode::Char('e'))),
+    command("key-breakdown", Some("b"), Action::Key(KeyCode::Char('b'))),
+    command("command-size-basis", Some("u"), Action::Key(KeyCode::Char('u'))),
+    command("command-reclassify", Some("t"), Action::Key(KeyCode::Char('t'))),
+    command("command-next-category", Some("T"), Action::Key(KeyCode::Char('T'))),
+    command("key-jump-path", Some("/"), Action::Key(KeyCode::Char('/'))),
+    command("command-group-view", Some("g"), Action::Key(KeyCode::Char('g'))),
+    command("command-two-pane-view", Some("v"), Action::Key(KeyCode::Char('v'))),
+    command("command-images-view", Some("i"), Action::Key(KeyCode::Char('i'))),
+    command("key-next-root", Some("Tab"), Action::Key(KeyCode::Tab)),
+    command("key-save-session", Some("s"), Action::Key(KeyCode::Char('s'))),
+    command("key-delete-selected", Some("d"), Action::Key(KeyCode::Char('d'))),
+    command("key-quit", Some("q"), Action::Key(KeyCode::Char('q'))),
+];
+
+/// Commands whose name fuzzy-matches `query`, best match first
+pub fn matching(query: &str) -> Vec<Command> {
+    let mut scored: Vec<(u32, Command)> = COMMANDS
+        .iter()
+        .filter_map(|command| fuzzy_score(query, &command.name()).map(|score| (score, *command)))
+        .collect();
+    // Stable, so equal scores keep the listed order
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, command)| command).collect()
+}
+
+/// How well `query` matches `text` when its characters appear in order, ignoring case and
+/// spaces: runs of adjacent characters and word starts score higher. `None` if it doesn't.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase) {
+        let found = position + text[position..].iter().position(|&c| c == wanted)?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 3;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 2;
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "Sort by size"), Some(0));
+        assert!(fuzzy_score("sbf", "Sort by files").is_some());
+        assert!(fuzzy_score("SORT", "Sort by files").is_some());
+        assert_eq!(fuzzy_score("zs", "Sort by files"), None);
+        // Adjacent characters and word starts beat scattered ones
+        assert!(fuzzy_score("files", "Sort by files") > fuzzy_score("files", "Show only temp directories, flies"));
+        assert!(fuzzy_score("sf", "Sort by files") > fuzzy_score("sf", "Units: SI"));
+    }
+
+    #[test]
+    fn test_matching_commands() {
+        assert_eq!(matching("").len(), COMMANDS.len());
+        assert_eq!(matching("sort file")[0].action, Action::SortBy(SortKey::Files));
+        assert_eq!(matching("export")[0].action, Action::ExportSelection);
+        assert_eq!(matching("units by")[0].action, Action::Units(SizeUnits::Bytes));
+        assert!(matching("zzz").is_empty());
+        // Every command has a name in the messages
+        for command in COMMANDS {
+            assert_ne!(command.name(), command.id);
+        }
+    }
+}
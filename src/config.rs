@@ -0,0 +1,340 @@
+use crate::bookmarks::Bookmark;
+use crate::classifier::MatchOptions;
+use crate::columns::Palette;
+use crate::email::SmtpConfig;
+use crate::hooks::Hooks;
+use crate::profiles::Profile;
+use crate::scanner::EntryType;
+use crate::utils::parse_size;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Cannot read config {path}: {source}")]
+    IoError {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid config {path}: {message}")]
+    ParseError { path: PathBuf, message: String },
+}
+
+/// Default for `large_deletion_threshold`: 50 GB
+const DEFAULT_LARGE_DELETION_THRESHOLD: u64 = 50 * 1024 * 1024 * 1024;
+
+/// User configuration loaded from `config.toml`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Path-based classification rules, evaluated before name matching
+    pub rules: Vec<PathRule>,
+
+    /// Deleting more than this (e.g. "50 GB", or a number of bytes) requires typing the
+    /// directory count or DELETE instead of pressing `y`
+    #[serde(deserialize_with = "deserialize_size")]
+    pub large_deletion_threshold: u64,
+
+    /// With `--trash`, refuse to grow a trash directory past this size (e.g. "20 GB");
+    /// unlimited by default
+    #[serde(deserialize_with = "deserialize_optional_size")]
+    pub trash_max_size: Option<u64>,
+
+    /// Always run in read-only mode, as with `--read-only`: nothing can be deleted or moved
+    pub read_only: bool,
+
+    /// Commands to run before and after deleting
+    pub hooks: Hooks,
+
+    /// Desktop notification for scans and deletions longer than this (e.g. "30s"), as with
+    /// `--notify-after`
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub notify_after: Option<Duration>,
+
+    /// HTTP headers sent with `--post-results`, e.g. an API token
+    pub post_headers: BTreeMap<String, String>,
+
+    /// Mail server for `--email-report`
+    pub smtp: Option<SmtpConfig>,
+
+    /// Case and Unicode handling when matching directory names against the rules
+    pub matching: MatchOptions,
+
+    /// Always leave cloud-synced folders out of scans, as with `--skip-sync-folders`
+    pub skip_sync_folders: bool,
+
+    /// Always count reflinked data once, as with `--dedupe-extents`
+    pub dedupe_extents: bool,
+
+    /// Named scan locations for `--bookmark NAME`
+    pub bookmarks: BTreeMap<String, Bookmark>,
+
+    /// Named cleanup recipes for `--profile NAME`
+    pub profiles: BTreeMap<String, Profile>,
+
+    /// Size column colors: "heat" (green to red) or "colorblind" (blue to vermillion)
+    pub palette: Palette,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            large_deletion_threshold: DEFAULT_LARGE_DELETION_THRESHOLD,
+            trash_max_size: None,
+            read_only: false,
+            hooks: Hooks::default(),
+            notify_after: None,
+            post_headers: BTreeMap::new(),
+            smtp: None,
+            matching: MatchOptions::default(),
+            skip_sync_folders: false,
+            dedupe_extents: false,
+            bookmarks: BTreeMap::new(),
+            profiles: BTreeMap::new(),
+            palette: Palette::Heat,
+        }
+    }
+}
+
+/// Accept sizes as byte counts or strings like "50 GB"
+pub(crate) fn deserialize_size<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Size {
+        Bytes(u64),
+        Text(String),
+    }
+    match Size::deserialize(deserializer)? {
+        Size::Bytes(bytes) => Ok(bytes),
+        Size::Text(text) => parse_size(&text).ok_or_else(|| serde::de::Error::custom(format!("invalid size '{}'", text))),
+    }
+}
+
+fn deserialize_optional_size<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    deserialize_size(deserializer).map(Some)
+}
+
+/// Accept durations like "30s" or "2m"
+pub(crate) fn deserialize_duration<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    humantime::parse_duration(&text)
+        .map(Some)
+        .map_err(|_| serde::de::Error::custom(format!("invalid duration '{}'", text)))
+}
+
+/// Classify directories by their full path rather than just their name.
+///
+/// ```toml
+/// [[rules]]
+/// path_pattern = "~/Library/Caches/*"
+/// category = "os_cache"
+///
+/// [[rules]]
+/// path_pattern = "**/Pods"
+/// category = "dependency_cache"
+/// sibling = "Podfile"
+/// label = "CocoaPods"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PathRule {
+    /// Glob matched against the absolute directory path. `~/` expands to the home
+    /// directory and relative patterns match at any depth.
+    pub path_pattern: String,
+
+    /// Category to assign; `normal` forces matching directories to stay normal
+    #[serde(default = "default_rule_category")]
+    pub category: EntryType,
+
+    /// Only apply when the parent directory also contains this file or directory
+    #[serde(default)]
+    pub sibling: Option<String>,
+
+    /// Short description shown next to matching directories (e.g. "Unity Library")
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+fn default_rule_category() -> EntryType {
+    EntryType::Temp
+}
+
+impl Config {
+    /// Default location: `<config dir>/disk-cleanup-tool/config.toml`
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("disk-cleanup-tool").join("config.toml"))
+    }
+
+    pub fn load(path: &Path) -> Result<Config, ConfigError> {
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::IoError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        toml::from_str(&content).map_err(|e| ConfigError::ParseError {
+            path: path.to_path_buf(),
+            message: e.message().to_string(),
+        })
+    }
+
+    /// Load an explicitly given config, or the default one if it exists
+    pub fn load_or_default(explicit: Option<&Path>) -> Result<Config, ConfigError> {
+        match explicit {
+            Some(path) => Config::load(path),
+            None => match Config::default_path() {
+                Some(path) if path.exists() => Config::load(&path),
+                _ => Ok(Config::default()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_load_rules() {
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(
+            temp_file.path(),
+            r#"
+[[rules]]
+path_pattern = "~/Library/Caches/*"
+category = "os_cache"
+
+[[rules]]
+path_pattern = "**/Pods"
+category = "dependency_cache"
+sibling = "Podfile"
+label = "CocoaPods"
+
+[[rules]]
+path_pattern = "photos/build"
+"#,
+        )
+        .unwrap();
+
+        let config = Config::load(temp_file.path()).unwrap();
+        assert_eq!(config.rules.len(), 3);
+        assert_eq!(config.rules[0].category, EntryType::OsCache);
+        assert_eq!(config.rules[1].sibling.as_deref(), Some("Podfile"));
+        assert_eq!(config.rules[1].label.as_deref(), Some("CocoaPods"));
+        assert_eq!(config.rules[2].label, None);
+        assert_eq!(config.rules[2].category, EntryType::Temp);
+    }
+
+    #[test]
+    fn test_empty_config() {
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), "").unwrap();
+
+        let config = Config::load(temp_file.path()).unwrap();
+        assert!(config.rules.is_empty());
+        assert!(!config.read_only);
+    }
+
+    #[test]
+    fn test_read_only() {
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), "read_only = true\n").unwrap();
+        assert!(Config::load(temp_file.path()).unwrap().read_only);
+    }
+
+    #[test]
+    fn test_matching() {
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), "[matching]\nignore_case = true\nnormalize_unicode = false\n").unwrap();
+        let matching = Config::load(temp_file.path()).unwrap().matching;
+        assert!(matching.ignore_case);
+        assert!(!matching.normalize_unicode);
+
+        fs::write(temp_file.path(), "[matching]\ncase = \"ignore\"\n").unwrap();
+        assert!(Config::load(temp_file.path()).is_err());
+    }
+
+    #[test]
+    fn test_palette() {
+        let temp_file = NamedTempFile::new().unwrap();
+        assert_eq!(Config::default().palette, Palette::Heat);
+        fs::write(temp_file.path(), "palette = \"colorblind\"\n").unwrap();
+        assert_eq!(Config::load(temp_file.path()).unwrap().palette, Palette::Colorblind);
+
+        fs::write(temp_file.path(), "palette = \"rainbow\"\n").unwrap();
+        assert!(Config::load(temp_file.path()).is_err());
+    }
+
+    #[test]
+    fn test_notify_after() {
+        assert_eq!(Config::default().notify_after, None);
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), "notify_after = \"2m\"\n").unwrap();
+        assert_eq!(Config::load(temp_file.path()).unwrap().notify_after, Some(Duration::from_secs(120)));
+
+        fs::write(temp_file.path(), "notify_after = \"soon\"\n").unwrap();
+        assert!(Config::load(temp_file.path()).is_err());
+    }
+
+    #[test]
+    fn test_hooks() {
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), "[hooks]\npost_deletion = \"sync\"\n").unwrap();
+        let hooks = Config::load(temp_file.path()).unwrap().hooks;
+        assert_eq!(hooks.pre_deletion, None);
+        assert_eq!(hooks.post_deletion.as_deref(), Some("sync"));
+
+        fs::write(temp_file.path(), "[hooks]\nafter = \"sync\"\n").unwrap();
+        assert!(Config::load(temp_file.path()).is_err());
+    }
+
+    #[test]
+    fn test_large_deletion_threshold() {
+        assert_eq!(Config::default().large_deletion_threshold, 50 * 1024 * 1024 * 1024);
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), "large_deletion_threshold = \"10 GB\"\n").unwrap();
+        let config = Config::load(temp_file.path()).unwrap();
+        assert_eq!(config.large_deletion_threshold, 10 * 1024 * 1024 * 1024);
+
+        fs::write(temp_file.path(), "large_deletion_threshold = 1000000\n").unwrap();
+        let config = Config::load(temp_file.path()).unwrap();
+        assert_eq!(config.large_deletion_threshold, 1_000_000);
+
+        fs::write(temp_file.path(), "large_deletion_threshold = \"lots\"\n").unwrap();
+        assert!(matches!(Config::load(temp_file.path()), Err(ConfigError::ParseError { .. })));
+    }
+
+    #[test]
+    fn test_bookmarks() {
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), "[bookmarks.downloads]\npath = \"~/Downloads\"\ninteractive = true\n").unwrap();
+        let bookmarks = Config::load(temp_file.path()).unwrap().bookmarks;
+        assert_eq!(bookmarks["downloads"].path, PathBuf::from("~/Downloads"));
+        assert!(bookmarks["downloads"].interactive);
+
+        fs::write(temp_file.path(), "[bookmarks.downloads]\ninteractive = true\n").unwrap();
+        assert!(Config::load(temp_file.path()).is_err());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), "[[rules]]\ncategory = \"temp\"\n").unwrap();
+
+        let result = Config::load(temp_file.path());
+        assert!(matches!(result, Err(ConfigError::ParseError { .. })));
+    }
+
+    #[test]
+    fn test_missing_explicit_config() {
+        let result = Config::load_or_default(Some(Path::new("/nonexistent/config.toml")));
+        assert!(matches!(result, Err(ConfigError::IoError { .. })));
+    }
+}
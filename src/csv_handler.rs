@@ -1,10 +1,20 @@
+use crate::ages::{AgeBuckets, BUCKET_COLUMNS};
 use crate::scanner::{DirectoryEntry, EntryType};
-use csv::{Reader, Writer};
-use std::fs::File;
-use std::path::Path;
+use csv::{Reader, StringRecord, Writer};
+use std::io::{BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use thiserror::Error;
 
+/// Schema written by this version. 1 is the legacy headerless format (with or without
+/// cumulative columns); 2 adds the `#` metadata block and requires the cumulative columns.
+pub const CSV_SCHEMA_VERSION: u32 = 2;
+
+/// First line of the metadata block
+const MAGIC: &str = "# disk-cleanup-tool csv";
+
 #[derive(Debug, Error)]
+#[allow(clippy::enum_variant_names)]
 pub enum CsvError {
     #[error("Missing required column: {0}")]
     MissingColumn(String),
@@ -12,6 +22,9 @@ pub enum CsvError {
     #[error("Parse error at line {line}: {message}")]
     ParseError { line: usize, message: String },
 
+    #[error("Unsupported scan file schema version {found} (this version reads up to {supported}); upgrade disk-cleanup-tool")]
+    UnsupportedVersion { found: u32, supported: u32 },
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
@@ -19,122 +32,386 @@ pub enum CsvError {
     CsvError(#[from] csv::Error),
 }
 
-pub fn write_csv(entries: &[DirectoryEntry], path: &Path) -> Result<(), CsvError> {
-    let file = File::create(path)?;
+/// Scan details stored in the CSV's metadata block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanMetadata {
+    pub schema_version: u32,
+    /// Directory that was scanned
+    pub root: Option<PathBuf>,
+    pub scanned_at: Option<SystemTime>,
+    pub hostname: Option<String>,
+    /// Version of disk-cleanup-tool that wrote the file
+    pub tool_version: Option<String>,
+}
+
+impl ScanMetadata {
+    /// Metadata for a scan of `root` taken now, on this machine
+    pub fn new(root: Option<&Path>) -> Self {
+        Self {
+            schema_version: CSV_SCHEMA_VERSION,
+            root: root.map(Path::to_path_buf),
+            scanned_at: Some(SystemTime::now()),
+            hostname: crate::utils::hostname(),
+            tool_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        }
+    }
+
+    /// Legacy files have no metadata block
+    fn legacy() -> Self {
+        Self {
+            schema_version: 1,
+            root: None,
+            scanned_at: None,
+            hostname: None,
+            tool_version: None,
+        }
+    }
+}
+
+/// Write a scan as CSV to any writer (e.g. a compressor), handing the writer back to be finished
+pub fn write_csv_to<W: Write>(mut file: W, entries: &[DirectoryEntry], metadata: &ScanMetadata) -> Result<W, CsvError> {
+
+    // Metadata block; read_csv_from skips it and older versions of the tool see a missing header
+    writeln!(file, "{}", MAGIC)?;
+    writeln!(file, "# schema_version: {}", metadata.schema_version)?;
+    if let Some(root) = &metadata.root {
+        writeln!(file, "# root: {}", root.display())?;
+    }
+    if let Some(scanned_at) = metadata.scanned_at {
+        writeln!(file, "# scanned_at: {}", humantime::format_rfc3339_seconds(scanned_at))?;
+    }
+    if let Some(hostname) = &metadata.hostname {
+        writeln!(file, "# hostname: {}", hostname)?;
+    }
+    if let Some(tool_version) = &metadata.tool_version {
+        writeln!(file, "# tool_version: {}", tool_version)?;
+    }
+
     let mut writer = Writer::from_writer(file);
 
+    // Merged scans get an extra column naming where each entry came from, saved
+    // interactive sessions one marking the selection, fresh scans the inode counts and
+    // file age buckets, scans that hit unreadable directories their error counts, and scans
+    // that reached a directory through two paths (bind mounts) the alias, scans of every
+    // mount the mount point, `--estimate` scans which sizes are extrapolated, and fresh
+    // scans the space allocated on disk
+    let with_source = entries.iter().any(|e| e.source.is_some());
+    let with_selected = entries.iter().any(|e| e.selected);
+    let with_inodes = entries.iter().any(|e| e.inodes > 0);
+    let with_ages = entries.iter().any(|e| !e.ages.is_empty());
+    let with_errors = entries.iter().any(|e| e.errors > 0);
+    let with_aliases = entries.iter().any(|e| e.alias_of.is_some());
+    let with_mounts = entries.iter().any(|e| e.mount.is_some());
+    let with_estimates = entries.iter().any(|e| e.estimated);
+    let with_allocated = entries.iter().any(|e| e.cumulative_allocated_bytes > 0);
+
     // Write header
-    writer.write_record(&["path", "files", "size_bytes", "cumulative_files", "cumulative_size_bytes", "type"])?;
+    let mut header = vec!["path", "files", "size_bytes", "cumulative_files", "cumulative_size_bytes", "type"];
+    if with_source {
+        header.push("source");
+    }
+    if with_selected {
+        header.push("selected");
+    }
+    if with_inodes {
+        header.push("inodes");
+    }
+    if with_ages {
+        header.extend(BUCKET_COLUMNS);
+    }
+    if with_errors {
+        header.push("errors");
+    }
+    if with_aliases {
+        header.push("alias_of");
+    }
+    if with_mounts {
+        header.push("mount");
+    }
+    if with_estimates {
+        header.push("estimated");
+    }
+    if with_allocated {
+        header.push("cumulative_allocated_bytes");
+    }
+    writer.write_record(&header)?;
 
     // Write entries
     for entry in entries {
-        let entry_type = match entry.entry_type {
-            EntryType::Temp => "temp",
-            EntryType::Normal => "normal",
-        };
-
-        writer.write_record(&[
-            entry.path.to_string_lossy().as_ref(),
-            &entry.file_count.to_string(),
-            &entry.size_bytes.to_string(),
-            &entry.cumulative_file_count.to_string(),
-            &entry.cumulative_size_bytes.to_string(),
-            entry_type,
-        ])?;
+        let mut record = vec![
+            entry.path.to_string_lossy().into_owned(),
+            entry.file_count.to_string(),
+            entry.size_bytes.to_string(),
+            entry.cumulative_file_count.to_string(),
+            entry.cumulative_size_bytes.to_string(),
+            entry.entry_type.as_str().to_string(),
+        ];
+        if with_source {
+            record.push(entry.source.clone().unwrap_or_default());
+        }
+        if with_selected {
+            record.push(entry.selected.to_string());
+        }
+        if with_inodes {
+            record.push(entry.inodes.to_string());
+        }
+        if with_ages {
+            record.extend(entry.ages.0.iter().map(u64::to_string));
+        }
+        if with_errors {
+            record.push(entry.errors.to_string());
+        }
+        if with_aliases {
+            record.push(entry.alias_of.as_ref().map(|p| p.to_string_lossy().into_owned()).unwrap_or_default());
+        }
+        if with_mounts {
+            record.push(entry.mount.as_ref().map(|p| p.to_string_lossy().into_owned()).unwrap_or_default());
+        }
+        if with_estimates {
+            record.push(entry.estimated.to_string());
+        }
+        if with_allocated {
+            record.push(entry.cumulative_allocated_bytes.to_string());
+        }
+        writer.write_record(&record)?;
     }
 
-    writer.flush()?;
-    Ok(())
+    writer.into_inner().map_err(|e| CsvError::IoError(e.into_error()))
 }
 
-pub fn read_csv(path: &Path) -> Result<Vec<DirectoryEntry>, CsvError> {
-    let file = File::open(path)?;
-    let mut reader = Reader::from_reader(file);
+/// Parse the leading `# key: value` block; returns the metadata and the number of lines it spans
+fn read_metadata(reader: &mut impl BufRead) -> Result<(ScanMetadata, usize, String), CsvError> {
+    let mut metadata = ScanMetadata::legacy();
+    let mut lines = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || !line.starts_with('#') {
+            // First non-metadata line (the CSV header) is handed back to the caller
+            return Ok((metadata, lines, line));
+        }
+        lines += 1;
 
-    // Verify headers
-    let headers = reader.headers()?;
-    let required = ["path", "files", "size_bytes", "type"];
-    for req in &required {
-        if !headers.iter().any(|h| h == *req) {
-            return Err(CsvError::MissingColumn(req.to_string()));
+        let Some((key, value)) = line[1..].split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        let invalid = |what: &str| CsvError::ParseError {
+            line: lines,
+            message: format!("Invalid {}: {}", what, value),
+        };
+        match key.trim() {
+            "schema_version" => metadata.schema_version = value.parse().map_err(|_| invalid("schema version"))?,
+            "root" => metadata.root = Some(PathBuf::from(value)),
+            "scanned_at" => {
+                metadata.scanned_at = Some(humantime::parse_rfc3339(value).map_err(|_| invalid("timestamp"))?);
+            }
+            "hostname" => metadata.hostname = Some(value.to_string()),
+            "tool_version" => metadata.tool_version = Some(value.to_string()),
+            // Keys added by newer versions of the same schema are ignored
+            _ => {}
         }
     }
+}
+
+/// Column positions, looked up by header name so columns can be reordered or added
+struct Columns {
+    path: usize,
+    files: usize,
+    size_bytes: usize,
+    cumulative: Option<(usize, usize)>,
+    entry_type: usize,
+    source: Option<usize>,
+    selected: Option<usize>,
+    inodes: Option<usize>,
+    ages: Option<[usize; 4]>,
+    errors: Option<usize>,
+    alias_of: Option<usize>,
+    mount: Option<usize>,
+    estimated: Option<usize>,
+    allocated: Option<usize>,
+}
+
+impl Columns {
+    fn from_headers(headers: &StringRecord, schema_version: u32) -> Result<Self, CsvError> {
+        let find = |name: &str| headers.iter().position(|h| h == name);
+        let require = |name: &str| find(name).ok_or_else(|| CsvError::MissingColumn(name.to_string()));
+
+        let cumulative = match (find("cumulative_files"), find("cumulative_size_bytes")) {
+            (Some(files), Some(size)) => Some((files, size)),
+            // Only legacy files may omit the cumulative columns
+            _ if schema_version >= 2 => {
+                require("cumulative_files")?;
+                return Err(CsvError::MissingColumn("cumulative_size_bytes".to_string()));
+            }
+            _ => None,
+        };
+        Ok(Self {
+            path: require("path")?,
+            files: require("files")?,
+            size_bytes: require("size_bytes")?,
+            cumulative,
+            entry_type: require("type")?,
+            source: find("source"),
+            selected: find("selected"),
+            inodes: find("inodes"),
+            ages: BUCKET_COLUMNS
+                .iter()
+                .map(|name| find(name))
+                .collect::<Option<Vec<_>>>()
+                .map(|found| [found[0], found[1], found[2], found[3]]),
+            errors: find("errors"),
+            alias_of: find("alias_of"),
+            mount: find("mount"),
+            estimated: find("estimated"),
+            allocated: find("cumulative_allocated_bytes"),
+        })
+    }
+}
+
+/// Read a CSV written by any supported schema version, along with its scan metadata, from
+/// any buffered reader (a file or a decompressor)
+pub fn read_csv_from<R: BufRead>(mut file: R) -> Result<(Vec<DirectoryEntry>, ScanMetadata), CsvError> {
+    let (metadata, metadata_lines, header_line) = read_metadata(&mut file)?;
+    if metadata.schema_version > CSV_SCHEMA_VERSION {
+        return Err(CsvError::UnsupportedVersion {
+            found: metadata.schema_version,
+            supported: CSV_SCHEMA_VERSION,
+        });
+    }
+    let mut reader = Reader::from_reader(header_line.as_bytes().chain(file));
 
-    // Check if we have cumulative columns (new format)
-    let has_cumulative = headers.iter().any(|h| h == "cumulative_files");
+    // Verify headers
+    let columns = Columns::from_headers(reader.headers()?, metadata.schema_version)?;
+    let width = [columns.path, columns.files, columns.size_bytes, columns.entry_type]
+        .into_iter()
+        .chain(columns.cumulative.into_iter().flat_map(|(files, size)| [files, size]))
+        .max()
+        .unwrap_or(0)
+        + 1;
 
     let mut entries = Vec::new();
 
     for (line_num, result) in reader.records().enumerate() {
+        // +2 because line 1 is header and enumerate starts at 0
+        let line = metadata_lines + line_num + 2;
         let record = result.map_err(|e| CsvError::ParseError {
-            line: line_num + 2, // +2 because line 1 is header and enumerate starts at 0
+            line,
             message: e.to_string(),
         })?;
 
-        let expected_cols = if has_cumulative { 6 } else { 4 };
-        if record.len() < expected_cols {
+        if record.len() < width {
             return Err(CsvError::ParseError {
-                line: line_num + 2,
-                message: format!("Expected {} columns, found {}", expected_cols, record.len()),
+                line,
+                message: format!("Expected {} columns, found {}", width, record.len()),
             });
         }
 
-        let path = record[0].into();
-        let file_count = record[1].parse::<u64>().map_err(|e| CsvError::ParseError {
-            line: line_num + 2,
-            message: format!("Invalid file count: {}", e),
-        })?;
-        let size_bytes = record[2].parse::<u64>().map_err(|e| CsvError::ParseError {
-            line: line_num + 2,
-            message: format!("Invalid size: {}", e),
-        })?;
+        let number = |idx: usize, what: &str| {
+            record[idx].parse::<u64>().map_err(|e| CsvError::ParseError {
+                line,
+                message: format!("Invalid {}: {}", what, e),
+            })
+        };
+
+        let path = record[columns.path].into();
+        let file_count = number(columns.files, "file count")?;
+        let size_bytes = number(columns.size_bytes, "size")?;
 
-        let (cumulative_file_count, cumulative_size_bytes, type_idx) = if has_cumulative {
-            let cum_files = record[3].parse::<u64>().map_err(|e| CsvError::ParseError {
-                line: line_num + 2,
-                message: format!("Invalid cumulative file count: {}", e),
-            })?;
-            let cum_size = record[4].parse::<u64>().map_err(|e| CsvError::ParseError {
-                line: line_num + 2,
-                message: format!("Invalid cumulative size: {}", e),
-            })?;
-            (cum_files, cum_size, 5)
-        } else {
+        let (cumulative_file_count, cumulative_size_bytes) = match columns.cumulative {
+            Some((files, size)) => (number(files, "cumulative file count")?, number(size, "cumulative size")?),
             // Old format: use direct values as cumulative
-            (file_count, size_bytes, 3)
+            None => (file_count, size_bytes),
         };
 
-        let entry_type = match &record[type_idx] {
-            "temp" => EntryType::Temp,
-            "normal" => EntryType::Normal,
-            other => {
-                return Err(CsvError::ParseError {
-                    line: line_num + 2,
-                    message: format!("Invalid entry type: {}", other),
-                })
-            }
-        };
+        let type_label = &record[columns.entry_type];
+        let entry_type = EntryType::from_label(type_label).ok_or_else(|| CsvError::ParseError {
+            line,
+            message: format!("Invalid entry type: {}", type_label),
+        })?;
 
+        let errors = columns.errors.map(|idx| number(idx, "error count")).transpose()?.unwrap_or(0);
         entries.push(DirectoryEntry {
             path,
             file_count,
             size_bytes,
             cumulative_file_count,
             cumulative_size_bytes,
+            cumulative_allocated_bytes: columns.allocated.map(|idx| number(idx, "allocated size")).transpose()?.unwrap_or(0),
             entry_type,
+            label: None,
+            active: false,
+            safety: None,
+            modified: None,
+            source: columns
+                .source
+                .and_then(|idx| record.get(idx))
+                .filter(|source| !source.is_empty())
+                .map(str::to_string),
+            selected: columns
+                .selected
+                .and_then(|idx| record.get(idx))
+                .is_some_and(is_marked),
+            owners: Vec::new(),
+            inodes: columns.inodes.map(|idx| number(idx, "inode count")).transpose()?.unwrap_or(0),
+            ages: match columns.ages {
+                Some(idx) => AgeBuckets([
+                    number(idx[0], "age bucket")?,
+                    number(idx[1], "age bucket")?,
+                    number(idx[2], "age bucket")?,
+                    number(idx[3], "age bucket")?,
+                ]),
+                None => AgeBuckets::default(),
+            },
+            incomplete: errors > 0,
+            errors,
+            alias_of: columns
+                .alias_of
+                .and_then(|idx| record.get(idx))
+                .filter(|original| !original.is_empty())
+                .map(PathBuf::from),
+            mount: columns
+                .mount
+                .and_then(|idx| record.get(idx))
+                .filter(|mount| !mount.is_empty())
+                .map(PathBuf::from),
+            estimated: columns
+                .estimated
+                .and_then(|idx| record.get(idx))
+                .is_some_and(|estimated| estimated == "true" || estimated == "1"),
+            images: Vec::new(),
+            previous_size: None,
+            canonical_path: None,
         });
     }
 
-    Ok(entries)
+    Ok((entries, metadata))
+}
+
+/// A `selected` cell that marks its row: `true` as written here, or `1`, `yes`, `y` or `x` as
+/// typed into a spreadsheet, in any case
+fn is_marked(value: &str) -> bool {
+    matches!(value.trim().to_ascii_lowercase().as_str(), "true" | "1" | "yes" | "y" | "x")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::scanner::EntryType;
+    use std::fs::File;
+    use std::io::BufReader;
     use std::path::PathBuf;
     use tempfile::NamedTempFile;
 
+    pub(super) fn write_file(entries: &[DirectoryEntry], path: &Path, metadata: &ScanMetadata) -> Result<(), CsvError> {
+        write_csv_to(File::create(path)?, entries, metadata)?;
+        Ok(())
+    }
+
+    pub(super) fn read_file(path: &Path) -> Result<(Vec<DirectoryEntry>, ScanMetadata), CsvError> {
+        read_csv_from(BufReader::new(File::open(path)?))
+    }
+
     #[test]
     fn test_write_and_read_csv() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -148,6 +425,7 @@ mod tests {
                 cumulative_file_count: 5100,
                 cumulative_size_bytes: 525312000,
                 entry_type: EntryType::Normal,
+                ..Default::default()
             },
             DirectoryEntry {
                 path: PathBuf::from("/home/user/project/node_modules"),
@@ -156,14 +434,15 @@ mod tests {
                 cumulative_file_count: 5000,
                 cumulative_size_bytes: 524288000,
                 entry_type: EntryType::Temp,
+                ..Default::default()
             },
         ];
 
         // Write CSV
-        write_csv(&entries, path).unwrap();
+        write_file(&entries, path, &ScanMetadata::new(None)).unwrap();
 
         // Read CSV back
-        let loaded = read_csv(path).unwrap();
+        let (loaded, _) = read_file(path).unwrap();
 
         assert_eq!(loaded.len(), 2);
         assert_eq!(loaded[0].path, PathBuf::from("/home/user/project"));
@@ -181,6 +460,225 @@ mod tests {
         assert_eq!(loaded[1].entry_type, EntryType::Temp);
     }
 
+    #[test]
+    fn test_csv_metadata() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path();
+
+        let mut metadata = ScanMetadata::new(Some(Path::new("/home/user/projects")));
+        metadata.hostname = Some("workstation".to_string());
+        metadata.scanned_at = Some(humantime::parse_rfc3339("2026-01-02T03:04:05Z").unwrap());
+        let entries = vec![DirectoryEntry {
+            path: PathBuf::from("/home/user/projects/app/target"),
+            entry_type: EntryType::BuildOutput,
+            ..Default::default()
+        }];
+        write_file(&entries, path, &metadata).unwrap();
+
+        let content = std::fs::read_to_string(path).unwrap();
+        assert!(content.starts_with("# disk-cleanup-tool csv\n# schema_version: 2\n"));
+        assert!(content.contains("# scanned_at: 2026-01-02T03:04:05Z\n"));
+
+        let (loaded, loaded_metadata) = read_file(path).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded_metadata, metadata);
+
+        // Legacy files have no metadata and are read as schema 1
+        std::fs::write(path, "path,files,size_bytes,type\n/test,10,100,normal\n").unwrap();
+        let (_, legacy) = read_file(path).unwrap();
+        assert_eq!(legacy.schema_version, 1);
+        assert_eq!(legacy.root, None);
+    }
+
+    #[test]
+    fn test_csv_schema_versions() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path();
+
+        // Columns are found by name, in any order, and unknown columns are ignored
+        std::fs::write(
+            path,
+            "# schema_version: 2\ntype,owner,path,cumulative_size_bytes,cumulative_files,size_bytes,files\nbuild_output,me,/a/target,500,5,100,1\n",
+        )
+        .unwrap();
+        let (loaded, _) = read_file(path).unwrap();
+        assert_eq!(loaded[0].path, PathBuf::from("/a/target"));
+        assert_eq!(loaded[0].entry_type, EntryType::BuildOutput);
+        assert_eq!((loaded[0].file_count, loaded[0].size_bytes), (1, 100));
+        assert_eq!((loaded[0].cumulative_file_count, loaded[0].cumulative_size_bytes), (5, 500));
+
+        // Schema 2 requires the cumulative columns
+        std::fs::write(path, "# schema_version: 2\npath,files,size_bytes,type\n/test,10,100,normal\n").unwrap();
+        assert!(matches!(read_file(path), Err(CsvError::MissingColumn(c)) if c == "cumulative_files"));
+
+        // Files from a newer version are refused rather than misread
+        std::fs::write(path, "# schema_version: 3\npath,files,size_bytes,type\n").unwrap();
+        assert!(matches!(
+            read_file(path),
+            Err(CsvError::UnsupportedVersion { found: 3, supported: CSV_SCHEMA_VERSION })
+        ));
+
+        // Line numbers in errors count the metadata block
+        std::fs::write(path, "# schema_version: 1\n# root: /a\npath,files,size_bytes,type\n/a,x,1,normal\n").unwrap();
+        assert!(matches!(read_file(path), Err(CsvError::ParseError { line: 4, .. })));
+    }
+
+    #[test]
+    fn test_selected_column() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path();
+
+        let entry = |p: &str, selected| DirectoryEntry {
+            path: PathBuf::from(p),
+            selected,
+            ..Default::default()
+        };
+        write_file(&[entry("/a", false), entry("/b", true)], path, &ScanMetadata::new(None)).unwrap();
+        let content = std::fs::read_to_string(path).unwrap();
+        assert!(content.contains(",type,selected\n"));
+        assert!(content.contains("/b,0,0,0,0,normal,true\n"));
+
+        let (loaded, _) = read_file(path).unwrap();
+        assert!(!loaded[0].selected);
+        assert!(loaded[1].selected);
+
+        // Marked by hand in a spreadsheet, in a column of its own
+        std::fs::write(path, "path,files,size_bytes,cumulative_files,cumulative_size_bytes,type,selected\n/a,0,0,0,0,normal,TRUE\n/b,0,0,0,0,normal, x \n/c,0,0,0,0,normal,no\n/d,0,0,0,0,normal,\n").unwrap();
+        let (loaded, _) = read_file(path).unwrap();
+        assert_eq!(loaded.iter().map(|e| e.selected).collect::<Vec<_>>(), [true, true, false, false]);
+
+        // Without any selection the column is left out
+        write_file(&[entry("/a", false)], path, &ScanMetadata::new(None)).unwrap();
+        assert!(!std::fs::read_to_string(path).unwrap().contains("selected"));
+    }
+
+    #[test]
+    fn test_inode_and_age_columns() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path();
+
+        let entries = vec![
+            DirectoryEntry {
+                path: PathBuf::from("/a"),
+                ages: AgeBuckets([1, 2, 3, 4]),
+                inodes: 7,
+                ..Default::default()
+            },
+            DirectoryEntry {
+                path: PathBuf::from("/b"),
+                ..Default::default()
+            },
+        ];
+        write_file(&entries, path, &ScanMetadata::new(None)).unwrap();
+        let content = std::fs::read_to_string(path).unwrap();
+        assert!(content.contains(",type,inodes,bytes_under_1m,bytes_1_6m,bytes_6_12m,bytes_over_1y\n"));
+        assert!(content.contains("/a,0,0,0,0,normal,7,1,2,3,4\n"));
+
+        let (loaded, _) = read_file(path).unwrap();
+        assert_eq!(loaded[0].ages, AgeBuckets([1, 2, 3, 4]));
+        assert_eq!(loaded[0].inodes, 7);
+        assert!(loaded[1].ages.is_empty());
+        assert_eq!(loaded[1].inodes, 0);
+    }
+
+    #[test]
+    fn test_errors_column() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path();
+
+        let entries = vec![
+            DirectoryEntry {
+                path: PathBuf::from("/a"),
+                incomplete: true,
+                errors: 2,
+                ..Default::default()
+            },
+            DirectoryEntry {
+                path: PathBuf::from("/b"),
+                ..Default::default()
+            },
+        ];
+        write_file(&entries, path, &ScanMetadata::new(None)).unwrap();
+        let content = std::fs::read_to_string(path).unwrap();
+        assert!(content.contains("/a,0,0,0,0,normal,2\n"));
+
+        let (loaded, _) = read_file(path).unwrap();
+        assert!(loaded[0].incomplete);
+        assert_eq!(loaded[0].errors, 2);
+        assert!(!loaded[1].incomplete);
+
+        // Complete scans leave the column out
+        write_file(&entries[1..], path, &ScanMetadata::new(None)).unwrap();
+        assert!(!std::fs::read_to_string(path).unwrap().contains("errors"));
+    }
+
+    #[test]
+    fn test_alias_column() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path();
+
+        let entries = vec![
+            DirectoryEntry {
+                path: PathBuf::from("/data"),
+                ..Default::default()
+            },
+            DirectoryEntry {
+                path: PathBuf::from("/mnt/data"),
+                alias_of: Some(PathBuf::from("/data")),
+                ..Default::default()
+            },
+        ];
+        write_file(&entries, path, &ScanMetadata::new(None)).unwrap();
+        let content = std::fs::read_to_string(path).unwrap();
+        assert!(content.contains(",type,alias_of\n"));
+        assert!(content.contains("/mnt/data,0,0,0,0,normal,/data\n"));
+
+        let (loaded, _) = read_file(path).unwrap();
+        assert_eq!(loaded[0].alias_of, None);
+        assert_eq!(loaded[1].alias_of, Some(PathBuf::from("/data")));
+    }
+
+    #[test]
+    fn test_mount_column() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path();
+
+        let entries = ["/", "/var", "/home"].map(|dir| DirectoryEntry {
+            path: PathBuf::from(dir),
+            mount: Some(PathBuf::from(if dir == "/home" { "/home" } else { "/" })),
+            ..Default::default()
+        });
+        write_file(&entries, path, &ScanMetadata::new(None)).unwrap();
+        let content = std::fs::read_to_string(path).unwrap();
+        assert!(content.contains(",type,mount\n"));
+        assert!(content.contains("/var,0,0,0,0,normal,/\n"));
+
+        let (loaded, _) = read_file(path).unwrap();
+        let mounts: Vec<_> = loaded.iter().map(|e| e.mount.as_deref().unwrap()).collect();
+        assert_eq!(mounts, [Path::new("/"), Path::new("/"), Path::new("/home")]);
+    }
+
+    #[test]
+    fn test_allocated_column() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path();
+
+        let entries = [("/vm", 8192), ("/vm/disks", 4096)].map(|(dir, allocated)| DirectoryEntry {
+            path: PathBuf::from(dir),
+            cumulative_size_bytes: 64 * 1024,
+            cumulative_allocated_bytes: allocated,
+            ..Default::default()
+        });
+        write_file(&entries, path, &ScanMetadata::new(None)).unwrap();
+        let content = std::fs::read_to_string(path).unwrap();
+        assert!(content.contains(",type,cumulative_allocated_bytes\n"));
+        assert!(content.contains("/vm/disks,0,0,0,65536,normal,4096\n"));
+
+        let (loaded, _) = read_file(path).unwrap();
+        assert_eq!(loaded[0].cumulative_allocated_bytes, 8192);
+        assert_eq!(loaded[1].allocation_difference(), 4096 - 65536);
+    }
+
     #[test]
     fn test_read_malformed_csv() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -189,7 +687,7 @@ mod tests {
         // Write malformed CSV (missing column)
         std::fs::write(path, "path,files,size_bytes\n/test,10,100\n").unwrap();
 
-        let result = read_csv(path);
+        let result = read_file(path);
         assert!(matches!(result, Err(CsvError::MissingColumn(_))));
     }
 
@@ -201,7 +699,7 @@ mod tests {
         // Write old format CSV (without cumulative columns)
         std::fs::write(path, "path,files,size_bytes,type\n/test,10,100,normal\n").unwrap();
 
-        let result = read_csv(path).unwrap();
+        let (result, _) = read_file(path).unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].file_count, 10);
         assert_eq!(result[0].size_bytes, 100);
@@ -210,6 +708,26 @@ mod tests {
         assert_eq!(result[0].cumulative_size_bytes, 100);
     }
 
+    #[test]
+    fn test_read_category_types() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path();
+
+        std::fs::write(
+            path,
+            "path,files,size_bytes,type\n/a/target,1,10,build_output\n/a/.venv,1,10,virtual_env\n/a/tmp,1,10,temp\n",
+        )
+        .unwrap();
+
+        let (result, _) = read_file(path).unwrap();
+        assert_eq!(result[0].entry_type, EntryType::BuildOutput);
+        assert_eq!(result[1].entry_type, EntryType::VirtualEnv);
+        assert_eq!(result[2].entry_type, EntryType::Temp);
+
+        std::fs::write(path, "path,files,size_bytes,type\n/a,1,10,bogus\n").unwrap();
+        assert!(matches!(read_file(path), Err(CsvError::ParseError { .. })));
+    }
+
     #[test]
     fn test_read_invalid_number() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -218,7 +736,7 @@ mod tests {
         // Write CSV with invalid number (old format)
         std::fs::write(path, "path,files,size_bytes,type\n/test,abc,100,normal\n").unwrap();
 
-        let result = read_csv(path);
+        let result = read_file(path);
         assert!(matches!(result, Err(CsvError::ParseError { .. })));
     }
 }
@@ -226,6 +744,7 @@ mod tests {
 
 #[cfg(test)]
 mod proptests {
+    use super::tests::{read_file, write_file};
     use super::*;
     use crate::scanner::EntryType;
     use proptest::prelude::*;
@@ -255,13 +774,14 @@ mod proptests {
                 cumulative_file_count: file_count,
                 cumulative_size_bytes: size_bytes,
                 entry_type,
+                ..Default::default()
             }];
 
-            write_csv(&entries, csv_path).unwrap();
+            write_file(&entries, csv_path, &ScanMetadata::new(None)).unwrap();
 
             // Read the CSV as text and check type column
             let content = std::fs::read_to_string(csv_path).unwrap();
-            let lines: Vec<&str> = content.lines().collect();
+            let lines: Vec<&str> = content.lines().filter(|l| !l.starts_with('#')).collect();
             
             prop_assert!(lines.len() >= 2); // header + data
             
@@ -289,12 +809,13 @@ mod proptests {
                 cumulative_file_count: 1,
                 cumulative_size_bytes: size_bytes,
                 entry_type: EntryType::Normal,
+                ..Default::default()
             }];
 
-            write_csv(&entries, csv_path).unwrap();
+            write_file(&entries, csv_path, &ScanMetadata::new(None)).unwrap();
 
             let content = std::fs::read_to_string(csv_path).unwrap();
-            let lines: Vec<&str> = content.lines().collect();
+            let lines: Vec<&str> = content.lines().filter(|l| !l.starts_with('#')).collect();
             let data_line = lines[1];
             let parts: Vec<&str> = data_line.split(',').collect();
             
@@ -326,12 +847,13 @@ mod proptests {
                     cumulative_file_count: file_count + i as u64,
                     cumulative_size_bytes: size_bytes + (i as u64 * 100),
                     entry_type: if i % 2 == 0 { EntryType::Temp } else { EntryType::Normal },
+                    ..Default::default()
                 });
             }
 
             // Write and read back
-            write_csv(&entries, csv_path).unwrap();
-            let loaded = read_csv(csv_path).unwrap();
+            write_file(&entries, csv_path, &ScanMetadata::new(None)).unwrap();
+            let (loaded, _) = read_file(csv_path).unwrap();
 
             prop_assert_eq!(entries.len(), loaded.len());
             
@@ -358,7 +880,7 @@ mod proptests {
             let content = format!("path,files,size_bytes,type\n/test,{},100,normal\n", bad_number);
             std::fs::write(csv_path, content).unwrap();
 
-            let result = read_csv(csv_path);
+            let result = read_file(csv_path);
             prop_assert!(result.is_err());
         }
     }
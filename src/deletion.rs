@@ -1,4 +1,9 @@
-use crate::utils::format_size;
+use crate::i18n::t;
+use crate::interactive::rebuild_cost_style;
+use crate::mounts;
+use crate::owners;
+use crate::trash::TrashBatch;
+use crate::utils::{format_size, RebuildCost};
 use crossterm::{
     event::{self, Event, KeyCode},
     execute,
@@ -12,9 +17,13 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, Paragraph},
     Frame, Terminal,
 };
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use thiserror::Error;
 use walkdir::WalkDir;
 
@@ -28,14 +37,118 @@ pub enum DeletionError {
     DeletionFailed { path: PathBuf, reason: String },
 }
 
+/// A directory that could not be deleted, for machine-readable output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FailedPath {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// What happens to a directory picked for deletion; chosen per entry with Tab in the
+/// confirmation step
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DeletionMode {
+    /// Remove the directory
+    #[default]
+    Remove,
+    /// Remove its contents and keep the (now empty) directory, for tools that break when
+    /// their cache directory disappears
+    Empty,
+    /// As `Empty`, but also keep a `.gitkeep` file at its top level
+    EmptyKeepGitkeep,
+}
+
+impl DeletionMode {
+    /// Next mode in the confirmation step's Tab cycle
+    fn next(self) -> Self {
+        match self {
+            DeletionMode::Remove => DeletionMode::Empty,
+            DeletionMode::Empty => DeletionMode::EmptyKeepGitkeep,
+            DeletionMode::EmptyKeepGitkeep => DeletionMode::Remove,
+        }
+    }
+
+    fn label(self) -> String {
+        match self {
+            DeletionMode::Remove => t!("deletion-mode-remove"),
+            DeletionMode::Empty => t!("deletion-mode-empty"),
+            DeletionMode::EmptyKeepGitkeep => t!("deletion-mode-empty-gitkeep"),
+        }
+    }
+}
+
+/// Why deleting a path is certain to fail, found before asking for confirmation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preflight {
+    /// It or the directory it is in is on a filesystem mounted read-only
+    ReadOnly,
+    /// The current user may not remove it
+    NoPermission,
+}
+
+impl Preflight {
+    /// Check `path`; `None` if nothing is known to stand in the way of deleting it
+    pub fn check(path: &Path) -> Option<Self> {
+        let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty());
+        if mounts::is_read_only(path) || parent.is_some_and(mounts::is_read_only) {
+            Some(Preflight::ReadOnly)
+        } else if owners::needs_elevation(path) {
+            Some(Preflight::NoPermission)
+        } else {
+            None
+        }
+    }
+
+    fn label(self) -> String {
+        match self {
+            Preflight::ReadOnly => t!("deletion-read-only"),
+            Preflight::NoPermission => t!("deletion-needs-sudo"),
+        }
+    }
+}
+
+/// Preflight every one of `paths`, keeping those that will fail
+fn preflight_all(paths: &[PathBuf]) -> HashMap<PathBuf, Preflight> {
+    paths.iter().filter_map(|path| Some((path.clone(), Preflight::check(path)?))).collect()
+}
+
 pub struct DeletionReport {
     pub successful: Vec<PathBuf>,
     pub failed: Vec<(PathBuf, String)>,
+    /// Paths left untouched because the batch was cancelled
+    pub cancelled: Vec<PathBuf>,
     pub total_freed_bytes: u64,
+    /// How many of `successful` were files rather than directories
+    pub deleted_files: usize,
+    /// Those of `failed` that something had open or locked, worth retrying once it is closed
+    pub retryable: Vec<PathBuf>,
 }
 
 impl DeletionReport {
-    pub fn show_report(&self) -> io::Result<()> {
+    pub fn failures(&self) -> Vec<FailedPath> {
+        self.failed
+            .iter()
+            .map(|(path, reason)| FailedPath { path: path.clone(), reason: reason.clone() })
+            .collect()
+    }
+
+    /// Fold in the report of retrying `retryable`: what went through now leaves `failed`,
+    /// and what is still busy can be retried again
+    pub fn merge_retry(&mut self, retry: DeletionReport) {
+        self.failed.retain(|(path, _)| {
+            !retry.successful.contains(path) && !retry.failed.iter().any(|(failed, _)| failed == path)
+        });
+        self.failed.extend(retry.failed);
+        self.successful.extend(retry.successful);
+        self.total_freed_bytes += retry.total_freed_bytes;
+        self.deleted_files += retry.deleted_files;
+        self.retryable = retry.retryable;
+        self.retryable.extend(retry.cancelled);
+    }
+
+    /// Show the report until it is closed; returns whether the user asked to retry
+    /// `retryable` instead
+    pub fn show_report(&self) -> io::Result<bool> {
         // Setup terminal
         enable_raw_mode()?;
         let mut stdout = io::stdout();
@@ -52,12 +165,39 @@ impl DeletionReport {
 
         result
     }
+
+    /// Plain-text version of the report, for scripted runs and when the UI can't start
+    pub fn print_summary(&self) {
+        println!("\n{}", t!("deletion-complete"));
+        println!("  {}{}", t!("deletion-successful"), self.successful.len());
+        if self.deleted_files > 0 {
+            println!("    {}{}", t!("deletion-files"), self.deleted_files);
+        }
+        println!("  {}{}", t!("deletion-failed"), self.failed.len());
+        for (path, reason) in &self.failed {
+            println!("    {}: {}", path.display(), reason);
+        }
+        if !self.retryable.is_empty() {
+            println!("    {}", t!("deletion-retryable", count = self.retryable.len()));
+        }
+        println!("  {}{}", t!("deletion-cancelled-count"), self.cancelled.len());
+        println!("  {}{}", t!("deletion-freed"), format_size(self.total_freed_bytes));
+    }
+}
+
+/// Whether a deletion failed because another program had something open, locked or was
+/// still writing to it, which closing that program fixes
+pub fn is_transient(error: &io::Error) -> bool {
+    use io::ErrorKind;
+    // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION, which std reports as uncategorized
+    matches!(error.kind(), ErrorKind::ResourceBusy | ErrorKind::ExecutableFileBusy | ErrorKind::DirectoryNotEmpty)
+        || (cfg!(windows) && matches!(error.raw_os_error(), Some(32 | 33)))
 }
 
 fn run_report_ui(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     report: &DeletionReport,
-) -> io::Result<()> {
+) -> io::Result<bool> {
     let mut scroll_offset = 0usize;
     
     loop {
@@ -69,7 +209,10 @@ fn run_report_ui(
             if let Event::Key(key) = event::read()? {
                 match key.code {
                     KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => {
-                        return Ok(());
+                        return Ok(false);
+                    }
+                    KeyCode::Char('r') if !report.retryable.is_empty() => {
+                        return Ok(true);
                     }
                     KeyCode::Up => {
                         scroll_offset = scroll_offset.saturating_sub(1);
@@ -103,20 +246,27 @@ fn render_report(f: &mut Frame, report: &DeletionReport, scroll_offset: usize) {
         .split(f.area());
 
     // Header
-    let success_color = if report.failed.is_empty() { Color::Green } else { Color::Yellow };
+    let success_color = if report.failed.is_empty() && report.cancelled.is_empty() {
+        Color::Green
+    } else {
+        Color::Yellow
+    };
+    let title = if report.cancelled.is_empty() { t!("deletion-report-complete") } else { t!("deletion-report-cancelled") };
     let header = Paragraph::new(vec![
         Line::from(vec![
-            Span::styled("✓ Deletion Complete", Style::default().fg(success_color).add_modifier(Modifier::BOLD)),
+            Span::styled(title, Style::default().fg(success_color).add_modifier(Modifier::BOLD)),
         ]),
         Line::from(""),
         Line::from(vec![
-            Span::raw("Successfully deleted: "),
+            Span::raw(t!("deletion-successful")),
             Span::styled(format!("{}", report.successful.len()), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
         ]),
         Line::from(vec![
-            Span::raw("Failed: "),
+            Span::raw(t!("deletion-failed")),
             Span::styled(format!("{}", report.failed.len()), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-            Span::raw("  |  Space freed: "),
+            Span::raw(format!("  |  {}", t!("deletion-cancelled-count"))),
+            Span::styled(format!("{}", report.cancelled.len()), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::raw(format!("  |  {}", t!("deletion-freed"))),
             Span::styled(format_size(report.total_freed_bytes), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
         ]),
     ])
@@ -135,7 +285,13 @@ fn render_report(f: &mut Frame, report: &DeletionReport, scroll_offset: usize) {
 
     // Add failed deletions
     for (path, reason) in &report.failed {
-        items.push((false, path.clone(), reason.clone()));
+        let reason = if report.retryable.contains(path) { t!("deletion-busy", reason = reason.clone()) } else { reason.clone() };
+        items.push((false, path.clone(), reason));
+    }
+
+    // Add directories skipped by cancelling
+    for path in &report.cancelled {
+        items.push((false, path.clone(), t!("deletion-not-deleted")));
     }
 
     let list_items: Vec<ListItem> = items
@@ -167,49 +323,58 @@ fn render_report(f: &mut Frame, report: &DeletionReport, scroll_offset: usize) {
         .block(Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::White))
-            .title(format!(" Results ({}/{}) ", scroll_offset + 1, items.len())));
+            .title(t!("deletion-results-title", position = scroll_offset + 1, count = items.len())));
     f.render_widget(list, chunks[1]);
 
     // Footer
-    let footer = Paragraph::new(vec![
-        Line::from(vec![
-            Span::styled("↑/↓", Style::default().fg(Color::Cyan)),
-            Span::raw(": Scroll  |  "),
-            Span::styled("PgUp/PgDn", Style::default().fg(Color::Cyan)),
-            Span::raw(": Page  |  "),
-            Span::styled("Enter", Style::default().fg(Color::Green)),
-            Span::raw(" or "),
-            Span::styled("q", Style::default().fg(Color::Green)),
-            Span::raw(": Close"),
-        ]),
-    ])
+    let mut keys = vec![
+        Span::styled("↑/↓", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  |  ", t!("key-scroll"))),
+        Span::styled("PgUp/PgDn", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  |  ", t!("key-page"))),
+    ];
+    if !report.retryable.is_empty() {
+        keys.push(Span::styled("r", Style::default().fg(Color::Yellow)));
+        keys.push(Span::raw(format!(": {}  |  ", t!("key-retry-failed", count = report.retryable.len()))));
+    }
+    keys.extend([
+        Span::styled("Enter", Style::default().fg(Color::Green)),
+        Span::raw(format!(" {} ", t!("key-or"))),
+        Span::styled("q", Style::default().fg(Color::Green)),
+        Span::raw(format!(": {}", t!("key-close"))),
+    ]);
+    let footer = Paragraph::new(vec![Line::from(keys)])
     .alignment(Alignment::Center)
     .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::White)));
     f.render_widget(footer, chunks[2]);
 }
 
-pub fn confirm_deletion(paths: &[PathBuf]) -> bool {
+/// Ask for confirmation; `costs` annotates each path with how hard it is to get back. With
+/// a `strong` reason (see [`strong_confirmation_reason`]) a single `y` is not enough: the
+/// directory count or DELETE has to be typed. Entries switched to emptying instead of
+/// removing end up in `modes`. Only a `permanent` deletion, not one into the trash or a
+/// grace period, is announced as impossible to undo.
+pub fn confirm_deletion(
+    paths: &[PathBuf],
+    costs: &HashMap<PathBuf, RebuildCost>,
+    modes: &mut HashMap<PathBuf, DeletionMode>,
+    total_size: u64,
+    strong: Option<&str>,
+    permanent: bool,
+) -> bool {
     if paths.is_empty() {
         return false;
     }
 
-    // Calculate total size
-    let mut total_size = 0u64;
-    for path in paths {
-        if let Ok(size) = calculate_dir_size(path) {
-            total_size += size;
-        }
-    }
-
     // Setup terminal
-    if let Err(_) = enable_raw_mode() {
-        return fallback_confirm_deletion(paths, total_size);
+    if enable_raw_mode().is_err() {
+        return fallback_confirm_deletion(paths, costs, modes, total_size, strong, permanent);
     }
     
     let mut stdout = io::stdout();
-    if let Err(_) = execute!(stdout, EnterAlternateScreen) {
+    if execute!(stdout, EnterAlternateScreen).is_err() {
         let _ = disable_raw_mode();
-        return fallback_confirm_deletion(paths, total_size);
+        return fallback_confirm_deletion(paths, costs, modes, total_size, strong, permanent);
     }
     
     let backend = CrosstermBackend::new(stdout);
@@ -217,11 +382,14 @@ pub fn confirm_deletion(paths: &[PathBuf]) -> bool {
         Ok(t) => t,
         Err(_) => {
             let _ = disable_raw_mode();
-            return fallback_confirm_deletion(paths, total_size);
+            return fallback_confirm_deletion(paths, costs, modes, total_size, strong, permanent);
         }
     };
 
-    let result = run_confirmation_ui(&mut terminal, paths, total_size);
+    // Checked once up front; the screen is redrawn ten times a second
+    let doomed = preflight_all(paths);
+    let view = ConfirmationView { paths, costs, total_size, permanent, doomed };
+    let result = run_confirmation_ui(&mut terminal, &view, modes, strong);
 
     // Restore terminal
     let _ = disable_raw_mode();
@@ -231,38 +399,163 @@ pub fn confirm_deletion(paths: &[PathBuf]) -> bool {
     result.unwrap_or(false)
 }
 
-fn fallback_confirm_deletion(paths: &[PathBuf], total_size: u64) -> bool {
-    println!("\n=== DELETION CONFIRMATION ===");
-    println!("You are about to delete {} directories:", paths.len());
+/// Why deleting a selection of `total_size` bytes with `non_temp` normal directories needs
+/// the stronger typed confirmation, or `None` if a single `y` will do
+pub fn strong_confirmation_reason(total_size: u64, threshold: u64, non_temp: usize) -> Option<String> {
+    let size = format_size(threshold);
+    match (total_size > threshold, non_temp > 0) {
+        (false, false) => None,
+        (true, false) => Some(t!("strong-reason-size", size = size)),
+        (false, true) => Some(t!("strong-reason-non-temp", count = non_temp)),
+        (true, true) => Some(t!("strong-reason-both", size = size, count = non_temp)),
+    }
+}
+
+/// Typed text that confirms deleting `count` directories: the count itself or DELETE
+fn is_strong_confirmation(input: &str, count: usize) -> bool {
+    let input = input.trim();
+    input == "DELETE" || input == count.to_string()
+}
+
+/// Current on-disk size of the directories about to be deleted, counting up to `jobs` of
+/// them at once (`--size-jobs`)
+pub fn selection_size(paths: &[PathBuf], jobs: usize) -> u64 {
+    let size = |path: &PathBuf| calculate_dir_size(path).unwrap_or(0);
+    match rayon::ThreadPoolBuilder::new().num_threads(jobs.max(1)).build() {
+        Ok(pool) => pool.install(|| paths.par_iter().map(size).sum()),
+        Err(_) => paths.iter().map(size).sum(),
+    }
+}
+
+/// List the directories to delete with their rebuild cost and, unless they are removed
+/// outright, what is kept of them, and the total size. Paths that will certainly fail are
+/// marked and counted.
+pub fn print_deletion_plan(
+    paths: &[PathBuf],
+    costs: &HashMap<PathBuf, RebuildCost>,
+    modes: &HashMap<PathBuf, DeletionMode>,
+    total_size: u64,
+) {
+    let doomed = preflight_all(paths);
     for path in paths {
-        println!("  - {}", path.display());
+        let mut notes: Vec<String> = costs.get(path).map(RebuildCost::describe).into_iter().collect();
+        match modes.get(path).copied().unwrap_or_default() {
+            DeletionMode::Remove => {}
+            mode => notes.push(mode.label()),
+        }
+        if let Some(failure) = doomed.get(path) {
+            notes.push(failure.label());
+        }
+        if notes.is_empty() {
+            println!("  - {}", path.display());
+        } else {
+            println!("  - {} ({})", path.display(), notes.join(", "));
+        }
+    }
+    println!("\n{}{}", t!("deletion-total-size"), format_size(total_size));
+    if !doomed.is_empty() {
+        println!("{}", t!("deletion-will-fail", count = doomed.len()));
+    }
+}
+
+fn fallback_confirm_deletion(
+    paths: &[PathBuf],
+    costs: &HashMap<PathBuf, RebuildCost>,
+    modes: &HashMap<PathBuf, DeletionMode>,
+    total_size: u64,
+    strong: Option<&str>,
+    permanent: bool,
+) -> bool {
+    println!("\n=== {} ===", t!("deletion-confirmation"));
+    println!("{}", t!("deletion-about-to-delete", count = paths.len()));
+    print_deletion_plan(paths, costs, modes, total_size);
+    if permanent {
+        println!("\n{}", t!("deletion-cannot-undo"));
+    }
+    if let Some(reason) = strong {
+        println!("{}.", reason);
+        print!("{}", t!("deletion-type-strong", count = paths.len()));
+    } else {
+        print!("{}", t!("deletion-type-yes"));
     }
-    println!("\nTotal size to be freed: {}", format_size(total_size));
-    println!("\nThis action cannot be undone!");
-    print!("Type 'yes' to confirm deletion: ");
     use std::io::Write;
     io::stdout().flush().unwrap();
 
     let mut input = String::new();
     io::stdin().read_line(&mut input).unwrap();
 
-    input.trim() == "yes"
+    match strong {
+        Some(_) => is_strong_confirmation(&input, paths.len()),
+        None => input.trim() == "yes",
+    }
+}
+
+/// What the confirmation screen lists, fixed while it is shown
+struct ConfirmationView<'a> {
+    paths: &'a [PathBuf],
+    costs: &'a HashMap<PathBuf, RebuildCost>,
+    total_size: u64,
+    /// Removed for good, rather than moved to the trash or kept for a grace period
+    permanent: bool,
+    /// Paths that will certainly fail to delete
+    doomed: HashMap<PathBuf, Preflight>,
 }
 
 fn run_confirmation_ui(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    paths: &[PathBuf],
-    total_size: u64,
+    view: &ConfirmationView,
+    modes: &mut HashMap<PathBuf, DeletionMode>,
+    strong: Option<&str>,
 ) -> io::Result<bool> {
-    let mut scroll_offset = 0usize;
-    
+    let paths = view.paths;
+    // The highlighted entry, whose deletion mode Tab changes
+    let mut cursor = 0usize;
+    // Text typed for a strong confirmation, and whether the last attempt was wrong
+    let mut typed = String::new();
+    let mut mismatch = false;
+
     loop {
         terminal.draw(|f| {
-            render_confirmation(f, paths, total_size, scroll_offset);
+            let strong = strong.map(|reason| (reason, typed.as_str(), mismatch));
+            render_confirmation(f, view, modes, cursor, strong);
         })?;
 
         if event::poll(std::time::Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
+                // Works in both modes, since Tab is never typed
+                if key.code == KeyCode::Tab {
+                    // Only directories can be emptied
+                    if let Some(path) = paths.get(cursor).filter(|path| path.is_dir()) {
+                        let mode = modes.get(path).copied().unwrap_or_default().next();
+                        modes.insert(path.clone(), mode);
+                    }
+                    continue;
+                }
+                // Typing takes over the letter keys; only Esc cancels
+                if strong.is_some() {
+                    match key.code {
+                        KeyCode::Esc => return Ok(false),
+                        KeyCode::Enter => {
+                            if is_strong_confirmation(&typed, paths.len()) {
+                                return Ok(true);
+                            }
+                            typed.clear();
+                            mismatch = true;
+                            continue;
+                        }
+                        KeyCode::Backspace => {
+                            typed.pop();
+                            continue;
+                        }
+                        KeyCode::Char(c) => {
+                            typed.push(c);
+                            mismatch = false;
+                            continue;
+                        }
+                        _ => {}
+                    }
+                }
+
                 match key.code {
                     KeyCode::Char('y') | KeyCode::Char('Y') => {
                         return Ok(true);
@@ -271,16 +564,16 @@ fn run_confirmation_ui(
                         return Ok(false);
                     }
                     KeyCode::Up => {
-                        scroll_offset = scroll_offset.saturating_sub(1);
+                        cursor = cursor.saturating_sub(1);
                     }
                     KeyCode::Down => {
-                        scroll_offset = scroll_offset.saturating_add(1).min(paths.len().saturating_sub(1));
+                        cursor = cursor.saturating_add(1).min(paths.len().saturating_sub(1));
                     }
                     KeyCode::PageUp => {
-                        scroll_offset = scroll_offset.saturating_sub(10);
+                        cursor = cursor.saturating_sub(10);
                     }
                     KeyCode::PageDown => {
-                        scroll_offset = scroll_offset.saturating_add(10).min(paths.len().saturating_sub(1));
+                        cursor = cursor.saturating_add(10).min(paths.len().saturating_sub(1));
                     }
                     _ => {}
                 }
@@ -289,7 +582,14 @@ fn run_confirmation_ui(
     }
 }
 
-fn render_confirmation(f: &mut Frame, paths: &[PathBuf], total_size: u64, scroll_offset: usize) {
+fn render_confirmation(
+    f: &mut Frame,
+    view: &ConfirmationView,
+    modes: &HashMap<PathBuf, DeletionMode>,
+    cursor: usize,
+    strong: Option<(&str, &str, bool)>,
+) {
+    let ConfirmationView { paths, costs, total_size, permanent, ref doomed } = *view;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -302,15 +602,15 @@ fn render_confirmation(f: &mut Frame, paths: &[PathBuf], total_size: u64, scroll
     // Header
     let header = Paragraph::new(vec![
         Line::from(vec![
-            Span::styled("⚠️  DELETION CONFIRMATION", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+            Span::styled(format!("⚠️  {}", t!("deletion-confirmation")), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
         ]),
         Line::from(""),
         Line::from(vec![
-            Span::raw("Directories to delete: "),
+            Span::raw(t!("deletion-directories")),
             Span::styled(format!("{}", paths.len()), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
         ]),
         Line::from(vec![
-            Span::raw("Total size to be freed: "),
+            Span::raw(t!("deletion-total-size")),
             Span::styled(format_size(total_size), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
         ]),
     ])
@@ -318,17 +618,32 @@ fn render_confirmation(f: &mut Frame, paths: &[PathBuf], total_size: u64, scroll
     .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Red)));
     f.render_widget(header, chunks[0]);
 
-    // List of paths
+    // List of paths, scrolled so the cursor stays visible
     let list_height = chunks[1].height.saturating_sub(2) as usize;
+    let scroll_offset = cursor.saturating_sub(list_height.saturating_sub(1));
     let items: Vec<ListItem> = paths
         .iter()
+        .enumerate()
         .skip(scroll_offset)
         .take(list_height)
-        .map(|path| {
-            ListItem::new(Line::from(vec![
-                Span::raw("  🗑  "),
+        .map(|(idx, path)| {
+            let mode = modes.get(path).copied().unwrap_or_default();
+            let icon = if mode == DeletionMode::Remove { "  🗑  " } else { "  🧽  " };
+            let mut line = vec![
+                Span::raw(icon),
                 Span::styled(path.display().to_string(), Style::default().fg(Color::White)),
-            ]))
+            ];
+            if let Some(&cost) = costs.get(path) {
+                line.push(Span::styled(format!("  [{}]", cost.describe()), rebuild_cost_style(cost)));
+            }
+            if mode != DeletionMode::Remove {
+                line.push(Span::styled(format!("  [{}]", mode.label()), Style::default().fg(Color::Cyan)));
+            }
+            if let Some(failure) = doomed.get(path) {
+                line.push(Span::styled(format!("  [{}]", failure.label()), Style::default().fg(Color::Red)));
+            }
+            let style = if idx == cursor { Style::default().bg(Color::DarkGray) } else { Style::default() };
+            ListItem::new(Line::from(line)).style(style)
         })
         .collect();
 
@@ -336,51 +651,146 @@ fn render_confirmation(f: &mut Frame, paths: &[PathBuf], total_size: u64, scroll
         .block(Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::White))
-            .title(format!(" Directories ({}/{}) ", scroll_offset + 1, paths.len())));
+            .title(t!("deletion-directories-title", position = cursor + 1, count = paths.len())));
     f.render_widget(list, chunks[1]);
 
     // Footer
-    let footer = Paragraph::new(vec![
-        Line::from(""),
+    let warning = if permanent {
         Line::from(vec![
-            Span::styled("⚠️  THIS ACTION CANNOT BE UNDONE!", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-        ]),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("Y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-            Span::raw(": Confirm deletion  |  "),
-            Span::styled("N", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-            Span::raw(" / "),
-            Span::styled("Esc", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-            Span::raw(": Cancel"),
-        ]),
-    ])
+            Span::styled(format!("⚠️  {}", t!("deletion-cannot-undo").to_uppercase()), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+        ])
+    } else {
+        Line::from("")
+    };
+    let footer_lines = match strong {
+        Some((reason, typed, mismatch)) => {
+            let status = if mismatch {
+                Span::styled(format!("  {}", t!("deletion-mismatch")), Style::default().fg(Color::Red))
+            } else {
+                Span::raw("")
+            };
+            vec![
+                warning,
+                Line::from(Span::styled(format!("{}.", reason), Style::default().fg(Color::Yellow))),
+                Line::from(vec![
+                    Span::raw(t!("deletion-type")),
+                    Span::styled("DELETE", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                    Span::raw(format!(" {} ", t!("key-or"))),
+                    Span::styled(paths.len().to_string(), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                    Span::raw(t!("deletion-press-enter")),
+                    Span::styled(format!("{}▏", typed), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+                    status,
+                ]),
+                Line::from(vec![
+                    Span::styled("Tab", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                    Span::raw(format!(": {}  |  ", t!("key-deletion-mode"))),
+                    Span::styled("Esc", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                    Span::raw(format!(": {}", t!("key-cancel"))),
+                ]),
+            ]
+        }
+        None => vec![
+            Line::from(""),
+            warning,
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("Y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                Span::raw(format!(": {}  |  ", t!("deletion-confirm"))),
+                Span::styled("Tab", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::raw(format!(": {}  |  ", t!("key-deletion-mode"))),
+                Span::styled("N", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::raw(" / "),
+                Span::styled("Esc", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::raw(format!(": {}", t!("key-cancel"))),
+            ]),
+        ],
+    };
+    let footer = Paragraph::new(footer_lines)
     .alignment(Alignment::Center)
     .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::White)));
     f.render_widget(footer, chunks[2]);
 }
 
-pub fn delete_directories(paths: &[PathBuf]) -> Result<DeletionReport, DeletionError> {
+/// Directories with at least this many entries are removed in chunks, with progress
+const CHUNKED_MIN_ENTRIES: u64 = 100_000;
+
+/// Entries removed between progress reports and cancel checks
+const CHUNK: u64 = 10_000;
+
+/// Delete `paths` one at a time, checking `cancel` before each directory. A directory
+/// that is being deleted is finished, unless it is big enough to be removed in chunks
+/// and stops at the next one; the rest end up in `report.cancelled`.
+/// Directories with an emptying mode in `modes` keep existing and only lose their contents.
+/// Paths may also be single files, which are removed the same way and counted apart.
+/// With `trash`, directories (or their contents) are moved there instead, so `undo` can
+/// restore them.
+pub fn delete_directories_cancellable(
+    paths: &[PathBuf],
+    modes: &HashMap<PathBuf, DeletionMode>,
+    cancel: &AtomicBool,
+    mut trash: Option<&mut TrashBatch>,
+) -> Result<DeletionReport, DeletionError> {
     let mut report = DeletionReport {
         successful: Vec::new(),
         failed: Vec::new(),
+        cancelled: Vec::new(),
         total_freed_bytes: 0,
+        deleted_files: 0,
+        retryable: Vec::new(),
     };
 
-    for path in paths {
+    for (idx, path) in paths.iter().enumerate() {
+        if cancel.load(Ordering::SeqCst) {
+            report.cancelled = paths[idx..].to_vec();
+            println!("{}", t!("deletion-left-untouched", count = report.cancelled.len()));
+            break;
+        }
+
         // Calculate size before deletion
-        let size = calculate_dir_size(path).unwrap_or(0);
+        let (size, entries) = tree_stats(path);
+        // Bytes freed by a chunked removal that stopped partway
+        let mut partial = 0;
 
-        match fs::remove_dir_all(path) {
-            Ok(_) => {
+        let mode = modes.get(path).copied().unwrap_or_default();
+        // Not following symlinks, so a link is removed rather than what it points to
+        let is_file = fs::symlink_metadata(path).is_ok_and(|metadata| !metadata.is_dir());
+        let result = match (mode, trash.as_deref_mut()) {
+            (DeletionMode::Remove, Some(batch)) => batch.move_in(path, size).map(|_| size),
+            (DeletionMode::Remove, None) if is_file => fs::remove_file(path).map(|_| size),
+            (DeletionMode::Remove, None) if entries >= CHUNKED_MIN_ENTRIES => {
+                remove_in_chunks(path, entries, CHUNK, cancel, &mut partial)
+            }
+            (DeletionMode::Remove, None) => fs::remove_dir_all(path).map(|_| size),
+            (_, batch) => empty_directory(path, mode == DeletionMode::EmptyKeepGitkeep, batch),
+        };
+        match result {
+            Ok(freed) => {
                 report.successful.push(path.clone());
-                report.total_freed_bytes += size;
-                println!("✓ Deleted: {}", path.display());
+                report.total_freed_bytes += freed;
+                report.deleted_files += usize::from(is_file);
+                if mode != DeletionMode::Remove {
+                    println!("{}", t!("deletion-emptied", path = path.display().to_string()));
+                } else if trash.is_some() {
+                    println!("{}", t!("deletion-trashed", path = path.display().to_string()));
+                } else {
+                    println!("{}", t!("deletion-deleted", path = path.display().to_string()));
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted && cancel.load(Ordering::SeqCst) => {
+                report.total_freed_bytes += partial;
+                report.cancelled = paths[idx..].to_vec();
+                println!("{}", t!("deletion-stopped-partway", path = path.display().to_string(), size = format_size(partial)));
+                println!("{}", t!("deletion-left-untouched", count = report.cancelled.len() - 1));
+                break;
             }
             Err(e) => {
+                report.total_freed_bytes += partial;
                 let reason = e.to_string();
+                if is_transient(&e) {
+                    report.retryable.push(path.clone());
+                }
                 report.failed.push((path.clone(), reason.clone()));
-                eprintln!("✗ Failed to delete {}: {}", path.display(), reason);
+                eprintln!("{}", t!("deletion-delete-failed", path = path.display().to_string(), reason = reason.clone()));
             }
         }
     }
@@ -388,16 +798,80 @@ pub fn delete_directories(paths: &[PathBuf]) -> Result<DeletionReport, DeletionE
     Ok(report)
 }
 
-fn calculate_dir_size(path: &PathBuf) -> io::Result<u64> {
-    let mut total = 0u64;
+/// Remove (or move to the trash) everything inside `path` but the directory itself and,
+/// with `keep_gitkeep`, its top-level `.gitkeep`. Returns the bytes freed.
+fn empty_directory(path: &Path, keep_gitkeep: bool, mut trash: Option<&mut TrashBatch>) -> io::Result<u64> {
+    if !fs::symlink_metadata(path)?.is_dir() {
+        return Err(io::Error::other("not a directory"));
+    }
+    let mut freed = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        if keep_gitkeep && entry.file_name() == ".gitkeep" {
+            continue;
+        }
+        let child = entry.path();
+        let file_type = entry.file_type()?;
+        let size = if file_type.is_dir() { calculate_dir_size(&child)? } else { entry.metadata()?.len() };
+        match trash.as_deref_mut() {
+            Some(batch) => batch.move_in(&child, size)?,
+            None if file_type.is_dir() => fs::remove_dir_all(&child)?,
+            None => fs::remove_file(&child)?,
+        }
+        freed += size;
+    }
+    Ok(freed)
+}
+
+fn calculate_dir_size(path: &Path) -> io::Result<u64> {
+    Ok(tree_stats(path).0)
+}
+
+/// Bytes of the files in `path` and below, and how many entries it holds
+fn tree_stats(path: &Path) -> (u64, u64) {
+    let (mut bytes, mut entries) = (0, 0);
     for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+        entries += u64::from(entry.depth() > 0);
         if entry.file_type().is_file() {
             if let Ok(metadata) = entry.metadata() {
-                total += metadata.len();
+                bytes += metadata.len();
+            }
+        }
+    }
+    (bytes, entries)
+}
+
+/// Remove `path`, which holds `entries` entries, bottom-up: the files of each directory,
+/// then the directory. Every `chunk` entries the progress is shown and `cancel` checked;
+/// once it is set this stops with an `Interrupted` error, leaving the rest in place.
+/// `freed` counts the bytes removed so far either way.
+fn remove_in_chunks(path: &Path, entries: u64, chunk: u64, cancel: &AtomicBool, freed: &mut u64) -> io::Result<u64> {
+    use std::io::Write;
+    let mut removed = 0;
+    for entry in WalkDir::new(path).contents_first(true) {
+        let entry = entry.map_err(io::Error::from)?;
+        if entry.file_type().is_dir() {
+            fs::remove_dir(entry.path())?;
+        } else {
+            let size = if entry.file_type().is_file() { entry.metadata().map_or(0, |m| m.len()) } else { 0 };
+            fs::remove_file(entry.path())?;
+            *freed += size;
+        }
+        removed += 1;
+        if removed % chunk == 0 {
+            let percent = (removed * 100 / entries.max(1)).min(100);
+            print!("\r{}", t!("deletion-progress", path = path.display().to_string(), percent = percent, removed = removed, entries = entries));
+            let _ = io::stdout().flush();
+            if cancel.load(Ordering::SeqCst) {
+                println!();
+                return Err(io::Error::new(io::ErrorKind::Interrupted, "cancelled partway"));
             }
         }
     }
-    Ok(total)
+    if removed >= chunk {
+        println!();
+    }
+    Ok(*freed)
 }
 
 #[cfg(test)]
@@ -421,7 +895,7 @@ mod tests {
 
         let paths = vec![dir1.clone(), dir2.clone()];
 
-        let report = delete_directories(&paths).unwrap();
+        let report = delete_directories_cancellable(&paths, &HashMap::new(), &AtomicBool::new(false), None).unwrap();
 
         assert_eq!(report.successful.len(), 2);
         assert_eq!(report.failed.len(), 0);
@@ -430,14 +904,199 @@ mod tests {
         assert!(!dir2.exists());
     }
 
+    #[test]
+    fn test_strong_confirmation() {
+        let gb = 1024 * 1024 * 1024;
+        assert_eq!(strong_confirmation_reason(10 * gb, 50 * gb, 0), None);
+        assert_eq!(strong_confirmation_reason(50 * gb, 50 * gb, 0), None);
+        assert_eq!(
+            strong_confirmation_reason(60 * gb, 50 * gb, 0).as_deref(),
+            Some("Selection includes more than 50.00 GB")
+        );
+        assert_eq!(
+            strong_confirmation_reason(gb, 50 * gb, 2).as_deref(),
+            Some("Selection includes 2 non-temp directories")
+        );
+        assert_eq!(
+            strong_confirmation_reason(60 * gb, 50 * gb, 1).as_deref(),
+            Some("Selection includes more than 50.00 GB and 1 non-temp directory")
+        );
+
+        assert!(is_strong_confirmation("DELETE", 12));
+        assert!(is_strong_confirmation(" 12\n", 12));
+        assert!(!is_strong_confirmation("delete", 12));
+        assert!(!is_strong_confirmation("y", 12));
+        assert!(!is_strong_confirmation("13", 12));
+    }
+
+    #[test]
+    fn test_empty_but_keep() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let [removed, emptied, gitkeep, trashed] = ["removed", "emptied", "gitkeep", "trashed"].map(|name| {
+            let dir = root.join(name);
+            fs::create_dir_all(dir.join("sub")).unwrap();
+            fs::write(dir.join("sub/file"), "content").unwrap();
+            fs::write(dir.join(".gitkeep"), "").unwrap();
+            dir
+        });
+        let modes = HashMap::from([
+            (emptied.clone(), DeletionMode::Empty),
+            (gitkeep.clone(), DeletionMode::EmptyKeepGitkeep),
+            (trashed.clone(), DeletionMode::Empty),
+        ]);
+
+        let paths = [removed.clone(), emptied.clone(), gitkeep.clone()];
+        let report = delete_directories_cancellable(&paths, &modes, &AtomicBool::new(false), None).unwrap();
+        assert_eq!(report.successful.len(), 3);
+        assert_eq!(report.total_freed_bytes, 21);
+        assert!(!removed.exists());
+        assert!(emptied.is_dir() && fs::read_dir(&emptied).unwrap().next().is_none());
+        let kept: Vec<_> = fs::read_dir(&gitkeep).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(kept, vec![".gitkeep"]);
+
+        // With the trash, the contents are moved there one by one and can be restored
+        let trash = crate::trash::Trash::new(root.join("trash"));
+        let mut batch = trash.batch();
+        delete_directories_cancellable(std::slice::from_ref(&trashed), &modes, &AtomicBool::new(false), Some(&mut batch)).unwrap();
+        assert_eq!(batch.moved(), 2);
+        assert!(trashed.is_dir() && !trashed.join("sub").exists());
+        trash.undo().unwrap();
+        assert!(trashed.join("sub/file").exists() && trashed.join(".gitkeep").exists());
+
+        let mut mode = DeletionMode::Remove;
+        for expected in [DeletionMode::Empty, DeletionMode::EmptyKeepGitkeep, DeletionMode::Remove] {
+            mode = mode.next();
+            assert_eq!(mode, expected);
+        }
+    }
+
+    #[test]
+    fn test_delete_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let dir = root.join("target");
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("file"), "content").unwrap();
+        let [big, trashed] = ["model.bin", "backup.iso"].map(|name| {
+            let file = root.join(name);
+            fs::write(&file, "0123456789").unwrap();
+            file
+        });
+
+        let report = delete_directories_cancellable(&[dir.clone(), big.clone()], &HashMap::new(), &AtomicBool::new(false), None).unwrap();
+        assert_eq!(report.successful.len(), 2);
+        assert_eq!(report.deleted_files, 1);
+        assert_eq!(report.total_freed_bytes, 17);
+        assert!(!dir.exists() && !big.exists());
+
+        // Files can't be emptied, and go to the trash like directories
+        let modes = HashMap::from([(trashed.clone(), DeletionMode::Empty)]);
+        let report = delete_directories_cancellable(std::slice::from_ref(&trashed), &modes, &AtomicBool::new(false), None).unwrap();
+        assert_eq!(report.failed.len(), 1);
+        let trash = crate::trash::Trash::new(root.join("trash"));
+        let mut batch = trash.batch();
+        let report = delete_directories_cancellable(std::slice::from_ref(&trashed), &HashMap::new(), &AtomicBool::new(false), Some(&mut batch)).unwrap();
+        assert_eq!((report.deleted_files, report.total_freed_bytes), (1, 10));
+        assert!(!trashed.exists());
+        trash.undo().unwrap();
+        assert_eq!(fs::read_to_string(&trashed).unwrap(), "0123456789");
+    }
+
     #[test]
     fn test_delete_nonexistent_directory() {
         let paths = vec![PathBuf::from("/nonexistent/path")];
 
-        let report = delete_directories(&paths).unwrap();
+        let report = delete_directories_cancellable(&paths, &HashMap::new(), &AtomicBool::new(false), None).unwrap();
 
         assert_eq!(report.successful.len(), 0);
         assert_eq!(report.failed.len(), 1);
+        // Missing isn't going to get better by retrying
+        assert!(report.retryable.is_empty());
+    }
+
+    #[test]
+    fn test_remove_in_chunks() {
+        let temp_dir = TempDir::new().unwrap();
+        let big = temp_dir.path().join("big");
+        for dir in 0..3 {
+            fs::create_dir_all(big.join(format!("d{}", dir))).unwrap();
+            for file in 0..9 {
+                fs::write(big.join(format!("d{}/f{}", dir, file)), "x".repeat(10)).unwrap();
+            }
+        }
+        assert_eq!(tree_stats(&big), (270, 30));
+
+        // Cancelled: stops after the first chunk, with what it freed counted
+        let mut freed = 0;
+        let error = remove_in_chunks(&big, 30, 10, &AtomicBool::new(true), &mut freed).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Interrupted);
+        assert_eq!(freed, 90);
+        assert_eq!(tree_stats(&big), (180, 20));
+
+        let mut freed = 0;
+        assert_eq!(remove_in_chunks(&big, 20, 10, &AtomicBool::new(false), &mut freed).unwrap(), 180);
+        assert!(!big.exists());
+    }
+
+    #[test]
+    fn test_preflight() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = temp_dir.path().join("cache");
+        fs::create_dir(&cache).unwrap();
+        assert!(!mounts::is_read_only(&cache));
+        assert_eq!(Preflight::check(&cache), None);
+        assert!(preflight_all(&[cache, PathBuf::from("relative")]).is_empty());
+    }
+
+    #[test]
+    fn test_retry_failed() {
+        assert!(is_transient(&io::Error::from(io::ErrorKind::ResourceBusy)));
+        assert!(is_transient(&io::Error::from(io::ErrorKind::DirectoryNotEmpty)));
+        assert!(!is_transient(&io::Error::from(io::ErrorKind::PermissionDenied)));
+        assert!(!is_transient(&io::Error::from(io::ErrorKind::NotFound)));
+
+        let temp_dir = TempDir::new().unwrap();
+        let busy = temp_dir.path().join("busy");
+        let locked = temp_dir.path().join("locked");
+        fs::create_dir(&busy).unwrap();
+        fs::write(busy.join("file"), "0123456789").unwrap();
+        let mut report = DeletionReport {
+            successful: vec![PathBuf::from("/p/done")],
+            failed: vec![(busy.clone(), "busy".to_string()), (locked.clone(), "locked".to_string())],
+            cancelled: Vec::new(),
+            total_freed_bytes: 5,
+            deleted_files: 0,
+            retryable: vec![busy.clone(), locked.clone()],
+        };
+
+        // The busy one goes through now, the locked one fails again
+        let retry = delete_directories_cancellable(&report.retryable, &HashMap::new(), &AtomicBool::new(false), None).unwrap();
+        report.merge_retry(retry);
+        assert_eq!(report.successful, [PathBuf::from("/p/done"), busy]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, locked);
+        assert_ne!(report.failed[0].1, "locked");
+        assert_eq!(report.total_freed_bytes, 15);
+        assert!(report.retryable.is_empty());
+    }
+
+    #[test]
+    fn test_cancelled_deletion() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir1 = temp_dir.path().join("dir1");
+        let dir2 = temp_dir.path().join("dir2");
+        fs::create_dir(&dir1).unwrap();
+        fs::create_dir(&dir2).unwrap();
+
+        let cancel = AtomicBool::new(true);
+        let report = delete_directories_cancellable(&[dir1.clone(), dir2.clone()], &HashMap::new(), &cancel, None).unwrap();
+
+        assert!(report.successful.is_empty());
+        assert!(report.failed.is_empty());
+        assert_eq!(report.cancelled, vec![dir1.clone(), dir2.clone()]);
+        assert!(dir1.exists());
+        assert!(dir2.exists());
     }
 
     #[test]
@@ -448,9 +1107,26 @@ mod tests {
         fs::write(root.join("file1.txt"), "hello").unwrap();
         fs::write(root.join("file2.txt"), "world").unwrap();
 
-        let size = calculate_dir_size(&root.to_path_buf()).unwrap();
+        let size = calculate_dir_size(root).unwrap();
         assert_eq!(size, 10); // "hello" + "world"
     }
+
+    #[test]
+    fn test_selection_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths: Vec<PathBuf> = (0..6).map(|i| temp_dir.path().join(format!("dir{}", i))).collect();
+        for (i, path) in paths.iter().enumerate() {
+            fs::create_dir_all(path.join("sub")).unwrap();
+            fs::write(path.join("sub/file"), "x".repeat(100 * (i + 1))).unwrap();
+        }
+        let mut with_missing = paths.clone();
+        with_missing.push(temp_dir.path().join("gone"));
+
+        assert_eq!(selection_size(&with_missing, 1), 2100);
+        assert_eq!(selection_size(&with_missing, 4), 2100);
+        assert_eq!(selection_size(&with_missing, 0), 2100);
+        assert_eq!(selection_size(&[], 4), 0);
+    }
 }
 
 
@@ -485,7 +1161,7 @@ mod proptests {
                 prop_assert!(path.exists());
             }
 
-            let report = delete_directories(&paths).unwrap();
+            let report = delete_directories_cancellable(&paths, &HashMap::new(), &AtomicBool::new(false), None).unwrap();
 
             // All should be deleted
             prop_assert_eq!(report.successful.len(), num_dirs);
@@ -518,12 +1194,13 @@ mod proptests {
             // Add a nonexistent path
             paths.push(PathBuf::from("/nonexistent/path"));
 
-            let report = delete_directories(&paths).unwrap();
+            let report = delete_directories_cancellable(&paths, &HashMap::new(), &AtomicBool::new(false), None).unwrap();
 
             // Should have some successes and some failures
-            prop_assert!(report.successful.len() > 0);
-            prop_assert!(report.failed.len() > 0);
+            prop_assert!(!report.successful.is_empty());
+            prop_assert!(!report.failed.is_empty());
             prop_assert_eq!(report.successful.len() + report.failed.len(), paths.len());
+            prop_assert!(report.cancelled.is_empty());
         }
     }
 }
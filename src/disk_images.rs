@@ -0,0 +1,137 @@
+use crate::cloud_sync;
+use crate::i18n::t;
+use serde::{Deserialize, Serialize};
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Disk images taking less than this on disk aren't worth a mention
+pub const MIN_IMAGE_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// What made a disk image, which decides how to shrink it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageKind {
+    /// QEMU/libvirt `.qcow2`
+    Qcow2,
+    /// VMware `.vmdk`
+    Vmdk,
+    /// VirtualBox `.vdi`
+    Vdi,
+    /// Hyper-V `.vhd` / `.vhdx`
+    Vhd,
+    /// Installer or CD/DVD `.iso`
+    Iso,
+    /// Docker Desktop's VM disk: `ext4.vhdx` (Windows) or `Docker.raw` (macOS)
+    DockerDesktop,
+    /// A WSL 2 distribution's `ext4.vhdx`
+    Wsl,
+}
+
+impl ImageKind {
+    /// Recognize a disk image by its name, and Docker Desktop's and WSL's by where they are
+    pub fn of(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+        if name == "docker.raw" {
+            return Some(ImageKind::DockerDesktop);
+        }
+        if name == "ext4.vhdx" {
+            let in_docker = path.to_string_lossy().to_ascii_lowercase().contains("docker");
+            return Some(if in_docker { ImageKind::DockerDesktop } else { ImageKind::Wsl });
+        }
+        match name.rsplit_once('.')?.1 {
+            "qcow2" => Some(ImageKind::Qcow2),
+            "vmdk" => Some(ImageKind::Vmdk),
+            "vdi" => Some(ImageKind::Vdi),
+            "vhd" | "vhdx" => Some(ImageKind::Vhd),
+            "iso" => Some(ImageKind::Iso),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            ImageKind::Qcow2 => t!("image-qcow2"),
+            ImageKind::Vmdk => t!("image-vmdk"),
+            ImageKind::Vdi => t!("image-vdi"),
+            ImageKind::Vhd => t!("image-vhd"),
+            ImageKind::Iso => t!("image-iso"),
+            ImageKind::DockerDesktop => t!("image-docker-desktop"),
+            ImageKind::Wsl => t!("image-wsl"),
+        }
+    }
+
+    /// How to get the space back without losing the machine, where there is a way
+    pub fn hint(&self) -> String {
+        match self {
+            ImageKind::Qcow2 => t!("image-hint-qcow2"),
+            ImageKind::Vmdk => t!("image-hint-vmdk"),
+            ImageKind::Vdi => t!("image-hint-vdi"),
+            ImageKind::Vhd => t!("image-hint-vhd"),
+            ImageKind::Iso => t!("image-hint-iso"),
+            ImageKind::DockerDesktop => t!("image-hint-docker-desktop"),
+            ImageKind::Wsl => t!("image-hint-wsl"),
+        }
+    }
+}
+
+/// A large disk image found by the scan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskImage {
+    pub path: PathBuf,
+    pub kind: ImageKind,
+    /// Length of the file; VM disks are often sparse, so this can be far more than...
+    pub size: u64,
+    /// ...the space it takes on disk
+    pub on_disk: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<SystemTime>,
+}
+
+impl DiskImage {
+    /// The file at `path` as a disk image, if it is one taking at least [`MIN_IMAGE_SIZE`]
+    pub fn detect(path: &Path, metadata: &Metadata) -> Option<Self> {
+        let kind = ImageKind::of(path)?;
+        let on_disk = cloud_sync::allocated_size(metadata);
+        (on_disk >= MIN_IMAGE_SIZE).then(|| Self {
+            path: path.to_path_buf(),
+            kind,
+            size: metadata.len(),
+            on_disk,
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_image_kind() {
+        let kind = |path: &str| ImageKind::of(Path::new(path));
+        assert_eq!(kind("/vms/ubuntu.qcow2"), Some(ImageKind::Qcow2));
+        assert_eq!(kind("/vms/Windows 11.VMDK"), Some(ImageKind::Vmdk));
+        assert_eq!(kind("/vms/old.vdi"), Some(ImageKind::Vdi));
+        assert_eq!(kind("/Downloads/ubuntu-24.04-desktop-amd64.iso"), Some(ImageKind::Iso));
+        assert_eq!(kind("/Hyper-V/disk.vhdx"), Some(ImageKind::Vhd));
+        assert_eq!(kind("/AppData/Local/Docker/wsl/disk/ext4.vhdx"), Some(ImageKind::DockerDesktop));
+        assert_eq!(kind("/Library/Containers/com.docker.docker/Data/vms/0/data/Docker.raw"), Some(ImageKind::DockerDesktop));
+        assert_eq!(kind("/AppData/Local/Packages/CanonicalGroupLimited.Ubuntu/LocalState/ext4.vhdx"), Some(ImageKind::Wsl));
+        assert_eq!(kind("/src/iso"), None);
+        assert_eq!(kind("/src/notes.txt"), None);
+    }
+
+    #[test]
+    fn test_detect() {
+        let temp_dir = TempDir::new().unwrap();
+        let image = temp_dir.path().join("big.qcow2");
+        fs::write(&image, "x".repeat(4096)).unwrap();
+        let metadata = fs::metadata(&image).unwrap();
+        // Small images are left out
+        assert_eq!(DiskImage::detect(&image, &metadata), None);
+        assert_eq!(DiskImage::detect(&temp_dir.path().join("big.txt"), &metadata), None);
+    }
+}
@@ -0,0 +1,497 @@
+use crate::i18n::t;
+use crate::utils::format_size;
+use crossterm::{
+    event::{self, Event, KeyCode},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame, Terminal,
+};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// One file or folder directly inside the Downloads directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadItem {
+    pub path: PathBuf,
+    pub size: u64,
+    /// Newest modification time inside a folder, so an extracted archive only counts as old
+    /// once nothing in it has been touched
+    pub modified: Option<SystemTime>,
+    pub is_dir: bool,
+}
+
+impl DownloadItem {
+    /// Time since the item was last modified; unknown or future times count as new
+    pub fn age(&self, now: SystemTime) -> Duration {
+        self.modified
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Default archive location: an `Archive` folder inside the Downloads directory
+pub fn default_archive(dir: &Path) -> PathBuf {
+    dir.join("Archive")
+}
+
+/// List the items directly inside `dir`, oldest first, leaving out `archive`
+pub fn list_downloads(dir: &Path, archive: &Path) -> io::Result<Vec<DownloadItem>> {
+    let mut items = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path == archive {
+            continue;
+        }
+        let metadata = fs::symlink_metadata(&path)?;
+        let mut item = DownloadItem {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            is_dir: metadata.is_dir(),
+            path,
+        };
+        if item.is_dir {
+            item.size = 0;
+            for metadata in WalkDir::new(&item.path).into_iter().filter_map(|e| e.ok()).filter_map(|e| e.metadata().ok()) {
+                if metadata.is_file() {
+                    item.size += metadata.len();
+                }
+                item.modified = item.modified.max(metadata.modified().ok());
+            }
+        }
+        items.push(item);
+    }
+    items.sort_by(|a, b| a.modified.cmp(&b.modified).then_with(|| a.path.cmp(&b.path)));
+    Ok(items)
+}
+
+/// Items not modified within `older_than`
+pub fn older_than(items: &[DownloadItem], older_than: Duration, now: SystemTime) -> Vec<PathBuf> {
+    items
+        .iter()
+        .filter(|item| item.age(now) >= older_than)
+        .map(|item| item.path.clone())
+        .collect()
+}
+
+/// `12 days`, `5 months` or `2 years`
+pub fn format_age(age: Duration) -> String {
+    let days = age.as_secs() / DAY;
+    match days {
+        0 => t!("age-today"),
+        1..=59 => t!("age-days", count = days),
+        60..=729 => t!("age-months", count = days / 30),
+        _ => t!("age-years", count = days / 365),
+    }
+}
+
+/// What the user chose in the triage view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TriageAction {
+    Delete(Vec<PathBuf>),
+    Archive(Vec<PathBuf>),
+    Quit,
+}
+
+/// Outcome of deleting or archiving downloads
+#[derive(Debug, Default)]
+pub struct TriageReport {
+    pub done: Vec<PathBuf>,
+    pub failed: Vec<(PathBuf, String)>,
+    pub bytes: u64,
+}
+
+impl TriageReport {
+    pub fn print_summary(&self, verb: &str) {
+        println!("{}", t!("downloads-done", verb = verb, count = self.done.len(), size = format_size(self.bytes)));
+        for (path, reason) in &self.failed {
+            println!("  ✗ {}: {}", path.display(), reason);
+        }
+    }
+}
+
+pub fn delete_items(items: &[DownloadItem], paths: &[PathBuf]) -> TriageReport {
+    apply(items, paths, |item| {
+        if item.is_dir {
+            fs::remove_dir_all(&item.path)
+        } else {
+            fs::remove_file(&item.path)
+        }
+    })
+}
+
+/// Move items into `archive`, never overwriting something already archived under the
+/// same name. Files are copied and removed when the archive is on another filesystem.
+pub fn archive_items(items: &[DownloadItem], paths: &[PathBuf], archive: &Path) -> TriageReport {
+    if let Err(e) = fs::create_dir_all(archive) {
+        return TriageReport {
+            failed: paths.iter().map(|path| (path.clone(), e.to_string())).collect(),
+            ..Default::default()
+        };
+    }
+    apply(items, paths, |item| {
+        let name = item.path.file_name().ok_or_else(|| io::Error::other("no file name"))?;
+        let target = archive.join(name);
+        if fs::symlink_metadata(&target).is_ok() {
+            return Err(io::Error::other(format!("{} already exists", target.display())));
+        }
+        match fs::rename(&item.path, &target) {
+            Err(_) if !item.is_dir => {
+                fs::copy(&item.path, &target)?;
+                fs::remove_file(&item.path)
+            }
+            result => result,
+        }
+    })
+}
+
+fn apply(items: &[DownloadItem], paths: &[PathBuf], action: impl Fn(&DownloadItem) -> io::Result<()>) -> TriageReport {
+    let mut report = TriageReport::default();
+    for item in items.iter().filter(|item| paths.contains(&item.path)) {
+        match action(item) {
+            Ok(()) => {
+                report.done.push(item.path.clone());
+                report.bytes += item.size;
+            }
+            Err(e) => report.failed.push((item.path.clone(), e.to_string())),
+        }
+    }
+    report
+}
+
+pub fn print_downloads(items: &[DownloadItem], selected: &[PathBuf], now: SystemTime) {
+    for item in items {
+        let mark = if selected.contains(&item.path) { "x" } else { " " };
+        println!(
+            "  [{}] {:>10} {:>10}  {}{}",
+            mark,
+            format_age(item.age(now)),
+            format_size(item.size),
+            item.path.display(),
+            if item.is_dir { "/" } else { "" }
+        );
+    }
+    let size: u64 = items.iter().filter(|item| selected.contains(&item.path)).map(|item| item.size).sum();
+    println!("{}", t!("downloads-selected", selected = selected.len(), count = items.len(), size = format_size(size)));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TriageSort {
+    /// Oldest first
+    Age,
+    /// Largest first
+    Size,
+}
+
+struct TriageState {
+    items: Vec<DownloadItem>,
+    selected: HashSet<PathBuf>,
+    cursor: usize,
+    sort: TriageSort,
+    /// Action waiting for y/n
+    confirming: Option<char>,
+    /// Browse only: delete and archive are disabled
+    read_only: bool,
+}
+
+impl TriageState {
+    fn resort(&mut self) {
+        match self.sort {
+            TriageSort::Age => self.items.sort_by(|a, b| a.modified.cmp(&b.modified).then_with(|| a.path.cmp(&b.path))),
+            TriageSort::Size => self.items.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path))),
+        }
+    }
+
+    fn selected_paths(&self) -> Vec<PathBuf> {
+        self.items
+            .iter()
+            .filter(|item| self.selected.contains(&item.path))
+            .map(|item| item.path.clone())
+            .collect()
+    }
+
+    fn selected_size(&self) -> u64 {
+        self.items
+            .iter()
+            .filter(|item| self.selected.contains(&item.path))
+            .map(|item| item.size)
+            .sum()
+    }
+}
+
+/// Browse the downloads with everything older than `age_limit` preselected; `read_only`
+/// leaves only browsing
+pub fn show_downloads(
+    items: Vec<DownloadItem>,
+    dir: &Path,
+    archive: &Path,
+    age_limit: Duration,
+    read_only: bool,
+) -> io::Result<TriageAction> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let result = run_downloads_ui(&mut terminal, items, dir, archive, age_limit, read_only);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+fn run_downloads_ui(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    items: Vec<DownloadItem>,
+    dir: &Path,
+    archive: &Path,
+    age_limit: Duration,
+    read_only: bool,
+) -> io::Result<TriageAction> {
+    let now = SystemTime::now();
+    let mut state = TriageState {
+        selected: older_than(&items, age_limit, now).into_iter().collect(),
+        items,
+        cursor: 0,
+        sort: TriageSort::Age,
+        confirming: None,
+        read_only,
+    };
+
+    loop {
+        terminal.draw(|f| render_downloads(f, &state, dir, archive, age_limit, now))?;
+
+        if event::poll(std::time::Duration::from_millis(100))? {
+            if let Event::Key(key) = event::read()? {
+                if let Some(action) = state.confirming.take() {
+                    if let KeyCode::Char('y') | KeyCode::Char('Y') = key.code {
+                        let paths = state.selected_paths();
+                        return Ok(if action == 'd' { TriageAction::Delete(paths) } else { TriageAction::Archive(paths) });
+                    }
+                    continue;
+                }
+                let last = state.items.len().saturating_sub(1);
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(TriageAction::Quit),
+                    KeyCode::Up | KeyCode::Char('k') => state.cursor = state.cursor.saturating_sub(1),
+                    KeyCode::Down | KeyCode::Char('j') => state.cursor = (state.cursor + 1).min(last),
+                    KeyCode::PageUp => state.cursor = state.cursor.saturating_sub(10),
+                    KeyCode::PageDown => state.cursor = (state.cursor + 10).min(last),
+                    KeyCode::Char(' ') => {
+                        if let Some(item) = state.items.get(state.cursor) {
+                            if !state.selected.remove(&item.path) {
+                                state.selected.insert(item.path.clone());
+                            }
+                        }
+                    }
+                    KeyCode::Char('o') => state.selected = older_than(&state.items, age_limit, now).into_iter().collect(),
+                    KeyCode::Char('n') => state.selected.clear(),
+                    KeyCode::Char('s') => {
+                        state.sort = if state.sort == TriageSort::Age { TriageSort::Size } else { TriageSort::Age };
+                        state.resort();
+                    }
+                    KeyCode::Char(c @ ('d' | 'm')) if !state.selected.is_empty() && !state.read_only => {
+                        state.confirming = Some(c)
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn render_downloads(
+    f: &mut Frame,
+    state: &TriageState,
+    dir: &Path,
+    archive: &Path,
+    age_limit: Duration,
+    now: SystemTime,
+) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4), // Header
+            Constraint::Min(0),    // List
+            Constraint::Length(3), // Footer
+        ])
+        .split(f.area());
+
+    let total: u64 = state.items.iter().map(|item| item.size).sum();
+    let header = Paragraph::new(vec![
+        Line::from(Span::styled(
+            t!("downloads-title", dir = dir.display().to_string()),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(vec![
+            Span::raw(t!("downloads-header", count = state.items.len(), size = format_size(total))),
+            Span::styled(
+                format!("{} ({})", state.selected.len(), format_size(state.selected_size())),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ),
+        ]),
+    ])
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Cyan)));
+    f.render_widget(header, chunks[0]);
+
+    let list_height = chunks[1].height.saturating_sub(2) as usize;
+    let scroll_offset = state.cursor.saturating_sub(list_height.saturating_sub(1));
+    let items: Vec<ListItem> = state
+        .items
+        .iter()
+        .enumerate()
+        .skip(scroll_offset)
+        .take(list_height)
+        .map(|(idx, item)| {
+            let selected = state.selected.contains(&item.path);
+            let old = item.age(now) >= age_limit;
+            let mut style = Style::default().fg(if old { Color::Yellow } else { Color::White });
+            if idx == state.cursor {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            let name = item.path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            ListItem::new(Line::from(Span::styled(
+                format!(
+                    " [{}] {:>10} {:>10}  {}{}",
+                    if selected { "x" } else { " " },
+                    format_age(item.age(now)),
+                    format_size(item.size),
+                    name,
+                    if item.is_dir { "/" } else { "" }
+                ),
+                style,
+            )))
+        })
+        .collect();
+    let title = match state.sort {
+        TriageSort::Age => t!("downloads-oldest-first"),
+        TriageSort::Size => t!("downloads-largest-first"),
+    };
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::White))
+            .title(title),
+    );
+    f.render_widget(list, chunks[1]);
+
+    let footer = match state.confirming {
+        Some(action) => {
+            let what = if action == 'd' {
+                t!("downloads-delete", count = state.selected.len(), size = format_size(state.selected_size()))
+            } else {
+                t!(
+                    "downloads-move",
+                    archive = archive.display().to_string(),
+                    count = state.selected.len(),
+                    size = format_size(state.selected_size())
+                )
+            };
+            Line::from(vec![
+                Span::styled(
+                    what,
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled("y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                Span::raw(format!(": {}  |  {}", t!("key-yes"), t!("key-other-no"))),
+            ])
+        }
+        None if state.read_only => Line::from(vec![
+            Span::styled("Space", Style::default().fg(Color::Cyan)),
+            Span::raw(format!(": {}  |  ", t!("key-toggle"))),
+            Span::styled("s", Style::default().fg(Color::Cyan)),
+            Span::raw(format!(": {}  |  ", t!("key-sort"))),
+            Span::styled(format!("{}  |  ", t!("read-only-badge")), Style::default().fg(Color::Yellow)),
+            Span::styled("q", Style::default().fg(Color::Green)),
+            Span::raw(format!(": {}", t!("key-quit"))),
+        ]),
+        None => Line::from(vec![
+            Span::styled("Space", Style::default().fg(Color::Cyan)),
+            Span::raw(format!(": {}  |  ", t!("key-toggle"))),
+            Span::styled("o", Style::default().fg(Color::Cyan)),
+            Span::raw(format!(": {}  |  ", t!("key-older-than", age = format_age(age_limit)))),
+            Span::styled("n", Style::default().fg(Color::Cyan)),
+            Span::raw(format!(": {}  |  ", t!("key-none"))),
+            Span::styled("s", Style::default().fg(Color::Cyan)),
+            Span::raw(format!(": {}  |  ", t!("key-sort"))),
+            Span::styled("d", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+            Span::raw(format!(": {}  |  ", t!("key-delete"))),
+            Span::styled("m", Style::default().fg(Color::Yellow)),
+            Span::raw(format!(": {}  |  ", t!("key-archive"))),
+            Span::styled("q", Style::default().fg(Color::Green)),
+            Span::raw(format!(": {}", t!("key-quit"))),
+        ]),
+    };
+    let footer = Paragraph::new(footer)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::White)));
+    f.render_widget(footer, chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::from_secs(3600)), "today");
+        assert_eq!(format_age(Duration::from_secs(DAY)), "1 day");
+        assert_eq!(format_age(Duration::from_secs(45 * DAY)), "45 days");
+        assert_eq!(format_age(Duration::from_secs(200 * DAY)), "6 months");
+        assert_eq!(format_age(Duration::from_secs(1000 * DAY)), "2 years");
+    }
+
+    #[test]
+    fn test_triage_downloads() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let archive = default_archive(dir);
+        fs::create_dir_all(&archive).unwrap();
+        fs::write(dir.join("installer.dmg"), vec![0u8; 1000]).unwrap();
+        fs::write(dir.join("invoice.pdf"), "pdf").unwrap();
+        fs::create_dir_all(dir.join("photos")).unwrap();
+        fs::write(dir.join("photos/a.jpg"), "jpeg").unwrap();
+        fs::write(archive.join("invoice.pdf"), "archived earlier").unwrap();
+
+        let items = list_downloads(dir, &archive).unwrap();
+        assert_eq!(items.len(), 3);
+        let photos = items.iter().find(|item| item.is_dir).unwrap();
+        assert_eq!(photos.size, 4);
+
+        // Everything was just written: nothing is old yet, all of it is a year from now
+        let now = SystemTime::now();
+        assert!(older_than(&items, Duration::from_secs(30 * DAY), now).is_empty());
+        let later = now + Duration::from_secs(365 * DAY);
+        assert_eq!(older_than(&items, Duration::from_secs(30 * DAY), later).len(), 3);
+
+        let report = archive_items(&items, &[dir.join("installer.dmg"), dir.join("invoice.pdf")], &archive);
+        assert_eq!(report.done, vec![dir.join("installer.dmg")]);
+        assert_eq!(report.bytes, 1000);
+        assert_eq!(report.failed.len(), 1);
+        assert!(archive.join("installer.dmg").exists());
+        assert_eq!(fs::read_to_string(archive.join("invoice.pdf")).unwrap(), "archived earlier");
+
+        let report = delete_items(&items, &[dir.join("photos"), dir.join("invoice.pdf")]);
+        assert_eq!(report.done.len(), 2);
+        assert!(!dir.join("photos").exists());
+        assert!(!dir.join("invoice.pdf").exists());
+    }
+}
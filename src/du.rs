@@ -0,0 +1,248 @@
+use crate::classifier::Classifier;
+use crate::ncdu::{self, NcduError};
+use crate::scanner::{DirectoryEntry, EntryType};
+use crate::utils::SizeUnits;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DuError {
+    #[error("Line {line}: expected \"size<TAB>path\" as written by du -ab")]
+    Format { line: usize },
+
+    #[error(transparent)]
+    Ncdu(#[from] NcduError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// `du`-style listing of `entries`: "size\tpath" lines, each directory after the directories
+/// inside it and siblings in name order, like `du` prints them. Sizes are 1K blocks by
+/// default, as with plain `du`; `human` gives `du -h` sizes (`du --si` with SI units) and
+/// byte units give `du -b`.
+pub fn format_du(entries: &[DirectoryEntry], human: bool, units: SizeUnits) -> String {
+    let mut sorted: Vec<&DirectoryEntry> = entries.iter().collect();
+    sorted.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut text = String::new();
+    let mut line = |entry: &DirectoryEntry| {
+        text.push_str(&format!("{}\t{}\n", du_size(entry.shown_size(), human, units), entry.path.display()));
+    };
+    // Ancestors of the current entry, whose lines come once everything inside them is done
+    let mut open: Vec<&DirectoryEntry> = Vec::new();
+    for entry in sorted {
+        while let Some(parent) = open.pop_if(|parent| !entry.path.starts_with(&parent.path)) {
+            line(parent);
+        }
+        open.push(entry);
+    }
+    while let Some(parent) = open.pop() {
+        line(parent);
+    }
+    text
+}
+
+/// One size the way `du` prints it
+pub fn du_size(bytes: u64, human: bool, units: SizeUnits) -> String {
+    match (human, units) {
+        (_, SizeUnits::Bytes) => bytes.to_string(),
+        (false, _) => bytes.div_ceil(1024).to_string(),
+        (true, SizeUnits::Binary) => human_size(bytes, 1024, ["K", "M", "G", "T", "P", "E"]),
+        (true, SizeUnits::Si) => human_size(bytes, 1000, ["k", "M", "G", "T", "P", "E"]),
+    }
+}
+
+/// `du -h`: rounded up, one decimal below 10 ("4.0K", "9.9M"), whole numbers from 10 on ("12G")
+fn human_size(bytes: u64, base: u64, suffixes: [&str; 6]) -> String {
+    if bytes < base {
+        return bytes.to_string();
+    }
+    let mut value = bytes as f64 / base as f64;
+    let mut unit = 0;
+    loop {
+        // Rounding up can reach the next unit: 1023.5K is "1.0M"
+        let rounded = if value < 10.0 { (value * 10.0).ceil() / 10.0 } else { value.ceil() };
+        if rounded >= base as f64 && unit + 1 < suffixes.len() {
+            value /= base as f64;
+            unit += 1;
+            continue;
+        }
+        return if rounded < 10.0 {
+            format!("{:.1}{}", rounded, suffixes[unit])
+        } else {
+            format!("{}{}", rounded, suffixes[unit])
+        };
+    }
+}
+
+/// Read `du -ab` output (sizes in bytes, files included) into entries, or an ncdu JSON export
+/// if that is what the input holds. Directories are classified like a scan would: temp
+/// directories are sized as a whole and not descended into.
+///
+/// `du` doesn't say which lines are directories, so a line is taken for one when other lines
+/// are inside it; an empty directory reads as an empty file.
+pub fn import_du<R: BufRead>(mut reader: R, classifier: &Classifier) -> Result<Vec<DirectoryEntry>, DuError> {
+    let starts_with_array = loop {
+        let buffer = reader.fill_buf()?;
+        match buffer.iter().position(|byte| !byte.is_ascii_whitespace()) {
+            Some(position) => break buffer[position] == b'[',
+            None if buffer.is_empty() => break false,
+            None => {
+                let len = buffer.len();
+                reader.consume(len);
+            }
+        }
+    };
+    if starts_with_array {
+        return Ok(ncdu::import_ncdu(reader, classifier)?);
+    }
+
+    let mut sizes: BTreeMap<PathBuf, u64> = BTreeMap::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+        let (size, path) = line
+            .split_once('\t')
+            .and_then(|(size, path)| Some((size.parse::<u64>().ok()?, path)))
+            .ok_or(DuError::Format { line: number + 1 })?;
+        sizes.insert(PathBuf::from(path), size);
+    }
+
+    let mut children: BTreeMap<&Path, BTreeSet<&Path>> = BTreeMap::new();
+    for path in sizes.keys() {
+        if let Some(parent) = path.parent().filter(|parent| sizes.contains_key(*parent)) {
+            children.entry(parent).or_default().insert(path);
+        }
+    }
+    let mut entries = Vec::new();
+    for root in children.keys().filter(|path| !path.parent().is_some_and(|parent| sizes.contains_key(parent))) {
+        import_directory(root, &children, &sizes, classifier, &mut entries);
+    }
+    entries.sort_by_key(|e| std::cmp::Reverse(e.cumulative_size_bytes));
+    Ok(entries)
+}
+
+/// Add `path` (and, unless it is a temp directory, its subdirectories) to `entries`; returns
+/// its cumulative file count, size and inode count
+fn import_directory(
+    path: &Path,
+    children: &BTreeMap<&Path, BTreeSet<&Path>>,
+    sizes: &BTreeMap<PathBuf, u64>,
+    classifier: &Classifier,
+    entries: &mut Vec<DirectoryEntry>,
+) -> (u64, u64, u64) {
+    let rule_path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let entry_type = classifier.classify_path(&rule_path).unwrap_or(EntryType::Normal);
+
+    let (mut file_count, mut size_bytes) = (0, 0);
+    let (mut cumulative_file_count, mut cumulative_size_bytes) = (0, 0);
+    let mut inodes = 1;
+    for child in children.get(path).into_iter().flatten() {
+        if children.contains_key(child) {
+            // Subdirectories of temp directories only count towards its size
+            let (files, size, dir_inodes) = if entry_type.is_temp() {
+                import_directory(child, children, sizes, classifier, &mut Vec::new())
+            } else {
+                import_directory(child, children, sizes, classifier, entries)
+            };
+            cumulative_file_count += files;
+            cumulative_size_bytes += size;
+            inodes += dir_inodes;
+        } else {
+            file_count += 1;
+            size_bytes += sizes[*child];
+            inodes += 1;
+        }
+    }
+    cumulative_file_count += file_count;
+    cumulative_size_bytes += size_bytes;
+
+    // Temp directories are sized as a whole, matching the scanner
+    if entry_type.is_temp() {
+        (file_count, size_bytes) = (cumulative_file_count, cumulative_size_bytes);
+    }
+    entries.push(DirectoryEntry {
+        label: entry_type.is_temp().then(|| classifier.label_path(&rule_path)).flatten(),
+        path: path.to_path_buf(),
+        file_count,
+        size_bytes,
+        cumulative_file_count,
+        cumulative_size_bytes,
+        entry_type,
+        inodes,
+        ..Default::default()
+    });
+    (cumulative_file_count, cumulative_size_bytes, inodes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_du() {
+        let entries = [("/p", 10_000), ("/p/b", 2048), ("/p/a", 5000), ("/p/a/x", 1), ("/q", 0)]
+            .map(|(path, size)| DirectoryEntry {
+                path: PathBuf::from(path),
+                cumulative_size_bytes: size,
+                ..Default::default()
+            });
+        assert_eq!(format_du(&entries, false, SizeUnits::Binary), "1\t/p/a/x\n5\t/p/a\n2\t/p/b\n10\t/p\n0\t/q\n");
+        assert_eq!(format_du(&entries[1..2], false, SizeUnits::Bytes), "2048\t/p/b\n");
+        // A sibling whose name starts like the directory isn't inside it
+        let entries = ["/p/a", "/p/ab"].map(|path| DirectoryEntry { path: PathBuf::from(path), ..Default::default() });
+        assert_eq!(format_du(&entries, false, SizeUnits::Binary), "0\t/p/a\n0\t/p/ab\n");
+    }
+
+    #[test]
+    fn test_du_size() {
+        let human = |bytes| du_size(bytes, true, SizeUnits::Binary);
+        assert_eq!(human(0), "0");
+        assert_eq!(human(1023), "1023");
+        assert_eq!(human(1024), "1.0K");
+        assert_eq!(human(1025), "1.1K");
+        assert_eq!(human(10 * 1024), "10K");
+        assert_eq!(human(10 * 1024 + 1), "11K");
+        assert_eq!(human(1024 * 1024 - 1), "1.0M");
+        assert_eq!(human(5 << 30), "5.0G");
+        assert_eq!(du_size(1500, true, SizeUnits::Si), "1.5k");
+        assert_eq!(du_size(1, false, SizeUnits::Binary), "1");
+        assert_eq!(du_size(4096, false, SizeUnits::Si), "4");
+    }
+
+    #[test]
+    fn test_import_du() {
+        // `du -ab /home/me/projects`, directories counting their own 4096 bytes
+        let output = "100\t/home/me/projects/README.md\n\
+                      200\t/home/me/projects/app/main.rs\n\
+                      1000\t/home/me/projects/app/node_modules/index.js\n\
+                      5000\t/home/me/projects/app/node_modules/lodash/lodash.js\n\
+                      9096\t/home/me/projects/app/node_modules/lodash\n\
+                      14192\t/home/me/projects/app/node_modules\n\
+                      18488\t/home/me/projects/app\n\
+                      22684\t/home/me/projects\n";
+        let entries = import_du(output.as_bytes(), &Classifier::default()).unwrap();
+        let find = |p: &str| entries.iter().find(|e| e.path == Path::new(p)).unwrap();
+
+        // The dependency tree is one temp entry, not descended into
+        assert_eq!(entries.len(), 3);
+        let node_modules = find("/home/me/projects/app/node_modules");
+        assert!(node_modules.entry_type.is_temp());
+        assert_eq!((node_modules.file_count, node_modules.size_bytes), (2, 6000));
+        let app = find("/home/me/projects/app");
+        assert_eq!((app.file_count, app.size_bytes, app.cumulative_size_bytes), (1, 200, 6200));
+        let root = find("/home/me/projects");
+        assert_eq!((root.cumulative_file_count, root.cumulative_size_bytes, root.inodes), (4, 6300, 8));
+
+        assert!(matches!(import_du("4.0K\t/a\n".as_bytes(), &Classifier::default()), Err(DuError::Format { line: 1 })));
+        assert!(import_du("".as_bytes(), &Classifier::default()).unwrap().is_empty());
+        // ncdu exports are read too
+        let ncdu = r#" [1,2,{},[{"name":"/p"},{"name":"f","asize":7}]]"#;
+        assert_eq!(import_du(ncdu.as_bytes(), &Classifier::default()).unwrap()[0].cumulative_size_bytes, 7);
+    }
+}
@@ -0,0 +1,432 @@
+use crate::csv_handler::ScanMetadata;
+use crate::i18n::t;
+use crate::report::RunSummary;
+use crate::utils::format_size;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::Deserialize;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+/// How long to wait for the mail server at each step
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Error)]
+pub enum EmailError {
+    #[error("Cannot reach mail server {host}: {source}")]
+    Connect { host: String, source: io::Error },
+
+    #[error("Mail server {host}: {source}")]
+    Io { host: String, source: io::Error },
+
+    #[error("Mail server {host} answered '{reply}' to {command}")]
+    Rejected { host: String, command: String, reply: String },
+
+    #[error("Cannot set up TLS with {host}: {message}")]
+    Tls { host: String, message: String },
+
+    #[error("The SMTP password variable {0} is not set")]
+    MissingPassword(String),
+}
+
+/// How the connection to the mail server is secured
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with STARTTLS (submission, port 587)
+    #[default]
+    Starttls,
+    /// TLS from the start (port 465)
+    Tls,
+    /// No encryption, for a relay on localhost or a trusted network
+    None,
+}
+
+/// Mail server for `--email-report`, from the `[smtp]` table of the config.
+///
+/// ```toml
+/// [smtp]
+/// host = "smtp.example.com"
+/// from = "disk-cleanup@example.com"
+/// username = "disk-cleanup@example.com"
+/// password_env = "SMTP_PASSWORD"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SmtpConfig {
+    pub host: String,
+    /// Defaults to 587, or 465 with `security = "tls"`, or 25 with `security = "none"`
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+    /// Sender address
+    pub from: String,
+    /// Log in with this user; no login without it
+    pub username: Option<String>,
+    /// Environment variable holding the password, so it stays out of the config file
+    pub password_env: Option<String>,
+}
+
+impl SmtpConfig {
+    fn port(&self) -> u16 {
+        self.port.unwrap_or(match self.security {
+            SmtpSecurity::Starttls => 587,
+            SmtpSecurity::Tls => 465,
+            SmtpSecurity::None => 25,
+        })
+    }
+}
+
+/// A report to mail: a text body and a file attached to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub to: Vec<String>,
+    pub subject: String,
+    pub body: String,
+    pub attachment: Option<Attachment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    pub name: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Subject of the report mail, e.g. "Disk cleanup report: /srv on build-01"
+pub fn report_subject(metadata: &ScanMetadata) -> String {
+    t!(
+        "email-subject",
+        root = root_text(metadata),
+        host = metadata.hostname.clone().unwrap_or_else(|| t!("unknown-host"))
+    )
+}
+
+/// Text of the report mail: what was scanned, what was found and what was deleted
+pub fn report_body(metadata: &ScanMetadata, summary: &RunSummary) -> String {
+    let mut lines = vec![
+        t!(
+            "email-body-scan",
+            root = root_text(metadata),
+            host = metadata.hostname.clone().unwrap_or_else(|| t!("unknown-host")),
+            time = metadata.scanned_at.map(|t| humantime::format_rfc3339_seconds(t).to_string()).unwrap_or_default()
+        ),
+        String::new(),
+        t!("email-body-size", size = format_size(summary.total_bytes), directories = summary.directories, files = summary.files),
+        t!("email-body-temp", count = summary.temp_directories, size = format_size(summary.temp_bytes)),
+    ];
+    if summary.scan_errors > 0 {
+        lines.push(t!("email-body-errors", count = summary.scan_errors));
+    }
+    if summary.dry_run {
+        lines.push(t!("email-body-dry-run"));
+    } else if summary.deleted > 0 || !summary.failed.is_empty() {
+        lines.push(t!("email-body-deleted", count = summary.deleted, size = format_size(summary.freed_bytes)));
+    }
+    if !summary.failed.is_empty() {
+        lines.push(t!("email-body-failed", count = summary.failed.len()));
+        lines.extend(summary.failed.iter().map(|failed| format!("  {}: {}", failed.path.display(), failed.reason)));
+    }
+    lines.join("\n") + "\n"
+}
+
+fn root_text(metadata: &ScanMetadata) -> String {
+    metadata.root.as_ref().map(|root| root.display().to_string()).unwrap_or_default()
+}
+
+/// The message as sent: headers, then a text part and the attachment, both base64 so that no
+/// line is too long and non-ASCII paths survive
+pub fn format_message(from: &str, message: &Message) -> String {
+    const BOUNDARY: &str = "disk-cleanup-report-boundary";
+    let mut out = format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nMIME-Version: 1.0\r\n",
+        from,
+        message.to.join(", "),
+        encode_header(&message.subject)
+    );
+    let text_part = format!(
+        "Content-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: base64\r\n\r\n{}",
+        wrap_base64(message.body.as_bytes())
+    );
+    match &message.attachment {
+        None => out.push_str(&text_part),
+        Some(attachment) => {
+            out.push_str(&format!("Content-Type: multipart/mixed; boundary=\"{}\"\r\n\r\n", BOUNDARY));
+            out.push_str(&format!("--{}\r\n{}", BOUNDARY, text_part));
+            out.push_str(&format!(
+                "--{}\r\nContent-Type: {}\r\nContent-Transfer-Encoding: base64\r\nContent-Disposition: attachment; filename=\"{}\"\r\n\r\n{}",
+                BOUNDARY,
+                attachment.content_type,
+                attachment.name,
+                wrap_base64(&attachment.data)
+            ));
+            out.push_str(&format!("--{}--\r\n", BOUNDARY));
+        }
+    }
+    out
+}
+
+/// A header value as an RFC 2047 encoded word when it isn't plain ASCII
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_string()
+    } else {
+        format!("=?UTF-8?B?{}?=", BASE64.encode(value))
+    }
+}
+
+/// Base64 in lines of 76 characters, each ending in CRLF
+fn wrap_base64(data: &[u8]) -> String {
+    let encoded = BASE64.encode(data);
+    let mut out = String::with_capacity(encoded.len() + encoded.len() / 38 + 2);
+    for line in encoded.as_bytes().chunks(76) {
+        out.push_str(std::str::from_utf8(line).unwrap_or_default());
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Deliver `message` through the mail server in `config`
+pub fn send(config: &SmtpConfig, message: &Message) -> Result<(), EmailError> {
+    let host = config.host.clone();
+    let password = match (&config.username, &config.password_env) {
+        (Some(_), Some(variable)) => {
+            Some(std::env::var(variable).map_err(|_| EmailError::MissingPassword(variable.clone()))?)
+        }
+        _ => None,
+    };
+    let tcp = TcpStream::connect((config.host.as_str(), config.port()))
+        .map_err(|source| EmailError::Connect { host: host.clone(), source })?;
+    let io_error = |source| EmailError::Io { host: host.clone(), source };
+    tcp.set_read_timeout(Some(TIMEOUT)).map_err(io_error)?;
+    tcp.set_write_timeout(Some(TIMEOUT)).map_err(io_error)?;
+
+    let login = config.username.as_deref().map(|user| (user, password.as_deref().unwrap_or("")));
+    match config.security {
+        SmtpSecurity::None => deliver(Smtp::open(tcp, &host)?, &config.from, login, message),
+        SmtpSecurity::Tls => deliver(Smtp::open(tls(tcp, &host)?, &host)?, &config.from, login, message),
+        SmtpSecurity::Starttls => {
+            let mut smtp = Smtp::open(tcp, &host)?;
+            smtp.command("STARTTLS", 220)?;
+            let tcp = smtp.stream.into_inner();
+            deliver(Smtp::open_greeted(tls(tcp, &host)?, &host)?, &config.from, login, message)
+        }
+    }
+}
+
+/// `tcp` wrapped in TLS, checking the server's certificate against the web's root certificates
+fn tls(tcp: TcpStream, host: &str) -> Result<rustls::StreamOwned<rustls::ClientConnection, TcpStream>, EmailError> {
+    let tls_error = |message: String| EmailError::Tls { host: host.to_string(), message };
+    let roots = rustls::RootCertStore { roots: webpki_roots::TLS_SERVER_ROOTS.to_vec() };
+    let config = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| tls_error(e.to_string()))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let name = rustls::pki_types::ServerName::try_from(host.to_string()).map_err(|e| tls_error(e.to_string()))?;
+    let connection = rustls::ClientConnection::new(Arc::new(config), name).map_err(|e| tls_error(e.to_string()))?;
+    Ok(rustls::StreamOwned::new(connection, tcp))
+}
+
+/// Log in if asked to, then hand over the message and say goodbye
+fn deliver<S: Read + Write>(mut smtp: Smtp<S>, from: &str, login: Option<(&str, &str)>, message: &Message) -> Result<(), EmailError> {
+    if let Some((user, password)) = login {
+        let credentials = BASE64.encode(format!("\0{}\0{}", user, password));
+        smtp.command(&format!("AUTH PLAIN {}", credentials), 235)?;
+    }
+    smtp.command(&format!("MAIL FROM:<{}>", from), 250)?;
+    for to in &message.to {
+        smtp.command(&format!("RCPT TO:<{}>", to), 250)?;
+    }
+    smtp.command("DATA", 354)?;
+    // A line with just "." ends the data, so lines starting with one get another
+    let mut data = String::new();
+    for line in format_message(from, message).split_inclusive("\r\n") {
+        if line.starts_with('.') {
+            data.push('.');
+        }
+        data.push_str(line);
+    }
+    data.push_str(".\r\n");
+    smtp.send_raw(data.as_bytes())?;
+    smtp.expect(250, "DATA")?;
+    let _ = smtp.command("QUIT", 221);
+    Ok(())
+}
+
+/// One SMTP conversation over `S`, a plain or TLS stream
+struct Smtp<'a, S: Read + Write> {
+    stream: BufReader<S>,
+    host: &'a str,
+}
+
+impl<'a, S: Read + Write> Smtp<'a, S> {
+    /// Wait for the server's greeting, then introduce ourselves
+    fn open(stream: S, host: &'a str) -> Result<Self, EmailError> {
+        let mut smtp = Smtp { stream: BufReader::new(stream), host };
+        smtp.expect(220, "connect")?;
+        smtp.ehlo()?;
+        Ok(smtp)
+    }
+
+    /// After STARTTLS there is no new greeting, but the introduction is repeated
+    fn open_greeted(stream: S, host: &'a str) -> Result<Self, EmailError> {
+        let mut smtp = Smtp { stream: BufReader::new(stream), host };
+        smtp.ehlo()?;
+        Ok(smtp)
+    }
+
+    fn ehlo(&mut self) -> Result<(), EmailError> {
+        let name = crate::utils::hostname().unwrap_or_else(|| "localhost".to_string());
+        self.command(&format!("EHLO {}", name), 250)
+    }
+
+    fn command(&mut self, command: &str, code: u16) -> Result<(), EmailError> {
+        self.send_raw(format!("{}\r\n", command).as_bytes())?;
+        // Keep the password out of error messages
+        let shown = if command.starts_with("AUTH") { "AUTH" } else { command };
+        self.expect(code, shown)
+    }
+
+    fn send_raw(&mut self, data: &[u8]) -> Result<(), EmailError> {
+        let stream = self.stream.get_mut();
+        stream.write_all(data).and_then(|_| stream.flush()).map_err(|source| EmailError::Io { host: self.host.to_string(), source })
+    }
+
+    /// Read a reply, which may span several "250-..." lines, and check its code
+    fn expect(&mut self, code: u16, command: &str) -> Result<(), EmailError> {
+        let reply = loop {
+            let mut line = String::new();
+            let read = self.stream.read_line(&mut line).map_err(|source| EmailError::Io { host: self.host.to_string(), source })?;
+            if read == 0 {
+                return Err(EmailError::Io { host: self.host.to_string(), source: io::ErrorKind::UnexpectedEof.into() });
+            }
+            if line.as_bytes().get(3) != Some(&b'-') {
+                break line.trim_end().to_string();
+            }
+        };
+        if reply.get(..3).and_then(|c| c.parse::<u16>().ok()) == Some(code) {
+            Ok(())
+        } else {
+            Err(EmailError::Rejected { host: self.host.to_string(), command: command.to_string(), reply })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::thread;
+
+    fn message() -> Message {
+        Message {
+            to: vec!["admin@example.com".to_string(), "ops@example.com".to_string()],
+            subject: "Disk cleanup report: /srv/données".to_string(),
+            body: "Total: 1.00 GB\n.hidden line\n".to_string(),
+            attachment: Some(Attachment {
+                name: "scan.csv".to_string(),
+                content_type: "text/csv".to_string(),
+                data: b"path,size\n/srv,1\n".to_vec(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_format_message() {
+        let text = format_message("tool@example.com", &message());
+        assert!(text.starts_with("From: tool@example.com\r\nTo: admin@example.com, ops@example.com\r\n"));
+        assert!(text.contains("Subject: =?UTF-8?B?"));
+        assert!(text.contains("Content-Disposition: attachment; filename=\"scan.csv\""));
+        assert!(text.contains(&BASE64.encode("Total: 1.00 GB\n.hidden line\n")));
+        assert!(text.ends_with("--disk-cleanup-report-boundary--\r\n"));
+        assert!(text.lines().all(|line| line.len() <= 998));
+    }
+
+    #[test]
+    fn test_report_body() {
+        let metadata = ScanMetadata { hostname: Some("build-01".to_string()), ..ScanMetadata::new(Some(&PathBuf::from("/srv"))) };
+        let mut summary = RunSummary { total_bytes: 2048, directories: 3, deleted: 1, freed_bytes: 1024, ..Default::default() };
+        summary.failed.push(crate::deletion::FailedPath { path: PathBuf::from("/srv/locked"), reason: "Permission denied".to_string() });
+        let body = report_body(&metadata, &summary);
+        assert!(body.starts_with("Scan of /srv on build-01"));
+        assert!(body.contains("/srv/locked: Permission denied"));
+        assert_eq!(report_subject(&metadata), "Disk cleanup report: /srv on build-01");
+    }
+
+    /// Plays the server's side of a delivery and returns what the client sent
+    fn fake_server(listener: TcpListener) -> thread::JoinHandle<Vec<String>> {
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+            let mut received = Vec::new();
+            writer.write_all(b"220 test ESMTP\r\n").unwrap();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 {
+                    break;
+                }
+                let line = line.trim_end().to_string();
+                let reply: &[u8] = if in_data {
+                    if line == "." {
+                        in_data = false;
+                        b"250 queued\r\n"
+                    } else {
+                        received.push(line);
+                        continue;
+                    }
+                } else if line.starts_with("EHLO") {
+                    b"250-test\r\n250 AUTH PLAIN\r\n"
+                } else if line.starts_with("AUTH PLAIN") {
+                    b"235 ok\r\n"
+                } else if line == "DATA" {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                } else if line == "QUIT" {
+                    writer.write_all(b"221 bye\r\n").unwrap();
+                    received.push(line);
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                received.push(line);
+                writer.write_all(reply).unwrap();
+            }
+            received
+        })
+    }
+
+    #[test]
+    fn test_send() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = fake_server(listener);
+        std::env::set_var("DISK_CLEANUP_TEST_SMTP_PASSWORD", "secret");
+        let config = SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port: Some(port),
+            security: SmtpSecurity::None,
+            from: "tool@example.com".to_string(),
+            username: Some("tool".to_string()),
+            password_env: Some("DISK_CLEANUP_TEST_SMTP_PASSWORD".to_string()),
+        };
+        send(&config, &message()).unwrap();
+
+        let received = server.join().unwrap();
+        assert_eq!(received[1], format!("AUTH PLAIN {}", BASE64.encode("\0tool\0secret")));
+        assert_eq!(received[2], "MAIL FROM:<tool@example.com>");
+        assert_eq!(received[3..5], ["RCPT TO:<admin@example.com>", "RCPT TO:<ops@example.com>"]);
+        assert!(received.contains(&"Subject: =?UTF-8?B?RGlzayBjbGVhbnVwIHJlcG9ydDogL3Nydi9kb25uw6llcw==?=".to_string()));
+        assert_eq!(received.last().unwrap(), "QUIT");
+
+        // A missing password is caught before connecting
+        let config = SmtpConfig { password_env: Some("DISK_CLEANUP_TEST_SMTP_UNSET".to_string()), ..config };
+        assert!(matches!(send(&config, &message()), Err(EmailError::MissingPassword(_))));
+    }
+}
@@ -0,0 +1,258 @@
+use crate::bookmarks::BookmarkError;
+use crate::classifier::ClassifierError;
+use crate::config::ConfigError;
+use crate::csv_handler::CsvError;
+use crate::deletion::DeletionError;
+use crate::i18n::t;
+use crate::interactive::InteractiveError;
+use crate::output_template::TemplateError;
+use crate::path_list::PathListError;
+use crate::policy::PolicyError;
+use crate::profiles::ProfileError;
+use crate::scanner::ScanError;
+use crate::schedule::ScheduleError;
+use crate::server::ServeError;
+use crate::trash::TrashError;
+use crossterm::{
+    event::{self, Event},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame, Terminal,
+};
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::process;
+use thiserror::Error;
+
+/// Everything that ends a run early, wrapping the errors of the modules involved
+#[derive(Debug, Error)]
+pub enum AppError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+
+    #[error(transparent)]
+    Classifier(#[from] ClassifierError),
+
+    #[error(transparent)]
+    Bookmark(#[from] BookmarkError),
+
+    #[error(transparent)]
+    Profile(#[from] ProfileError),
+
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+
+    #[error(transparent)]
+    Template(#[from] TemplateError),
+
+    #[error(transparent)]
+    Scan(#[from] ScanError),
+
+    #[error(transparent)]
+    Csv(#[from] CsvError),
+
+    #[error(transparent)]
+    PathList(#[from] PathListError),
+
+    #[error(transparent)]
+    Deletion(#[from] DeletionError),
+
+    #[error(transparent)]
+    Trash(#[from] TrashError),
+
+    #[error(transparent)]
+    Interactive(#[from] InteractiveError),
+
+    #[error(transparent)]
+    Schedule(#[from] ScheduleError),
+
+    #[error(transparent)]
+    Serve(#[from] ServeError),
+
+    #[error("Terminal error: {0}")]
+    Terminal(#[from] io::Error),
+
+    /// An input file (scan, ncdu export, du output, ...) could not be read
+    #[error("Cannot read {path}: {message}")]
+    Read { path: PathBuf, message: String },
+
+    /// An output file could not be written
+    #[error("Cannot write {path}: {message}")]
+    Write { path: PathBuf, message: String },
+
+    /// Already worded for the user in the UI language, e.g. a missing path
+    #[error("{0}")]
+    Message(String),
+
+    /// The run finished, but some paths were refused or failed to delete; each one has
+    /// been reported already
+    #[error("{0}")]
+    Incomplete(String),
+}
+
+impl AppError {
+    pub fn read(path: impl Into<PathBuf>, error: impl ToString) -> Self {
+        AppError::Read { path: path.into(), message: error.to_string() }
+    }
+
+    pub fn write(path: impl Into<PathBuf>, error: impl ToString) -> Self {
+        AppError::Write { path: path.into(), message: error.to_string() }
+    }
+
+    pub fn incomplete() -> Self {
+        AppError::Incomplete(t!("error-incomplete"))
+    }
+
+    /// Stable tag for scripts reading stderr, e.g. `scan` or `config`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::Config(_)
+            | AppError::Classifier(_)
+            | AppError::Bookmark(_)
+            | AppError::Profile(_)
+            | AppError::Policy(_)
+            | AppError::Template(_) => "config",
+            AppError::Scan(_) => "scan",
+            AppError::Csv(_) | AppError::PathList(_) | AppError::Read { .. } => "read",
+            AppError::Write { .. } => "write",
+            AppError::Deletion(_) | AppError::Trash(_) | AppError::Incomplete(_) => "deletion",
+            AppError::Interactive(_) | AppError::Terminal(_) => "terminal",
+            AppError::Schedule(_) => "schedule",
+            AppError::Serve(_) => "serve",
+            AppError::Message(_) => "usage",
+        }
+    }
+
+    /// Heading of the error screen, in the UI language
+    pub fn title(&self) -> String {
+        match self.kind() {
+            "config" => t!("error-title-config"),
+            "scan" => t!("error-title-scan"),
+            "read" => t!("error-title-read"),
+            "write" => t!("error-title-write"),
+            "deletion" => t!("error-title-deletion"),
+            "terminal" => t!("error-title-terminal"),
+            "schedule" => t!("error-title-schedule"),
+            "serve" => t!("error-title-serve"),
+            _ => t!("error-title-usage"),
+        }
+    }
+
+    /// `Error [scan]: Permission denied: /srv`, the one line written to stderr
+    pub fn report_line(&self) -> String {
+        t!("error-report", kind = self.kind(), error = self.to_string())
+    }
+
+    /// Write the error to stderr without stopping, for runs that carry on with the rest
+    pub fn print(&self) {
+        eprintln!("{}", self.report_line());
+    }
+}
+
+/// Show `error` and exit with status 1: on a terminal as a screen that waits for a key,
+/// otherwise (cron, pipes, CI) only as its line on stderr. The line is written either way,
+/// so it stays in the scrollback once the screen is gone. An incomplete run gets no screen,
+/// since what failed is already listed above it.
+pub fn exit_with(error: &AppError) -> ! {
+    let listed = matches!(error, AppError::Incomplete(_));
+    if !listed && io::stdin().is_terminal() && io::stdout().is_terminal() {
+        let _ = show_error(error);
+    }
+    error.print();
+    process::exit(1);
+}
+
+fn show_error(error: &AppError) -> io::Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let result = terminal.draw(|f| render_error(f, error)).and_then(|_| loop {
+        if let Event::Key(_) = event::read()? {
+            return Ok(());
+        }
+    });
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    result
+}
+
+fn render_error(f: &mut Frame, error: &AppError) {
+    let area = centered(f.area(), 70, 9);
+    let text = vec![
+        Line::from(error.to_string()),
+        Line::from(""),
+        Line::from(Span::styled(t!("error-press-any-key"), Style::default().fg(Color::DarkGray))),
+    ];
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red))
+        .title(Span::styled(
+            format!(" {} ", error.title()),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ));
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(text).block(block).wrap(Wrap { trim: false }).alignment(Alignment::Left),
+        area,
+    );
+}
+
+/// A box of at most `width` x `height` in the middle of `area`
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Fill(1), Constraint::Length(height.min(area.height)), Constraint::Fill(1)])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Fill(1), Constraint::Length(width.min(area.width)), Constraint::Fill(1)])
+        .split(vertical[1])[1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use std::path::Path;
+
+    #[test]
+    fn test_report_line() {
+        let error = AppError::from(ScanError::PathNotFound { path: PathBuf::from("/srv/gone") });
+        assert_eq!(error.kind(), "scan");
+        assert_eq!(error.report_line(), "Error [scan]: Path not found: /srv/gone");
+
+        let error = AppError::read("/tmp/scan.csv", CsvError::MissingColumn("size".to_string()));
+        assert_eq!(error.report_line(), "Error [read]: Cannot read /tmp/scan.csv: Missing required column: size");
+
+        let error = AppError::Message(t!("error-path-missing", path = "/nowhere"));
+        assert_eq!(error.kind(), "usage");
+        assert_eq!(error.report_line(), "Error [usage]: Path does not exist: /nowhere");
+        assert_eq!(AppError::from(ProfileError::NoPaths).kind(), "config");
+        assert_eq!(
+            AppError::incomplete().report_line(),
+            "Error [deletion]: Not everything could be deleted; see the failures listed above"
+        );
+    }
+
+    #[test]
+    fn test_error_screen() {
+        let error = AppError::write(Path::new("/out/report.txt"), "Permission denied");
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|f| render_error(f, &error)).unwrap();
+        let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains(&error.title()));
+        assert!(screen.contains("Cannot write /out/report.txt"));
+        assert!(screen.contains(&t!("error-press-any-key")));
+    }
+}
@@ -0,0 +1,88 @@
+use crate::classifier::{Classifier, Explanation};
+use crate::i18n::t;
+use crate::utils::rebuild_cost;
+use std::path::Path;
+
+/// Describe why a directory is (or isn't) flagged, one line per fact.
+/// Used by the `explain` command and the interactive mode popup; `root` is the scan root.
+pub fn describe(classifier: &Classifier, path: &Path, root: &Path) -> Vec<String> {
+    // Path rules match against absolute paths
+    let absolute = |p: &Path| std::path::absolute(p).unwrap_or_else(|_| p.to_path_buf());
+    let path = absolute(path);
+    let explanation = classifier.explain(&path);
+
+    let mut lines = format_explanation(&explanation);
+    if let Some(ancestor) = flagged_ancestor(classifier, &path, &absolute(root)) {
+        lines.push(t!(
+            "explain-inside",
+            path = ancestor.path.display().to_string(),
+            category = ancestor.entry_type.as_str()
+        ));
+    }
+    lines
+}
+
+fn format_explanation(explanation: &Explanation) -> Vec<String> {
+    let entry_type = explanation.entry_type;
+    let mut lines = vec![
+        explanation.path.display().to_string(),
+        t!("explain-category", name = entry_type.display_name(), category = entry_type.as_str()),
+    ];
+    if let Some(label) = &explanation.label {
+        lines.push(t!("explain-label", label = label.as_str()));
+    }
+    lines.push(t!("explain-rule", rule = explanation.reason.to_string()));
+    lines.push(t!("explain-safety", note = entry_type.safety_note()));
+    if entry_type.is_temp() {
+        let name = explanation.path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        let cost = rebuild_cost(entry_type, &name, explanation.label.as_deref());
+        lines.push(t!("explain-rebuild", cost = cost.describe()));
+    }
+    lines
+}
+
+/// Nearest ancestor within the scan root that is itself flagged; the scanner
+/// doesn't descend into it
+fn flagged_ancestor(classifier: &Classifier, path: &Path, root: &Path) -> Option<Explanation> {
+    path.ancestors()
+        .skip(1)
+        .take_while(|ancestor| ancestor.starts_with(root))
+        .map(|ancestor| classifier.explain(ancestor))
+        .find(|explanation| explanation.entry_type.is_temp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::EntryType;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_describe_temp_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("app/node_modules");
+        fs::create_dir_all(&path).unwrap();
+
+        let lines = describe(&Classifier::default(), &path, temp_dir.path());
+        assert_eq!(lines[0], path.display().to_string());
+        assert_eq!(lines[1], "Category: Dependencies (dependency_cache)");
+        assert_eq!(lines[2], "Rule:     built-in directory name 'node_modules'");
+        assert_eq!(lines[3], format!("Safety:   {}", EntryType::DependencyCache.safety_note()));
+        assert_eq!(lines[4], "Rebuild:  cheap to regenerate");
+        assert_eq!(lines.len(), 5);
+    }
+
+    #[test]
+    fn test_describe_inside_temp_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("app/node_modules/left-pad/lib");
+        fs::create_dir_all(&path).unwrap();
+
+        let lines = describe(&Classifier::default(), &path, temp_dir.path());
+        assert_eq!(lines[1], "Category: Normal (normal)");
+        assert_eq!(lines[2], "Rule:     no rule matched");
+        assert!(lines[4].starts_with("Inside:   "));
+        assert!(lines[4].contains("node_modules (dependency_cache)"));
+    }
+}
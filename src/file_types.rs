@@ -0,0 +1,135 @@
+use crate::cloud_sync;
+use crate::i18n::t;
+use crate::utils::format_size;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use walkdir::WalkDir;
+
+/// How many extensions the details pane lists
+const SHOWN: usize = 5;
+
+/// Bytes of the files in `path` and below by extension (lowercased, empty for none),
+/// largest first
+pub fn composition(path: &Path) -> Vec<(String, u64)> {
+    let mut sizes: HashMap<String, u64> = HashMap::new();
+    for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let extension = entry.path().extension().map(|e| e.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+        *sizes.entry(extension).or_default() += cloud_sync::local_size(&metadata);
+    }
+    let mut sizes: Vec<(String, u64)> = sizes.into_iter().collect();
+    sizes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    sizes
+}
+
+/// ".mp4 3.2 GB · .jpg 410 MB · (no extension) 2 KB" for the largest few extensions
+pub fn describe(sizes: &[(String, u64)]) -> String {
+    if sizes.is_empty() {
+        return t!("file-types-none");
+    }
+    sizes
+        .iter()
+        .take(SHOWN)
+        .map(|(extension, bytes)| {
+            let extension = if extension.is_empty() { t!("file-types-no-extension") } else { format!(".{}", extension) };
+            format!("{} {}", extension, format_size(*bytes))
+        })
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+/// Compositions counted so far, by directory
+type Counted = Arc<Mutex<HashMap<PathBuf, Vec<(String, u64)>>>>;
+
+/// File-type compositions of the directories highlighted in the details pane, worked out
+/// on a background thread so a large tree doesn't freeze the UI. The most recently
+/// requested directory goes first; results are kept for the session.
+#[derive(Default)]
+pub struct Compositions {
+    done: Counted,
+    requested: HashSet<PathBuf>,
+    worker: Option<Sender<PathBuf>>,
+}
+
+impl Compositions {
+    /// The composition of `path`, or `None` while it is still being counted
+    pub fn get(&mut self, path: &Path) -> Option<Vec<(String, u64)>> {
+        if let Some(sizes) = self.done.lock().ok()?.get(path) {
+            return Some(sizes.clone());
+        }
+        if self.requested.insert(path.to_path_buf()) {
+            let worker = self.worker.get_or_insert_with(|| spawn_worker(Arc::clone(&self.done)));
+            let _ = worker.send(path.to_path_buf());
+        }
+        None
+    }
+}
+
+/// Count the directories sent to it, newest first, until the sender is dropped
+fn spawn_worker(done: Counted) -> Sender<PathBuf> {
+    let (sender, receiver) = mpsc::channel::<PathBuf>();
+    thread::spawn(move || {
+        let mut pending = Vec::new();
+        loop {
+            pending.extend(receiver.try_iter());
+            let path = match pending.pop() {
+                Some(path) => path,
+                None => match receiver.recv() {
+                    Ok(path) => path,
+                    Err(_) => return,
+                },
+            };
+            let sizes = composition(&path);
+            if let Ok(mut done) = done.lock() {
+                done.insert(path, sizes);
+            }
+        }
+    });
+    sender
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_composition() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("clips")).unwrap();
+        fs::write(root.join("clips/a.MP4"), "a".repeat(300)).unwrap();
+        fs::write(root.join("b.mp4"), "b".repeat(200)).unwrap();
+        fs::write(root.join("notes.txt"), "n".repeat(50)).unwrap();
+        fs::write(root.join("Makefile"), "m".repeat(10)).unwrap();
+
+        let sizes = composition(root);
+        assert_eq!(sizes, [("mp4".to_string(), 500), ("txt".to_string(), 50), (String::new(), 10)]);
+        assert_eq!(describe(&sizes), format!(".mp4 {} · .txt {} · {} {}", format_size(500), format_size(50), t!("file-types-no-extension"), format_size(10)));
+        assert_eq!(describe(&[]), t!("file-types-none"));
+
+        // Counted in the background, then served from the cache
+        let mut compositions = Compositions::default();
+        let started = Instant::now();
+        let sizes = loop {
+            if let Some(sizes) = compositions.get(root) {
+                break sizes;
+            }
+            assert!(started.elapsed() < Duration::from_secs(10));
+            thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(sizes[0], ("mp4".to_string(), 500));
+        fs::write(root.join("late.txt"), "l".repeat(1000)).unwrap();
+        assert_eq!(compositions.get(root).unwrap(), sizes);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::checkpoint::root_hash;
+use crate::scanner::DirectoryEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use thiserror::Error;
+
+/// Scans kept per root; older ones are dropped
+pub const MAX_SNAPSHOTS: usize = 10;
+
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    #[error("Cannot access scan history {path}: {source}")]
+    IoError {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid scan history {path}: {source}")]
+    JsonError {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+}
+
+/// Directory sizes from one scan of a root
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub scanned_at: SystemTime,
+    /// Cumulative size of each directory, relative to the root so that any spelling of the
+    /// root compares
+    pub sizes: Vec<(PathBuf, u64)>,
+}
+
+/// The last scans of one root, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScanHistory {
+    pub root: PathBuf,
+    pub snapshots: Vec<Snapshot>,
+}
+
+impl ScanHistory {
+    /// Default location for scans of `root`: `<data dir>/disk-cleanup-tool/history/<hash>.json`
+    pub fn default_path(root: &Path) -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("disk-cleanup-tool").join("history").join(format!("{:016x}.json", root_hash(root))))
+    }
+
+    /// The history at `path`, or an empty one for `root` when there is none yet
+    pub fn load(path: &Path, root: &Path) -> Result<ScanHistory, HistoryError> {
+        let content = match fs::read(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(ScanHistory { root: root.to_path_buf(), snapshots: Vec::new() });
+            }
+            Err(source) => return Err(HistoryError::IoError { path: path.to_path_buf(), source }),
+        };
+        serde_json::from_slice(&content).map_err(|source| HistoryError::JsonError {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Write the history next to `path` and rename it into place
+    pub fn save(&self, path: &Path) -> Result<(), HistoryError> {
+        let io_error = |source| HistoryError::IoError {
+            path: path.to_path_buf(),
+            source,
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let content = serde_json::to_vec(self).map_err(|source| HistoryError::JsonError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let partial = path.with_extension("json.partial");
+        fs::write(&partial, content).map_err(io_error)?;
+        fs::rename(&partial, path).map_err(io_error)
+    }
+
+    pub fn latest(&self) -> Option<&Snapshot> {
+        self.snapshots.last()
+    }
+
+    /// Add the sizes of the `entries` under the root as the newest scan
+    pub fn record(&mut self, entries: &[DirectoryEntry], scanned_at: SystemTime) {
+        let sizes = entries
+            .iter()
+            .filter_map(|e| Some((e.path.strip_prefix(&self.root).ok()?.to_path_buf(), e.cumulative_size_bytes)))
+            .collect();
+        self.snapshots.push(Snapshot { scanned_at, sizes });
+        let excess = self.snapshots.len().saturating_sub(MAX_SNAPSHOTS);
+        self.snapshots.drain(..excess);
+    }
+}
+
+impl Snapshot {
+    /// Set `previous_size` on the `entries` under `root` that this scan listed
+    pub fn apply(&self, root: &Path, entries: &mut [DirectoryEntry]) {
+        let sizes: HashMap<&Path, u64> = self.sizes.iter().map(|(path, size)| (path.as_path(), *size)).collect();
+        for entry in entries {
+            if let Some(size) = entry.path.strip_prefix(root).ok().and_then(|relative| sizes.get(relative)) {
+                entry.previous_size = Some(*size);
+            }
+        }
+    }
+}
+
+/// Compare the `entries` under `root` with its last recorded scan and record this scan in
+/// the history at `path`; the time of the scan compared with, if there was one
+pub fn compare_and_record(path: &Path, root: &Path, entries: &mut [DirectoryEntry], now: SystemTime) -> Result<Option<SystemTime>, HistoryError> {
+    let mut history = ScanHistory::load(path, root)?;
+    // The stored root is the spelling used the first time; entries use this run's
+    history.root = root.to_path_buf();
+    let previous = history.latest().map(|snapshot| {
+        snapshot.apply(root, entries);
+        snapshot.scanned_at
+    });
+    history.record(entries, now);
+    history.save(path)?;
+    Ok(previous)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn entry(path: &str, size: u64) -> DirectoryEntry {
+        DirectoryEntry {
+            path: PathBuf::from(path),
+            cumulative_size_bytes: size,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_compare_and_record() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("history").join("data.json");
+        let first = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+
+        // The first scan has nothing to compare with
+        let mut entries = vec![entry("/data", 500), entry("/data/cache", 300), entry("/elsewhere", 9)];
+        assert_eq!(compare_and_record(&path, Path::new("/data"), &mut entries, first).unwrap(), None);
+        assert!(entries.iter().all(|e| e.previous_size.is_none()));
+
+        // Spelled differently, the root still matches its directories
+        let mut entries = vec![entry("./data", 900), entry("./data/cache", 200), entry("./data/new", 400)];
+        let previous = compare_and_record(&path, Path::new("./data"), &mut entries, first + Duration::from_secs(60)).unwrap();
+        assert_eq!(previous, Some(first));
+        assert_eq!(entries[0].growth(), Some(400));
+        assert_eq!(entries[1].growth(), Some(-100));
+        assert_eq!(entries[2].growth(), None);
+
+        let history = ScanHistory::load(&path, Path::new("/data")).unwrap();
+        assert_eq!(history.snapshots.len(), 2);
+        assert_eq!(history.snapshots[0].sizes, [(PathBuf::new(), 500), (PathBuf::from("cache"), 300)]);
+        assert!(!path.with_extension("json.partial").exists());
+    }
+
+    #[test]
+    fn test_history_limit() {
+        let mut history = ScanHistory { root: PathBuf::from("/data"), snapshots: Vec::new() };
+        for i in 0..MAX_SNAPSHOTS as u64 + 3 {
+            history.record(&[entry("/data", i)], SystemTime::UNIX_EPOCH + Duration::from_secs(i));
+        }
+        assert_eq!(history.snapshots.len(), MAX_SNAPSHOTS);
+        assert_eq!(history.latest().unwrap().sizes, [(PathBuf::new(), MAX_SNAPSHOTS as u64 + 2)]);
+        assert_eq!(history.snapshots[0].sizes, [(PathBuf::new(), 3)]);
+    }
+}
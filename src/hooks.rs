@@ -0,0 +1,185 @@
+use crate::deletion::{DeletionReport, FailedPath};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Stdio};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum HookError {
+    #[error("Cannot run hook '{command}': {source}")]
+    Spawn { command: String, source: io::Error },
+
+    #[error("Hook '{command}' failed ({status})")]
+    Failed { command: String, status: ExitStatus },
+}
+
+/// Shell commands run around a deletion, from the `[hooks]` table of the config.
+///
+/// ```toml
+/// [hooks]
+/// pre_deletion = "notify-send 'Disk cleanup starting'"
+/// post_deletion = "~/bin/post-to-slack.sh"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Hooks {
+    /// Runs after the deletion is confirmed and before anything is deleted; a non-zero
+    /// exit status cancels the deletion
+    pub pre_deletion: Option<String>,
+
+    /// Runs once the deletion has finished (or was cancelled part-way)
+    pub post_deletion: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookEvent {
+    PreDeletion,
+    PostDeletion,
+}
+
+impl HookEvent {
+    fn as_str(self) -> &'static str {
+        match self {
+            HookEvent::PreDeletion => "pre-deletion",
+            HookEvent::PostDeletion => "post-deletion",
+        }
+    }
+}
+
+/// What a hook is told about the deletion, as JSON on stdin and in `DISK_CLEANUP_*`
+/// environment variables. Before deleting, only `directories` and `total_bytes` are filled in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HookSummary {
+    pub event: HookEvent,
+    /// Directories selected for deletion
+    pub directories: Vec<PathBuf>,
+    /// Their size just before deleting
+    pub total_bytes: u64,
+    pub deleted: Vec<PathBuf>,
+    pub failed: Vec<FailedPath>,
+    /// Directories left untouched because the deletion was cancelled
+    pub cancelled: Vec<PathBuf>,
+    pub freed_bytes: u64,
+}
+
+impl HookSummary {
+    pub fn before(directories: &[PathBuf], total_bytes: u64) -> Self {
+        Self {
+            event: HookEvent::PreDeletion,
+            directories: directories.to_vec(),
+            total_bytes,
+            deleted: Vec::new(),
+            failed: Vec::new(),
+            cancelled: Vec::new(),
+            freed_bytes: 0,
+        }
+    }
+
+    pub fn after(directories: &[PathBuf], total_bytes: u64, report: &DeletionReport) -> Self {
+        Self {
+            event: HookEvent::PostDeletion,
+            directories: directories.to_vec(),
+            total_bytes,
+            deleted: report.successful.clone(),
+            failed: report.failures(),
+            cancelled: report.cancelled.clone(),
+            freed_bytes: report.total_freed_bytes,
+        }
+    }
+
+    /// Counts and sizes for hooks that don't parse JSON
+    fn env(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("DISK_CLEANUP_EVENT", self.event.as_str().to_string()),
+            ("DISK_CLEANUP_DIRS", self.directories.len().to_string()),
+            ("DISK_CLEANUP_BYTES", self.total_bytes.to_string()),
+            ("DISK_CLEANUP_DELETED", self.deleted.len().to_string()),
+            ("DISK_CLEANUP_FAILED", self.failed.len().to_string()),
+            ("DISK_CLEANUP_CANCELLED", self.cancelled.len().to_string()),
+            ("DISK_CLEANUP_FREED_BYTES", self.freed_bytes.to_string()),
+        ]
+    }
+}
+
+/// Run `command` through the shell with `summary` as JSON on stdin and in the environment.
+/// The hook's output goes straight to the terminal; it may ignore stdin.
+pub fn run_hook(command: &str, summary: &HookSummary) -> Result<(), HookError> {
+    let spawn_error = |source| HookError::Spawn { command: command.to_string(), source };
+
+    let mut child = shell(command)
+        .envs(summary.env())
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error)?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let json = serde_json::to_string(summary).unwrap_or_default() + "\n";
+        // A hook that exits without reading stdin closes the pipe; that's fine
+        match stdin.write_all(json.as_bytes()) {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(spawn_error(e)),
+            _ => {}
+        }
+    }
+
+    let status = child.wait().map_err(spawn_error)?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(HookError::Failed { command: command.to_string(), status })
+    }
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(not(unix))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_hook() {
+        let temp_dir = TempDir::new().unwrap();
+        let out = temp_dir.path().join("out");
+        let report = DeletionReport {
+            successful: vec![PathBuf::from("/p/a/node_modules")],
+            failed: vec![(PathBuf::from("/p/b/target"), "Permission denied".to_string())],
+            cancelled: Vec::new(),
+            total_freed_bytes: 1500,
+            deleted_files: 0,
+            retryable: Vec::new(),
+        };
+        let dirs = [PathBuf::from("/p/a/node_modules"), PathBuf::from("/p/b/target")];
+        let summary = HookSummary::after(&dirs, 2000, &report);
+
+        let command = format!("cat > '{0}'; echo \"$DISK_CLEANUP_EVENT $DISK_CLEANUP_FREED_BYTES\" >> '{0}'", out.display());
+        run_hook(&command, &summary).unwrap();
+        let output = fs::read_to_string(&out).unwrap();
+        let (json, env) = output.split_once('\n').unwrap();
+        let json: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(json["event"], "post-deletion");
+        assert_eq!(json["failed"][0]["reason"], "Permission denied");
+        assert_eq!(json["freed_bytes"], 1500);
+        assert_eq!(env.trim(), "post-deletion 1500");
+
+        // Hooks may ignore stdin, but their exit status counts
+        run_hook("true", &HookSummary::before(&dirs, 2000)).unwrap();
+        let result = run_hook("exit 3", &HookSummary::before(&dirs, 2000));
+        assert!(matches!(result, Err(HookError::Failed { .. })));
+    }
+}
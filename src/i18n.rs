@@ -0,0 +1,202 @@
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use std::sync::OnceLock;
+use thiserror::Error;
+use unic_langid::LanguageIdentifier;
+
+/// Built-in Fluent catalogs by language. To add a translation, put its messages in
+/// `locales/<language>/main.ftl` and list it here; missing messages fall back to English.
+const CATALOGS: &[(&str, &str)] = &[("en", include_str!("../locales/en/main.ftl"))];
+
+const FALLBACK: &str = "en";
+
+#[derive(Debug, Error)]
+pub enum I18nError {
+    #[error("Invalid language '{0}'")]
+    InvalidLanguage(String),
+
+    #[error("No translation for '{0}' (available: {1}); using English")]
+    Unavailable(String, String),
+}
+
+/// Bundles to look messages up in, the selected language first and English last
+struct Localizer {
+    bundles: Vec<FluentBundle<FluentResource>>,
+}
+
+impl Localizer {
+    fn new(language: Option<&LanguageIdentifier>) -> Self {
+        let mut languages: Vec<&str> = language.and_then(catalog_language).into_iter().collect();
+        if !languages.contains(&FALLBACK) {
+            languages.push(FALLBACK);
+        }
+        let bundles = languages
+            .into_iter()
+            .filter_map(|name| {
+                let (_, source) = CATALOGS.iter().find(|(lang, _)| *lang == name)?;
+                let id: LanguageIdentifier = name.parse().ok()?;
+                // Catalogs are checked by the tests; a broken message only loses itself
+                let resource = FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, _)| resource);
+                let mut bundle = FluentBundle::new_concurrent(vec![id]);
+                // Terminals don't need the Unicode isolation marks around arguments
+                bundle.set_use_isolating(false);
+                bundle.add_resource(resource).ok()?;
+                Some(bundle)
+            })
+            .collect();
+        Self { bundles }
+    }
+}
+
+static LOCALIZER: OnceLock<Localizer> = OnceLock::new();
+
+/// Select the UI language: `lang` (from `--lang`), or else the locale environment
+/// (`LC_ALL`, `LC_MESSAGES`, `LANG`). Call once, before anything is printed.
+pub fn init(lang: Option<&str>) -> Result<(), I18nError> {
+    let (language, result) = match lang {
+        Some(lang) => match parse_language(lang) {
+            Some(language) if catalog_language(&language).is_some() => (Some(language), Ok(())),
+            Some(_) => (None, Err(I18nError::Unavailable(lang.to_string(), available()))),
+            None => (None, Err(I18nError::InvalidLanguage(lang.to_string()))),
+        },
+        None => (env_language(), Ok(())),
+    };
+    let _ = LOCALIZER.set(Localizer::new(language.as_ref()));
+    result
+}
+
+/// Comma-separated list of built-in languages
+pub fn available() -> String {
+    CATALOGS.iter().map(|(lang, _)| *lang).collect::<Vec<_>>().join(", ")
+}
+
+fn env_language() -> Option<LanguageIdentifier> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| parse_language(&value))
+}
+
+/// `de_DE.UTF-8`, `pt-BR` or `fr`; the POSIX `C` locale means no preference
+fn parse_language(value: &str) -> Option<LanguageIdentifier> {
+    let tag = value.split(['.', '@']).next()?.replace('_', "-");
+    if tag.is_empty() || tag == "C" || tag == "POSIX" {
+        return None;
+    }
+    tag.parse().ok()
+}
+
+/// Built-in catalog for `language`, matched on the language subtag (`de-AT` uses `de`)
+fn catalog_language(language: &LanguageIdentifier) -> Option<&'static str> {
+    CATALOGS
+        .iter()
+        .map(|(lang, _)| *lang)
+        .find(|lang| *lang == language.language.as_str())
+}
+
+/// The message `id` in the selected language, falling back to English and then to the id
+/// itself. Use the [`t!`] macro rather than calling this directly.
+pub fn tr(id: &str, args: Option<&FluentArgs>) -> String {
+    let localizer = LOCALIZER.get_or_init(|| Localizer::new(env_language().as_ref()));
+    for bundle in &localizer.bundles {
+        if let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) {
+            let mut errors = Vec::new();
+            return bundle.format_pattern(pattern, args, &mut errors).into_owned();
+        }
+    }
+    id.to_string()
+}
+
+/// Localized message: `t!("scan-complete")`, or with arguments
+/// `t!("loaded-entries", count = entries.len(), file = path.display().to_string())`
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __t {
+    ($id:expr) => {
+        $crate::i18n::tr($id, None)
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = ::fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::tr($id, Some(&args))
+    }};
+}
+pub use crate::__t as t;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_parse_language() {
+        assert_eq!(parse_language("de_DE.UTF-8").unwrap().to_string(), "de-DE");
+        assert_eq!(parse_language("pt-BR").unwrap().to_string(), "pt-BR");
+        assert_eq!(parse_language("sr_RS@latin").unwrap().to_string(), "sr-RS");
+        assert!(parse_language("C").is_none());
+        assert!(parse_language("C.UTF-8").is_none());
+        assert!(parse_language("").is_none());
+
+        assert_eq!(catalog_language(&"en-GB".parse().unwrap()), Some("en"));
+        assert_eq!(catalog_language(&"xx".parse().unwrap()), None);
+    }
+
+    #[test]
+    fn test_messages() {
+        assert_eq!(t!("error-path-missing", path = "/nowhere"), "Path does not exist: /nowhere");
+        assert_eq!(t!("filtered-temp", count = 1), "Filtered to 1 temporary directory");
+        assert_eq!(t!("filtered-temp", count = 3), "Filtered to 3 temporary directories");
+        assert_eq!(
+            t!("notify-deletion-finished", count = 2, size = "1.00 GB", failed = 0),
+            "Deleted 2 directories and freed 1.00 GB"
+        );
+        assert_eq!(
+            t!("notify-deletion-finished", count = 2, size = "1.00 GB", failed = 1),
+            "Deleted 2 directories and freed 1.00 GB; 1 failed"
+        );
+        assert_eq!(
+            t!("warning-access", path = "/srv/private", error = "Permission denied"),
+            "Warning: Cannot access /srv/private: Permission denied"
+        );
+        // Padding survives Fluent's whitespace trimming
+        assert_eq!(t!("deletion-freed"), "Space freed: ");
+        assert_eq!(t!("no-such-message"), "no-such-message");
+    }
+
+    /// Every catalog parses, and every message id used with `t!` in the sources is in English
+    #[test]
+    fn test_catalogs() {
+        let mut english = HashSet::new();
+        for (lang, source) in CATALOGS {
+            let resource = FluentResource::try_new(source.to_string());
+            assert!(resource.is_ok(), "{} catalog: {:?}", lang, resource.err().map(|(_, e)| e));
+            if *lang == FALLBACK {
+                // Messages start at the beginning of a line: `id = ...`
+                let ids = source
+                    .lines()
+                    .filter(|line| line.starts_with(|c: char| c.is_ascii_alphabetic()))
+                    .filter_map(|line| line.split_once(" ="))
+                    .map(|(id, _)| id.to_string());
+                english.extend(ids);
+            }
+        }
+
+        let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        for file in std::fs::read_dir(src).unwrap() {
+            let path = file.unwrap().path();
+            let code = std::fs::read_to_string(&path).unwrap();
+            for (start, _) in code.match_indices("t!(\"") {
+                // Not `format!(` or `print!(`
+                if code[..start].ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+                    continue;
+                }
+                let id = code[start + 4..].split('"').next().unwrap();
+                if id == "no-such-message" {
+                    continue;
+                }
+                assert!(english.contains(id), "{}: no English message '{}'", path.display(), id);
+            }
+        }
+    }
+}
@@ -1,5 +1,21 @@
+use crate::audit;
+use crate::classifier::Classifier;
+use crate::cloud_sync;
+use crate::columns::{format_age, format_entry_path, format_entry_size, allocation_difference_color, format_allocation_difference, format_growth, format_share_with_bar, growth_color, heat_color, parent_shares, size_width, truncate_path, BAR_WIDTH};
+use crate::commands::{self, Action, Command};
+use crate::csv_handler::ScanMetadata;
+use crate::disk_images::DiskImage;
+use crate::explain;
+use crate::file_types;
+use crate::i18n::t;
+use crate::largest_files::{self, LargeFile, LargestFiles};
+use crate::live_sizes::{self, LiveSizes};
+use crate::overrides::Overrides;
+use crate::owners;
+use crate::safety;
+use crate::scan_file;
 use crate::scanner::{DirectoryEntry, EntryType};
-use crate::utils::format_size;
+use crate::utils::{self, format_size, RebuildCost, SizeBasis, SortKey};
 use crossterm::{
     event::{self, Event, KeyCode, KeyEventKind},
     execute,
@@ -10,12 +26,13 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, Tabs},
     Frame, Terminal,
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -28,31 +45,582 @@ pub enum InteractiveError {
     IoError(#[from] std::io::Error),
 }
 
+/// Green for cheap, yellow for slow, red for irreplaceable
+pub(crate) fn rebuild_cost_style(cost: RebuildCost) -> Style {
+    let color = match cost {
+        RebuildCost::Cheap => Color::Green,
+        RebuildCost::Slow => Color::Yellow,
+        RebuildCost::Irreplaceable => Color::Red,
+    };
+    Style::default().fg(color)
+}
+
+/// Safety score of a temp directory, green when it is safe to delete
+fn safety_cell(safety: Option<u8>) -> Cell<'static> {
+    let Some(score) = safety else {
+        return Cell::from("");
+    };
+    let color = match score {
+        70.. => Color::Green,
+        40.. => Color::Yellow,
+        _ => Color::Red,
+    };
+    Cell::from(Line::from(score.to_string()).right_aligned()).style(Style::default().fg(color))
+}
+
+/// Width of the type column ("🗑 Dependencies", rule labels are clipped)
+const TYPE_WIDTH: u16 = 22;
+
+/// Width of the "% of parent" column: percentage plus inline bar
+const PARENT_WIDTH: u16 = 17;
+
+/// Height of the session log pane, borders included
+const LOG_HEIGHT: u16 = 8;
+
+/// How long a status bar message stays visible
+const STATUS_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// Which list the session is showing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Directories,
+    /// Temp directories aggregated by name, e.g. every `node_modules`
+    Groups,
+    /// Directory tree on the left, the highlighted directory's details and children on the right
+    Tree,
+    /// Large disk images (VM disks, ISOs, Docker Desktop and WSL disks) with how to shrink them
+    Images,
+    /// The largest files in the current tab's directories, selected one by one
+    Files,
+}
+
+/// Which side of the two-pane view has the keyboard focus
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Pane {
+    #[default]
+    Tree,
+    Children,
+}
+
+/// The current tab's directories as a tree: each directory hangs below its nearest listed
+/// ancestor, so directories the list leaves out (too small) don't break the hierarchy
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct DirectoryTree {
+    /// Directories without a listed ancestor, in list order
+    roots: Vec<usize>,
+    /// Listed children of each directory, in list order
+    children: HashMap<usize, Vec<usize>>,
+    parents: HashMap<usize, usize>,
+    expanded: HashSet<usize>,
+    /// Visible rows: entry index and depth
+    rows: Vec<(usize, usize)>,
+    index: usize,
+    scroll: usize,
+    focus: Pane,
+    child_index: usize,
+    child_scroll: usize,
+}
+
+impl DirectoryTree {
+    /// Tree of `members` (indices into `entries`, in list order) with the top level expanded
+    fn new(entries: &[DirectoryEntry], members: &[usize]) -> Self {
+        let by_path: HashMap<&Path, usize> = members.iter().map(|&i| (entries[i].path.as_path(), i)).collect();
+        let mut tree = Self::default();
+        for &idx in members {
+            let parent = entries[idx].path.ancestors().skip(1).find_map(|p| by_path.get(p).copied());
+            match parent {
+                Some(parent) => {
+                    tree.children.entry(parent).or_default().push(idx);
+                    tree.parents.insert(idx, parent);
+                }
+                None => tree.roots.push(idx),
+            }
+        }
+        tree.expanded = tree.roots.iter().copied().collect();
+        tree.refresh_rows();
+        tree
+    }
+
+    fn refresh_rows(&mut self) {
+        fn visit(tree: &DirectoryTree, idx: usize, depth: usize, rows: &mut Vec<(usize, usize)>) {
+            rows.push((idx, depth));
+            if tree.expanded.contains(&idx) {
+                for &child in tree.children_of(idx) {
+                    visit(tree, child, depth + 1, rows);
+                }
+            }
+        }
+        let mut rows = Vec::new();
+        for &root in &self.roots {
+            visit(self, root, 0, &mut rows);
+        }
+        self.rows = rows;
+        self.index = self.index.min(self.rows.len().saturating_sub(1));
+    }
+
+    fn children_of(&self, idx: usize) -> &[usize] {
+        self.children.get(&idx).map_or(&[], Vec::as_slice)
+    }
+
+    /// Entry under the tree cursor
+    fn current(&self) -> Option<usize> {
+        self.rows.get(self.index).map(|&(idx, _)| idx)
+    }
+
+    /// Entry with the keyboard focus: the tree row, or the child picked on the right
+    fn focused(&self) -> Option<usize> {
+        let current = self.current()?;
+        match self.focus {
+            Pane::Tree => Some(current),
+            Pane::Children => self.children_of(current).get(self.child_index).copied(),
+        }
+    }
+
+    /// Expand or collapse the directory under the cursor
+    fn toggle_expanded(&mut self) {
+        if let Some(idx) = self.current().filter(|idx| self.children.contains_key(idx)) {
+            if !self.expanded.remove(&idx) {
+                self.expanded.insert(idx);
+            }
+            self.refresh_rows();
+        }
+    }
+
+    /// Move the tree cursor to `idx`, expanding its ancestors
+    fn reveal(&mut self, idx: usize) {
+        let mut parent = self.parents.get(&idx).copied();
+        while let Some(p) = parent {
+            self.expanded.insert(p);
+            parent = self.parents.get(&p).copied();
+        }
+        self.refresh_rows();
+        if let Some(pos) = self.rows.iter().position(|&(i, _)| i == idx) {
+            self.index = pos;
+        }
+    }
+
+    /// Move the focus to the other pane; the children pane starts at the largest child
+    fn switch_focus(&mut self, focus: Pane) {
+        let has_children = self.current().is_some_and(|idx| !self.children_of(idx).is_empty());
+        if focus == Pane::Children && !has_children {
+            return;
+        }
+        self.focus = focus;
+        self.child_index = 0;
+        self.child_scroll = 0;
+    }
+}
+
+/// All temp directories sharing a name
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NameGroup {
+    name: String,
+    /// Indices into the session's entries, largest first
+    members: Vec<usize>,
+    total_bytes: u64,
+}
+
+/// The disk images found in the directories among `members`, largest on disk first, each
+/// with the entry it is in
+fn disk_images(entries: &[DirectoryEntry], members: &[usize]) -> Vec<(usize, DiskImage)> {
+    let mut images: Vec<(usize, DiskImage)> =
+        members.iter().flat_map(|&i| entries[i].images.iter().map(move |image| (i, image.clone()))).collect();
+    images.sort_by(|a, b| b.1.on_disk.cmp(&a.1.on_disk).then_with(|| a.1.path.cmp(&b.1.path)));
+    images
+}
+
+/// Group the temp entries among `members` by directory name, largest groups first
+fn name_groups(entries: &[DirectoryEntry], members: &[usize]) -> Vec<NameGroup> {
+    let mut groups: HashMap<String, NameGroup> = HashMap::new();
+    for (idx, entry) in members.iter().map(|&i| (i, &entries[i])).filter(|(_, e)| e.entry_type.is_temp()) {
+        let name = entry
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| entry.path.display().to_string());
+        let group = groups.entry(name.clone()).or_insert_with(|| NameGroup {
+            name,
+            members: Vec::new(),
+            total_bytes: 0,
+        });
+        group.members.push(idx);
+        group.total_bytes += entry.shown_size();
+    }
+
+    let mut groups: Vec<NameGroup> = groups.into_values().collect();
+    groups.sort_by(|a, b| b.total_bytes.cmp(&a.total_bytes).then_with(|| a.name.cmp(&b.name)));
+    groups
+}
+
+/// One tab of the directory list: a scan root, a source of a merged scan, or all of them
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Tab {
+    label: String,
+    /// Indices into the session's entries, in list order
+    members: Vec<usize>,
+    /// Every member, including those the temp-only filter hides
+    all: Vec<usize>,
+    /// Cursor and scroll position, restored when the tab is shown again
+    current_index: usize,
+    scroll_offset: usize,
+}
+
+impl Tab {
+    fn new(label: impl Into<String>, members: Vec<usize>) -> Self {
+        Self {
+            label: label.into(),
+            all: members.clone(),
+            members,
+            current_index: 0,
+            scroll_offset: 0,
+        }
+    }
+}
+
+/// A combined "All" tab followed by one tab per scan root, or, for a merged scan of a
+/// single root, one per source. Only the "All" tab when there is nothing to split. Mounts
+/// nest (`/home` is below `/`), so entries of an `--all-mounts` scan go by their mount.
+fn root_tabs(entries: &[DirectoryEntry], roots: &[PathBuf]) -> Vec<Tab> {
+    let mut tabs = vec![Tab::new(t!("tab-all"), (0..entries.len()).collect())];
+    if roots.len() > 1 {
+        for root in roots {
+            let members = (0..entries.len())
+                .filter(|&i| match &entries[i].mount {
+                    Some(mount) => mount == root,
+                    None => entries[i].path.starts_with(root),
+                })
+                .collect();
+            tabs.push(Tab::new(root.display().to_string(), members));
+        }
+        return tabs;
+    }
+
+    let mut sources: Vec<Option<&str>> = Vec::new();
+    for entry in entries {
+        if !sources.contains(&entry.source.as_deref()) {
+            sources.push(entry.source.as_deref());
+        }
+    }
+    if sources.len() > 1 {
+        for source in sources {
+            let members = (0..entries.len()).filter(|&i| entries[i].source.as_deref() == source).collect();
+            tabs.push(Tab::new(source.map(str::to_string).unwrap_or_else(|| t!("tab-this-scan")), members));
+        }
+    }
+    tabs
+}
+
+/// Text typed into the ':' command palette, and the highlighted match
+#[derive(Default)]
+struct CommandPalette {
+    input: String,
+    index: usize,
+}
+
+/// Immediate children of one directory by size, for the 'b' popup
+struct Breakdown {
+    path: PathBuf,
+    total: u64,
+    /// Name and cumulative size, largest first
+    children: Vec<(String, u64)>,
+}
+
+/// "☁ " and "🔒 " markers for an entry's name, with their width on screen
+fn badges(synced: &HashMap<usize, &'static str>, locked: &HashSet<usize>, changed: &HashSet<usize>, idx: usize) -> (String, usize) {
+    let mut badges = (String::new(), 0);
+    if changed.contains(&idx) {
+        badges.0.push_str("↻ ");
+        badges.1 += 2;
+    }
+    if synced.contains_key(&idx) {
+        badges.0.push_str("☁ ");
+        badges.1 += 2;
+    }
+    if locked.contains(&idx) {
+        badges.0.push_str("🔒 ");
+        badges.1 += 3;
+    }
+    badges
+}
+
+/// Tab completion for the jump prompt: the longest common prefix of the `paths` that start
+/// with `input`, or `input` itself when none do
+fn complete_path<'a>(input: &str, paths: impl IntoIterator<Item = &'a str>) -> String {
+    let mut common: Option<&str> = None;
+    for path in paths.into_iter().filter(|p| p.starts_with(input)) {
+        common = Some(match common {
+            None => path,
+            Some(prefix) => {
+                let len = prefix.chars().zip(path.chars()).take_while(|(a, b)| a == b).map(|(a, _)| a.len_utf8()).sum();
+                &prefix[..len]
+            }
+        });
+    }
+    common.unwrap_or(input).to_string()
+}
+
+/// Transient feedback shown in the status bar
+struct StatusMessage {
+    text: String,
+    is_error: bool,
+    shown_at: Instant,
+}
+
 pub struct InteractiveSession {
     entries: Vec<DirectoryEntry>,
     selected: HashSet<usize>,
+    /// Position in the current tab's members
     current_index: usize,
     scroll_offset: usize,
+    tabs: Vec<Tab>,
+    tab: usize,
+    classifier: Classifier,
+    root_path: PathBuf,
+    /// Whether 't' / 'T' changed any classification this session
+    overrides_changed: bool,
+    /// Explanation popup for the current entry, closed by the next key press
+    explanation: Option<Vec<String>>,
+    /// Children breakdown popup for the current entry, closed by the next key press
+    breakdown: Option<Breakdown>,
+    /// Text typed into the '/' jump-to-path prompt while it is open
+    jump: Option<String>,
+    /// The ':' command palette while it is open
+    commands: Option<CommandPalette>,
+    /// Current list order, changed from the command palette
+    sort: SortKey,
+    /// Whether the lists show only temp directories
+    temp_only: bool,
+    status: Option<StatusMessage>,
+    /// Share of each entry in its parent's size
+    parent_shares: HashMap<PathBuf, f64>,
+    /// Entries in a cloud-synced folder, with the service syncing them
+    synced: HashMap<usize, &'static str>,
+    /// Entries the current user lacks the permissions to delete
+    locked: HashSet<usize>,
+    /// Owner and group names for the details pane
+    id_names: owners::IdNames,
+    /// File types of the directories highlighted in the details pane
+    file_types: file_types::Compositions,
+    view: View,
+    /// Name groups, rebuilt each time the grouped view is opened
+    groups: Vec<NameGroup>,
+    group_index: usize,
+    group_scroll: usize,
+    /// Two-pane view of the current tab, rebuilt each time it is opened
+    tree: DirectoryTree,
+    /// Disk images in the current tab's directories, largest first, with the directory each
+    /// is in; rebuilt each time the images view is opened
+    images: Vec<(usize, DiskImage)>,
+    image_index: usize,
+    image_scroll: usize,
+    /// Largest files of the directories each tab lists, looked for in the background
+    largest_files: LargestFiles,
+    /// Directories the files view looks in, and the files found once the search is done
+    file_roots: Vec<PathBuf>,
+    files: Option<Vec<LargeFile>>,
+    file_index: usize,
+    file_scroll: usize,
+    /// Files selected in the files view, with their sizes
+    selected_files: HashMap<PathBuf, u64>,
+    /// Where 's' saves the session, and the scan details stored with it
+    session_file: Option<(PathBuf, ScanMetadata)>,
+    /// Whether 's' saved the session during this run
+    session_saved: bool,
+    /// Browse and select only; 'd' is disabled
+    read_only: bool,
+    /// Selections and reclassifications made this session, oldest first
+    log: Vec<audit::Record>,
+    /// Whether the session log pane ('L') is open
+    show_log: bool,
+    /// Watches the scanned roots and recounts directories that change while the session is open
+    live: Option<LiveSizes>,
+    /// Entries whose size changed since the scan, marked with ↻
+    changed: HashSet<usize>,
+    /// Whether an earlier scan of the root was recorded, adding the growth column
+    show_growth: bool,
+    /// Whether the scan recorded allocated sizes, which 'u' switches to
+    has_allocated: bool,
 }
 
 impl InteractiveSession {
-    pub fn new(mut entries: Vec<DirectoryEntry>) -> Self {
+    /// List `entries` ranked by `sort`; sorting by files also keeps small directories with
+    /// many files, which matter when inodes run out
+    pub fn new(mut entries: Vec<DirectoryEntry>, sort: SortKey) -> Self {
         const MIN_SIZE_BYTES: u64 = 1024 * 1024; // 1 MB
+        const MIN_FILES: u64 = 1000;
 
         // Filter out directories smaller than 1MB
-        entries.retain(|e| e.cumulative_size_bytes >= MIN_SIZE_BYTES);
+        entries.retain(|e| {
+            e.cumulative_size_bytes >= MIN_SIZE_BYTES || (sort == SortKey::Files && e.cumulative_file_count >= MIN_FILES)
+        });
+
+        sort.sort(&mut entries);
+
+        // Resumed sessions bring their selection along
+        let selected = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.selected && !e.active)
+            .map(|(i, _)| i)
+            .collect();
+
+        // Deleting in a synced folder also deletes in the cloud, so these get a badge
+        let mut sync_folders = cloud_sync::SyncFolders::default();
+        let synced = entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| sync_folders.service_of(&e.path).map(|service| (i, service)))
+            .collect();
 
-        // Sort by cumulative size descending
-        entries.sort_by(|a, b| b.cumulative_size_bytes.cmp(&a.cumulative_size_bytes));
+        // Flagged up front, rather than as a page of permission errors after confirming
+        let locked = (0..entries.len()).filter(|&i| owners::needs_elevation(&entries[i].path)).collect();
 
         Self {
+            parent_shares: parent_shares(&entries),
+            show_growth: entries.iter().any(|e| e.previous_size.is_some()),
+            has_allocated: entries.iter().any(|e| e.cumulative_allocated_bytes > 0),
+            synced,
+            locked,
+            id_names: owners::IdNames::load(),
+            file_types: file_types::Compositions::default(),
+            tabs: vec![Tab::new(t!("tab-all"), (0..entries.len()).collect())],
+            tab: 0,
             entries,
-            selected: HashSet::new(),
+            selected,
             current_index: 0,
             scroll_offset: 0,
+            classifier: Classifier::default(),
+            root_path: PathBuf::new(),
+            overrides_changed: false,
+            explanation: None,
+            breakdown: None,
+            jump: None,
+            commands: None,
+            sort,
+            temp_only: false,
+            status: None,
+            view: View::Directories,
+            groups: Vec::new(),
+            group_index: 0,
+            group_scroll: 0,
+            tree: DirectoryTree::default(),
+            images: Vec::new(),
+            image_index: 0,
+            image_scroll: 0,
+            largest_files: LargestFiles::default(),
+            file_roots: Vec::new(),
+            files: None,
+            file_index: 0,
+            file_scroll: 0,
+            selected_files: HashMap::new(),
+            session_file: None,
+            session_saved: false,
+            read_only: false,
+            log: Vec::new(),
+            show_log: false,
+            live: None,
+            changed: HashSet::new(),
         }
     }
 
+    /// Use the run's classifier (with overrides and config rules) and scan root for explanations
+    pub fn with_classifier(mut self, classifier: Classifier, root_path: PathBuf) -> Self {
+        self.classifier = classifier;
+        self.root_path = root_path;
+        self
+    }
+
+    /// Split the list into tabs when several roots were scanned, or a merged scan has several
+    /// sources; selections are kept per directory, so every tab shows its share of them
+    pub fn with_roots(mut self, roots: &[PathBuf]) -> Self {
+        self.tabs = root_tabs(&self.entries, roots);
+        self
+    }
+
+    /// Start in the two-pane view (`--two-pane`); 'v' switches back to the list
+    pub fn with_two_pane(mut self, two_pane: bool) -> Self {
+        if two_pane {
+            self.toggle_tree_view();
+        }
+        self
+    }
+
+    /// Start with the suggested deletions selected, for `--auto-select safe`
+    pub fn with_suggested_selection(mut self, select: bool) -> Self {
+        if select {
+            self.select_suggested();
+        }
+        self
+    }
+
+    /// Disable deletion, for `--read-only`
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Keep sizes current while the session is open by watching `roots` for changes, unless
+    /// `--no-watch` was given
+    pub fn with_live_updates(mut self, roots: &[PathBuf], enabled: bool) -> Self {
+        if enabled {
+            self.live = Some(LiveSizes::start(roots, &self.entries));
+        }
+        self
+    }
+
+    /// Default session file, used by 's' when no `--session FILE` was given
+    pub fn default_session_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("disk-cleanup-tool").join("session.csv"))
+    }
+
+    /// Let 's' save the entries and selection to `path`, to resume with `--session`
+    pub fn with_session_file(mut self, path: Option<PathBuf>, metadata: ScanMetadata) -> Self {
+        self.session_file = path.map(|path| (path, metadata));
+        self
+    }
+
+    /// Session file written during this run, if any
+    pub fn saved_session(&self) -> Option<&Path> {
+        self.session_saved.then(|| self.session_file.as_ref().map(|(path, _)| path.as_path())).flatten()
+    }
+
+    /// Rebuild cost of every listed directory, for the confirmation screen
+    pub fn rebuild_costs(&self) -> HashMap<PathBuf, RebuildCost> {
+        self.entries.iter().map(|e| (e.path.clone(), e.rebuild_cost())).collect()
+    }
+
+    /// Directories currently classified as normal, and selected files, which need a stronger
+    /// confirmation to delete
+    pub fn normal_paths(&self) -> HashSet<PathBuf> {
+        self.entries
+            .iter()
+            .filter(|e| e.entry_type == EntryType::Normal)
+            .map(|e| e.path.clone())
+            .chain(self.selected_files.keys().cloned())
+            .collect()
+    }
+
+    /// Files selected in the files view, with their sizes, to take out of the directories
+    /// above them once deleted
+    pub fn selected_file_sizes(&self) -> HashMap<PathBuf, u64> {
+        self.selected_files.clone()
+    }
+
+    /// Classification overrides to persist, if any were changed in this session
+    /// What was done this session, for the audit journal
+    /// The listed directories, as reclassified and recounted during the session
+    pub fn entries(&self) -> &[DirectoryEntry] {
+        &self.entries
+    }
+
+    pub fn session_log(&self) -> &[audit::Record] {
+        &self.log
+    }
+
+    pub fn changed_overrides(&self) -> Option<&Overrides> {
+        self.overrides_changed.then(|| self.classifier.overrides())
+    }
+
     pub fn run(&mut self) -> Result<Vec<PathBuf>, InteractiveError> {
         // Setup terminal
         enable_raw_mode()?;
@@ -73,48 +641,61 @@ impl InteractiveSession {
 
     fn run_loop(&mut self, terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<Vec<PathBuf>, InteractiveError> {
         loop {
+            self.apply_live_updates();
             terminal.draw(|f| self.ui(f))?;
 
             if event::poll(std::time::Duration::from_millis(100))? {
                 if let Event::Key(key) = event::read()? {
                     if key.kind == KeyEventKind::Press {
-                        match key.code {
-                            KeyCode::Char('q') | KeyCode::Esc => {
-                                return Ok(Vec::new());
-                            }
-                            KeyCode::Char(' ') => {
-                                self.toggle_selection();
-                            }
-                            KeyCode::Char('d') | KeyCode::Char('D') => {
-                                if !self.selected.is_empty() {
-                                    return Ok(self.get_selected_paths());
+                        if self.explanation.take().is_some() || self.breakdown.take().is_some() {
+                            continue;
+                        }
+                        if let Some(palette) = &mut self.commands {
+                            match key.code {
+                                KeyCode::Esc => self.commands = None,
+                                KeyCode::Up => palette.index = palette.index.saturating_sub(1),
+                                KeyCode::Down => {
+                                    let last = commands::matching(&palette.input).len().saturating_sub(1);
+                                    palette.index = (palette.index + 1).min(last);
                                 }
+                                KeyCode::Enter => {
+                                    let palette = self.commands.take().unwrap_or_default();
+                                    if let Some(&command) = commands::matching(&palette.input).get(palette.index) {
+                                        if let Some(paths) = self.run_command(command) {
+                                            return Ok(paths);
+                                        }
+                                    }
+                                }
+                                KeyCode::Backspace => {
+                                    palette.input.pop();
+                                    palette.index = 0;
+                                }
+                                KeyCode::Char(c) => {
+                                    palette.input.push(c);
+                                    palette.index = 0;
+                                }
+                                _ => {}
                             }
-                            KeyCode::Up | KeyCode::Char('k') => {
-                                self.move_up();
-                            }
-                            KeyCode::Down | KeyCode::Char('j') => {
-                                self.move_down();
-                            }
-                            KeyCode::Char('a') | KeyCode::Char('A') => {
-                                self.select_all_visible();
-                            }
-                            KeyCode::Char('c') | KeyCode::Char('C') => {
-                                self.clear_all_selections();
-                            }
-                            KeyCode::PageUp => {
-                                self.page_up();
-                            }
-                            KeyCode::PageDown => {
-                                self.page_down();
-                            }
-                            KeyCode::Home => {
-                                self.go_to_top();
-                            }
-                            KeyCode::End => {
-                                self.go_to_bottom();
+                            continue;
+                        }
+                        if let Some(input) = &mut self.jump {
+                            match key.code {
+                                KeyCode::Esc => self.jump = None,
+                                KeyCode::Enter => {
+                                    let input = self.jump.take().unwrap_or_default();
+                                    self.jump_to(&input);
+                                }
+                                KeyCode::Tab => self.complete_jump(),
+                                KeyCode::Backspace => {
+                                    input.pop();
+                                }
+                                KeyCode::Char(c) => input.push(c),
+                                _ => {}
                             }
-                            _ => {}
+                            continue;
+                        }
+                        if let Some(paths) = self.handle_key(key.code) {
+                            return Ok(paths);
                         }
                     }
                 }
@@ -122,197 +703,1829 @@ impl InteractiveSession {
         }
     }
 
-    fn ui(&mut self, f: &mut Frame) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3),  // Header
-                Constraint::Min(0),     // List
-                Constraint::Length(4),  // Footer
-            ])
-            .split(f.area());
+    /// Act on a key pressed in the list, or on a palette command; the paths to delete, or
+    /// nothing, when it ends the session
+    fn handle_key(&mut self, code: KeyCode) -> Option<Vec<PathBuf>> {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                return Some(Vec::new());
+            }
+            KeyCode::Char(' ') if self.view == View::Groups => {
+                self.toggle_group();
+            }
+            // Images are files; their directories are selected from the list
+            KeyCode::Char(' ') if self.view == View::Images => {
+                self.set_error(t!("status-images-not-selectable"));
+            }
+            KeyCode::Char(' ') if self.view == View::Files => {
+                self.toggle_file();
+            }
+            KeyCode::Char(' ') => {
+                self.toggle_selection();
+            }
+            KeyCode::Char('s') | KeyCode::Char('S') => {
+                self.save_session();
+            }
+            KeyCode::Char('g') | KeyCode::Char('G') => {
+                self.toggle_view();
+            }
+            KeyCode::Char('i') | KeyCode::Char('I') => {
+                self.toggle_images();
+            }
+            KeyCode::Char('f') | KeyCode::Char('F') => {
+                self.toggle_files();
+            }
+            KeyCode::Char('/') => {
+                self.open_jump();
+            }
+            KeyCode::Char(':') => {
+                self.commands = Some(CommandPalette::default());
+            }
+            KeyCode::Tab => {
+                self.switch_tab((self.tab + 1) % self.tabs.len());
+            }
+            KeyCode::BackTab => {
+                self.switch_tab((self.tab + self.tabs.len() - 1) % self.tabs.len());
+            }
+            KeyCode::Enter if self.view == View::Groups => {
+                self.open_group();
+            }
+            KeyCode::Enter if self.view == View::Images => {
+                self.open_image();
+            }
+            KeyCode::Enter if self.view == View::Files => {
+                self.open_file();
+            }
+            KeyCode::Char('v') | KeyCode::Char('V') => {
+                self.toggle_tree_view();
+            }
+            KeyCode::Enter if self.view == View::Tree => {
+                self.open_tree_node();
+            }
+            KeyCode::Left | KeyCode::Char('h') if self.view == View::Tree => {
+                self.tree.switch_focus(Pane::Tree);
+            }
+            KeyCode::Right | KeyCode::Char('l') if self.view == View::Tree => {
+                self.tree.switch_focus(Pane::Children);
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                if self.read_only {
+                    self.set_error(t!("status-read-only"));
+                } else if self.nothing_selected() {
+                    self.set_error(t!("status-nothing-selected"));
+                } else {
+                    return Some(self.get_selected_paths());
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.move_up();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.move_down();
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') if self.view == View::Files => {
+                self.select_all_files();
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => {
+                self.select_all_visible();
+            }
+            KeyCode::Char('c') | KeyCode::Char('C') => {
+                self.clear_all_selections();
+            }
+            KeyCode::Char('p') | KeyCode::Char('P') => {
+                self.select_suggested();
+            }
+            KeyCode::Char('L') => {
+                self.show_log = !self.show_log;
+            }
+            KeyCode::Char('u') | KeyCode::Char('U') => {
+                self.toggle_size_basis();
+            }
+            // Reclassifying and explaining act on single directories
+            KeyCode::Char('t' | 'T' | 'e' | 'E' | 'b' | 'B') if matches!(self.view, View::Groups | View::Images | View::Files) => {}
+            KeyCode::Char('t') => {
+                self.toggle_classification();
+            }
+            KeyCode::Char('T') => {
+                self.cycle_category();
+            }
+            KeyCode::Char('e') | KeyCode::Char('E') => {
+                self.explain_current();
+            }
+            KeyCode::Char('b') | KeyCode::Char('B') => {
+                self.open_breakdown();
+            }
+            KeyCode::PageUp => {
+                self.page_up();
+            }
+            KeyCode::PageDown => {
+                self.page_down();
+            }
+            KeyCode::Home => {
+                self.go_to_top();
+            }
+            KeyCode::End => {
+                self.go_to_bottom();
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn ui(&mut self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),  // Header
+                Constraint::Length(if self.tabs.len() > 1 { 1 } else { 0 }), // Tabs
+                Constraint::Min(0),     // List
+                Constraint::Length(1),  // Status bar
+                Constraint::Length(4),  // Footer
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0]);
+        if self.tabs.len() > 1 {
+            self.render_tabs(f, chunks[1]);
+        }
+        let main = if self.show_log {
+            let split = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(LOG_HEIGHT)])
+                .split(chunks[2]);
+            self.render_log(f, split[1]);
+            split[0]
+        } else {
+            chunks[2]
+        };
+        match self.view {
+            View::Directories => self.render_list(f, main),
+            View::Groups => self.render_groups(f, main),
+            View::Images => self.render_images(f, main),
+            View::Files => self.render_files(f, main),
+            View::Tree => self.render_tree(f, main),
+        }
+        self.render_status(f, chunks[3]);
+        self.render_footer(f, chunks[4]);
+
+        if let Some(lines) = &self.explanation {
+            Self::render_explanation(f, lines);
+        }
+        if let Some(breakdown) = &self.breakdown {
+            Self::render_breakdown(f, breakdown);
+        }
+        if let Some(input) = &self.jump {
+            self.render_jump(f, input);
+        }
+        if let Some(palette) = &self.commands {
+            Self::render_commands(f, palette);
+        }
+    }
+
+    /// The command palette with the commands matching what was typed
+    fn render_commands(f: &mut Frame, palette: &CommandPalette) {
+        let area = f.area();
+        let matches = commands::matching(&palette.input);
+        let list_height = (area.height.saturating_sub(5) as usize).clamp(1, 12);
+        let first = palette.index.saturating_sub(list_height - 1);
+
+        let mut text = vec![Line::from(vec![
+            Span::styled(" : ", Style::default().fg(Color::Cyan)),
+            Span::raw(palette.input.clone()),
+            Span::styled("▏", Style::default().fg(Color::Cyan)),
+        ])];
+        for (pos, command) in matches.iter().enumerate().skip(first).take(list_height) {
+            let style = if pos == palette.index {
+                Style::default().fg(Color::White).bg(Color::DarkGray).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            let mut spans = vec![Span::styled(format!("   {}", command.name()), style)];
+            if let Some(key) = command.key {
+                spans.push(Span::styled(format!("  {}", key), Style::default().fg(Color::Cyan)));
+            }
+            text.push(Line::from(spans));
+        }
+        if matches.is_empty() {
+            text.push(Line::styled(format!("   {}", t!("commands-none")), Style::default().fg(Color::DarkGray)));
+        }
+        text.push(Line::styled(format!(" {}", t!("commands-hint")), Style::default().fg(Color::DarkGray)));
+
+        let width = area.width.min(60);
+        let height = (text.len() as u16 + 2).min(area.height);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        let paragraph = Paragraph::new(text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .title(format!(" {} ", t!("commands-title"))),
+        );
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+    }
+
+    /// The jump prompt with the directories it would match
+    fn render_jump(&self, f: &mut Frame, input: &str) {
+        const MAX_CANDIDATES: usize = 8;
+        let area = f.area();
+        let candidates = self.jump_candidates(input);
+
+        let mut text = vec![Line::from(vec![
+            Span::styled(" > ", Style::default().fg(Color::Cyan)),
+            Span::raw(input.to_string()),
+            Span::styled("▏", Style::default().fg(Color::Cyan)),
+        ])];
+        for (_, path) in candidates.iter().take(MAX_CANDIDATES) {
+            text.push(Line::styled(format!("   {}", path), Style::default().fg(Color::DarkGray)));
+        }
+        if candidates.len() > MAX_CANDIDATES {
+            let more = t!("jump-more", count = candidates.len() - MAX_CANDIDATES);
+            text.push(Line::styled(format!("   {}", more), Style::default().fg(Color::DarkGray)));
+        }
+        text.push(Line::styled(format!(" {}", t!("jump-hint")), Style::default().fg(Color::DarkGray)));
+
+        let width = area.width.min(80.max(input.chars().count() as u16 + 8));
+        let height = (text.len() as u16 + 2).min(area.height);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        let paragraph = Paragraph::new(text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .title(format!(" {} ", t!("jump-title"))),
+        );
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+    }
+
+    fn render_explanation(f: &mut Frame, lines: &[String]) {
+        let area = f.area();
+        let width = lines
+            .iter()
+            .map(|l| l.chars().count() as u16 + 4)
+            .max()
+            .unwrap_or(0)
+            .max(40)
+            .min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        let text: Vec<Line> = lines.iter().map(|l| Line::from(format!(" {}", l))).collect();
+        let paragraph = Paragraph::new(text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .title(format!(" {} ", t!("explain-title"))),
+        );
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+    }
+
+    /// The children table in a centered popup, as many rows as fit
+    fn render_breakdown(f: &mut Frame, breakdown: &Breakdown) {
+        let area = f.area();
+        let width = area.width.min(90);
+        let height = (breakdown.children.len() as u16 + 3).min(area.height);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        let share_width = 7 + BAR_WIDTH as u16;
+        let largest = breakdown.children.iter().map(|(_, size)| *size).max().unwrap_or(0);
+        let name_width = width.saturating_sub(2 + 2 + size_width() + 1 + share_width + 2) as usize;
+        let rows: Vec<Row> = breakdown
+            .children
+            .iter()
+            .map(|(name, size)| {
+                let share = if breakdown.total > 0 { *size as f64 / breakdown.total as f64 } else { 0.0 };
+                Row::new(vec![
+                    Cell::from(truncate_path(name, name_width)),
+                    Cell::from(Line::from(format_size(*size)).right_aligned()).style(Style::default().fg(heat_color(*size, largest))),
+                    Cell::from(format_share_with_bar(Some(share))).style(Style::default().fg(Color::Magenta)),
+                ])
+            })
+            .collect();
+        let table = Table::new(
+            rows,
+            [Constraint::Min(10), Constraint::Length(size_width()), Constraint::Length(share_width)],
+        )
+        .header(Row::new(vec![t!("column-name"), t!("column-size"), t!("column-parent-share")]).style(Style::default().fg(Color::DarkGray)))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .title(format!(" {} ", t!("breakdown-title", path = breakdown.path.display().to_string()))),
+        );
+        f.render_widget(Clear, popup);
+        f.render_widget(table, popup);
+    }
+
+    fn render_header(&self, f: &mut Frame, area: Rect) {
+        let total_size: u64 = self.entries.iter().map(|e| e.shown_size()).sum();
+        let selected_size = self.selected_size();
+
+        let header_text = vec![
+            Line::from(vec![
+                Span::styled(t!("app-name"), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::raw(format!(" - {} ", t!("interactive-title"))),
+                Span::styled(t!("interactive-filter"), Style::default().fg(Color::DarkGray)),
+                Span::styled(
+                    if self.read_only { format!(" {}", t!("read-only-badge")) } else { String::new() },
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(vec![
+                Span::raw(format!("{}: ", t!("label-total"))),
+                Span::styled(t!("count-dirs", count = self.entries.len()), Style::default().fg(Color::Yellow)),
+                Span::raw(format!(" | {}: ", t!("label-size"))),
+                Span::styled(format_size(total_size), Style::default().fg(Color::Yellow)),
+                Span::raw(format!(" | {}: ", t!("label-selected"))),
+                Span::styled(format!("{}", self.selected.len() + self.separate_files().count()), Style::default().fg(Color::Green)),
+                Span::raw(" ("),
+                Span::styled(format_size(selected_size), Style::default().fg(Color::Green)),
+                Span::raw(")"),
+            ]),
+        ];
+
+        let header = Paragraph::new(header_text)
+            .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Cyan)));
+        f.render_widget(header, area);
+    }
+
+    /// "All (3.2 GB, 4 selected) │ /home (1.1 GB) │ /data (2.1 GB, 4 selected)"
+    fn render_tabs(&self, f: &mut Frame, area: Rect) {
+        let titles: Vec<Line> = self
+            .tabs
+            .iter()
+            .map(|tab| {
+                let size: u64 = tab.members.iter().map(|&i| self.entries[i].shown_size()).sum();
+                let selected = tab.members.iter().filter(|i| self.selected.contains(i)).count();
+                let title = if selected > 0 {
+                    t!("tab-title-selected", label = tab.label.clone(), size = format_size(size), selected = selected)
+                } else {
+                    t!("tab-title", label = tab.label.clone(), size = format_size(size))
+                };
+                Line::from(title)
+            })
+            .collect();
+        let tabs = Tabs::new(titles)
+            .select(self.tab)
+            .style(Style::default().fg(Color::Gray))
+            .highlight_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD | Modifier::REVERSED));
+        f.render_widget(tabs, area);
+    }
+
+    fn render_list(&mut self, f: &mut Frame, area: Rect) {
+        let list_height = area.height.saturating_sub(3) as usize; // Account for borders and header row
+        
+        // Adjust scroll offset to keep current item visible
+        if self.current_index < self.scroll_offset {
+            self.scroll_offset = self.current_index;
+        } else if self.current_index >= self.scroll_offset + list_height {
+            self.scroll_offset = self.current_index.saturating_sub(list_height.saturating_sub(1));
+        }
+
+        // Optional columns are dropped on narrow terminals; the path gets the remaining width
+        let inner_width = area.width.saturating_sub(2);
+        let show_details = inner_width >= 100;
+        let show_rebuild = inner_width >= 120;
+        let mut widths = vec![Constraint::Length(3), Constraint::Length(TYPE_WIDTH)];
+        // "▲ " ahead of a size
+        let growth_width = size_width() + 2;
+        let show_difference = utils::size_basis() == SizeBasis::Allocated;
+        let mut fixed = 3 + TYPE_WIDTH + size_width() + 11;
+        if self.show_growth {
+            fixed += growth_width;
+        }
+        if show_difference {
+            fixed += growth_width;
+        }
+        if show_details {
+            fixed += 9 + PARENT_WIDTH;
+        }
+        if show_rebuild {
+            fixed += 13 + 6;
+        }
+        let column_count = 5 + u16::from(self.show_growth) + u16::from(show_difference) + if show_details { 2 } else { 0 } + if show_rebuild { 2 } else { 0 };
+        let path_width = inner_width.saturating_sub(fixed + column_count - 1);
+        widths.extend([Constraint::Length(path_width), Constraint::Length(size_width())]);
+        let mut header = vec![
+            Cell::from(""),
+            Cell::from(t!("column-type")),
+            Cell::from(t!("column-path")),
+            Cell::from(Line::from(t!("column-size")).right_aligned()),
+        ];
+        if self.show_growth {
+            widths.push(Constraint::Length(growth_width));
+            header.push(Cell::from(Line::from(t!("column-growth")).right_aligned()));
+        }
+        if show_difference {
+            widths.push(Constraint::Length(growth_width));
+            header.push(Cell::from(Line::from(t!("column-allocation-difference")).right_aligned()));
+        }
+        widths.push(Constraint::Length(11));
+        header.push(Cell::from(Line::from(t!("column-files")).right_aligned()));
+        if show_details {
+            widths.extend([Constraint::Length(9), Constraint::Length(PARENT_WIDTH)]);
+            header.extend([Cell::from(t!("column-modified")), Cell::from(t!("column-parent-share"))]);
+        }
+        if show_rebuild {
+            widths.extend([Constraint::Length(13), Constraint::Length(6)]);
+            header.extend([Cell::from(t!("column-rebuild")), Cell::from(Line::from(t!("column-safety")).right_aligned())]);
+        }
+
+        let now = SystemTime::now();
+        let members = &self.tabs[self.tab].members;
+        let largest = self.largest_entry();
+        let rows: Vec<Row> = members
+            .iter()
+            .enumerate()
+            .skip(self.scroll_offset)
+            .take(list_height)
+            .map(|(pos, &idx)| {
+                let entry = &self.entries[idx];
+                let is_selected = self.selected.contains(&idx);
+                let is_current = pos == self.current_index;
+                
+                let checkbox = if is_selected { "[✓]" } else { "[ ]" };
+                let type_text = if entry.entry_type.is_temp() {
+                    format!("🗑 {}", entry.label.clone().unwrap_or_else(|| entry.entry_type.display_name()))
+                } else {
+                    "📁".to_string()
+                };
+
+                let (badges, badges_width) = badges(&self.synced, &self.locked, &self.changed, idx);
+                let path_str = format!("{}{}", badges, format_entry_path(entry, (path_width as usize).saturating_sub(badges_width)));
+                let mut cells = vec![
+                    Cell::from(checkbox).style(if is_selected { 
+                        Style::default().fg(Color::Green).add_modifier(Modifier::BOLD) 
+                    } else { 
+                        Style::default().fg(Color::DarkGray) 
+                    }),
+                    Cell::from(type_text).style(Style::default().fg(Color::DarkGray)),
+                    Cell::from(path_str).style(if is_current {
+                        Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(Color::Gray)
+                    }),
+                    Cell::from(Line::from(format_entry_size(entry)).right_aligned())
+                        .style(Style::default().fg(heat_color(entry.shown_size(), largest))),
+                ];
+                if self.show_growth {
+                    cells.push(Cell::from(Line::from(format_growth(entry.growth())).right_aligned())
+                        .style(Style::default().fg(growth_color(entry.growth()))));
+                }
+                if show_difference {
+                    let difference = entry.allocation_difference();
+                    cells.push(Cell::from(Line::from(format_allocation_difference(difference)).right_aligned())
+                        .style(Style::default().fg(allocation_difference_color(difference))));
+                }
+                cells.push(Cell::from(Line::from(t!("count-files", count = entry.cumulative_file_count)).right_aligned())
+                    .style(Style::default().fg(Color::Blue)));
+                if show_details {
+                    cells.push(if entry.active {
+                        Cell::from(t!("in-use")).style(Style::default().fg(Color::Yellow))
+                    } else {
+                        Cell::from(format_age(entry.modified, now)).style(Style::default().fg(Color::DarkGray))
+                    });
+                    let share = self.parent_shares.get(&entry.path).copied();
+                    cells.push(Cell::from(format_share_with_bar(share)).style(Style::default().fg(Color::Magenta)));
+                }
+                if show_rebuild && entry.entry_type.is_temp() {
+                    let cost = entry.rebuild_cost();
+                    cells.push(Cell::from(cost.short_label()).style(rebuild_cost_style(cost)));
+                    cells.push(safety_cell(entry.safety));
+                }
+
+                let row = Row::new(cells);
+                if is_current {
+                    row.style(Style::default().bg(Color::DarkGray))
+                } else {
+                    row
+                }
+            })
+            .collect();
+
+        let table = Table::new(rows, widths)
+            .column_spacing(1)
+            .header(Row::new(header).style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)))
+            .block(Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::White))
+                .title(format!(" {} ({}/{}) ", t!("list-directories"), self.current_index + 1, members.len())));
+
+        f.render_widget(table, area);
+    }
+
+    fn render_groups(&mut self, f: &mut Frame, area: Rect) {
+        let list_height = area.height.saturating_sub(3) as usize;
+        if self.group_index < self.group_scroll {
+            self.group_scroll = self.group_index;
+        } else if self.group_index >= self.group_scroll + list_height {
+            self.group_scroll = self.group_index.saturating_sub(list_height.saturating_sub(1));
+        }
+
+        let inner_width = area.width.saturating_sub(2);
+        let show_share = inner_width >= 70;
+        let fixed = 3 + 10 + size_width() + if show_share { PARENT_WIDTH } else { 0 };
+        let column_count = if show_share { 5 } else { 4 };
+        let name_width = inner_width.saturating_sub(fixed + column_count - 1);
+        let mut widths = vec![
+            Constraint::Length(3),
+            Constraint::Length(name_width),
+            Constraint::Length(10),
+            Constraint::Length(size_width()),
+        ];
+        let mut header = vec![
+            Cell::from(""),
+            Cell::from(t!("column-name")),
+            Cell::from(Line::from(t!("column-count")).right_aligned()),
+            Cell::from(Line::from(t!("column-size")).right_aligned()),
+        ];
+        if show_share {
+            widths.push(Constraint::Length(PARENT_WIDTH));
+            header.push(Cell::from(t!("column-temp-share")));
+        }
+
+        let temp_total: u64 = self.groups.iter().map(|g| g.total_bytes).sum();
+        let largest = self.groups.iter().map(|g| g.total_bytes).max().unwrap_or(0);
+        let rows: Vec<Row> = self.groups
+            .iter()
+            .enumerate()
+            .skip(self.group_scroll)
+            .take(list_height)
+            .map(|(idx, group)| {
+                let selectable: Vec<usize> = group.members.iter().copied().filter(|&i| !self.entries[i].active).collect();
+                let selected = selectable.iter().filter(|i| self.selected.contains(i)).count();
+                let (checkbox, style) = if selected == 0 {
+                    ("[ ]", Style::default().fg(Color::DarkGray))
+                } else if selected == selectable.len() {
+                    ("[✓]", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+                } else {
+                    ("[~]", Style::default().fg(Color::Yellow))
+                };
+                let is_current = idx == self.group_index;
+
+                let mut cells = vec![
+                    Cell::from(checkbox).style(style),
+                    Cell::from(truncate_path(&group.name, name_width as usize)).style(if is_current {
+                        Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(Color::Gray)
+                    }),
+                    Cell::from(Line::from(t!("count-dirs", count = group.members.len())).right_aligned())
+                        .style(Style::default().fg(Color::Blue)),
+                    Cell::from(Line::from(format_size(group.total_bytes)).right_aligned())
+                        .style(Style::default().fg(heat_color(group.total_bytes, largest))),
+                ];
+                if show_share {
+                    let share = (temp_total > 0).then(|| group.total_bytes as f64 / temp_total as f64);
+                    cells.push(Cell::from(format_share_with_bar(share)).style(Style::default().fg(Color::Magenta)));
+                }
+
+                let row = Row::new(cells);
+                if is_current {
+                    row.style(Style::default().bg(Color::DarkGray))
+                } else {
+                    row
+                }
+            })
+            .collect();
+
+        let table = Table::new(rows, widths)
+            .column_spacing(1)
+            .header(Row::new(header).style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)))
+            .block(Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::White))
+                .title(format!(
+                    " {} ({}/{}) ",
+                    t!("list-groups"),
+                    (self.group_index + 1).min(self.groups.len()),
+                    self.groups.len()
+                )));
+
+        f.render_widget(table, area);
+    }
+
+    fn render_images(&mut self, f: &mut Frame, area: Rect) {
+        let list_height = area.height.saturating_sub(3) as usize;
+        if self.image_index < self.image_scroll {
+            self.image_scroll = self.image_index;
+        } else if self.image_index >= self.image_scroll + list_height {
+            self.image_scroll = self.image_index.saturating_sub(list_height.saturating_sub(1));
+        }
+
+        // The hint is dropped on narrow terminals; the path gets the remaining width
+        let inner_width = area.width.saturating_sub(2);
+        let show_hint = inner_width >= 110;
+        let hint_width = if show_hint { inner_width * 2 / 5 } else { 0 };
+        let fixed = 20 + 2 * size_width() + 9 + hint_width;
+        let column_count = if show_hint { 6 } else { 5 };
+        let path_width = inner_width.saturating_sub(fixed + column_count - 1);
+        let mut widths = vec![
+            Constraint::Length(20),
+            Constraint::Length(path_width),
+            Constraint::Length(size_width()),
+            Constraint::Length(size_width()),
+            Constraint::Length(9),
+        ];
+        let mut header = vec![
+            Cell::from(t!("column-type")),
+            Cell::from(t!("column-path")),
+            Cell::from(Line::from(t!("column-size")).right_aligned()),
+            Cell::from(Line::from(t!("column-on-disk")).right_aligned()),
+            Cell::from(t!("column-modified")),
+        ];
+        if show_hint {
+            widths.push(Constraint::Length(hint_width));
+            header.push(Cell::from(t!("column-hint")));
+        }
+
+        let now = SystemTime::now();
+        let largest = self.images.first().map_or(0, |(_, image)| image.on_disk);
+        let rows: Vec<Row> = self.images
+            .iter()
+            .enumerate()
+            .skip(self.image_scroll)
+            .take(list_height)
+            .map(|(pos, (_, image))| {
+                let is_current = pos == self.image_index;
+                let mut cells = vec![
+                    Cell::from(format!("💿 {}", image.kind.label())).style(Style::default().fg(Color::DarkGray)),
+                    Cell::from(truncate_path(&image.path.display().to_string(), path_width as usize)).style(if is_current {
+                        Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(Color::Gray)
+                    }),
+                    Cell::from(Line::from(format_size(image.size)).right_aligned()).style(Style::default().fg(Color::DarkGray)),
+                    Cell::from(Line::from(format_size(image.on_disk)).right_aligned())
+                        .style(Style::default().fg(heat_color(image.on_disk, largest))),
+                    Cell::from(format_age(image.modified, now)).style(Style::default().fg(Color::DarkGray)),
+                ];
+                if show_hint {
+                    cells.push(Cell::from(image.kind.hint()).style(Style::default().fg(Color::Yellow)));
+                }
+
+                let row = Row::new(cells);
+                if is_current {
+                    row.style(Style::default().bg(Color::DarkGray))
+                } else {
+                    row
+                }
+            })
+            .collect();
+
+        // Without the hint column, the highlighted image's hint goes below the list
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::White))
+            .title(format!(
+                " {} ({}/{}) ",
+                t!("list-images"),
+                (self.image_index + 1).min(self.images.len()),
+                self.images.len()
+            ));
+        if let Some((_, image)) = self.images.get(self.image_index).filter(|_| !show_hint) {
+            block = block.title_bottom(Line::from(format!(" {} ", image.kind.hint())).style(Style::default().fg(Color::Yellow)));
+        }
+        let table = Table::new(rows, widths)
+            .column_spacing(1)
+            .header(Row::new(header).style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)))
+            .block(block);
+
+        f.render_widget(table, area);
+    }
+
+    fn render_files(&mut self, f: &mut Frame, area: Rect) {
+        self.poll_files();
+        let list_height = area.height.saturating_sub(3) as usize;
+        if self.file_index < self.file_scroll {
+            self.file_scroll = self.file_index;
+        } else if self.file_index >= self.file_scroll + list_height {
+            self.file_scroll = self.file_index.saturating_sub(list_height.saturating_sub(1));
+        }
+
+        let inner_width = area.width.saturating_sub(2);
+        let fixed = 3 + size_width() + 9;
+        let path_width = inner_width.saturating_sub(fixed + 3);
+        let widths = [
+            Constraint::Length(3),
+            Constraint::Length(path_width),
+            Constraint::Length(size_width()),
+            Constraint::Length(9),
+        ];
+        let header = vec![
+            Cell::from(""),
+            Cell::from(t!("column-path")),
+            Cell::from(Line::from(t!("column-size")).right_aligned()),
+            Cell::from(t!("column-modified")),
+        ];
+
+        let now = SystemTime::now();
+        let files = self.files.as_deref().unwrap_or_default();
+        let largest = files.first().map_or(0, |file| file.size);
+        let rows: Vec<Row> = files
+            .iter()
+            .enumerate()
+            .skip(self.file_scroll)
+            .take(list_height)
+            .map(|(pos, file)| {
+                let is_current = pos == self.file_index;
+                let is_selected = self.selected_files.contains_key(&file.path);
+                let row = Row::new(vec![
+                    if is_selected {
+                        Cell::from("[✓]").style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+                    } else {
+                        Cell::from("[ ]").style(Style::default().fg(Color::DarkGray))
+                    },
+                    Cell::from(truncate_path(&file.path.display().to_string(), path_width as usize)).style(if is_current {
+                        Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(Color::Gray)
+                    }),
+                    Cell::from(Line::from(format_size(file.size)).right_aligned())
+                        .style(Style::default().fg(heat_color(file.size, largest))),
+                    Cell::from(format_age(file.modified, now)).style(Style::default().fg(Color::DarkGray)),
+                ]);
+                if is_current {
+                    row.style(Style::default().bg(Color::DarkGray))
+                } else {
+                    row
+                }
+            })
+            .collect();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::White))
+            .title(format!(" {} ({}/{}) ", t!("list-files"), (self.file_index + 1).min(files.len()), files.len()));
+        let table = Table::new(rows, widths)
+            .column_spacing(1)
+            .header(Row::new(header).style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)))
+            .block(block);
+
+        f.render_widget(table, area);
+    }
+
+    /// Tree on the left; details and listed children of the highlighted directory on the right
+    fn render_tree(&mut self, f: &mut Frame, area: Rect) {
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(area);
+        let right = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(11), Constraint::Min(0)])
+            .split(panes[1]);
+        let border = |focused: bool| Style::default().fg(if focused { Color::Cyan } else { Color::White });
+        let checkbox = |idx: usize| {
+            if self.selected.contains(&idx) {
+                Cell::from("[✓]").style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+            } else {
+                Cell::from("[ ]").style(Style::default().fg(Color::DarkGray))
+            }
+        };
+        let badges = |idx: usize| badges(&self.synced, &self.locked, &self.changed, idx).0;
+        let largest = self.largest_entry();
+        let size_cell = |idx: usize| {
+            let size = self.entries[idx].shown_size();
+            Cell::from(Line::from(format_size(size)).right_aligned()).style(Style::default().fg(heat_color(size, largest)))
+        };
+        let name = |idx: usize| {
+            let entry = &self.entries[idx];
+            let name = entry.path.file_name().map(|n| n.to_string_lossy().into_owned());
+            let name = name.unwrap_or_else(|| entry.path.display().to_string());
+            let name = format!("{}{}", badges(idx), name);
+            if entry.entry_type.is_temp() { format!("🗑 {}", name) } else { name }
+        };
+        let row_style = |is_current: bool| {
+            if is_current {
+                Style::default().fg(Color::White).bg(Color::DarkGray).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Gray)
+            }
+        };
+
+        // Left: the tree
+        let tree = &mut self.tree;
+        let list_height = panes[0].height.saturating_sub(2) as usize;
+        if tree.index < tree.scroll {
+            tree.scroll = tree.index;
+        } else if tree.index >= tree.scroll + list_height {
+            tree.scroll = tree.index.saturating_sub(list_height.saturating_sub(1));
+        }
+        let name_width = panes[0].width.saturating_sub(2 + 3 + size_width() + 2) as usize;
+        let rows: Vec<Row> = tree
+            .rows
+            .iter()
+            .enumerate()
+            .skip(tree.scroll)
+            .take(list_height)
+            .map(|(pos, &(idx, depth))| {
+                let marker = match (tree.children.contains_key(&idx), tree.expanded.contains(&idx)) {
+                    (false, _) => "  ",
+                    (true, true) => "▾ ",
+                    (true, false) => "▸ ",
+                };
+                let label = match depth {
+                    0 => format!("{}{}", badges(idx), self.entries[idx].path.display()),
+                    _ => name(idx),
+                };
+                let text = truncate_path(&format!("{}{}{}", "  ".repeat(depth), marker, label), name_width);
+                Row::new(vec![
+                    checkbox(idx),
+                    Cell::from(text),
+                    size_cell(idx),
+                ])
+                .style(row_style(pos == tree.index && tree.focus == Pane::Tree))
+            })
+            .collect();
+        let table = Table::new(
+            rows,
+            [Constraint::Length(3), Constraint::Length(name_width as u16), Constraint::Length(size_width())],
+        )
+        .column_spacing(1)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border(tree.focus == Pane::Tree))
+                .title(format!(" {} ({}/{}) ", t!("list-tree"), (tree.index + 1).min(tree.rows.len()), tree.rows.len())),
+        );
+        f.render_widget(table, panes[0]);
+
+        let Some(current) = tree.current() else {
+            return;
+        };
+
+        // Right, top: details of the highlighted directory
+        let entry = &self.entries[current];
+        let label = |id: &str| Span::styled(format!("{}: ", id), Style::default().fg(Color::Cyan));
+        let kind = if entry.entry_type.is_temp() {
+            format!("🗑 {}", entry.label.clone().unwrap_or_else(|| entry.entry_type.display_name()))
+        } else {
+            entry.entry_type.display_name()
+        };
+        let mut details = vec![
+            Line::from(vec![label(&t!("column-path")), Span::raw(entry.path.display().to_string())]),
+            Line::from(vec![label(&t!("column-type")), Span::raw(kind)]),
+            Line::from(vec![
+                label(&t!("column-size")),
+                Span::styled(format_entry_size(entry), Style::default().fg(Color::Yellow)),
+                Span::raw("  "),
+                label(&t!("column-files")),
+                Span::styled(
+                    t!("count-files", count = entry.cumulative_file_count),
+                    Style::default().fg(Color::Blue),
+                ),
+                Span::styled(
+                    if self.changed.contains(&current) { format!("  ↻ {}", t!("changed-since-scan")) } else { String::new() },
+                    Style::default().fg(Color::Yellow),
+                ),
+            ]),
+            Line::from(vec![
+                label(&t!("column-modified")),
+                if entry.active {
+                    Span::styled(t!("in-use"), Style::default().fg(Color::Yellow))
+                } else {
+                    Span::raw(format_age(entry.modified, SystemTime::now()))
+                },
+            ]),
+            Line::from(vec![
+                label(&t!("column-parent-share")),
+                Span::styled(
+                    format_share_with_bar(self.parent_shares.get(&entry.path).copied()),
+                    Style::default().fg(Color::Magenta),
+                ),
+            ]),
+            Line::from(vec![
+                label(&t!("column-file-types")),
+                match self.file_types.get(&entry.path) {
+                    Some(sizes) => Span::raw(file_types::describe(&sizes)),
+                    None => Span::styled(t!("file-types-counting"), Style::default().fg(Color::DarkGray)),
+                },
+            ]),
+        ];
+        if let Some(access) = owners::access(&entry.path) {
+            let (text, color) = if access.deletable {
+                (format!("✓ {}", t!("access-deletable")), Color::Green)
+            } else {
+                (format!("🔒 {}", t!("locked-warning", path = entry.path.display().to_string())), Color::Red)
+            };
+            details.push(Line::from(vec![
+                label(&t!("column-owner")),
+                Span::raw(self.id_names.describe(access.uid, access.gid)),
+                Span::raw("  "),
+                Span::styled(text, Style::default().fg(color)),
+            ]));
+        }
+        if let Some(service) = self.synced.get(&current) {
+            details.push(Line::from(Span::styled(
+                format!("☁ {}", t!("synced-warning", service = *service)),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+        if entry.entry_type.is_temp() {
+            let cost = entry.rebuild_cost();
+            details.push(Line::from(vec![
+                label(&t!("column-rebuild")),
+                Span::styled(cost.short_label(), rebuild_cost_style(cost)),
+            ]));
+            if let Some(score) = entry.safety {
+                details.push(Line::from(vec![label(&t!("column-safety")), Span::raw(score.to_string())]));
+            }
+        }
+        let details = Paragraph::new(details)
+            .block(Block::default().borders(Borders::ALL).border_style(border(false)).title(format!(" {} ", t!("list-details"))));
+        f.render_widget(details, right[0]);
+
+        // Right, bottom: its listed children
+        let children = tree.children_of(current).to_vec();
+        let list_height = right[1].height.saturating_sub(2) as usize;
+        if tree.child_index < tree.child_scroll {
+            tree.child_scroll = tree.child_index;
+        } else if tree.child_index >= tree.child_scroll + list_height {
+            tree.child_scroll = tree.child_index.saturating_sub(list_height.saturating_sub(1));
+        }
+        let name_width = right[1].width.saturating_sub(2 + 3 + size_width() + PARENT_WIDTH + 3) as usize;
+        let rows: Vec<Row> = children
+            .iter()
+            .enumerate()
+            .skip(tree.child_scroll)
+            .take(list_height)
+            .map(|(pos, &idx)| {
+                let share = self.parent_shares.get(&self.entries[idx].path).copied();
+                Row::new(vec![
+                    checkbox(idx),
+                    Cell::from(truncate_path(&name(idx), name_width)),
+                    size_cell(idx),
+                    Cell::from(format_share_with_bar(share)).style(Style::default().fg(Color::Magenta)),
+                ])
+                .style(row_style(pos == tree.child_index && tree.focus == Pane::Children))
+            })
+            .collect();
+        let title = if children.is_empty() {
+            format!(" {} ", t!("tree-no-children"))
+        } else {
+            format!(" {} ({}) ", t!("list-children"), children.len())
+        };
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Length(name_width as u16),
+                Constraint::Length(size_width()),
+                Constraint::Length(PARENT_WIDTH),
+            ],
+        )
+        .column_spacing(1)
+        .block(Block::default().borders(Borders::ALL).border_style(border(tree.focus == Pane::Children)).title(title));
+        f.render_widget(table, right[1]);
+    }
+
+    /// The most recent actions of the session, newest at the bottom
+    fn render_log(&self, f: &mut Frame, area: Rect) {
+        let shown = area.height.saturating_sub(2) as usize;
+        let lines: Vec<Line> = if self.log.is_empty() {
+            vec![Line::from(Span::styled(t!("log-empty"), Style::default().fg(Color::DarkGray)))]
+        } else {
+            self.log[self.log.len().saturating_sub(shown)..].iter().map(|record| Line::from(record.describe())).collect()
+        };
+        let log = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::White))
+                .title(format!(" {} ({}) ", t!("list-session-log"), self.log.len())),
+        );
+        f.render_widget(log, area);
+    }
+
+    fn render_status(&self, f: &mut Frame, area: Rect) {
+        let Some(status) = self.status.as_ref().filter(|s| s.shown_at.elapsed() < STATUS_TIMEOUT) else {
+            return;
+        };
+        let style = if status.is_error {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Cyan)
+        };
+        f.render_widget(Paragraph::new(Line::from(Span::styled(format!(" {}", status.text), style))), area);
+    }
+
+    /// Take in the directories recounted since the last frame: each gets its new counts and
+    /// the listed directories above it the difference. The list keeps its order, so the
+    /// cursor doesn't jump while something is building.
+    fn apply_live_updates(&mut self) {
+        let Some(live) = &self.live else {
+            return;
+        };
+        let updates = live.pending();
+        if updates.is_empty() {
+            return;
+        }
+        for update in updates {
+            let recounted = match update {
+                live_sizes::Update::Recounted(entry) => entry,
+                live_sizes::Update::Failed(error) => {
+                    self.live = None;
+                    self.set_error(t!("live-updates-failed", error = error));
+                    return;
+                }
+            };
+            self.apply_recount(*recounted);
+        }
+        self.parent_shares = parent_shares(&self.entries);
+    }
+
+    /// New counts for one listed directory, and the difference for those above it
+    fn apply_recount(&mut self, recounted: DirectoryEntry) {
+        let Some(idx) = self.entries.iter().position(|e| e.path == recounted.path) else {
+            return;
+        };
+        let entry = &mut self.entries[idx];
+        let size_change = recounted.cumulative_size_bytes as i64 - entry.cumulative_size_bytes as i64;
+        let allocated_change = recounted.cumulative_allocated_bytes as i64 - entry.cumulative_allocated_bytes as i64;
+        let files_change = recounted.cumulative_file_count as i64 - entry.cumulative_file_count as i64;
+        entry.file_count = recounted.file_count;
+        entry.size_bytes = recounted.size_bytes;
+        entry.cumulative_file_count = recounted.cumulative_file_count;
+        entry.cumulative_size_bytes = recounted.cumulative_size_bytes;
+        entry.cumulative_allocated_bytes = recounted.cumulative_allocated_bytes;
+        entry.modified = recounted.modified;
+        if size_change == 0 && allocated_change == 0 && files_change == 0 {
+            return;
+        }
+        self.changed.insert(idx);
+        for (i, above) in self.entries.iter_mut().enumerate() {
+            if i != idx && recounted.path.starts_with(&above.path) {
+                above.cumulative_size_bytes = above.cumulative_size_bytes.saturating_add_signed(size_change);
+                above.cumulative_allocated_bytes = above.cumulative_allocated_bytes.saturating_add_signed(allocated_change);
+                above.cumulative_file_count = above.cumulative_file_count.saturating_add_signed(files_change);
+                self.changed.insert(i);
+            }
+        }
+    }
+
+    fn set_status(&mut self, text: impl Into<String>) {
+        self.status = Some(StatusMessage {
+            text: text.into(),
+            is_error: false,
+            shown_at: Instant::now(),
+        });
+    }
+
+    fn set_error(&mut self, text: impl Into<String>) {
+        self.status = Some(StatusMessage {
+            text: text.into(),
+            is_error: true,
+            shown_at: Instant::now(),
+        });
+    }
+
+    /// "Selected 12 dirs (3.2 GB)", or "Selected 12 dirs and 3 files (4.0 GB)"
+    fn selection_status(&self) -> String {
+        let size = format_size(self.selected_size());
+        match self.separate_files().count() {
+            0 => t!("status-selected", count = self.selected.len(), size = size),
+            files => t!("status-selected-files", count = self.selected.len(), files = files, size = size),
+        }
+    }
+
+    /// Size of the selected directories and of the selected files outside them
+    fn selected_size(&self) -> u64 {
+        let dirs: u64 = self.selected.iter()
+            .filter_map(|&idx| self.entries.get(idx))
+            .map(|e| e.shown_size())
+            .sum();
+        dirs + self.separate_files().map(|(_, size)| size).sum::<u64>()
+    }
+
+    /// Selected files that no selected directory holds; the others go with their directory
+    fn separate_files(&self) -> impl Iterator<Item = (&PathBuf, u64)> {
+        self.selected_files
+            .iter()
+            .filter(|(path, _)| !self.selected.iter().any(|&i| path.starts_with(&self.entries[i].path)))
+            .map(|(path, &size)| (path, size))
+    }
+
+    fn nothing_selected(&self) -> bool {
+        self.selected.is_empty() && self.selected_files.is_empty()
+    }
+
+    fn render_footer(&self, f: &mut Frame, area: Rect) {
+        let delete_keys = if self.read_only {
+            Vec::new()
+        } else {
+            vec![
+                Span::styled("d", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                Span::raw(format!(": {} | ", t!("key-delete-selected"))),
+            ]
+        };
+        let tree_keys = if self.view == View::Tree {
+            vec![
+                Span::raw(" | "),
+                Span::styled("←/→", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {} | ", t!("key-switch-pane"))),
+                Span::styled("Enter", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {}", t!("key-expand"))),
+            ]
+        } else {
+            Vec::new()
+        };
+        let footer_text = vec![
+            Line::from([vec![
+                Span::styled("↑/↓", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(" {} ", t!("key-or"))),
+                Span::styled("j/k", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {} | ", t!("key-navigate"))),
+                Span::styled("Space", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {} | ", t!("key-toggle"))),
+                Span::styled("a", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {} | ", t!("key-select-all"))),
+                Span::styled("c", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {} | ", t!("key-clear"))),
+                Span::styled("p", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {} | ", t!("key-select-suggested"))),
+                Span::styled("e", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {} | ", t!("key-explain"))),
+                Span::styled("b", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {} | ", t!("key-breakdown"))),
+                Span::styled(":", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(" {} | ", t!("key-commands"))),
+                Span::styled("t/T", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {}", t!("key-reclassify"))),
+            ], tree_keys].concat()),
+            Line::from([vec![
+                Span::styled("PgUp/PgDn", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {} | ", t!("key-page"))),
+                Span::styled("Home/End", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {} | ", t!("key-jump"))),
+                Span::styled("/", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {} | ", t!("key-jump-path"))),
+                Span::styled("g", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(
+                    ": {} | ",
+                    if self.view == View::Groups { t!("key-directories") } else { t!("key-group") }
+                )),
+                Span::styled("v", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(
+                    ": {} | ",
+                    if self.view == View::Tree { t!("key-list") } else { t!("key-two-pane") }
+                )),
+                Span::styled("i", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(
+                    ": {} | ",
+                    if self.view == View::Images { t!("key-directories") } else { t!("key-images") }
+                )),
+                Span::styled("f", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(
+                    ": {} | ",
+                    if self.view == View::Files { t!("key-directories") } else { t!("key-files") }
+                )),
+                Span::styled("Tab", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {} | ", t!("key-next-root"))),
+                Span::styled("s", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {} | ", t!("key-save-session"))),
+                Span::styled("L", Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {} | ", t!("key-session-log"))),
+            ], delete_keys, vec![
+                Span::styled("q/Esc", Style::default().fg(Color::Red)),
+                Span::raw(format!(": {}", t!("key-quit"))),
+            ]].concat()),
+        ];
+
+        let footer = Paragraph::new(footer_text)
+            .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::White)));
+        f.render_widget(footer, area);
+    }
+
+    /// Index into `entries` of the row under the cursor, or in the two-pane view the
+    /// directory with the keyboard focus
+    fn current_entry(&self) -> Option<usize> {
+        match self.view {
+            View::Tree => self.tree.focused(),
+            _ => self.tabs[self.tab].members.get(self.current_index).copied(),
+        }
+    }
+
+    /// Show another tab, keeping each tab's cursor where it was left
+    fn switch_tab(&mut self, tab: usize) {
+        if tab == self.tab {
+            return;
+        }
+        let current = &mut self.tabs[self.tab];
+        current.current_index = self.current_index;
+        current.scroll_offset = self.scroll_offset;
+        self.tab = tab;
+        self.current_index = self.tabs[tab].current_index;
+        self.scroll_offset = self.tabs[tab].scroll_offset;
+        match self.view {
+            View::Groups => {
+                self.groups = name_groups(&self.entries, &self.tabs[tab].members);
+                self.group_index = 0;
+                self.group_scroll = 0;
+            }
+            View::Tree => self.tree = DirectoryTree::new(&self.entries, &self.tabs[tab].members),
+            View::Images => {
+                self.images = disk_images(&self.entries, &self.tabs[tab].members);
+                self.image_index = 0;
+                self.image_scroll = 0;
+            }
+            View::Files => self.load_files(),
+            View::Directories => {}
+        }
+    }
+
+    fn toggle_selection(&mut self) {
+        let Some(idx) = self.current_entry() else {
+            return;
+        };
+        let entry = &self.entries[idx];
+        if entry.active {
+            let message = t!("status-in-use", path = entry.path.display().to_string());
+            self.set_error(message);
+            return;
+        }
+        let path = entry.path.clone();
+        if self.selected.remove(&idx) {
+            self.log.push(audit::Record::now(audit::Action::Deselect { path }));
+        } else {
+            self.selected.insert(idx);
+            self.log.push(audit::Record::now(audit::Action::Select { path }));
+            if self.locked.contains(&idx) {
+                let message = t!("locked-warning", path = entry.path.display().to_string());
+                self.set_error(message);
+                return;
+            }
+            if let Some(service) = self.synced.get(&idx) {
+                self.set_error(t!("synced-warning", service = *service));
+                return;
+            }
+        }
+        self.set_status(self.selection_status());
+    }
+
+    /// Write the entries, classifications and selection to the session file
+    fn save_session(&mut self) {
+        let Some((path, metadata)) = &self.session_file else {
+            self.set_error(t!("status-no-session-file"));
+            return;
+        };
+        let entries: Vec<DirectoryEntry> = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(idx, entry)| DirectoryEntry {
+                selected: self.selected.contains(&idx),
+                ..entry.clone()
+            })
+            .collect();
+
+        let result = match path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            Some(parent) => std::fs::create_dir_all(parent).map_err(Into::into),
+            None => Ok(()),
+        }
+        .and_then(|_| scan_file::write_scan(&entries, path, metadata));
+        match result {
+            Ok(_) => {
+                let message = t!("status-session-saved", path = path.display().to_string(), count = self.selected.len());
+                self.session_saved = true;
+                self.set_status(message);
+            }
+            Err(e) => self.set_error(t!("status-session-error", error = e.to_string())),
+        }
+    }
+
+    /// Switch between the directory list and the grouped-by-name view
+    fn toggle_view(&mut self) {
+        match self.view {
+            View::Directories | View::Tree => {
+                self.groups = name_groups(&self.entries, &self.tabs[self.tab].members);
+                self.group_index = 0;
+                self.group_scroll = 0;
+                self.view = View::Groups;
+                if self.groups.is_empty() {
+                    self.set_error(t!("status-nothing-to-group"));
+                }
+            }
+            View::Groups | View::Images | View::Files => self.view = View::Directories,
+        }
+    }
+
+    /// Switch between the list and the large images view
+    fn toggle_images(&mut self) {
+        if self.view == View::Images {
+            self.view = View::Directories;
+            return;
+        }
+        self.images = disk_images(&self.entries, &self.tabs[self.tab].members);
+        self.image_index = 0;
+        self.image_scroll = 0;
+        self.view = View::Images;
+        if self.images.is_empty() {
+            self.set_error(t!("status-no-images"));
+        }
+    }
+
+    /// Back to the directory list, positioned on the directory the current image is in
+    fn open_image(&mut self) {
+        let dir = self.images.get(self.image_index).map(|(idx, _)| *idx);
+        if let Some(pos) = dir.and_then(|dir| self.tabs[self.tab].members.iter().position(|&i| i == dir)) {
+            self.current_index = pos;
+        }
+        self.view = View::Directories;
+    }
+
+    /// Switch between the list and the largest files view
+    fn toggle_files(&mut self) {
+        if self.view == View::Files {
+            self.view = View::Directories;
+            return;
+        }
+        self.load_files();
+        self.view = View::Files;
+    }
+
+    /// Start looking for the largest files in the current tab's directories
+    fn load_files(&mut self) {
+        let members = &self.tabs[self.tab].members;
+        self.file_roots = largest_files::topmost(members.iter().map(|&i| self.entries[i].path.as_path()));
+        self.files = None;
+        self.file_index = 0;
+        self.file_scroll = 0;
+        self.poll_files();
+    }
+
+    /// Pick up the largest files once the background search is done
+    fn poll_files(&mut self) {
+        if self.files.is_some() {
+            return;
+        }
+        self.files = self.largest_files.get(&self.file_roots);
+        match &self.files {
+            None => self.set_status(t!("status-files-searching")),
+            Some(files) if files.is_empty() => self.set_error(t!("status-no-files")),
+            Some(_) => self.status = None,
+        }
+    }
+
+    /// Select the file under the cursor, or deselect it
+    fn toggle_file(&mut self) {
+        let Some(file) = self.files.as_ref().and_then(|files| files.get(self.file_index)) else {
+            return;
+        };
+        let path = file.path.clone();
+        if self.selected_files.remove(&path).is_some() {
+            self.log.push(audit::Record::now(audit::Action::Deselect { path }));
+        } else {
+            self.selected_files.insert(path.clone(), file.size);
+            self.log.push(audit::Record::now(audit::Action::Select { path: path.clone() }));
+            if owners::needs_elevation(&path) {
+                self.set_error(t!("locked-warning", path = path.display().to_string()));
+                return;
+            }
+        }
+        self.set_status(self.selection_status());
+    }
+
+    /// Select every file the files view lists
+    fn select_all_files(&mut self) {
+        let files = self.files.as_deref().unwrap_or_default();
+        let added = files.iter().filter(|file| self.selected_files.insert(file.path.clone(), file.size).is_none()).count();
+        if added > 0 {
+            self.log.push(audit::Record::now(audit::Action::SelectMany { count: added }));
+        }
+        self.set_status(self.selection_status());
+    }
+
+    /// Back to the directory list, positioned on the deepest listed directory holding the
+    /// current file
+    fn open_file(&mut self) {
+        let file = self.files.as_ref().and_then(|files| files.get(self.file_index));
+        let members = &self.tabs[self.tab].members;
+        let holder = file.and_then(|file| {
+            (0..members.len())
+                .filter(|&pos| file.path.starts_with(&self.entries[members[pos]].path))
+                .max_by_key(|&pos| self.entries[members[pos]].path.components().count())
+        });
+        if let Some(pos) = holder {
+            self.current_index = pos;
+        }
+        self.view = View::Directories;
+    }
+
+    /// Select every directory in the current group, or deselect them if all are selected
+    fn toggle_group(&mut self) {
+        let Some(group) = self.groups.get(self.group_index) else {
+            return;
+        };
+        let selectable: Vec<usize> = group.members.iter().copied().filter(|&i| !self.entries[i].active).collect();
+        let skipped = group.members.len() - selectable.len();
+        let name = group.name.clone();
+
+        if selectable.iter().all(|i| self.selected.contains(i)) {
+            for idx in &selectable {
+                self.selected.remove(idx);
+            }
+            self.log.push(audit::Record::now(audit::Action::DeselectMany { count: selectable.len() }));
+        } else {
+            let added = selectable.iter().filter(|&&idx| self.selected.insert(idx)).count();
+            self.log.push(audit::Record::now(audit::Action::SelectMany { count: added }));
+        }
+
+        let mut message = format!("{}: {}", name, self.selection_status());
+        if skipped > 0 {
+            message.push_str(&t!("status-in-use-skipped", count = skipped));
+        }
+        self.set_status(message);
+    }
+
+    /// Switch between the directory list and the two-pane view, keeping the directory under
+    /// the cursor
+    fn toggle_tree_view(&mut self) {
+        let current = self.current_entry();
+        if self.view == View::Tree {
+            let members = &self.tabs[self.tab].members;
+            if let Some(pos) = current.and_then(|idx| members.iter().position(|&i| i == idx)) {
+                self.current_index = pos;
+            }
+            self.view = View::Directories;
+            return;
+        }
+        self.tree = DirectoryTree::new(&self.entries, &self.tabs[self.tab].members);
+        if let Some(idx) = current.filter(|_| self.view == View::Directories) {
+            self.tree.reveal(idx);
+        }
+        self.view = View::Tree;
+    }
+
+    /// Expand or collapse the tree node, or show the child picked on the right in the tree
+    fn open_tree_node(&mut self) {
+        match self.tree.focus {
+            Pane::Tree => self.tree.toggle_expanded(),
+            Pane::Children => {
+                if let Some(idx) = self.tree.focused() {
+                    self.tree.reveal(idx);
+                    self.tree.switch_focus(Pane::Tree);
+                }
+            }
+        }
+    }
+
+    /// Back to the directory list, positioned on the current group's largest directory
+    fn open_group(&mut self) {
+        let first = self.groups.get(self.group_index).and_then(|g| g.members.first());
+        if let Some(pos) = first.and_then(|first| self.tabs[self.tab].members.iter().position(|i| i == first)) {
+            self.current_index = pos;
+        }
+        self.view = View::Directories;
+    }
+
+    /// Open the jump prompt, starting from the scan root
+    fn open_jump(&mut self) {
+        let mut input = self.root_path.display().to_string();
+        if !input.is_empty() && !input.ends_with(std::path::MAIN_SEPARATOR) {
+            input.push(std::path::MAIN_SEPARATOR);
+        }
+        self.jump = Some(input);
+    }
+
+    /// Positions and paths of the current tab's directories starting with `input`, in list order
+    fn jump_candidates(&self, input: &str) -> Vec<(usize, String)> {
+        self.tabs[self.tab]
+            .members
+            .iter()
+            .enumerate()
+            .map(|(pos, &idx)| (pos, self.entries[idx].path.display().to_string()))
+            .filter(|(_, path)| path.starts_with(input))
+            .collect()
+    }
 
-        self.render_header(f, chunks[0]);
-        self.render_list(f, chunks[1]);
-        self.render_footer(f, chunks[2]);
+    /// Complete the jump prompt as far as the matching paths agree
+    fn complete_jump(&mut self) {
+        if let Some(input) = &self.jump {
+            let candidates = self.jump_candidates(input);
+            let completed = complete_path(input, candidates.iter().map(|(_, path)| path.as_str()));
+            self.jump = Some(completed);
+        }
     }
 
-    fn render_header(&self, f: &mut Frame, area: Rect) {
-        let total_size: u64 = self.entries.iter().map(|e| e.cumulative_size_bytes).sum();
-        let selected_size: u64 = self.selected.iter()
-            .filter_map(|&idx| self.entries.get(idx))
-            .map(|e| e.cumulative_size_bytes)
-            .sum();
+    /// Move the cursor to the directory `input`, or else to the first listed one whose path
+    /// starts with it. Directories outside the current tab are found in the "All" tab.
+    fn jump_to(&mut self, input: &str) {
+        for tab in [self.tab, 0] {
+            let members = &self.tabs[tab].members;
+            let exact = members.iter().position(|&i| self.entries[i].path == Path::new(input));
+            let prefix = || members.iter().position(|&i| self.entries[i].path.display().to_string().starts_with(input));
+            if let Some(pos) = exact.or_else(prefix) {
+                self.switch_tab(tab);
+                self.current_index = pos;
+                self.view = View::Directories;
+                return;
+            }
+        }
+        self.set_error(t!("status-jump-none", path = input));
+    }
 
-        let header_text = vec![
-            Line::from(vec![
-                Span::styled("Disk Cleanup Tool", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-                Span::raw(" - Interactive Mode "),
-                Span::styled("(≥1 MB)", Style::default().fg(Color::DarkGray)),
-            ]),
-            Line::from(vec![
-                Span::raw("Total: "),
-                Span::styled(format!("{} dirs", self.entries.len()), Style::default().fg(Color::Yellow)),
-                Span::raw(" | Size: "),
-                Span::styled(format_size(total_size), Style::default().fg(Color::Yellow)),
-                Span::raw(" | Selected: "),
-                Span::styled(format!("{}", self.selected.len()), Style::default().fg(Color::Green)),
-                Span::raw(" ("),
-                Span::styled(format_size(selected_size), Style::default().fg(Color::Green)),
-                Span::raw(")"),
-            ]),
-        ];
+    /// Run a command picked in the palette; the paths to delete, or nothing, when it ends the
+    /// session
+    fn run_command(&mut self, command: Command) -> Option<Vec<PathBuf>> {
+        match command.action {
+            Action::Key(code) => return self.handle_key(code),
+            Action::SortBy(sort) => {
+                self.sort = sort;
+                self.refresh_tabs();
+                self.set_status(match sort {
+                    SortKey::Size => t!("status-sorted-size"),
+                    SortKey::Files => t!("status-sorted-files"),
+                    SortKey::Safety => t!("status-sorted-safety"),
+                    SortKey::Growth => t!("status-sorted-growth"),
+                });
+            }
+            Action::ToggleTempOnly => {
+                self.temp_only = !self.temp_only;
+                self.refresh_tabs();
+                self.set_status(if self.temp_only { t!("status-temp-only") } else { t!("status-temp-only-off") });
+            }
+            Action::ExportSelection => self.export_selection(),
+            Action::Units(units) => {
+                utils::set_size_units(units);
+                self.set_status(command.name());
+            }
+        }
+        None
+    }
 
-        let header = Paragraph::new(header_text)
-            .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Cyan)));
-        f.render_widget(header, area);
+    /// Switch between apparent and allocated sizes, re-sorting the lists and regrouping the
+    /// views by the new sizes
+    fn toggle_size_basis(&mut self) {
+        if !self.has_allocated {
+            self.set_error(t!("status-no-allocated-sizes"));
+            return;
+        }
+        let basis = match utils::size_basis() {
+            SizeBasis::Apparent => SizeBasis::Allocated,
+            SizeBasis::Allocated => SizeBasis::Apparent,
+        };
+        utils::set_size_basis(basis);
+        self.parent_shares = parent_shares(&self.entries);
+        self.refresh_tabs();
+        self.set_status(match basis {
+            SizeBasis::Apparent => t!("status-size-apparent"),
+            SizeBasis::Allocated => t!("status-size-allocated"),
+        });
     }
 
-    fn render_list(&mut self, f: &mut Frame, area: Rect) {
-        let list_height = area.height.saturating_sub(2) as usize; // Account for borders
-        
-        // Adjust scroll offset to keep current item visible
-        if self.current_index < self.scroll_offset {
-            self.scroll_offset = self.current_index;
-        } else if self.current_index >= self.scroll_offset + list_height {
-            self.scroll_offset = self.current_index.saturating_sub(list_height - 1);
+    /// Re-sort and re-filter every tab after the sort or filter changed, keeping the
+    /// directory under the cursor when it is still listed
+    fn refresh_tabs(&mut self) {
+        let current = self.current_entry();
+        let (entries, sort, temp_only) = (&self.entries, self.sort, self.temp_only);
+        for tab in &mut self.tabs {
+            tab.all.sort_by(|&a, &b| sort.compare(&entries[a], &entries[b]));
+            tab.members = tab.all.iter().copied().filter(|&i| !temp_only || entries[i].entry_type.is_temp()).collect();
+            tab.current_index = 0;
+            tab.scroll_offset = 0;
         }
+        let members = &self.tabs[self.tab].members;
+        self.current_index = current.and_then(|idx| members.iter().position(|&i| i == idx)).unwrap_or(0);
+        match self.view {
+            View::Groups => {
+                self.groups = name_groups(&self.entries, members);
+                self.group_index = 0;
+                self.group_scroll = 0;
+            }
+            View::Tree => {
+                self.tree = DirectoryTree::new(&self.entries, members);
+                if let Some(idx) = current.filter(|idx| members.contains(idx)) {
+                    self.tree.reveal(idx);
+                }
+            }
+            View::Images => {
+                self.images = disk_images(&self.entries, members);
+                self.image_index = 0;
+                self.image_scroll = 0;
+            }
+            View::Files => self.load_files(),
+            View::Directories => {}
+        }
+    }
 
-        let visible_entries: Vec<ListItem> = self.entries
-            .iter()
-            .enumerate()
-            .skip(self.scroll_offset)
-            .take(list_height)
-            .map(|(idx, entry)| {
-                let is_selected = self.selected.contains(&idx);
-                let is_current = idx == self.current_index;
-                
-                let checkbox = if is_selected { "[✓]" } else { "[ ]" };
-                let type_marker = match entry.entry_type {
-                    EntryType::Temp => "🗑 ",
-                    EntryType::Normal => "📁 ",
-                };
+    /// Write the selected paths to a file, one per line, for `--delete-from-file`
+    fn export_selection(&mut self) {
+        if self.nothing_selected() {
+            self.set_error(t!("status-nothing-selected"));
+            return;
+        }
+        let path = Self::default_selection_path();
+        let paths = self.get_selected_paths();
+        let text: String = paths.iter().map(|p| format!("{}\n", p.display())).collect();
+        let result = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&path, text));
+        match result {
+            Ok(()) => self.set_status(t!("status-selection-exported", count = paths.len(), path = path.display().to_string())),
+            Err(e) => self.set_error(t!("status-export-error", error = e.to_string())),
+        }
+    }
 
-                let path_str = entry.path.display().to_string();
-                let size_str = format_size(entry.cumulative_size_bytes);
-                let files_str = format!("{} files", entry.cumulative_file_count);
+    /// Where the palette exports the selection: next to the default session file, or the
+    /// current directory without a data directory
+    fn default_selection_path() -> PathBuf {
+        dirs::data_dir()
+            .map(|dir| dir.join("disk-cleanup-tool").join("selection.txt"))
+            .unwrap_or_else(|| PathBuf::from("selection.txt"))
+    }
 
-                let line = vec![
-                    Span::styled(checkbox.to_string(), if is_selected { 
-                        Style::default().fg(Color::Green).add_modifier(Modifier::BOLD) 
-                    } else { 
-                        Style::default().fg(Color::DarkGray) 
-                    }),
-                    Span::raw(" "),
-                    Span::raw(type_marker.to_string()),
-                    Span::styled(path_str, if is_current {
-                        Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
-                    } else {
-                        Style::default().fg(Color::Gray)
-                    }),
-                    Span::raw(" - "),
-                    Span::styled(size_str, Style::default().fg(Color::Yellow)),
-                    Span::raw(" ("),
-                    Span::styled(files_str, Style::default().fg(Color::Blue)),
-                    Span::raw(")"),
-                ];
+    /// Size of the biggest entry, the top of the size column's color scale
+    fn largest_entry(&self) -> u64 {
+        self.entries.iter().map(|e| e.shown_size()).max().unwrap_or(0)
+    }
 
-                let item = ListItem::new(Line::from(line));
-                if is_current {
-                    item.style(Style::default().bg(Color::DarkGray))
-                } else {
-                    item
-                }
+    /// Size of each listed child of the current entry, the rest of the entry (its own files
+    /// and directories too small to list) as one more row
+    fn open_breakdown(&mut self) {
+        let Some(idx) = self.current_entry() else {
+            return;
+        };
+        let parent = &self.entries[idx];
+        let mut children: Vec<(String, u64)> = self
+            .entries
+            .iter()
+            .filter(|e| e.path.parent() == Some(parent.path.as_path()))
+            .map(|e| {
+                let name = e.path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                (name, e.shown_size())
             })
             .collect();
+        children.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let listed: u64 = children.iter().map(|(_, size)| size).sum();
+        let rest = parent.shown_size().saturating_sub(listed);
+        if rest > 0 {
+            children.push((t!("breakdown-rest"), rest));
+        }
+        self.breakdown = Some(Breakdown {
+            path: parent.path.clone(),
+            total: parent.shown_size(),
+            children,
+        });
+    }
 
-        let list = List::new(visible_entries)
-            .block(Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::White))
-                .title(format!(" Directories ({}/{}) ", self.current_index + 1, self.entries.len())));
+    fn explain_current(&mut self) {
+        if let Some(entry) = self.current_entry().map(|idx| &self.entries[idx]) {
+            self.explanation = Some(explain::describe(&self.classifier, &entry.path, &self.root_path));
+        }
+    }
 
-        f.render_widget(list, area);
+    /// Switch the current entry between normal and temp
+    fn toggle_classification(&mut self) {
+        if let Some(entry) = self.current_entry().map(|idx| &self.entries[idx]) {
+            let entry_type = if entry.entry_type.is_temp() { EntryType::Normal } else { EntryType::Temp };
+            self.set_current_type(entry_type);
+        }
     }
 
-    fn render_footer(&self, f: &mut Frame, area: Rect) {
-        let footer_text = vec![
-            Line::from(vec![
-                Span::styled("↑/↓", Style::default().fg(Color::Cyan)),
-                Span::raw(" or "),
-                Span::styled("j/k", Style::default().fg(Color::Cyan)),
-                Span::raw(": Navigate | "),
-                Span::styled("Space", Style::default().fg(Color::Cyan)),
-                Span::raw(": Toggle | "),
-                Span::styled("a", Style::default().fg(Color::Cyan)),
-                Span::raw(": Select all | "),
-                Span::styled("c", Style::default().fg(Color::Cyan)),
-                Span::raw(": Clear"),
-            ]),
-            Line::from(vec![
-                Span::styled("PgUp/PgDn", Style::default().fg(Color::Cyan)),
-                Span::raw(": Page | "),
-                Span::styled("Home/End", Style::default().fg(Color::Cyan)),
-                Span::raw(": Jump | "),
-                Span::styled("d", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-                Span::raw(": Delete selected | "),
-                Span::styled("q/Esc", Style::default().fg(Color::Red)),
-                Span::raw(": Quit"),
-            ]),
-        ];
+    /// Move the current entry to the next temp category
+    fn cycle_category(&mut self) {
+        if let Some(entry) = self.current_entry().map(|idx| &self.entries[idx]) {
+            let categories = &EntryType::ALL[1..];
+            let next = categories
+                .iter()
+                .position(|&t| t == entry.entry_type)
+                .map_or(categories[0], |idx| categories[(idx + 1) % categories.len()]);
+            self.set_current_type(next);
+        }
+    }
 
-        let footer = Paragraph::new(footer_text)
-            .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::White)));
-        f.render_widget(footer, area);
+    /// Reclassify the current entry and remember it as a per-path override
+    fn set_current_type(&mut self, entry_type: EntryType) {
+        if let Some(entry) = self.current_entry().map(|idx| &mut self.entries[idx]) {
+            entry.entry_type = entry_type;
+            entry.label = None;
+            let path = std::path::absolute(&entry.path).unwrap_or_else(|_| entry.path.clone());
+            let message = t!(
+                "status-reclassified",
+                path = entry.path.display().to_string(),
+                category = entry_type.display_name()
+            );
+            let action = audit::Action::Reclassify { path: entry.path.clone(), category: entry_type.display_name() };
+            self.classifier.set_override(path, entry_type);
+            self.overrides_changed = true;
+            self.log.push(audit::Record::now(action));
+            self.set_status(message);
+        }
     }
 
-    fn toggle_selection(&mut self) {
-        if self.current_index < self.entries.len() {
-            if self.selected.contains(&self.current_index) {
-                self.selected.remove(&self.current_index);
-            } else {
-                self.selected.insert(self.current_index);
+    /// Select every directory in the current tab that is not in use
+    fn select_all_visible(&mut self) {
+        let mut added = 0;
+        for &i in &self.tabs[self.tab].members {
+            if !self.entries[i].active {
+                added += usize::from(self.selected.insert(i));
             }
         }
+        if added > 0 {
+            self.log.push(audit::Record::now(audit::Action::SelectMany { count: added }));
+        }
+        self.set_status(self.selection_status());
     }
 
-    fn select_all_visible(&mut self) {
-        for i in 0..self.entries.len() {
-            self.selected.insert(i);
+    /// Select the suggested deletions in the current tab (see `safety::is_suggested`) to
+    /// review before deleting
+    fn select_suggested(&mut self) {
+        let now = SystemTime::now();
+        let suggested: Vec<usize> =
+            self.tabs[self.tab].members.iter().copied().filter(|&i| safety::is_suggested(&self.entries[i], now)).collect();
+        if suggested.is_empty() {
+            self.set_error(t!("status-no-suggestions"));
+            return;
+        }
+        let size = suggested.iter().map(|&i| self.entries[i].shown_size()).sum();
+        let added = suggested.iter().filter(|&&i| self.selected.insert(i)).count();
+        if added > 0 {
+            self.log.push(audit::Record::now(audit::Action::SelectMany { count: added }));
         }
+        self.set_status(t!("status-suggested", count = suggested.len(), size = format_size(size)));
     }
 
+    /// Clear the selection in the current tab, files included; other roots keep theirs
     fn clear_all_selections(&mut self) {
-        self.selected.clear();
+        let members = &self.tabs[self.tab].members;
+        let files_before = self.selected_files.len();
+        self.selected_files.retain(|path, _| !members.iter().any(|&i| path.starts_with(&self.entries[i].path)));
+        let removed = files_before - self.selected_files.len()
+            + members.iter().filter(|i| self.selected.remove(i)).count();
+        if removed > 0 {
+            self.log.push(audit::Record::now(audit::Action::DeselectMany { count: removed }));
+        }
+        self.set_status(t!("status-selection-cleared"));
     }
 
-    fn move_up(&mut self) {
-        if self.current_index > 0 {
-            self.current_index -= 1;
+    /// Cursor and row count of the list being shown
+    fn cursor(&mut self) -> (&mut usize, usize) {
+        match self.view {
+            View::Directories => (&mut self.current_index, self.tabs[self.tab].members.len()),
+            View::Groups => (&mut self.group_index, self.groups.len()),
+            View::Images => (&mut self.image_index, self.images.len()),
+            View::Files => (&mut self.file_index, self.files.as_ref().map_or(0, Vec::len)),
+            View::Tree => match self.tree.focus {
+                Pane::Tree => (&mut self.tree.index, self.tree.rows.len()),
+                Pane::Children => {
+                    let len = self.tree.current().map_or(0, |idx| self.tree.children_of(idx).len());
+                    (&mut self.tree.child_index, len)
+                }
+            },
         }
     }
 
+    fn move_up(&mut self) {
+        let (cursor, _) = self.cursor();
+        *cursor = cursor.saturating_sub(1);
+    }
+
     fn move_down(&mut self) {
-        if self.current_index + 1 < self.entries.len() {
-            self.current_index += 1;
+        let (cursor, len) = self.cursor();
+        if *cursor + 1 < len {
+            *cursor += 1;
         }
     }
 
     fn page_up(&mut self) {
-        self.current_index = self.current_index.saturating_sub(10);
+        let (cursor, _) = self.cursor();
+        *cursor = cursor.saturating_sub(10);
     }
 
     fn page_down(&mut self) {
-        self.current_index = (self.current_index + 10).min(self.entries.len().saturating_sub(1));
+        let (cursor, len) = self.cursor();
+        *cursor = (*cursor + 10).min(len.saturating_sub(1));
     }
 
     fn go_to_top(&mut self) {
-        self.current_index = 0;
-        self.scroll_offset = 0;
+        let (cursor, _) = self.cursor();
+        *cursor = 0;
     }
 
     fn go_to_bottom(&mut self) {
-        self.current_index = self.entries.len().saturating_sub(1);
+        let (cursor, len) = self.cursor();
+        *cursor = len.saturating_sub(1);
     }
 
     fn get_selected_paths(&self) -> Vec<PathBuf> {
@@ -322,6 +2535,7 @@ impl InteractiveSession {
                 paths.push(self.entries[idx].path.clone());
             }
         }
+        paths.extend(self.separate_files().map(|(path, _)| path.clone()));
         paths
     }
 }
@@ -352,10 +2566,11 @@ mod proptests {
                     cumulative_file_count: 1,
                     cumulative_size_bytes: *size,
                     entry_type: EntryType::Normal,
+                    ..Default::default()
                 });
             }
 
-            let session = InteractiveSession::new(entries);
+            let session = InteractiveSession::new(entries, SortKey::Size);
 
             // Verify entries are sorted by cumulative size descending
             for i in 0..session.entries.len() - 1 {
@@ -377,10 +2592,11 @@ mod proptests {
                     cumulative_file_count: 1,
                     cumulative_size_bytes: MIN_SIZE,
                     entry_type: EntryType::Normal,
+                    ..Default::default()
                 });
             }
 
-            let mut session = InteractiveSession::new(entries);
+            let mut session = InteractiveSession::new(entries, SortKey::Size);
             
             // Session should have all entries since they're all >= 1MB
             prop_assert_eq!(session.entries.len(), num_entries);
@@ -399,5 +2615,639 @@ mod proptests {
             session.toggle_selection();
             prop_assert!(!session.selected.contains(&idx));
         }
+
+        #[test]
+        fn test_status_reports_selection(num_entries in 1usize..10, active_idx in 0usize..10) {
+            const MIN_SIZE: u64 = 1024 * 1024; // 1 MB
+            let active_idx = active_idx % num_entries;
+            let entries: Vec<DirectoryEntry> = (0..num_entries)
+                .map(|i| DirectoryEntry {
+                    path: PathBuf::from(format!("/dir{}", i)),
+                    cumulative_size_bytes: MIN_SIZE,
+                    active: i == active_idx,
+                    ..Default::default()
+                })
+                .collect();
+
+            let mut session = InteractiveSession::new(entries, SortKey::Size);
+            prop_assert!(session.status.is_none());
+
+            session.select_all_visible();
+            let status = session.status.as_ref().unwrap();
+            prop_assert!(!status.is_error);
+            prop_assert_eq!(
+                &status.text,
+                &format!("Selected {} dirs ({})", num_entries - 1, format_size(MIN_SIZE * (num_entries as u64 - 1)))
+            );
+
+            // Selecting an in-use entry is refused with an error message
+            session.clear_all_selections();
+            session.current_index = session.entries.iter().position(|e| e.active).unwrap();
+            session.toggle_selection();
+            prop_assert!(session.selected.is_empty());
+            prop_assert!(session.status.as_ref().unwrap().is_error);
+        }
+
+        #[test]
+        fn test_classification_override(num_entries in 1usize..10, idx in 0usize..10, cycles in 0usize..20) {
+            const MIN_SIZE: u64 = 1024 * 1024; // 1 MB
+            let entries: Vec<DirectoryEntry> = (0..num_entries)
+                .map(|i| DirectoryEntry {
+                    path: PathBuf::from(format!("/dir{}", i)),
+                    cumulative_size_bytes: MIN_SIZE,
+                    ..Default::default()
+                })
+                .collect();
+
+            let mut session = InteractiveSession::new(entries, SortKey::Size);
+            prop_assert!(session.changed_overrides().is_none());
+            session.current_index = idx % num_entries;
+            let path = session.entries[session.current_index].path.clone();
+
+            session.toggle_classification();
+            prop_assert_eq!(session.entries[session.current_index].entry_type, EntryType::Temp);
+
+            // Cycling always stays within the temp categories
+            for _ in 0..cycles {
+                session.cycle_category();
+                prop_assert!(session.entries[session.current_index].entry_type.is_temp());
+            }
+            let entry_type = session.entries[session.current_index].entry_type;
+            prop_assert_eq!(session.changed_overrides().unwrap().get(&path), Some(entry_type));
+
+            session.toggle_classification();
+            prop_assert_eq!(session.entries[session.current_index].entry_type, EntryType::Normal);
+            prop_assert_eq!(session.changed_overrides().unwrap().get(&path), Some(EntryType::Normal));
+        }
+
+        #[test]
+        fn test_session_roundtrip(num_entries in 1usize..10, picks in prop::collection::vec(0usize..10, 0..5)) {
+            const MIN_SIZE: u64 = 1024 * 1024; // 1 MB
+            let entries: Vec<DirectoryEntry> = (0..num_entries)
+                .map(|i| DirectoryEntry {
+                    path: PathBuf::from(format!("/dir{}", i)),
+                    cumulative_size_bytes: MIN_SIZE * (i as u64 + 1),
+                    ..Default::default()
+                })
+                .collect();
+            let temp_dir = tempfile::TempDir::new().unwrap();
+            let path = temp_dir.path().join("nested/session.csv");
+
+            let mut session = InteractiveSession::new(entries, SortKey::Size)
+                .with_session_file(Some(path.clone()), ScanMetadata::new(None));
+            for pick in &picks {
+                session.current_index = pick % num_entries;
+                session.toggle_selection();
+            }
+            prop_assert!(session.saved_session().is_none());
+            session.save_session();
+            prop_assert!(!session.status.as_ref().unwrap().is_error);
+            prop_assert_eq!(session.saved_session(), Some(path.as_path()));
+
+            let (loaded, _) = scan_file::read_scan(&path).unwrap();
+            let resumed = InteractiveSession::new(loaded, SortKey::Size);
+            prop_assert_eq!(resumed.entries.len(), num_entries);
+            let mut expected = session.get_selected_paths();
+            let mut actual = resumed.get_selected_paths();
+            expected.sort();
+            actual.sort();
+            prop_assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn test_group_selection(projects in 1usize..8, active_idx in 0usize..8) {
+            const MIN_SIZE: u64 = 1024 * 1024; // 1 MB
+            let active_idx = active_idx % projects;
+            let mut entries = Vec::new();
+            for i in 0..projects {
+                entries.push(DirectoryEntry {
+                    path: PathBuf::from(format!("/p{}/node_modules", i)),
+                    cumulative_size_bytes: 2 * MIN_SIZE,
+                    entry_type: EntryType::DependencyCache,
+                    active: i == active_idx,
+                    ..Default::default()
+                });
+                entries.push(DirectoryEntry {
+                    path: PathBuf::from(format!("/p{}/target", i)),
+                    cumulative_size_bytes: MIN_SIZE,
+                    entry_type: EntryType::BuildOutput,
+                    ..Default::default()
+                });
+            }
+
+            let mut session = InteractiveSession::new(entries, SortKey::Size);
+            session.toggle_view();
+            prop_assert_eq!(session.groups.len(), 2);
+            prop_assert_eq!(&session.groups[0].name, "node_modules");
+            prop_assert_eq!(session.groups[0].members.len(), projects);
+            prop_assert_eq!(session.groups[0].total_bytes, 2 * MIN_SIZE * projects as u64);
+
+            // One keystroke selects the whole group except in-use directories
+            session.toggle_group();
+            prop_assert_eq!(session.selected.len(), projects - 1);
+            prop_assert!(session.get_selected_paths().iter().all(|p| p.ends_with("node_modules")));
+
+            session.move_down();
+            session.toggle_group();
+            prop_assert_eq!(session.selected.len(), 2 * projects - 1);
+
+            // A fully selected group is deselected
+            session.toggle_group();
+            prop_assert_eq!(session.selected.len(), projects - 1);
+
+            session.open_group();
+            prop_assert_eq!(session.view, View::Directories);
+            prop_assert!(session.entries[session.current_index].path.ends_with("target"));
+        }
+
+        #[test]
+        fn test_root_tabs(per_root in 1usize..6, cursor in 0usize..6) {
+            const MIN_SIZE: u64 = 1024 * 1024; // 1 MB
+            let entries: Vec<DirectoryEntry> = (0..per_root)
+                .flat_map(|i| ["/a", "/b"].map(|root| DirectoryEntry {
+                    path: PathBuf::from(format!("{}/dir{}", root, i)),
+                    cumulative_size_bytes: MIN_SIZE * (i as u64 + 1),
+                    ..Default::default()
+                }))
+                .collect();
+            let roots = [PathBuf::from("/a"), PathBuf::from("/b")];
+
+            let mut session = InteractiveSession::new(entries.clone(), SortKey::Size).with_roots(&roots);
+            prop_assert_eq!(session.tabs.len(), 3);
+            prop_assert_eq!(session.tabs[0].members.len(), 2 * per_root);
+
+            // Selecting all in one root's tab leaves the other root alone
+            session.switch_tab(1);
+            session.select_all_visible();
+            prop_assert_eq!(session.selected.len(), per_root);
+            prop_assert!(session.get_selected_paths().iter().all(|p| p.starts_with("/a")));
+
+            session.switch_tab(2);
+            session.current_index = cursor % per_root;
+            session.toggle_selection();
+            prop_assert_eq!(session.selected.len(), per_root + 1);
+            session.clear_all_selections();
+            prop_assert_eq!(session.selected.len(), per_root);
+
+            // Each tab remembers its cursor
+            session.switch_tab(0);
+            prop_assert_eq!(session.current_index, 0);
+            session.switch_tab(2);
+            prop_assert_eq!(session.current_index, cursor % per_root);
+
+            // A merged scan of one root gets a tab per source
+            let mut merged = entries;
+            for entry in &mut merged {
+                entry.source = Some(if entry.path.starts_with("/a") { "laptop" } else { "server" }.to_string());
+            }
+            let session = InteractiveSession::new(merged.clone(), SortKey::Size).with_roots(&roots[..1]);
+            let labels: Vec<&str> = session.tabs.iter().map(|t| t.label.as_str()).collect();
+            prop_assert_eq!(labels, vec!["All", "laptop", "server"]);
+            let session = InteractiveSession::new(merged[..1].to_vec(), SortKey::Size).with_roots(&roots[..1]);
+            prop_assert_eq!(session.tabs.len(), 1);
+
+            // Mounts nest, so an --all-mounts scan splits by mount rather than by path
+            let mounts = [PathBuf::from("/"), PathBuf::from("/home")];
+            let mut scanned = merged;
+            for entry in &mut scanned {
+                entry.source = None;
+                entry.path = Path::new("/home").join(entry.path.strip_prefix("/").unwrap());
+                entry.mount = Some(mounts[1].clone());
+            }
+            scanned[0].path = PathBuf::from("/var/cache");
+            scanned[0].mount = Some(mounts[0].clone());
+            let session = InteractiveSession::new(scanned.clone(), SortKey::Size).with_roots(&mounts);
+            let sizes: Vec<usize> = session.tabs.iter().map(|t| t.members.len()).collect();
+            prop_assert_eq!(sizes, vec![scanned.len(), 1, scanned.len() - 1]);
+        }
+
+        #[test]
+        fn test_jump_to_path(num_entries in 1usize..10, pick in 0usize..10) {
+            const MIN_SIZE: u64 = 1024 * 1024; // 1 MB
+            let entries: Vec<DirectoryEntry> = (0..num_entries)
+                .map(|i| DirectoryEntry {
+                    path: PathBuf::from(format!("/root/project{}/node_modules", i)),
+                    cumulative_size_bytes: MIN_SIZE * (i as u64 + 1),
+                    ..Default::default()
+                })
+                .collect();
+            let target = format!("/root/project{}/node_modules", pick % num_entries);
+
+            let mut session = InteractiveSession::new(entries, SortKey::Size).with_classifier(Classifier::default(), PathBuf::from("/root"));
+            session.open_jump();
+            prop_assert_eq!(session.jump.as_deref(), Some("/root/"));
+
+            // Tab completes as far as the listed paths agree; a single entry completes fully
+            session.complete_jump();
+            let common = if num_entries == 1 { target.as_str() } else { "/root/project" };
+            prop_assert_eq!(session.jump.as_deref(), Some(common));
+            session.jump = Some(format!("/root/project{}", pick % num_entries));
+            session.complete_jump();
+            prop_assert_eq!(session.jump.as_deref(), Some(target.as_str()));
+
+            session.jump_to(&target);
+            prop_assert_eq!(session.entries[session.current_index].path.display().to_string(), target);
+            prop_assert!(session.status.is_none());
+
+            session.jump_to("/elsewhere");
+            prop_assert!(session.status.as_ref().unwrap().is_error);
+        }
+    }
+
+    #[test]
+    fn test_complete_path() {
+        let paths = ["/home/me/src/app/node_modules", "/home/me/src/app/target", "/home/me/.cache"];
+        assert_eq!(complete_path("/home/me/s", paths), "/home/me/src/app/");
+        assert_eq!(complete_path("/home/me/src/app/t", paths), "/home/me/src/app/target");
+        assert_eq!(complete_path("/home/me/", paths), "/home/me/");
+        assert_eq!(complete_path("/tmp", paths), "/tmp");
+    }
+
+    #[test]
+    fn test_synced_badge() {
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        const MIN_SIZE: u64 = 1024 * 1024; // 1 MB
+        let entries = ["/home/me/Dropbox/app/node_modules", "/home/me/src/app/node_modules"]
+            .map(|path| DirectoryEntry {
+                path: PathBuf::from(path),
+                cumulative_size_bytes: MIN_SIZE,
+                entry_type: EntryType::DependencyCache,
+                ..Default::default()
+            })
+            .to_vec();
+        let mut session = InteractiveSession::new(entries, SortKey::Size);
+        let synced = |session: &InteractiveSession| session.current_entry().and_then(|idx| session.synced.get(&idx).copied());
+        assert_eq!(synced(&session), Some("Dropbox"));
+
+        // Selecting it works, with a warning instead of the usual count
+        session.toggle_selection();
+        assert_eq!(session.selected.len(), 1);
+        assert!(session.status.as_ref().is_some_and(|status| status.is_error && status.text.contains("Dropbox")));
+        session.move_down();
+        assert_eq!(synced(&session), None);
+        session.toggle_selection();
+        assert!(session.status.as_ref().is_some_and(|status| !status.is_error));
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 12)).unwrap();
+        terminal.draw(|f| session.render_list(f, f.area())).unwrap();
+        let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+        assert_eq!(screen.matches('☁').count(), 1);
+
+        // Entries the user can't delete are flagged the same way, before confirming
+        session.clear_all_selections();
+        let current = session.current_entry().unwrap();
+        session.locked.insert(current);
+        session.toggle_selection();
+        assert!(session.status.as_ref().is_some_and(|status| status.is_error && status.text.contains("sudo")));
+        terminal.draw(|f| session.render_list(f, f.area())).unwrap();
+        let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+        assert_eq!(screen.matches('🔒').count(), 1);
+    }
+
+    #[test]
+    fn test_two_pane_tree() {
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        const MIN_SIZE: u64 = 1024 * 1024; // 1 MB
+        let entry = |path: &str, size: u64, entry_type: EntryType| DirectoryEntry {
+            path: PathBuf::from(path),
+            cumulative_size_bytes: size * MIN_SIZE,
+            entry_type,
+            ..Default::default()
+        };
+        let entries = vec![
+            entry("/src", 10, EntryType::Normal),
+            entry("/src/app", 8, EntryType::Normal),
+            entry("/src/app/node_modules", 5, EntryType::DependencyCache),
+            entry("/src/app/target", 2, EntryType::BuildOutput),
+            entry("/other", 1, EntryType::Normal),
+        ];
+        let mut session = InteractiveSession::new(entries, SortKey::Size);
+        let path_of = |session: &InteractiveSession| {
+            session.current_entry().map(|idx| session.entries[idx].path.display().to_string())
+        };
+
+        // Starting on node_modules reveals it inside its expanded ancestors
+        session.move_down();
+        session.move_down();
+        session.toggle_tree_view();
+        assert_eq!(session.view, View::Tree);
+        assert_eq!(path_of(&session).as_deref(), Some("/src/app/node_modules"));
+        let depths: Vec<usize> = session.tree.rows.iter().map(|&(_, depth)| depth).collect();
+        assert_eq!(depths, vec![0, 1, 2, 2, 0]);
+
+        // Collapsing /src hides its subtree; the leaf has no children to focus
+        session.tree.switch_focus(Pane::Children);
+        assert_eq!(session.tree.focus, Pane::Tree);
+        session.tree.index = 0;
+        session.open_tree_node();
+        assert_eq!(session.tree.rows.len(), 2);
+        session.open_tree_node();
+        assert_eq!(session.tree.rows.len(), 5);
+
+        // The children pane picks /src/app/target, selects it and shows it in the tree
+        session.move_down();
+        session.tree.switch_focus(Pane::Children);
+        session.move_down();
+        assert_eq!(path_of(&session).as_deref(), Some("/src/app/target"));
+        session.toggle_selection();
+        assert_eq!(session.get_selected_paths(), vec![PathBuf::from("/src/app/target")]);
+        session.open_tree_node();
+        assert_eq!(session.tree.focus, Pane::Tree);
+        assert_eq!(path_of(&session).as_deref(), Some("/src/app/target"));
+
+        for width in [30, 120] {
+            let mut terminal = Terminal::new(TestBackend::new(width, 12)).unwrap();
+            terminal.draw(|f| session.render_tree(f, f.area())).unwrap();
+        }
+
+        // Back in the list, on the same directory
+        session.toggle_tree_view();
+        assert_eq!(session.view, View::Directories);
+        assert_eq!(path_of(&session).as_deref(), Some("/src/app/target"));
+    }
+
+    #[test]
+    fn test_explanation_narrow_terminal() {
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        let lines = vec!["Matched built-in name 'node_modules'".to_string(), "Dependencies".to_string()];
+        for width in [20, 39, 40, 120] {
+            let mut terminal = Terminal::new(TestBackend::new(width, 10)).unwrap();
+            terminal.draw(|f| InteractiveSession::render_explanation(f, &lines)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_command_palette() {
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        const MB: u64 = 1024 * 1024;
+        let entries = [("/p/big", 30, 10, EntryType::Normal), ("/p/many", 20, 5000, EntryType::Temp), ("/p/mid", 10, 50, EntryType::Temp)]
+            .map(|(path, size, files, entry_type)| DirectoryEntry {
+                path: PathBuf::from(path),
+                cumulative_size_bytes: size * MB,
+                cumulative_file_count: files,
+                entry_type,
+                ..Default::default()
+            })
+            .to_vec();
+        let mut session = InteractiveSession::new(entries, SortKey::Size);
+        let listed = |session: &InteractiveSession| {
+            session.tabs[session.tab].members.iter().map(|&i| session.entries[i].path.to_string_lossy().into_owned()).collect::<Vec<_>>()
+        };
+        let run = |session: &mut InteractiveSession, query: &str| session.run_command(commands::matching(query)[0]);
+
+        // Re-sorting keeps the cursor on the same directory
+        session.move_down();
+        assert_eq!(run(&mut session, "sort file"), None);
+        assert_eq!(listed(&session), ["/p/many", "/p/mid", "/p/big"]);
+        assert_eq!(session.entries[session.current_entry().unwrap()].path, PathBuf::from("/p/many"));
+
+        run(&mut session, "temp only");
+        assert_eq!(listed(&session), ["/p/many", "/p/mid"]);
+        run(&mut session, "temp only");
+        assert_eq!(listed(&session), ["/p/many", "/p/mid", "/p/big"]);
+
+        // Commands with a key do what the key does
+        run(&mut session, "select all");
+        assert_eq!(session.selected.len(), 3);
+        assert_eq!(run(&mut session, "quit"), Some(Vec::new()));
+
+        session.commands = Some(CommandPalette { input: "units".to_string(), index: 1 });
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|f| session.ui(f)).unwrap();
+        let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains("Units: SI") && !screen.contains("Sort by size"));
+        let mut terminal = Terminal::new(TestBackend::new(20, 4)).unwrap();
+        terminal.draw(|f| session.ui(f)).unwrap();
+    }
+
+    #[test]
+    fn test_breakdown() {
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        const MB: u64 = 1024 * 1024;
+        let entries = [("/p", 100), ("/p/a", 60), ("/p/a/x", 50), ("/p/b", 30)]
+            .map(|(path, size)| DirectoryEntry {
+                path: PathBuf::from(path),
+                cumulative_size_bytes: size * MB,
+                ..Default::default()
+            })
+            .to_vec();
+        let mut session = InteractiveSession::new(entries, SortKey::Size);
+        assert_eq!(session.entries[session.current_entry().unwrap()].path, PathBuf::from("/p"));
+        session.open_breakdown();
+
+        // Only immediate children, largest first, and what's left over
+        let breakdown = session.breakdown.as_ref().unwrap();
+        let names: Vec<&str> = breakdown.children.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["a", "b", "(files and smaller directories)"]);
+        assert_eq!(breakdown.children[2].1, 10 * MB);
+
+        for (width, height) in [(20, 4), (120, 12)] {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            terminal.draw(|f| InteractiveSession::render_breakdown(f, breakdown)).unwrap();
+        }
+        let mut terminal = Terminal::new(TestBackend::new(120, 12)).unwrap();
+        terminal.draw(|f| InteractiveSession::render_breakdown(f, breakdown)).unwrap();
+        let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains("60.0%") && screen.contains("30.0%"));
+    }
+
+    #[test]
+    fn test_session_log() {
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        let entries = ["/p/a", "/p/b", "/p/c"]
+            .map(|path| DirectoryEntry {
+                path: PathBuf::from(path),
+                cumulative_size_bytes: 5 * 1024 * 1024,
+                ..Default::default()
+            })
+            .to_vec();
+        let mut session = InteractiveSession::new(entries, SortKey::Size);
+        let first = session.entries[session.current_entry().unwrap()].path.clone();
+        session.handle_key(KeyCode::Char(' '));
+        session.handle_key(KeyCode::Char(' '));
+        session.handle_key(KeyCode::Char('a'));
+        session.handle_key(KeyCode::Char('c'));
+        session.handle_key(KeyCode::Char('t'));
+        let actions: Vec<&audit::Action> = session.session_log().iter().map(|record| &record.action).collect();
+        assert_eq!(
+            actions,
+            [
+                &audit::Action::Select { path: first.clone() },
+                &audit::Action::Deselect { path: first.clone() },
+                &audit::Action::SelectMany { count: 3 },
+                &audit::Action::DeselectMany { count: 3 },
+                &audit::Action::Reclassify { path: first, category: EntryType::Temp.display_name() },
+            ]
+        );
+
+        session.handle_key(KeyCode::Char('L'));
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|f| session.ui(f)).unwrap();
+        let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains(&t!("audit-select-many", count = 3)));
+    }
+
+    #[test]
+    fn test_apply_recount() {
+        const MB: u64 = 1024 * 1024;
+        let entries = [("/p", 100), ("/p/a", 60), ("/p/a/target", 50), ("/p/b", 30)]
+            .map(|(path, size)| DirectoryEntry {
+                path: PathBuf::from(path),
+                cumulative_size_bytes: size * MB,
+                cumulative_file_count: size,
+                ..Default::default()
+            })
+            .to_vec();
+        let mut session = InteractiveSession::new(entries, SortKey::Size);
+        let size = |session: &InteractiveSession, path: &str| {
+            session.entries.iter().find(|e| e.path == Path::new(path)).unwrap().cumulative_size_bytes / MB
+        };
+
+        // A build grew target: it and the directories above it change, the sibling doesn't
+        session.apply_recount(DirectoryEntry {
+            path: PathBuf::from("/p/a/target"),
+            cumulative_size_bytes: 80 * MB,
+            cumulative_file_count: 70,
+            ..Default::default()
+        });
+        assert_eq!([size(&session, "/p"), size(&session, "/p/a"), size(&session, "/p/a/target"), size(&session, "/p/b")], [130, 90, 80, 30]);
+        assert_eq!(session.changed.len(), 3);
+        assert!(session.entries.iter().all(|e| e.path == Path::new("/p/b") || e.cumulative_file_count >= 70));
+        let (badge, _) = badges(&session.synced, &session.locked, &session.changed, 0);
+        assert_eq!(badge, "↻ ");
+
+        // Deleted in another terminal
+        session.apply_recount(DirectoryEntry { path: PathBuf::from("/p/b"), ..Default::default() });
+        assert_eq!([size(&session, "/p"), size(&session, "/p/b")], [100, 0]);
+        assert_eq!(session.changed.len(), 4);
+    }
+
+
+    #[test]
+    fn test_files_view() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        const MB: u64 = 1024 * 1024;
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("cache")).unwrap();
+        fs::write(root.join("movie.mkv"), "m".repeat(3000)).unwrap();
+        fs::write(root.join("cache/blob"), "b".repeat(2000)).unwrap();
+        fs::write(root.join("notes.txt"), "n".repeat(10)).unwrap();
+        let entries = vec![
+            DirectoryEntry { path: root.to_path_buf(), cumulative_size_bytes: 5 * MB, ..Default::default() },
+            DirectoryEntry { path: root.join("cache"), cumulative_size_bytes: 2 * MB, ..Default::default() },
+        ];
+        let mut session = InteractiveSession::new(entries, SortKey::Size);
+        session.handle_key(KeyCode::Char('f'));
+        assert_eq!(session.view, View::Files);
+        assert_eq!(session.file_roots, [root.to_path_buf()]);
+
+        let started = Instant::now();
+        while session.files.is_none() {
+            assert!(started.elapsed() < Duration::from_secs(10));
+            std::thread::sleep(Duration::from_millis(10));
+            session.poll_files();
+        }
+        let paths: Vec<PathBuf> = session.files.iter().flatten().map(|file| file.path.clone()).collect();
+        assert_eq!(paths, [root.join("movie.mkv"), root.join("cache/blob"), root.join("notes.txt")]);
+
+        // Files are selected one by one and deleted along with the selected directories
+        session.handle_key(KeyCode::Char(' '));
+        session.handle_key(KeyCode::Down);
+        session.handle_key(KeyCode::Char(' '));
+        assert_eq!(session.selection_status(), format!("Selected 0 dirs and 2 files ({})", format_size(5000)));
+        let mut paths = session.handle_key(KeyCode::Char('d')).unwrap();
+        paths.sort();
+        assert_eq!(paths, [root.join("cache/blob"), root.join("movie.mkv")]);
+        assert!(session.normal_paths().contains(&root.join("movie.mkv")));
+
+        // A file in a selected directory goes with it
+        session.handle_key(KeyCode::Enter);
+        assert_eq!(session.view, View::Directories);
+        assert_eq!(session.entries[session.current_entry().unwrap()].path, root.join("cache"));
+        session.handle_key(KeyCode::Char(' '));
+        let mut paths = session.get_selected_paths();
+        paths.sort();
+        assert_eq!(paths, [root.join("cache"), root.join("movie.mkv")]);
+
+        session.handle_key(KeyCode::Char('c'));
+        assert!(session.nothing_selected());
+        session.handle_key(KeyCode::Char('f'));
+        session.handle_key(KeyCode::Char('a'));
+        assert_eq!(session.selected_file_sizes().len(), 3);
+    }
+
+    #[test]
+    fn test_images_view() {
+        use crate::disk_images::ImageKind;
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        const GB: u64 = 1024 * 1024 * 1024;
+        let image = |path: &str, kind, on_disk| DiskImage { path: PathBuf::from(path), kind, size: 64 * GB, on_disk, modified: None };
+        let entries = vec![
+            DirectoryEntry { path: PathBuf::from("/p"), cumulative_size_bytes: 100 * GB, ..Default::default() },
+            DirectoryEntry {
+                path: PathBuf::from("/p/wsl"),
+                cumulative_size_bytes: 30 * GB,
+                images: vec![image("/p/wsl/ext4.vhdx", ImageKind::Wsl, 30 * GB)],
+                ..Default::default()
+            },
+            DirectoryEntry {
+                path: PathBuf::from("/p/vms"),
+                cumulative_size_bytes: 60 * GB,
+                images: vec![image("/p/vms/a.qcow2", ImageKind::Qcow2, 8 * GB), image("/p/vms/b.vmdk", ImageKind::Vmdk, 50 * GB)],
+                ..Default::default()
+            },
+        ];
+        let mut session = InteractiveSession::new(entries, SortKey::Size);
+        session.handle_key(KeyCode::Char('i'));
+        assert_eq!(session.view, View::Images);
+        let paths: Vec<&Path> = session.images.iter().map(|(_, image)| image.path.as_path()).collect();
+        assert_eq!(paths, [Path::new("/p/vms/b.vmdk"), Path::new("/p/wsl/ext4.vhdx"), Path::new("/p/vms/a.qcow2")]);
+
+        // The hint is a column on wide terminals, below the list on narrow ones
+        session.handle_key(KeyCode::Down);
+        for width in [80, 160] {
+            let mut terminal = Terminal::new(TestBackend::new(width, 20)).unwrap();
+            terminal.draw(|f| session.ui(f)).unwrap();
+            let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+            assert!(screen.contains("Compact WSL disk"));
+        }
+
+        // Images aren't selectable; Enter goes to the directory the image is in
+        session.handle_key(KeyCode::Char(' '));
+        assert!(session.selected.is_empty());
+        session.handle_key(KeyCode::Enter);
+        assert_eq!(session.view, View::Directories);
+        assert_eq!(session.entries[session.current_entry().unwrap()].path, PathBuf::from("/p/wsl"));
+    }
+
+    #[test]
+    fn test_size_basis_needs_allocated_sizes() {
+        let entries = vec![DirectoryEntry {
+            path: PathBuf::from("/p/a"),
+            cumulative_size_bytes: 5 * 1024 * 1024,
+            ..Default::default()
+        }];
+        let mut session = InteractiveSession::new(entries, SortKey::Size);
+        session.handle_key(KeyCode::Char('u'));
+        assert!(session.status.as_ref().is_some_and(|status| status.is_error));
+        assert_eq!(utils::size_basis(), SizeBasis::Apparent);
     }
+
 }
@@ -0,0 +1,150 @@
+use crate::cloud_sync;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+/// How many files the largest-files view lists
+pub const LIMIT: usize = 200;
+
+/// One of the largest files below the listed directories
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LargeFile {
+    pub path: PathBuf,
+    /// Bytes stored locally; cloud placeholders count as nothing
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+/// The `limit` largest files in and below `roots`, largest first. Symlinks are listed as
+/// themselves, never followed, and files under two roots are listed once.
+pub fn largest(roots: &[PathBuf], limit: usize) -> Vec<LargeFile> {
+    let mut seen = HashSet::new();
+    // Smallest kept file on top, so it is the one a larger file pushes out
+    let mut heap: BinaryHeap<Reverse<(u64, PathBuf)>> = BinaryHeap::new();
+    let mut modified = HashMap::new();
+    for root in roots {
+        for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() || !seen.insert(entry.path().to_path_buf()) {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let size = cloud_sync::local_size(&metadata);
+            if heap.len() == limit && heap.peek().is_some_and(|Reverse((smallest, _))| size <= *smallest) {
+                continue;
+            }
+            modified.insert(entry.path().to_path_buf(), metadata.modified().ok());
+            heap.push(Reverse((size, entry.into_path())));
+            if heap.len() > limit {
+                heap.pop();
+            }
+        }
+    }
+
+    let mut files: Vec<LargeFile> = heap
+        .into_iter()
+        .map(|Reverse((size, path))| LargeFile { modified: modified.get(&path).copied().flatten(), path, size })
+        .collect();
+    files.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    files
+}
+
+/// Largest files found so far, by the roots they were looked for under
+type Found = Arc<Mutex<HashMap<Vec<PathBuf>, Vec<LargeFile>>>>;
+
+/// Largest files of the directories a tab lists, looked for on a background thread so a
+/// large tree doesn't freeze the UI; results are kept for the session
+#[derive(Default)]
+pub struct LargestFiles {
+    done: Found,
+    requested: HashSet<Vec<PathBuf>>,
+}
+
+impl LargestFiles {
+    /// The largest files under `roots`, or `None` while they are still being looked for
+    pub fn get(&mut self, roots: &[PathBuf]) -> Option<Vec<LargeFile>> {
+        if let Some(files) = self.done.lock().ok()?.get(roots) {
+            return Some(files.clone());
+        }
+        if self.requested.insert(roots.to_vec()) {
+            let done = Arc::clone(&self.done);
+            let roots = roots.to_vec();
+            thread::spawn(move || {
+                let files = largest(&roots, LIMIT);
+                if let Ok(mut done) = done.lock() {
+                    done.insert(roots, files);
+                }
+            });
+        }
+        None
+    }
+}
+
+/// The directories among `paths` without another one of them above, which together cover
+/// all of them
+pub fn topmost<'a>(paths: impl IntoIterator<Item = &'a Path>) -> Vec<PathBuf> {
+    let mut paths: Vec<&Path> = paths.into_iter().collect();
+    paths.sort();
+    let mut topmost: Vec<PathBuf> = Vec::new();
+    for path in paths {
+        if !topmost.last().is_some_and(|top| path.starts_with(top)) {
+            topmost.push(path.to_path_buf());
+        }
+    }
+    topmost
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_largest() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("a/deep")).unwrap();
+        fs::create_dir_all(root.join("b")).unwrap();
+        fs::write(root.join("a/deep/model.bin"), "m".repeat(500)).unwrap();
+        fs::write(root.join("a/small.txt"), "s".repeat(10)).unwrap();
+        fs::write(root.join("b/video.mp4"), "v".repeat(300)).unwrap();
+        fs::write(root.join("b/notes.txt"), "n".repeat(50)).unwrap();
+
+        let files = largest(&[root.to_path_buf()], 2);
+        let found: Vec<(&Path, u64)> = files.iter().map(|f| (f.path.as_path(), f.size)).collect();
+        assert_eq!(found, [(root.join("a/deep/model.bin").as_path(), 500), (root.join("b/video.mp4").as_path(), 300)]);
+        assert!(files[0].modified.is_some());
+
+        // Overlapping roots list a file once
+        let files = largest(&[root.join("a"), root.join("a/deep")], LIMIT);
+        assert_eq!(files.len(), 2);
+
+        // Looked for in the background, then served from the cache
+        let mut cache = LargestFiles::default();
+        let roots = [root.join("b")];
+        let started = Instant::now();
+        let files = loop {
+            if let Some(files) = cache.get(&roots) {
+                break files;
+            }
+            assert!(started.elapsed() < Duration::from_secs(10));
+            thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(files.len(), 2);
+        fs::write(root.join("b/late.iso"), "l".repeat(1000)).unwrap();
+        assert_eq!(cache.get(&roots).unwrap(), files);
+    }
+
+    #[test]
+    fn test_topmost() {
+        let paths = [Path::new("/p/b"), Path::new("/p"), Path::new("/q/x"), Path::new("/p/a/target"), Path::new("/q/x/y")];
+        assert_eq!(topmost(paths), [PathBuf::from("/p"), PathBuf::from("/q/x")]);
+    }
+}
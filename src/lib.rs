@@ -0,0 +1,69 @@
+//! Disk cleanup tool: finds temporary and rebuildable directories (build output, caches,
+//! dependency folders) and deletes the ones you pick.
+//!
+//! The modules are shared with the `disk-cleanup-tool` binary. To embed the scanner in a
+//! GUI or a server, start with [`scan_stream::scan_stream`]: it scans on a background
+//! thread and hands the entries over as a `Stream` or an iterator, and its
+//! [`scan_stream::CancelToken`] stops it.
+
+pub mod ages;
+pub mod app;
+pub mod audit;
+pub mod bookmarks;
+pub mod bulk_stat;
+pub mod checkpoint;
+pub mod classifier;
+pub mod cli;
+pub mod cloud_sync;
+pub mod clutter;
+pub mod columns;
+pub mod commands;
+pub mod config;
+pub mod csv_handler;
+pub mod deletion;
+pub mod disk_images;
+pub mod downloads;
+pub mod du;
+pub mod email;
+pub mod error;
+pub mod explain;
+pub mod file_types;
+pub mod history;
+pub mod hooks;
+pub mod i18n;
+pub mod interactive;
+pub mod largest_files;
+pub mod live_sizes;
+pub mod merge;
+pub mod metrics;
+pub mod mounts;
+pub mod ncdu;
+pub mod notifications;
+pub mod open_files;
+pub mod output_template;
+pub mod overrides;
+pub mod owners;
+pub mod path_list;
+pub mod pending;
+pub mod policy;
+pub mod profiles;
+pub mod prune;
+pub mod push;
+pub mod report;
+pub mod rule_files;
+pub mod safety;
+pub mod scan_file;
+pub mod scan_stream;
+pub mod scan_ui;
+pub mod scanner;
+pub mod schedule;
+pub mod server;
+pub mod shared_data;
+pub mod summary_ui;
+pub mod telemetry;
+pub mod trash;
+pub mod utils;
+pub mod vcs;
+#[cfg(all(unix, not(target_os = "macos")))]
+pub mod xdg_trash;
+pub mod yaml;
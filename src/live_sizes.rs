@@ -0,0 +1,181 @@
+use crate::scanner::{self, DirectoryEntry};
+use notify::event::ModifyKind;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long changes have to settle before the directories they touched are recounted
+const QUIET: Duration = Duration::from_millis(500);
+
+/// Recount at least this often while changes keep coming, e.g. during a long build
+const MAX_DELAY: Duration = Duration::from_secs(3);
+
+/// What the watcher thread reports to the interactive session
+#[derive(Debug)]
+pub enum Update {
+    /// A listed directory recounted after something below it changed; gone directories
+    /// come back with zero counts
+    Recounted(Box<DirectoryEntry>),
+    /// Watching could not start, e.g. the inotify watch limit was reached
+    Failed(String),
+}
+
+/// Keeps the listed directories' sizes current while the interactive session is open.
+/// A background thread watches the scanned roots and recounts the deepest listed directory
+/// above each change; the session adds the difference to the listed directories above it.
+pub struct LiveSizes {
+    updates: Receiver<Update>,
+    stop: Arc<AtomicBool>,
+}
+
+impl LiveSizes {
+    /// Watch `roots` for changes to the directories in `entries`
+    pub fn start(roots: &[PathBuf], entries: &[DirectoryEntry]) -> Self {
+        let (sender, updates) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let roots: Vec<PathBuf> = roots.iter().filter(|root| root.is_dir()).cloned().collect();
+        // Only what a recount needs; the session keeps everything else
+        let listed: Vec<DirectoryEntry> = entries
+            .iter()
+            .map(|e| DirectoryEntry {
+                path: e.path.clone(),
+                entry_type: e.entry_type,
+                alias_of: e.alias_of.clone(),
+                ..Default::default()
+            })
+            .collect();
+
+        let stopped = Arc::clone(&stop);
+        thread::spawn(move || {
+            let (event_sender, events) = mpsc::channel();
+            // Adding a recursive watch visits every directory, so it is done here rather
+            // than before the session opens
+            let watching = notify::recommended_watcher(event_sender).and_then(|mut watcher| {
+                for root in &roots {
+                    watcher.watch(root, RecursiveMode::Recursive)?;
+                }
+                Ok(watcher)
+            });
+            let _watcher = match watching {
+                Ok(watcher) => watcher,
+                Err(e) => {
+                    let _ = sender.send(Update::Failed(e.to_string()));
+                    return;
+                }
+            };
+
+            let index: HashMap<&Path, usize> = listed.iter().enumerate().map(|(i, e)| (e.path.as_path(), i)).collect();
+            let mut changed: HashSet<PathBuf> = HashSet::new();
+            let mut first_change: Option<Instant> = None;
+            while !stopped.load(Ordering::Relaxed) {
+                match events.recv_timeout(QUIET) {
+                    Ok(Ok(event)) => {
+                        if changes_size(&event.kind) {
+                            changed.extend(event.paths);
+                            first_change.get_or_insert_with(Instant::now);
+                        }
+                        if !first_change.is_some_and(|first| first.elapsed() >= MAX_DELAY) {
+                            continue;
+                        }
+                    }
+                    Ok(Err(_)) => continue,
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+                first_change = None;
+                for idx in affected(&index, changed.drain()) {
+                    let mut entry = listed[idx].clone();
+                    if !scanner::refresh_entry(&mut entry) {
+                        entry = DirectoryEntry { path: entry.path, entry_type: entry.entry_type, ..Default::default() };
+                    }
+                    if sender.send(Update::Recounted(Box::new(entry))).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        Self { updates, stop }
+    }
+
+    /// Updates that arrived since the last call, without waiting
+    pub fn pending(&self) -> Vec<Update> {
+        self.updates.try_iter().collect()
+    }
+}
+
+impl Drop for LiveSizes {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Whether an event can change sizes; reads and permission changes can't
+fn changes_size(kind: &EventKind) -> bool {
+    match kind {
+        EventKind::Modify(ModifyKind::Metadata(_)) => false,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => true,
+        _ => false,
+    }
+}
+
+/// The deepest listed directory at or above each changed path, once each
+fn affected(index: &HashMap<&Path, usize>, changed: impl IntoIterator<Item = PathBuf>) -> Vec<usize> {
+    let mut affected: Vec<usize> = changed
+        .into_iter()
+        .filter_map(|path| path.ancestors().find_map(|ancestor| index.get(ancestor).copied()))
+        .collect();
+    affected.sort_unstable();
+    affected.dedup();
+    affected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_affected() {
+        let paths = [PathBuf::from("/p"), PathBuf::from("/p/a/node_modules"), PathBuf::from("/p/b")];
+        let index: HashMap<&Path, usize> = paths.iter().enumerate().map(|(i, p)| (p.as_path(), i)).collect();
+        let changed = ["/p/a/node_modules/x/index.js", "/p/a/src/main.js", "/p/b", "/p/b/c", "/elsewhere/file"];
+        assert_eq!(affected(&index, changed.map(PathBuf::from)), [0, 1, 2]);
+        assert_eq!(affected(&index, [PathBuf::from("/p/b/c")]), [2]);
+    }
+
+    #[test]
+    fn test_live_updates() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let target = root.join("target");
+        fs::create_dir(&target).unwrap();
+        fs::write(target.join("a.o"), "a".repeat(100)).unwrap();
+        let entries = [DirectoryEntry { path: target.clone(), ..Default::default() }];
+
+        let live = LiveSizes::start(std::slice::from_ref(&root), &entries);
+        fs::write(target.join("b.o"), "b".repeat(400)).unwrap();
+        // The watch is set up in the background and may miss that write; empty files added
+        // until a recount arrives don't change the size
+        let started = Instant::now();
+        let recounted = loop {
+            fs::File::create(target.join(format!("{}.d", started.elapsed().as_millis()))).unwrap();
+            thread::sleep(Duration::from_millis(100));
+            match live.pending().into_iter().next() {
+                Some(Update::Recounted(entry)) => break entry,
+                Some(Update::Failed(error)) => panic!("{}", error),
+                None => {}
+            }
+            assert!(started.elapsed() < Duration::from_secs(20), "no update");
+        };
+        assert_eq!(recounted.path, target);
+        assert_eq!(recounted.cumulative_size_bytes, 500);
+        assert!(recounted.cumulative_file_count > 2);
+    }
+}
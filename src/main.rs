@@ -1,151 +1,1071 @@
-mod cli;
-mod csv_handler;
-mod deletion;
-mod interactive;
-mod scan_ui;
-mod scanner;
-mod summary_ui;
-mod utils;
-
-use scanner::ScanConfig;
+use disk_cleanup_tool::{
+    ages, app, bookmarks, checkpoint, classifier, cli, clutter, columns, config, csv_handler,
+    deletion, downloads, du, error, explain, history, i18n, interactive, merge, metrics, mounts,
+    ncdu, notifications, output_template, overrides, owners, path_list, pending, policy, profiles,
+    prune, report, rule_files, safety, scan_file, scan_ui, scanner, schedule, server, telemetry,
+    trash, utils, vcs,
+};
+
+use app::{DeletionOptions, RunResults};
+use checkpoint::ScanCheckpoint;
+use classifier::{Classifier, MatchOptions};
+use cli::Command;
+use config::Config;
+use csv_handler::ScanMetadata;
+use error::AppError;
+use i18n::t;
+use overrides::Overrides;
+use rule_files::RuleFile;
+use scanner::{EntryType, ScanConfig};
+use utils::{RebuildCost, SortKey};
+use std::collections::{HashMap, HashSet};
 use std::env;
-use std::process;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
 
 fn main() {
-    let args = cli::parse_args();
+    if let Err(e) = run() {
+        error::exit_with(&e);
+    }
+}
+
+fn run() -> Result<(), AppError> {
+    let mut args = cli::parse_args();
+    utils::set_size_units(args.units);
+    utils::set_size_basis(args.size_basis);
+    if let Err(e) = i18n::init(args.lang.as_deref()) {
+        eprintln!("{}", t!("warning", message = e.to_string()));
+    }
+
+    let config = Config::load_or_default(args.config.as_deref())?;
+    columns::set_palette(config.palette);
+
+    // A bookmark supplies the path and default flags
+    if let Some(name) = &args.bookmark {
+        let bookmark = match name {
+            Some(name) => bookmarks::find(&config.bookmarks, name).map(|b| Some((name.as_str(), b))),
+            None => bookmarks::choose(&config.bookmarks),
+        };
+        match bookmark? {
+            Some((name, bookmark)) => {
+                println!("{}", t!("bookmark-using", name = name, path = bookmark.root().display().to_string()));
+                bookmark.apply(&mut args);
+            }
+            None => {
+                println!("{}", t!("bookmark-none-chosen"));
+                return Ok(());
+            }
+        }
+    }
+
+    // A profile supplies the paths, what to select there and what to do with it
+    let profile = match args.profile.clone() {
+        Some(name) => {
+            let profile = profiles::find(&config.profiles, &name)?;
+            profile.apply(&mut args)?;
+            Some((name, profile.clone()))
+        }
+        None => None,
+    };
+
+    // Without a path, an interactive run starts on the disk overview to pick what to scan
+    let starts_interactive = args.command.is_none()
+        && args.input_csv.is_none()
+        && args.import_ncdu.is_none()
+        && args.input_du.is_none()
+        && args.serve.is_none()
+        && !args.resume
+        && !args.all_mounts
+        && !args.all_drives
+        && args.delete_from_file.is_none()
+        && !args.yes
+        && !args.summary_json;
+    if args.path.is_empty() && starts_interactive && std::io::stdin().is_terminal() && std::io::stdout().is_terminal() {
+        let mounts = mounts::list();
+        if !mounts.is_empty() {
+            let current = env::current_dir().map_err(|e| AppError::Message(t!("error-current-dir", error = e.to_string())))?;
+            match mounts::choose(&mounts, &current) {
+                Ok(Some(path)) => args.path.push(path),
+                Ok(None) => {
+                    println!("{}", t!("mounts-none-chosen"));
+                    return Ok(());
+                }
+                Err(e) => eprintln!("{}", t!("error-display", view = "disks", error = e.to_string())),
+            }
+        }
+    }
+
+    // Determine the starting paths; the first one is the root for everything but scanning
+    let roots = if args.all_mounts {
+        let roots = mounts::local_roots(&mounts::list());
+        if roots.is_empty() {
+            return Err(AppError::Message(t!("error-no-mounts")));
+        }
+        roots
+    } else if args.all_drives {
+        let roots = mounts::fixed_drives();
+        if roots.is_empty() {
+            return Err(AppError::Message(t!("error-no-drives")));
+        }
+        roots
+    } else if args.path.is_empty() {
+        vec![env::current_dir().map_err(|e| AppError::Message(t!("error-current-dir", error = e.to_string())))?]
+    } else {
+        args.path.clone()
+    };
+    let root_path = roots[0].clone();
+
+    // Verify paths exist
+    for root in &roots {
+        if !root.exists() {
+            return Err(AppError::Message(t!("error-path-missing", path = root.display().to_string())));
+        }
+    }
+    if args.resume && roots.len() > 1 {
+        return Err(AppError::Message(t!("error-resume-multiple-roots")));
+    }
+
+    // Placeholders in output paths, so scheduled runs each keep their own file
+    let template = output_template::TemplateValues::new(&root_path, utils::hostname().as_deref(), SystemTime::now());
+    let expand = |path: &mut Option<PathBuf>| match path {
+        Some(template_path) => output_template::expand(template_path, &template).map(|expanded| *template_path = expanded),
+        None => Ok(()),
+    };
+    expand(&mut args.output_csv).and_then(|_| expand(&mut args.export_ncdu)).and_then(|_| match &mut args.command {
+        Some(Command::Report { output, .. }) => expand(output),
+        _ => Ok(()),
+    })?;
+
+    // Read-only mode turns every destructive action off, whatever else is asked for
+    let read_only = args.read_only || config.read_only;
+    // Fail before a long scan rather than at the end of it
+    if !args.email_report.is_empty() && config.smtp.is_none() {
+        return Err(AppError::Message(t!("error-smtp-missing")));
+    }
+    let notify_after = args.notify_after.or(config.notify_after);
+    if read_only && args.yes {
+        return Err(AppError::Message(t!("error-read-only-yes")));
+    }
+
+    if let Some(Command::Undo) = &args.command {
+        let Some(trash) = trash::Trash::default_location().map(|trash| trash.with_max_size(config.trash_max_size)) else {
+            return Err(AppError::Message(t!("error-trash-dir")));
+        };
+        trash.undo()?.print_summary();
+        return Ok(());
+    }
+
+    if let Some(Command::Schedule { profile, policy, every, at, format, install }) = &args.command {
+        let exe = env::current_exe().unwrap_or_else(|_| PathBuf::from("disk-cleanup-tool"));
+        let mut command = vec![exe.to_string_lossy().into_owned()];
+        // Timers and cron don't start in the current directory or with the same environment
+        if let Some(path) = &args.config {
+            command.extend(["--config".to_string(), absolute_path(path)]);
+        }
+        let name = match (profile, policy) {
+            (Some(name), _) => {
+                // The profile must not need --path, which the timer won't pass
+                if profiles::find(&config.profiles, name)?.paths.is_empty() {
+                    return Err(profiles::ProfileError::NoPaths.into());
+                }
+                command.extend(["--profile".to_string(), name.clone(), "--yes".to_string()]);
+                name.clone()
+            }
+            (None, Some(file)) => {
+                policy::PolicySet::load(file)?;
+                command.extend(["apply-policy".to_string(), absolute_path(file)]);
+                file.file_stem().unwrap_or_default().to_string_lossy().into_owned()
+            }
+            (None, None) => unreachable!("clap requires --profile or --policy"),
+        };
+        let Some(log) = schedule::Schedule::default_log(&name) else {
+            return Err(AppError::Message(t!("error-data-dir")));
+        };
+        let schedule = schedule::Schedule::new(&name, command, *every, *at, log);
+        match format {
+            schedule::ScheduleFormat::Systemd => {
+                let (service, timer) = schedule.systemd_units();
+                println!("# {}.service\n{}", schedule.unit_name(), service);
+                println!("# {}.timer\n{}", schedule.unit_name(), timer);
+            }
+            schedule::ScheduleFormat::Cron => println!("{}", schedule.crontab_line()),
+        }
+        if !*install {
+            return Ok(());
+        }
+
+        // Installing changes the user's setup, so it needs a yes
+        let units_dir = schedule::Schedule::systemd_user_dir();
+        let target = match (format, &units_dir) {
+            (schedule::ScheduleFormat::Systemd, Some(dir)) => dir.display().to_string(),
+            (schedule::ScheduleFormat::Systemd, None) => return Err(AppError::Message(t!("error-data-dir"))),
+            (schedule::ScheduleFormat::Cron, _) => "crontab".to_string(),
+        };
+        if !args.yes {
+            print!("{}", t!("schedule-install-prompt", target = target));
+            let _ = std::io::Write::flush(&mut std::io::stdout());
+            let mut input = String::new();
+            if std::io::stdin().read_line(&mut input).is_err() || !input.trim().eq_ignore_ascii_case("y") {
+                println!("{}", t!("schedule-not-installed"));
+                return Ok(());
+            }
+        }
+        match (format, units_dir) {
+            (schedule::ScheduleFormat::Systemd, Some(dir)) => schedule.install_systemd(&dir).map(|written| {
+                for path in written {
+                    println!("{}", t!("schedule-written", path = path.display().to_string()));
+                }
+                println!("{}", t!("schedule-enabled", timer = format!("{}.timer", schedule.unit_name())));
+            }),
+            _ => schedule.install_cron().map(|_| println!("{}", t!("schedule-cron-installed"))),
+        }?;
+        return Ok(());
+    }
 
-    // Determine the starting path
-    let root_path = args.path.unwrap_or_else(|| {
-        env::current_dir().unwrap_or_else(|e| {
-            eprintln!("Error: Cannot determine current directory: {}", e);
-            process::exit(1);
+    if let Some(Command::Clutter) = &args.command {
+        let found = clutter::find_clutter(&root_path);
+        if found.is_empty() {
+            println!("{}", t!("clutter-none", root = root_path.display().to_string()));
+            return Ok(());
+        }
+        // Scripted runs and dry runs only print; otherwise browse and confirm in the view
+        let clean = if args.yes || args.dry_run {
+            clutter::print_clutter(&found);
+            args.yes && !args.dry_run
+        } else {
+            clutter::show_clutter(&found, &root_path, read_only).unwrap_or_else(|e| {
+                eprintln!("{}", t!("error-display", view = "clutter", error = e.to_string()));
+                clutter::print_clutter(&found);
+                false
+            })
+        };
+        if clean {
+            clutter::clean_up(&found).print_summary();
+        } else {
+            println!("{}", t!("clutter-kept", summary = found.summary()));
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Downloads { dir, older_than, archive, move_to_archive }) = &args.command {
+        let Some(dir) = dir.clone().or_else(dirs::download_dir) else {
+            return Err(AppError::Message(t!("error-downloads-dir")));
+        };
+        let archive = archive.clone().unwrap_or_else(|| downloads::default_archive(&dir));
+        let items = downloads::list_downloads(&dir, &archive).map_err(|e| AppError::read(&dir, e))?;
+        let now = SystemTime::now();
+        let action = if args.yes || args.dry_run {
+            let old = downloads::older_than(&items, *older_than, now);
+            downloads::print_downloads(&items, &old, now);
+            match (args.dry_run, move_to_archive) {
+                (true, _) => downloads::TriageAction::Quit,
+                (false, true) => downloads::TriageAction::Archive(old),
+                (false, false) => downloads::TriageAction::Delete(old),
+            }
+        } else {
+            downloads::show_downloads(items.clone(), &dir, &archive, *older_than, read_only).unwrap_or_else(|e| {
+                eprintln!("{}", t!("error-display", view = "downloads", error = e.to_string()));
+                downloads::TriageAction::Quit
+            })
+        };
+        match action {
+            downloads::TriageAction::Delete(paths) => {
+                downloads::delete_items(&items, &paths).print_summary(&t!("downloads-deleted"))
+            }
+            downloads::TriageAction::Archive(paths) => downloads::archive_items(&items, &paths, &archive)
+                .print_summary(&t!("downloads-moved", archive = archive.display().to_string())),
+            downloads::TriageAction::Quit => println!("{}", t!("downloads-untouched")),
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Prune { dirs, older_than }) = &args.command {
+        let now = SystemTime::now();
+        let cutoff = now.checked_sub(*older_than).unwrap_or(SystemTime::UNIX_EPOCH);
+        let age = downloads::format_age(*older_than);
+        let mut plans = Vec::new();
+        let mut failed = false;
+        for dir in dirs {
+            match vcs::tracked_file_count(dir) {
+                Ok(count) if count > 0 && !args.allow_tracked => {
+                    eprintln!("{}", t!("prune-tracked", dir = dir.display().to_string(), count = count));
+                    failed = true;
+                    continue;
+                }
+                _ => {}
+            }
+            match prune::plan(dir, cutoff) {
+                Ok(plan) => {
+                    prune::print_plan(&plan, &age, args.dry_run);
+                    plans.push(plan);
+                }
+                Err(e) => {
+                    AppError::read(dir, e).print();
+                    failed = true;
+                }
+            }
+        }
+        let files: usize = plans.iter().map(|plan| plan.files.len()).sum();
+        let bytes: u64 = plans.iter().map(|plan| plan.bytes).sum();
+        if read_only {
+            println!("{}", t!("prune-read-only"));
+        } else if args.dry_run || files == 0 {
+            println!("{}", t!("prune-nothing"));
+        } else {
+            let confirmed = args.yes || {
+                print!("{}", t!("prune-prompt", count = files, size = utils::format_size(bytes)));
+                let _ = std::io::Write::flush(&mut std::io::stdout());
+                let mut input = String::new();
+                std::io::stdin().read_line(&mut input).is_ok() && input.trim().eq_ignore_ascii_case("y")
+            };
+            if confirmed {
+                for plan in &plans {
+                    let report = prune::prune(plan);
+                    report.print_summary();
+                    failed |= !report.failed.is_empty();
+                }
+            } else {
+                println!("{}", t!("prune-nothing"));
+            }
+        }
+        if failed {
+            return Err(AppError::incomplete());
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::PurgePending) = &args.command {
+        let now = SystemTime::now();
+        let (due, waiting): (Vec<_>, Vec<_>) = pending::find_pending(&roots).into_iter().partition(|item| item.is_due(now));
+        if !waiting.is_empty() {
+            println!("{}", t!("pending-waiting", count = waiting.len()));
+            for item in &waiting {
+                println!("  {}", item.describe());
+            }
+        }
+        if due.is_empty() {
+            println!("{}", t!("pending-nothing-due"));
+            return Ok(());
+        }
+        println!("{}", t!("pending-due", count = due.len()));
+        for item in &due {
+            println!("  {}", item.describe());
+        }
+        if read_only {
+            println!("{}", t!("pending-read-only"));
+            return Ok(());
+        }
+        if args.dry_run {
+            println!("{}", t!("pending-dry-run"));
+            return Ok(());
+        }
+        let size: u64 = due.iter().map(|item| item.size).sum();
+        let confirmed = args.yes || {
+            print!("{}", t!("pending-prompt", count = due.len(), size = utils::format_size(size)));
+            let _ = std::io::Write::flush(&mut std::io::stdout());
+            let mut input = String::new();
+            std::io::stdin().read_line(&mut input).is_ok() && input.trim().eq_ignore_ascii_case("y")
+        };
+        if !confirmed {
+            println!("{}", t!("deletion-cancelled"));
+            return Ok(());
+        }
+        let paths: Vec<PathBuf> = due.into_iter().map(|item| item.path).collect();
+        let cancel = app::cancel_flag();
+        let report = deletion::delete_directories_cancellable(&paths, &HashMap::new(), cancel, None)?;
+        report.print_summary();
+        if !report.failed.is_empty() {
+            return Err(AppError::incomplete());
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Merge { inputs, output }) = &args.command {
+        let mut loaded = Vec::new();
+        for file in inputs {
+            let (entries, metadata) = scan_file::read_scan(file).map_err(|e| AppError::read(file, e))?;
+            loaded.push(merge::MergeInput {
+                file: file.clone(),
+                entries,
+                metadata,
+            });
+        }
+
+        let merged = merge::merge_scans(loaded);
+        scan_file::write_scan(&merged.entries, output, &merged.metadata).map_err(|e| AppError::write(output, e))?;
+        println!(
+            "{}",
+            t!(
+                "merged-scans",
+                scans = inputs.len(),
+                output = output.display().to_string(),
+                entries = merged.entries.len(),
+                duplicates = merged.duplicates
+            )
+        );
+        return Ok(());
+    }
+
+    let overrides_path = Overrides::default_path();
+    let overrides = Overrides::load_or_default(overrides_path.as_deref())?;
+
+    let matching = MatchOptions {
+        ignore_case: args.ignore_case || config.matching.ignore_case,
+        ..config.matching
+    };
+    let mut rule_files = RuleFile::default_dir().map(|dir| RuleFile::load_dir(&dir)).unwrap_or_else(|| Ok(Vec::new()))?;
+    for path in &args.rule_files {
+        rule_files.push(RuleFile::load(path)?);
+    }
+    let classifier = Classifier::new(args.temp_dirs, args.keep)
+        .and_then(|c| c.with_match_options(matching))
+        .and_then(|c| c.with_path_rules(&config.rules))
+        .and_then(|c| {
+            rule_files
+                .iter()
+                .try_fold(c, |c, file| c.with_backend(file.backend(matching)?))
         })
-    });
+        .map(|c| c.with_overrides(overrides))?;
 
-    // Verify path exists
-    if !root_path.exists() {
-        eprintln!("Error: Path does not exist: {}", root_path.display());
-        process::exit(1);
+    if let Some(Command::Explain { path }) = &args.command {
+        if !path.exists() {
+            return Err(AppError::Message(t!("error-path-missing", path = path.display().to_string())));
+        }
+        for line in explain::describe(&classifier, path, &root_path) {
+            println!("{}", line);
+        }
+        return Ok(());
     }
 
+    if let Some(file) = &args.delete_from_file {
+        if read_only {
+            return Err(AppError::Message(t!("error-read-only-path-list")));
+        }
+        let listed = path_list::read(file)?;
+        println!("{}", t!("path-list-loaded", count = listed.len(), file = file.display().to_string()));
+        // Classified like scanned directories, so non-temp ones need the typed confirmation
+        let entries: Vec<scanner::DirectoryEntry> = listed
+            .iter()
+            .map(|path| scanner::DirectoryEntry {
+                path: path.clone(),
+                entry_type: classifier.classify_path(path).unwrap_or(EntryType::Normal),
+                label: classifier.label_path(path),
+                ..Default::default()
+            })
+            .collect();
+        let trash = if args.trash { Some(default_trash(&config)?) } else { None };
+        let options = DeletionOptions {
+            foreign: HashSet::new(),
+            active_since: args.skip_active.and_then(|window| SystemTime::now().checked_sub(window)),
+            allow_tracked: args.allow_tracked,
+            open_files: args.open_files,
+            yes: args.yes,
+            dry_run: args.dry_run,
+            read_only,
+            large_deletion_threshold: config.large_deletion_threshold,
+            size_jobs: args.size_jobs,
+            hooks: config.hooks.clone(),
+            notify_after,
+            trash,
+            grace_period: args.grace_period,
+        };
+        let (_, complete) = app::delete_listed(&listed, &entries, &options)?;
+        if !complete {
+            return Err(AppError::incomplete());
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::ApplyPolicy { file }) = &args.command {
+        let policies = policy::PolicySet::load(file)?.policies;
+        let trash = if policies.iter().any(|p| p.action == policy::PolicyAction::Trash) {
+            trash::Trash::default_location().map(|trash| trash.with_max_size(config.trash_max_size))
+        } else {
+            None
+        };
+        let mut failed = false;
+        for policy in &policies {
+            let mut entries = Vec::new();
+            for root in policy.roots() {
+                println!("{}", t!("policy-scanning", name = policy.name.clone(), path = root.display().to_string()));
+                let scan_config = ScanConfig {
+                    root_path: root.clone(),
+                    temp_only: false,
+                    classifier: classifier.clone(),
+                    skip_sync_folders: args.skip_sync_folders || config.skip_sync_folders,
+                    include_snapshots: args.include_snapshots,
+                    dedupe_extents: args.dedupe_extents || config.dedupe_extents,
+                    checkpoint: None,
+                    resume: false,
+                    one_file_system: false,
+                    skip_larger_than: None,
+                    only_larger_than: None,
+                    estimate: false,
+                    cancel: None,
+                };
+                match scanner::scan_directory(scan_config) {
+                    Ok(found) => entries.extend(found),
+                    Err(e) => {
+                        AppError::from(e).print();
+                        failed = true;
+                    }
+                }
+            }
+
+            let matched = match policy.evaluate(&entries, matching, SystemTime::now()) {
+                Ok(matched) => matched,
+                Err(e) => {
+                    AppError::from(e).print();
+                    failed = true;
+                    continue;
+                }
+            };
+            let size = matched.iter().map(|m| m.size).sum();
+            println!(
+                "{}",
+                t!("policy-matched", name = policy.name.clone(), count = matched.len(), size = utils::format_size(size))
+            );
+            if matched.is_empty() {
+                continue;
+            }
+
+            let action = if read_only && policy.action != policy::PolicyAction::Report {
+                println!("{}", t!("policy-read-only", name = policy.name.clone()));
+                policy::PolicyAction::Report
+            } else {
+                policy.action
+            };
+            if action == policy::PolicyAction::Report {
+                for m in &matched {
+                    println!(
+                        "{}",
+                        t!("policy-match", size = utils::format_size(m.size), path = m.path.display().to_string())
+                    );
+                }
+                continue;
+            }
+            if action == policy::PolicyAction::Trash && trash.is_none() {
+                AppError::Message(t!("error-trash-dir")).print();
+                failed = true;
+                continue;
+            }
+
+            // The policy file is the confirmation; the safety checks still apply
+            let costs = entries.iter().map(|e| (e.path.clone(), e.rebuild_cost())).collect();
+            let options = DeletionOptions {
+                foreign: HashSet::new(),
+                active_since: args.skip_active.and_then(|window| SystemTime::now().checked_sub(window)),
+                allow_tracked: args.allow_tracked,
+                open_files: args.open_files,
+                yes: true,
+                dry_run: args.dry_run,
+                read_only,
+                large_deletion_threshold: config.large_deletion_threshold,
+                size_jobs: args.size_jobs,
+                hooks: config.hooks.clone(),
+                notify_after,
+                trash: if action == policy::PolicyAction::Trash { trash.clone() } else { None },
+                grace_period: if action == policy::PolicyAction::Trash { None } else { args.grace_period },
+            };
+            let paths = matched.into_iter().map(|m| m.path).collect();
+            if let Some(report) = app::delete_selected(paths, &costs, &HashSet::new(), &options)? {
+                failed |= !report.failed.is_empty();
+            }
+        }
+        if failed {
+            return Err(AppError::incomplete());
+        }
+        return Ok(());
+    }
+
+    if let Some(addr) = &args.serve {
+        let token = env::var(server::TOKEN_VARIABLE).ok().filter(|token| !token.is_empty());
+        let trash = if args.trash { Some(default_trash(&config)?) } else { None };
+        let options = server::ServerOptions {
+            roots: roots.clone(),
+            scan: ScanConfig {
+                temp_only: args.temp_only,
+                classifier: classifier.clone(),
+                skip_sync_folders: args.skip_sync_folders || config.skip_sync_folders,
+                include_snapshots: args.include_snapshots,
+                dedupe_extents: args.dedupe_extents || config.dedupe_extents,
+                skip_larger_than: args.skip_larger_than,
+                only_larger_than: args.only_larger_than,
+                estimate: args.estimate,
+                ..Default::default()
+            },
+            token,
+            read_only,
+            dry_run: args.dry_run,
+            allow_tracked: args.allow_tracked,
+            skip_active: args.skip_active,
+            open_files: args.open_files,
+            trash,
+        };
+        let deletion = if options.token.is_none() {
+            t!("serve-deletion-off", variable = server::TOKEN_VARIABLE)
+        } else if read_only {
+            t!("serve-deletion-read-only")
+        } else {
+            t!("serve-deletion-on")
+        };
+        println!("{}", t!("serve-listening", addr = addr.clone(), deletion = deletion));
+        server::Server::new(options).serve(addr)?;
+        return Ok(());
+    }
+
+    // Scan details of a loaded CSV, kept when it is exported again
+    let mut csv_metadata = None;
+
+    // `--session` resumes a saved session if its file exists; 's' in interactive mode saves to it
+    let session_file = match &args.session {
+        Some(Some(path)) => Some(path.clone()),
+        _ => interactive::InteractiveSession::default_session_path(),
+    };
+    let resume_session = args.session.is_some() && session_file.as_ref().is_some_and(|p| p.exists());
+    let input_file = if resume_session { session_file.clone() } else { args.input_csv };
+
+    // With --du, stdout is only for the du lines
+    let du = args.du;
+    let say = move |message: String| if du { eprintln!("{}", message) } else { println!("{}", message) };
+
     // Load entries from CSV or scan filesystem
-    let entries = if let Some(input_csv) = args.input_csv {
+    // Set when scanning; loaded scans have no duration or resource usage
+    let mut scan_duration = None;
+    let mut scan_telemetry = None;
+    let mut entries = if let Some(input_csv) = input_file {
         // Load from CSV
-        match csv_handler::read_csv(&input_csv) {
-            Ok(mut entries) => {
-                println!("Loaded {} entries from {}", entries.len(), input_csv.display());
+        match scan_file::read_scan(&input_csv) {
+            Ok((mut entries, metadata)) => {
+                say(t!("loaded-entries", count = entries.len(), file = input_csv.display().to_string()));
+                if let Some(root) = &metadata.root {
+                    let host = metadata.hostname.clone().unwrap_or_else(|| t!("unknown-host"));
+                    say(t!(
+                        "loaded-scan-of",
+                        root = root.display().to_string(),
+                        host = host,
+                        age = columns::format_age(metadata.scanned_at, SystemTime::now())
+                    ));
+                }
+                csv_metadata = Some(metadata);
+
+                // Directories deleted since the session was saved
+                if resume_session {
+                    let count = entries.len();
+                    entries.retain(|e| e.path.exists());
+                    if entries.len() < count {
+                        say(t!("session-dropped", count = count - entries.len()));
+                    }
+                }
+
+                // Re-apply --temp-dir / --keep, config rules and overrides to the stored classification
+                if classifier.has_overrides() {
+                    for entry in &mut entries {
+                        match classifier.classify_path(&entry.path) {
+                            Some(entry_type) => entry.entry_type = entry_type,
+                            None => entry.entry_type = EntryType::Normal,
+                        }
+                    }
+                }
+
+                // Labels aren't stored in the CSV; derive them from the path rules
+                for entry in entries.iter_mut().filter(|e| e.entry_type.is_temp()) {
+                    entry.label = classifier.label_path(&entry.path);
+                }
                 
                 // Apply temp_only filter if specified
                 if args.temp_only {
-                    entries.retain(|e| matches!(e.entry_type, scanner::EntryType::Temp));
-                    println!("Filtered to {} temporary directories", entries.len());
+                    entries.retain(|e| e.entry_type.is_temp());
+                    say(t!("filtered-temp", count = entries.len()));
+                }
+
+                // The CSV may be days old; drop vanished directories and recount the rest
+                if args.refresh {
+                    let summary = scanner::refresh_entries(&mut entries);
+                    for path in &summary.vanished {
+                        say(t!("refresh-vanished", path = path.display().to_string()));
+                    }
+                    for (path, old_size, new_size) in &summary.changed {
+                        say(t!(
+                            "refresh-changed",
+                            path = path.display().to_string(),
+                            old = utils::format_size(*old_size),
+                            new = utils::format_size(*new_size)
+                        ));
+                    }
+                    say(t!(
+                        "refresh-summary",
+                        count = entries.len() + summary.vanished.len(),
+                        vanished = summary.vanished.len(),
+                        changed = summary.changed.len()
+                    ));
                 }
                 
                 entries
             }
-            Err(e) => {
-                eprintln!("Error reading CSV: {}", e);
-                process::exit(1);
+            Err(e) => return Err(AppError::read(input_csv, e)),
+        }
+    } else if let Some(import_ncdu) = &args.import_ncdu {
+        let result = if import_ncdu.as_os_str() == "-" {
+            ncdu::import_ncdu(std::io::stdin().lock(), &classifier)
+        } else {
+            std::fs::File::open(import_ncdu)
+                .map_err(Into::into)
+                .and_then(|file| ncdu::import_ncdu(std::io::BufReader::new(file), &classifier))
+        };
+        match result {
+            Ok(mut entries) => {
+                say(t!("ncdu-imported", count = entries.len(), file = import_ncdu.display().to_string()));
+                if args.temp_only {
+                    entries.retain(|e| e.entry_type.is_temp());
+                    say(t!("filtered-temp", count = entries.len()));
+                }
+                entries
             }
+            Err(e) => return Err(AppError::read(import_ncdu, e)),
+        }
+    } else if let Some(input_du) = &args.input_du {
+        let result = if input_du.as_os_str() == "-" {
+            du::import_du(std::io::stdin().lock(), &classifier)
+        } else {
+            std::fs::File::open(input_du)
+                .map_err(Into::into)
+                .and_then(|file| du::import_du(std::io::BufReader::new(file), &classifier))
+        };
+        match result {
+            Ok(mut entries) => {
+                say(t!("du-imported", count = entries.len(), file = input_du.display().to_string()));
+                if args.temp_only {
+                    entries.retain(|e| e.entry_type.is_temp());
+                    say(t!("filtered-temp", count = entries.len()));
+                }
+                entries
+            }
+            Err(e) => return Err(AppError::read(input_du, e)),
         }
     } else {
-        // Scan filesystem with progress UI
-        let config = ScanConfig {
-            root_path: root_path.clone(),
+        // Scan filesystem with progress UI, one root after the other
+        let started = Instant::now();
+        let baseline = telemetry::ResourceUsage::sample();
+        let mut entries = Vec::new();
+        let skip_sync_folders = args.skip_sync_folders || config.skip_sync_folders;
+        let dedupe_extents = args.dedupe_extents || config.dedupe_extents;
+        let configs = roots.iter().map(|root| ScanConfig {
+            root_path: root.clone(),
             temp_only: args.temp_only,
+            classifier: classifier.clone(),
+            skip_sync_folders,
+            include_snapshots: args.include_snapshots,
+            dedupe_extents,
+            // Checkpoints are kept per root, but --resume only continues single-root scans
+            checkpoint: if roots.len() == 1 { ScanCheckpoint::default_path(root) } else { None },
+            resume: args.resume,
+            one_file_system: args.all_mounts || args.all_drives,
+            skip_larger_than: args.skip_larger_than,
+            only_larger_than: args.only_larger_than,
+            estimate: args.estimate,
+            cancel: None,
+        });
+
+        // Reports and du output may run unattended (e.g. from cron), so skip the progress UI;
+        // parallel mount scans have no single progress to show
+        let results: Vec<Result<Vec<scanner::DirectoryEntry>, AppError>> = if args.parallel_mounts {
+            std::thread::scope(|scope| {
+                let scans: Vec<_> = configs.map(|config| scope.spawn(|| scanner::scan_directory(config))).collect();
+                scans
+                    .into_iter()
+                    .map(|scan| scan.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)).map_err(Into::into))
+                    .collect()
+            })
+        } else if matches!(args.command, Some(Command::Report { .. })) || args.du {
+            configs.map(|config| scanner::scan_directory(config).map_err(Into::into)).collect()
+        } else {
+            configs.map(scan_ui::scan_with_progress).collect()
         };
 
-        match scan_ui::scan_with_progress(config) {
-            Ok(entries) => {
-                println!("✓ Scan complete! Found {} directories", entries.len());
-                entries
+        for (root, result) in roots.iter().zip(results) {
+            match result {
+                Ok(mut found) => {
+                    if args.all_mounts || args.all_drives {
+                        for entry in &mut found {
+                            entry.mount = Some(root.clone());
+                        }
+                    }
+                    if roots.len() == 1 {
+                        say(t!("scan-complete", count = found.len()));
+                    } else {
+                        say(t!("scan-root-complete", root = root.display().to_string(), count = found.len()));
+                    }
+                    entries.extend(found);
+                }
+                // One unreadable mount shouldn't stop a whole-host scan
+                Err(e) if args.all_mounts || args.all_drives => {
+                    eprintln!("{}", t!("warning-access", path = root.display().to_string(), error = e.to_string()));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        // Nested roots (e.g. `--path ~ --path ~/projects`) list the inner directories twice
+        let mut seen = HashSet::new();
+        entries.retain(|e| seen.insert(e.path.clone()));
+
+        // Show how each root changed since its last scan, and keep this scan for the next run
+        if !args.no_history {
+            let now = SystemTime::now();
+            for root in &roots {
+                // Unreadable mounts were skipped; an empty scan would read as everything gone
+                if !entries.iter().any(|e| e.path.starts_with(root)) {
+                    continue;
+                }
+                let Some(path) = history::ScanHistory::default_path(root) else { continue };
+                match history::compare_and_record(&path, root, &mut entries, now) {
+                    Ok(Some(previous)) => say(t!(
+                        "history-compared",
+                        root = root.display().to_string(),
+                        age = columns::format_age(Some(previous), now)
+                    )),
+                    Ok(None) => {}
+                    Err(e) => eprintln!("{}", t!("warning-history", error = e.to_string())),
+                }
+            }
+        }
+        for entry in &entries {
+            if let Some(original) = &entry.alias_of {
+                say(t!("scan-alias", path = entry.path.display().to_string(), original = original.display().to_string()));
+            }
+        }
+
+        let size = roots.iter().filter_map(|root| scanner::find_root(&entries, root)).map(|e| e.cumulative_size_bytes).sum();
+        let message = t!("notify-scan-finished", count = entries.len(), size = utils::format_size(size));
+        notifications::notify_if_long(started.elapsed(), notify_after, &message);
+        scan_duration = Some(started.elapsed());
+        let usage = telemetry::ScanTelemetry::between(&baseline, &telemetry::ResourceUsage::sample(), started.elapsed(), entries.len() as u64);
+        say(usage.describe());
+        scan_telemetry = Some(usage);
+        entries
+    };
+
+    // Scans saved before allocated sizes were recorded, and `du` imports, only have apparent ones
+    if utils::size_basis() == utils::SizeBasis::Allocated && !entries.iter().any(|e| e.cumulative_allocated_bytes > 0) {
+        eprintln!("{}", t!("warning-no-allocated-sizes"));
+        utils::set_size_basis(utils::SizeBasis::Apparent);
+    }
+
+    // Flag temp directories that are still being written to, e.g. by a running build
+    let active_since = args.skip_active.and_then(|window| SystemTime::now().checked_sub(window));
+    if let (Some(since), Some(window)) = (active_since, args.skip_active) {
+        scanner::mark_active(&mut entries, since);
+        let active = entries.iter().filter(|e| e.active).count();
+        if active > 0 {
+            say(t!("marked-active", count = active, window = humantime::format_duration(window).to_string()));
+        }
+    }
+
+    if args.du {
+        print!("{}", du::format_du(&entries, args.human_readable, args.units));
+        return Ok(());
+    }
+
+    // Rate how safe each temp directory is to delete, for the Safety column and sort
+    safety::score_entries(&mut entries, SystemTime::now());
+
+    if let Some((name, profile)) = &profile {
+        let count = profile.select(&mut entries, &roots, matching, SystemTime::now())?;
+        let size = entries.iter().filter(|e| e.selected).map(|e| e.cumulative_size_bytes).sum();
+        println!("{}", t!("profile-selected", name = name.clone(), count = count, size = utils::format_size(size)));
+    }
+
+    if let Some(target) = args.target_free {
+        let free = mounts::available(&root_path)
+            .ok_or_else(|| AppError::Message(t!("error-free-space", path = root_path.display().to_string())))?;
+        if free >= target {
+            println!("{}", t!("target-free-reached", free = utils::format_size(free), target = utils::format_size(target)));
+        } else {
+            let needed = target - free;
+            let device = mounts::device_of(&root_path);
+            let on_volume = |path: &Path| device.is_some() && mounts::device_of(path) == device;
+            let (picked, total) = safety::pick_for_target(&entries, &roots, needed, on_volume);
+            let picked: HashSet<&PathBuf> = picked.iter().collect();
+            for entry in &mut entries {
+                entry.selected = picked.contains(&entry.path);
             }
-            Err(e) => {
-                eprintln!("Error scanning directory: {}", e);
-                process::exit(1);
+            println!(
+                "{}",
+                t!(
+                    "target-free-selected",
+                    count = picked.len(),
+                    size = utils::format_size(total),
+                    needed = utils::format_size(needed),
+                    free = utils::format_size(free)
+                )
+            );
+            if total < needed {
+                eprintln!("{}", t!("target-free-short", missing = utils::format_size(needed - total)));
             }
         }
+    }
+
+    args.sort.sort(&mut entries);
+
+    let scan_metadata = match csv_metadata {
+        Some(metadata) => ScanMetadata {
+            schema_version: csv_handler::CSV_SCHEMA_VERSION,
+            ..metadata
+        },
+        None => ScanMetadata::new(Some(&std::path::absolute(&root_path).unwrap_or_else(|_| root_path.clone()))),
     };
 
     // Write to CSV if output path specified
     if let Some(output_csv) = args.output_csv {
-        match csv_handler::write_csv(&entries, &output_csv) {
-            Ok(_) => println!("Results saved to {}", output_csv.display()),
-            Err(e) => {
-                eprintln!("Error writing CSV: {}", e);
-                process::exit(1);
-            }
+        scan_file::write_scan_as(&entries, &output_csv, &scan_metadata, args.format).map_err(|e| AppError::write(&output_csv, e))?;
+        println!("{}", t!("results-saved", file = output_csv.display().to_string()));
+    }
+
+    if let Some(metrics_out) = &args.metrics_out {
+        let text = metrics::format_metrics(&entries, scan_duration, SystemTime::now());
+        metrics::write_metrics(metrics_out, &text).map_err(|e| AppError::write(metrics_out, e))?;
+        println!("{}", t!("metrics-written", file = metrics_out.display().to_string()));
+    }
+
+    if let Some(export_ncdu) = &args.export_ncdu {
+        let export_root = if entries.iter().any(|e| e.path.starts_with(&root_path)) {
+            Some(root_path.clone())
+        } else {
+            ncdu::common_root(&entries)
+        };
+        let result = match export_root {
+            Some(export_root) => std::fs::File::create(export_ncdu)
+                .map_err(Into::into)
+                .and_then(|file| ncdu::export_ncdu(&entries, &export_root, std::io::BufWriter::new(file))),
+            None => Err(ncdu::NcduError::Format("entries have no common root directory".to_string())),
+        };
+        result.map_err(|e| AppError::write(export_ncdu, e))?;
+        println!("{}", t!("ncdu-exported", file = export_ncdu.display().to_string()));
+    }
+
+    let mut results = RunResults {
+        summary: report::RunSummary {
+            scan: scan_telemetry,
+            ..report::RunSummary::new(&entries, args.dry_run)
+        },
+        metadata: scan_metadata.clone(),
+        // The list is handed to interactive mode, so keep a copy for posting
+        entries: (args.post_results.is_some() && args.post_entries).then(|| entries.clone()),
+        summary_json: args.summary_json,
+        summary_format: args.format.unwrap_or(scan_file::ScanFormat::Json),
+        post_url: args.post_results.clone(),
+        post_headers: config.post_headers.clone().into_iter().chain(args.post_headers.clone()).collect(),
+        email: match &config.smtp {
+            Some(smtp) if !args.email_report.is_empty() => Some(app::EmailReport {
+                to: args.email_report.clone(),
+                smtp: smtp.clone(),
+                csv: csv_handler::write_csv_to(Vec::new(), &entries, &scan_metadata).map_err(|e| AppError::Message(e.to_string()))?,
+            }),
+            _ => None,
+        },
+    };
+
+    if let Some(Command::Report { output, examples, by_owner }) = &args.command {
+        let groups = report::build_report(&entries, *examples);
+        let mut text = report::format_report(&groups, &root_path);
+        text.push_str(&ages::format_age_report(&ages::age_report(&entries, &root_path), 10));
+        if args.sort == SortKey::Files {
+            text.push_str(&report::format_file_counts(&entries, &root_path, 10));
+        }
+        if *by_owner {
+            let (users, groups) = owners::owner_totals(&entries);
+            text.push_str(&owners::format_owners(&users, &groups));
+        }
+        print!("{}", text);
+        if let Some(output) = output {
+            std::fs::write(output, &text).map_err(|e| AppError::write(output, e))?;
         }
+        results.finish();
+        return Ok(());
     }
 
+    let trash_location = if args.trash { Some(default_trash(&config)?) } else { None };
+
     // Display summary with TUI and check if user wants interactive mode
-    let mut launch_interactive = args.interactive;
-    
-    if !entries.is_empty() && !args.interactive {
-        match summary_ui::show_summary(&entries, &root_path) {
-            Ok(summary_ui::SummaryAction::LaunchInteractive) => {
-                launch_interactive = true;
-            }
-            Ok(summary_ui::SummaryAction::Continue) => {
-                // User chose to exit
-            }
-            Err(e) => {
-                eprintln!("Error displaying summary: {}", e);
-                // Fallback to text summary
-                let root_entry = entries.iter().find(|e| e.path == root_path);
-                if let Some(root) = root_entry {
-                    println!("\nSummary:");
-                    println!("  Total directories: {}", entries.len());
-                    println!("  Total files: {}", root.cumulative_file_count);
-                    println!("  Total size: {}", utils::format_size(root.cumulative_size_bytes));
-                }
-            }
-        }
-    }
-
-    // Launch interactive mode if requested
-    if launch_interactive {
-        if entries.is_empty() {
-            println!("\nNo directories to display in interactive mode.");
-            return;
-        }
-
-        println!("\nLaunching interactive mode...");
-        let mut session = interactive::InteractiveSession::new(entries);
-        
-        match session.run() {
-            Ok(selected_paths) => {
-                if selected_paths.is_empty() {
-                    println!("No directories selected for deletion.");
-                    return;
-                }
-
-                // Confirm deletion
-                if deletion::confirm_deletion(&selected_paths) {
-                    match deletion::delete_directories(&selected_paths) {
-                        Ok(report) => {
-                            if let Err(e) = report.show_report() {
-                                eprintln!("Error displaying report: {}", e);
-                                // Fallback to text report
-                                println!("\nDeletion complete:");
-                                println!("  Successfully deleted: {}", report.successful.len());
-                                println!("  Failed: {}", report.failed.len());
-                                println!("  Space freed: {}", utils::format_size(report.total_freed_bytes));
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("Error during deletion: {}", e);
-                            process::exit(1);
-                        }
-                    }
-                } else {
-                    println!("Deletion cancelled.");
-                }
-            }
-            Err(e) => {
-                eprintln!("Error in interactive mode: {}", e);
-                process::exit(1);
-            }
+    let deletion_options = DeletionOptions {
+        // Merged scans may list directories of other machines; those are browse-only here
+        foreign: merge::foreign_paths(&entries, utils::hostname().as_deref()),
+        active_since,
+        allow_tracked: args.allow_tracked,
+        open_files: args.open_files,
+        yes: args.yes,
+        dry_run: args.dry_run,
+        read_only,
+        large_deletion_threshold: config.large_deletion_threshold,
+        size_jobs: args.size_jobs,
+        hooks: config.hooks.clone(),
+        notify_after,
+        trash: trash_location,
+        grace_period: args.grace_period,
+    };
+
+    // A selection edited outside the tool is checked like a path list before the usual
+    // confirmation; a saved session's own selection is trusted
+    if args.apply_selection {
+        if read_only {
+            return Err(AppError::Message(t!("error-read-only-apply-selection")));
+        }
+        let listed = app::selected_paths(&entries);
+        println!("{}", t!("selection-loaded", count = listed.len()));
+        let (deleted, complete) = app::delete_listed(&listed, &entries, &deletion_options)?;
+        if let Some(deleted) = deleted {
+            results.summary.add_deletion(&deleted);
+        }
+        results.finish();
+        if !complete {
+            return Err(AppError::incomplete());
+        }
+        return Ok(());
+    }
+
+    // Scripted runs (`--yes` without --interactive) never open a UI; they act on the
+    // selection stored in a saved session
+    if args.yes && !args.interactive {
+        let costs: HashMap<PathBuf, RebuildCost> = entries.iter().map(|e| (e.path.clone(), e.rebuild_cost())).collect();
+        let selected_paths = app::selected_paths(&entries);
+        let normal = entries.iter().filter(|e| e.entry_type == EntryType::Normal).map(|e| e.path.clone()).collect();
+        if let Some(deleted) = app::delete_selected(selected_paths, &costs, &normal, &deletion_options)? {
+            results.summary.add_deletion(&deleted);
         }
+        results.finish();
+        return Ok(());
     }
+
+    // From here on the screens take over: summary, interactive mode, confirmation, deletion
+    // and its report, which leads back to the summary
+    let settings = app::InteractiveSettings {
+        sort: args.sort,
+        classifier,
+        root_path,
+        roots,
+        two_pane: args.two_pane,
+        suggested_selection: args.auto_select == Some(cli::AutoSelect::Safe),
+        read_only,
+        session_file,
+        scan_metadata,
+        watch: !args.no_watch,
+        overrides_path,
+    };
+    // A --target-free selection is reviewed before anything is deleted
+    let start = if args.interactive || args.session.is_some() || args.target_free.is_some() { app::Stage::Interactive } else { app::Stage::Summary };
+    app::App::new(entries, settings, &deletion_options, results).run(start)
+}
+
+/// The trash in the user data directory, with the configured size limit
+fn default_trash(config: &Config) -> Result<trash::Trash, AppError> {
+    trash::Trash::default_location()
+        .map(|trash| trash.with_max_size(config.trash_max_size))
+        .ok_or_else(|| AppError::Message(t!("error-trash-dir")))
+}
+
+/// `path` made absolute for commands that run from another directory
+fn absolute_path(path: &Path) -> String {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()).to_string_lossy().into_owned()
 }
@@ -0,0 +1,150 @@
+use crate::csv_handler::{ScanMetadata, CSV_SCHEMA_VERSION};
+use crate::scanner::DirectoryEntry;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// One loaded scan file to merge
+pub struct MergeInput {
+    pub file: PathBuf,
+    pub entries: Vec<DirectoryEntry>,
+    pub metadata: ScanMetadata,
+}
+
+impl MergeInput {
+    /// Where this scan's entries came from: the scanning host, or the file name for
+    /// scans without metadata
+    fn source(&self) -> String {
+        self.metadata.hostname.clone().unwrap_or_else(|| {
+            self.file
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| self.file.display().to_string())
+        })
+    }
+}
+
+/// Result of combining several scans
+pub struct Merged {
+    pub entries: Vec<DirectoryEntry>,
+    pub metadata: ScanMetadata,
+    /// Entries dropped because a newer scan of the same source covered the same path
+    pub duplicates: usize,
+}
+
+/// Combine scans into one entry set tagged with a source per entry.
+///
+/// The same path from the same source (e.g. two overlapping scans of one machine) is kept
+/// once, from the most recent scan; the same path on different machines is kept for each.
+pub fn merge_scans(inputs: Vec<MergeInput>) -> Merged {
+    // Newest scans first, so their entries win; inputs without a timestamp go last
+    let mut inputs = inputs;
+    inputs.sort_by_key(|input| std::cmp::Reverse(input.metadata.scanned_at));
+
+    let scanned_at = inputs.iter().filter_map(|input| input.metadata.scanned_at).min();
+    let mut seen: HashSet<(String, PathBuf)> = HashSet::new();
+    let mut entries = Vec::new();
+    let mut duplicates = 0;
+    for input in inputs {
+        let source = input.source();
+        for mut entry in input.entries {
+            // Entries of an already merged file keep their original source
+            let source = entry.source.take().unwrap_or_else(|| source.clone());
+            if !seen.insert((source.clone(), entry.path.clone())) {
+                duplicates += 1;
+                continue;
+            }
+            entry.source = Some(source);
+            entries.push(entry);
+        }
+    }
+    entries.sort_by_key(|e| std::cmp::Reverse(e.cumulative_size_bytes));
+
+    Merged {
+        entries,
+        // The combined set spans several roots and hosts, and is only as fresh as its oldest scan
+        metadata: ScanMetadata {
+            schema_version: CSV_SCHEMA_VERSION,
+            root: None,
+            scanned_at,
+            hostname: None,
+            tool_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        },
+        duplicates,
+    }
+}
+
+/// Paths of merged entries scanned on a machine other than `local_host`; they must not be
+/// deleted here, where the same path may be an unrelated directory
+pub fn foreign_paths(entries: &[DirectoryEntry], local_host: Option<&str>) -> HashSet<PathBuf> {
+    entries
+        .iter()
+        .filter(|e| e.source.as_deref().is_some_and(|source| Some(source) != local_host))
+        .map(|e| e.path.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    fn entry(path: &str, size: u64) -> DirectoryEntry {
+        DirectoryEntry {
+            path: PathBuf::from(path),
+            cumulative_size_bytes: size,
+            ..Default::default()
+        }
+    }
+
+    fn input(file: &str, hostname: Option<&str>, age_secs: u64, entries: Vec<DirectoryEntry>) -> MergeInput {
+        MergeInput {
+            file: PathBuf::from(file),
+            entries,
+            metadata: ScanMetadata {
+                schema_version: CSV_SCHEMA_VERSION,
+                root: None,
+                scanned_at: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 - age_secs)),
+                hostname: hostname.map(str::to_string),
+                tool_version: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_merge_scans() {
+        let merged = merge_scans(vec![
+            // Older scan of the whole home directory on the laptop
+            input("home.csv", Some("laptop"), 500, vec![entry("/home/me", 900), entry("/home/me/app/target", 100)]),
+            // Newer, overlapping scan of one project on the same laptop
+            input("app.csv", Some("laptop"), 10, vec![entry("/home/me/app/target", 300)]),
+            // Same path on another machine is a different directory
+            input("server.csv", Some("server"), 100, vec![entry("/home/me/app/target", 200)]),
+            input("old-export.csv", None, 1000, vec![entry("/data/cache", 50)]),
+        ]);
+
+        assert_eq!(merged.duplicates, 1);
+        assert_eq!(merged.entries.len(), 4);
+        let laptop_target = merged
+            .entries
+            .iter()
+            .find(|e| e.path.ends_with("target") && e.source.as_deref() == Some("laptop"))
+            .unwrap();
+        assert_eq!(laptop_target.cumulative_size_bytes, 300);
+        assert!(merged.entries.iter().any(|e| e.source.as_deref() == Some("server")));
+        assert!(merged.entries.iter().any(|e| e.source.as_deref() == Some("old-export")));
+        assert_eq!(merged.metadata.scanned_at, Some(SystemTime::UNIX_EPOCH + Duration::from_secs(999_000)));
+        assert_eq!(merged.metadata.hostname, None);
+
+        let foreign = foreign_paths(&merged.entries, Some("laptop"));
+        assert!(foreign.contains(&PathBuf::from("/data/cache")));
+        assert!(!foreign.contains(&PathBuf::from("/home/me")));
+    }
+
+    #[test]
+    fn test_merge_keeps_existing_sources() {
+        let mut tagged = entry("/srv/build", 10);
+        tagged.source = Some("ci-runner".to_string());
+        let merged = merge_scans(vec![input("merged.csv", None, 0, vec![tagged])]);
+        assert_eq!(merged.entries[0].source.as_deref(), Some("ci-runner"));
+    }
+}
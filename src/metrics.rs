@@ -0,0 +1,142 @@
+use crate::report::top_level;
+use crate::scanner::{DirectoryEntry, EntryType};
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Prometheus text exposition of a scan, for node_exporter's textfile collector. Each
+/// top-level directory (scan root, or source of a merged scan) gets its own `root` label.
+/// `scan_duration` is `None` for scans loaded from a file.
+pub fn format_metrics(entries: &[DirectoryEntry], scan_duration: Option<Duration>, now: SystemTime) -> String {
+    let roots = top_level(entries);
+    let mut out = String::new();
+
+    gauge(&mut out, "disk_cleanup_total_bytes", "Total size of the scanned directory");
+    for root in &roots {
+        let _ = writeln!(out, "disk_cleanup_total_bytes{{root=\"{}\"}} {}", label(root), root.cumulative_size_bytes);
+    }
+
+    gauge(&mut out, "disk_cleanup_category_bytes", "Bytes by category; normal is everything not in a temp directory");
+    for root in &roots {
+        let under_root: Vec<&DirectoryEntry> = entries.iter().filter(|e| e.path.starts_with(&root.path)).collect();
+        let temp_bytes: u64 = under_root.iter().filter(|e| e.entry_type.is_temp()).map(|e| e.cumulative_size_bytes).sum();
+        for category in EntryType::ALL {
+            let bytes = if category == EntryType::Normal {
+                root.cumulative_size_bytes.saturating_sub(temp_bytes)
+            } else {
+                under_root.iter().filter(|e| e.entry_type == category).map(|e| e.cumulative_size_bytes).sum()
+            };
+            let _ = writeln!(
+                out,
+                "disk_cleanup_category_bytes{{root=\"{}\",category=\"{}\"}} {}",
+                label(root),
+                category.as_str(),
+                bytes
+            );
+        }
+    }
+
+    gauge(&mut out, "disk_cleanup_temp_bytes", "Reclaimable bytes in temp directories");
+    for root in &roots {
+        let bytes: u64 = entries
+            .iter()
+            .filter(|e| e.entry_type.is_temp() && e.path.starts_with(&root.path))
+            .map(|e| e.cumulative_size_bytes)
+            .sum();
+        let _ = writeln!(out, "disk_cleanup_temp_bytes{{root=\"{}\"}} {}", label(root), bytes);
+    }
+
+    gauge(&mut out, "disk_cleanup_temp_directories", "Number of temp directories");
+    for root in &roots {
+        let count = entries.iter().filter(|e| e.entry_type.is_temp() && e.path.starts_with(&root.path)).count();
+        let _ = writeln!(out, "disk_cleanup_temp_directories{{root=\"{}\"}} {}", label(root), count);
+    }
+
+    if let Some(duration) = scan_duration {
+        gauge(&mut out, "disk_cleanup_scan_duration_seconds", "How long the scan took");
+        let _ = writeln!(out, "disk_cleanup_scan_duration_seconds {:.3}", duration.as_secs_f64());
+    }
+
+    gauge(&mut out, "disk_cleanup_last_run_timestamp_seconds", "When the metrics were written");
+    let timestamp = now.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs();
+    let _ = writeln!(out, "disk_cleanup_last_run_timestamp_seconds {}", timestamp);
+    out
+}
+
+/// Write the metrics next to `path` and rename them into place, so the collector never
+/// reads a half-written file
+pub fn write_metrics(path: &Path, text: &str) -> io::Result<()> {
+    let partial = path.with_extension("prom.partial");
+    fs::write(&partial, text)?;
+    fs::rename(&partial, path)
+}
+
+fn gauge(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+}
+
+/// Label value with `\`, `"` and newlines escaped
+fn label(entry: &DirectoryEntry) -> String {
+    entry
+        .path
+        .display()
+        .to_string()
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn entry(path: &str, size: u64, entry_type: EntryType) -> DirectoryEntry {
+        DirectoryEntry {
+            path: PathBuf::from(path),
+            cumulative_size_bytes: size,
+            entry_type,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_format_metrics() {
+        let entries = vec![
+            entry("/p", 10_000, EntryType::Normal),
+            entry("/p/a/target", 3000, EntryType::BuildOutput),
+            entry("/p/b/target", 1000, EntryType::BuildOutput),
+            entry("/p/web/node_modules", 1500, EntryType::DependencyCache),
+            entry("/q \"x\"", 500, EntryType::Normal),
+        ];
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let text = format_metrics(&entries, Some(Duration::from_millis(2500)), now);
+
+        assert!(text.contains("# TYPE disk_cleanup_total_bytes gauge\n"));
+        assert!(text.contains("disk_cleanup_total_bytes{root=\"/p\"} 10000\n"));
+        assert!(text.contains("disk_cleanup_total_bytes{root=\"/q \\\"x\\\"\"} 500\n"));
+        assert!(text.contains("disk_cleanup_category_bytes{root=\"/p\",category=\"build_output\"} 4000\n"));
+        assert!(text.contains("disk_cleanup_category_bytes{root=\"/p\",category=\"normal\"} 4500\n"));
+        assert!(text.contains("disk_cleanup_category_bytes{root=\"/p\",category=\"model_cache\"} 0\n"));
+        assert!(text.contains("disk_cleanup_temp_bytes{root=\"/p\"} 5500\n"));
+        assert!(text.contains("disk_cleanup_temp_directories{root=\"/p\"} 3\n"));
+        assert!(text.contains("disk_cleanup_scan_duration_seconds 2.500\n"));
+        assert!(text.contains("disk_cleanup_last_run_timestamp_seconds 1700000000\n"));
+
+        let loaded = format_metrics(&entries, None, now);
+        assert!(!loaded.contains("scan_duration"));
+    }
+
+    #[test]
+    fn test_write_metrics() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("disk_cleanup.prom");
+        write_metrics(&path, "disk_cleanup_temp_bytes 1\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "disk_cleanup_temp_bytes 1\n");
+        assert!(!path.with_extension("prom.partial").exists());
+    }
+}
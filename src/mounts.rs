@@ -0,0 +1,542 @@
+use crate::columns::share_bar;
+use crate::i18n::t;
+use crate::utils::format_size;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Row, Table, TableState},
+    Frame, Terminal,
+};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Kernel and in-memory filesystems that hold nothing worth cleaning up
+const PSEUDO_FILESYSTEMS: [&str; 27] = [
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devfs",
+    "devpts",
+    "devtmpfs",
+    "efivarfs",
+    "fdesc",
+    "fusectl",
+    "hugetlbfs",
+    "mqueue",
+    "nsfs",
+    "nullfs",
+    "proc",
+    "pstore",
+    "ramfs",
+    "rpc_pipefs",
+    "securityfs",
+    "selinuxfs",
+    "squashfs",
+    "sysfs",
+    "tmpfs",
+    "tracefs",
+];
+
+/// Filesystems served over the network, which `--all-mounts` leaves to the host they live on
+const NETWORK_FILESYSTEMS: [&str; 12] = [
+    "9p",
+    "afpfs",
+    "ceph",
+    "cifs",
+    "davfs",
+    "fuse.rclone",
+    "fuse.sshfs",
+    "glusterfs",
+    "nfs",
+    "nfs4",
+    "smb3",
+    "smbfs",
+];
+
+/// One mounted filesystem with its space usage, as `df` shows it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mount {
+    pub mount_point: PathBuf,
+    pub device: String,
+    pub fs_type: String,
+    pub total: u64,
+    pub used: u64,
+    pub available: u64,
+}
+
+impl Mount {
+    /// Share of the space usable by ordinary users that is taken; like `df`, blocks
+    /// reserved for root count as neither used nor available
+    pub fn use_share(&self) -> f64 {
+        let usable = self.used + self.available;
+        if usable == 0 {
+            0.0
+        } else {
+            self.used as f64 / usable as f64
+        }
+    }
+}
+
+/// Whether `fs_type` is a kernel or in-memory filesystem rather than storage
+pub fn is_pseudo(fs_type: &str) -> bool {
+    PSEUDO_FILESYSTEMS.contains(&fs_type)
+}
+
+/// Whether `fs_type` lives on another machine
+pub fn is_network(fs_type: &str) -> bool {
+    NETWORK_FILESYSTEMS.contains(&fs_type)
+}
+
+/// Mount points of the local filesystems to scan one by one for `--all-mounts`. A
+/// filesystem mounted twice (a bind mount) is only scanned at its first mount point.
+pub fn local_roots(mounts: &[Mount]) -> Vec<PathBuf> {
+    let mut seen = std::collections::HashSet::new();
+    mounts
+        .iter()
+        .filter(|mount| !is_network(&mount.fs_type))
+        .filter(|mount| device_of(&mount.mount_point).is_none_or(|device| seen.insert(device)))
+        .map(|mount| mount.mount_point.clone())
+        .collect()
+}
+
+/// Roots of the fixed (not removable, network or optical) drives, `C:\`, `D:\` and so on, to
+/// scan one by one for `--all-drives`; empty off Windows
+#[cfg(windows)]
+pub fn fixed_drives() -> Vec<PathBuf> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::{GetDriveTypeW, GetLogicalDrives};
+    // From winbase.h
+    const DRIVE_FIXED: u32 = 3;
+
+    // SAFETY: takes no arguments and returns a bit mask of the drive letters in use
+    let mask = unsafe { GetLogicalDrives() };
+    drive_roots(mask)
+        .into_iter()
+        .filter(|root| {
+            let wide: Vec<u16> = root.as_os_str().encode_wide().chain([0]).collect();
+            // SAFETY: `wide` is a NUL-terminated UTF-16 path
+            unsafe { GetDriveTypeW(wide.as_ptr()) == DRIVE_FIXED }
+        })
+        .collect()
+}
+
+#[cfg(not(windows))]
+pub fn fixed_drives() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+/// `A:\` to `Z:\` for the bits set in a `GetLogicalDrives` mask, bit 0 being A
+#[cfg_attr(not(windows), allow(dead_code))]
+fn drive_roots(mask: u32) -> Vec<PathBuf> {
+    (b'A'..=b'Z')
+        .enumerate()
+        .filter(|(bit, _)| mask & (1 << bit) != 0)
+        .map(|(_, letter)| PathBuf::from(format!("{}:\\", letter as char)))
+        .collect()
+}
+
+/// Device of the filesystem `path` is on: `st_dev` on Unix, the volume serial number on
+/// Windows
+#[cfg(unix)]
+pub fn device_of(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).ok().map(|metadata| metadata.dev())
+}
+
+#[cfg(windows)]
+pub fn device_of(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::{GetVolumeInformationW, GetVolumePathNameW};
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+    let mut volume = [0u16; 261];
+    // SAFETY: `wide` is a NUL-terminated UTF-16 path and `volume` a buffer of the given length
+    if unsafe { GetVolumePathNameW(wide.as_ptr(), volume.as_mut_ptr(), volume.len() as u32) } == 0 {
+        return None;
+    }
+    let mut serial = 0u32;
+    // SAFETY: `volume` was NUL-terminated by GetVolumePathNameW; the other outputs are optional
+    let ok = unsafe {
+        GetVolumeInformationW(
+            volume.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            &mut serial,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    (ok != 0).then_some(serial as u64)
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn device_of(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Mounted storage filesystems sorted by mount point, without pseudo filesystems, ones
+/// reporting no size and files mounted over files (as containers do for `/etc/hosts`);
+/// empty where mounts can't be listed
+pub fn list() -> Vec<Mount> {
+    let mut mounts: Vec<Mount> = mount_table()
+        .into_iter()
+        .filter(|(_, mount_point, fs_type)| !is_pseudo(fs_type) && mount_point.is_dir())
+        .filter_map(|(device, mount_point, fs_type)| {
+            let (total, used, available) = space(&mount_point)?;
+            (total > 0).then_some(Mount {
+                mount_point,
+                device,
+                fs_type,
+                total,
+                used,
+                available,
+            })
+        })
+        .collect();
+    mounts.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+    mounts
+}
+
+/// (device, mount point, filesystem type) for every line of a mount table in the
+/// `/proc/self/mounts` format, where spaces and the like are octal escapes
+fn parse_mount_table(table: &str) -> Vec<(String, PathBuf, String)> {
+    table
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let device = unescape(fields.next()?);
+            let mount_point = unescape(fields.next()?);
+            let fs_type = fields.next()?.to_string();
+            Some((device, PathBuf::from(mount_point), fs_type))
+        })
+        .collect()
+}
+
+/// `\040` and friends back to the characters they stand for
+fn unescape(field: &str) -> String {
+    let mut bytes = Vec::with_capacity(field.len());
+    let raw = field.as_bytes();
+    let mut i = 0;
+    while i < raw.len() {
+        let octal = raw.get(i + 1..i + 4).and_then(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 8).ok());
+        match (raw[i], octal) {
+            (b'\\', Some(byte)) => {
+                bytes.push(byte);
+                i += 4;
+            }
+            (byte, _) => {
+                bytes.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(target_os = "linux")]
+fn mount_table() -> Vec<(String, PathBuf, String)> {
+    std::fs::read_to_string("/proc/self/mounts")
+        .map(|table| parse_mount_table(&table))
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "macos")]
+fn mount_table() -> Vec<(String, PathBuf, String)> {
+    use std::ffi::CStr;
+    let mut mounts: *mut libc::statfs = std::ptr::null_mut();
+    // SAFETY: getmntinfo points `mounts` at `count` statfs structs it owns
+    let count = unsafe { libc::getmntinfo(&mut mounts, libc::MNT_NOWAIT) };
+    if count <= 0 || mounts.is_null() {
+        return Vec::new();
+    }
+    // SAFETY: see above; the buffer stays valid until the next getmntinfo call
+    let mounts = unsafe { std::slice::from_raw_parts(mounts, count as usize) };
+    let text = |chars: &[libc::c_char]| {
+        // SAFETY: the name fields are NUL-terminated within their fixed-size arrays
+        unsafe { CStr::from_ptr(chars.as_ptr()) }.to_string_lossy().into_owned()
+    };
+    mounts
+        .iter()
+        .map(|m| (text(&m.f_mntfromname), PathBuf::from(text(&m.f_mntonname)), text(&m.f_fstypename)))
+        .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn mount_table() -> Vec<(String, PathBuf, String)> {
+    Vec::new()
+}
+
+/// Bytes available to ordinary users on the filesystem `path` is on, as `df` shows them
+pub fn available(path: &Path) -> Option<u64> {
+    space(path).map(|(_, _, available)| available)
+}
+
+/// Total, used and available bytes of the filesystem mounted at `path`
+#[cfg(unix)]
+fn space(path: &Path) -> Option<(u64, u64, u64)> {
+    use std::os::unix::ffi::OsStrExt;
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `path` is a valid NUL-terminated string and `stat` a writable statvfs
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    // fsblkcnt_t is 32-bit on some platforms
+    #[allow(clippy::unnecessary_cast)]
+    let (blocks, free, available, size) = (stat.f_blocks as u64, stat.f_bfree as u64, stat.f_bavail as u64, stat.f_frsize as u64);
+    Some((blocks * size, blocks.saturating_sub(free) * size, available * size))
+}
+
+#[cfg(not(unix))]
+fn space(_path: &Path) -> Option<(u64, u64, u64)> {
+    None
+}
+
+/// Whether the filesystem containing `path` is mounted read-only
+#[cfg(unix)]
+pub fn is_read_only(path: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+    let Ok(path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `path` is a valid NUL-terminated string and `stat` a writable statvfs
+    unsafe { libc::statvfs(path.as_ptr(), &mut stat) == 0 && stat.f_flag & libc::ST_RDONLY != 0 }
+}
+
+#[cfg(not(unix))]
+pub fn is_read_only(_path: &Path) -> bool {
+    false
+}
+
+/// Show the mounts with their usage and let the user pick one to scan. The first row is
+/// `current`, so Enter right away scans the current directory as before. `None` if the
+/// user quit.
+pub fn choose(mounts: &[Mount], current: &Path) -> io::Result<Option<PathBuf>> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let result = run_picker(&mut terminal, mounts, current);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    Ok(result?.map(|row| match row {
+        0 => current.to_path_buf(),
+        row => mounts[row - 1].mount_point.clone(),
+    }))
+}
+
+fn run_picker(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, mounts: &[Mount], current: &Path) -> io::Result<Option<usize>> {
+    let mut selected = 0usize;
+    loop {
+        terminal.draw(|f| render_picker(f, mounts, current, selected))?;
+
+        if event::poll(Duration::from_millis(100))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
+                    KeyCode::Enter => return Ok(Some(selected)),
+                    KeyCode::Up | KeyCode::Char('k') => selected = selected.saturating_sub(1),
+                    KeyCode::Down | KeyCode::Char('j') => selected = (selected + 1).min(mounts.len()),
+                    KeyCode::Home => selected = 0,
+                    KeyCode::End => selected = mounts.len(),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn render_picker(f: &mut Frame, mounts: &[Mount], current: &Path, selected: usize) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Min(0),    // Mounts
+            Constraint::Length(3), // Footer
+        ])
+        .split(f.area());
+
+    let header = Paragraph::new(Line::from(Span::styled(
+        t!("mounts-title"),
+        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+    )))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Cyan)));
+    f.render_widget(header, chunks[0]);
+
+    let mut rows = vec![Row::new(vec![
+        t!("mounts-current-dir"),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        current.display().to_string(),
+    ])
+    .style(Style::default().add_modifier(Modifier::BOLD))];
+    rows.extend(mounts.iter().map(|mount| {
+        let share = mount.use_share();
+        let color = if share >= 0.9 {
+            Color::Red
+        } else if share >= 0.75 {
+            Color::Yellow
+        } else {
+            Color::Green
+        };
+        Row::new(vec![
+            mount.mount_point.display().to_string(),
+            mount.fs_type.clone(),
+            format_size(mount.total),
+            format_size(mount.available),
+            format!("{:>3.0}% {}", share * 100.0, share_bar(share, 20)),
+            mount.device.clone(),
+        ])
+        .style(Style::default().fg(color))
+    }));
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Percentage(25),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(25),
+            Constraint::Min(10),
+        ],
+    )
+    .header(
+        Row::new(vec![
+            t!("mounts-column-mount"),
+            t!("mounts-column-type"),
+            t!("mounts-column-size"),
+            t!("mounts-column-free"),
+            t!("mounts-column-used"),
+            t!("mounts-column-device"),
+        ])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::White)))
+    .row_highlight_style(Style::default().bg(Color::DarkGray))
+    .highlight_symbol("▶ ");
+    let mut state = TableState::default().with_selected(Some(selected));
+    f.render_stateful_widget(table, chunks[1], &mut state);
+
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled("↑/↓", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  |  ", t!("key-navigate"))),
+        Span::styled("Enter", Style::default().fg(Color::Cyan)),
+        Span::raw(format!(": {}  |  ", t!("key-scan-mount"))),
+        Span::styled("q", Style::default().fg(Color::Green)),
+        Span::raw(format!(": {}", t!("key-quit"))),
+    ]))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::White)));
+    f.render_widget(footer, chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    #[test]
+    fn test_parse_mount_table() {
+        let table = "\
+/dev/nvme0n1p2 / ext4 rw,relatime 0 0
+proc /proc proc rw,nosuid,nodev,noexec,relatime 0 0
+tmpfs /run tmpfs rw,nosuid,nodev 0 0
+/dev/sdb1 /media/me/My\\040Disk vfat rw 0 0
+";
+        let mounts = parse_mount_table(table);
+        assert_eq!(mounts.len(), 4);
+        assert_eq!(mounts[3], ("/dev/sdb1".to_string(), PathBuf::from("/media/me/My Disk"), "vfat".to_string()));
+        let storage: Vec<&str> = mounts.iter().filter(|(_, _, t)| !is_pseudo(t)).map(|(_, _, t)| t.as_str()).collect();
+        assert_eq!(storage, vec!["ext4", "vfat"]);
+        assert_eq!(unescape("a\\134b\\04"), "a\\b\\04");
+    }
+
+    #[test]
+    fn test_drive_roots() {
+        assert_eq!(drive_roots(0b1100), [PathBuf::from(r"C:\"), PathBuf::from(r"D:\")]);
+        assert_eq!(drive_roots(1 << 25), [PathBuf::from(r"Z:\")]);
+        assert!(drive_roots(0).is_empty());
+    }
+
+    #[test]
+    fn test_local_roots() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mount = |path: &Path, fs_type: &str| Mount {
+            mount_point: path.to_path_buf(),
+            device: "/dev/sda1".to_string(),
+            fs_type: fs_type.to_string(),
+            total: 1,
+            used: 0,
+            available: 1,
+        };
+        let bind = temp_dir.path().join("bind");
+        std::fs::create_dir(&bind).unwrap();
+        let mounts = [
+            mount(temp_dir.path(), "ext4"),
+            // Same device as the first one, like a bind mount of a directory in it
+            mount(&bind, "ext4"),
+            mount(Path::new("/net/share"), "nfs4"),
+        ];
+        assert_eq!(local_roots(&mounts), vec![temp_dir.path().to_path_buf()]);
+    }
+
+    #[test]
+    fn test_mount_picker() {
+        let mounts = vec![
+            Mount {
+                mount_point: PathBuf::from("/"),
+                device: "/dev/nvme0n1p2".to_string(),
+                fs_type: "ext4".to_string(),
+                total: 100 << 30,
+                used: 92 << 30,
+                available: 8 << 30,
+            },
+            Mount {
+                mount_point: PathBuf::from("/data"),
+                device: "/dev/sdb1".to_string(),
+                fs_type: "xfs".to_string(),
+                total: 1000 << 30,
+                used: 250 << 30,
+                available: 750 << 30,
+            },
+        ];
+        assert!((mounts[0].use_share() - 0.92).abs() < 1e-9);
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 12)).unwrap();
+        terminal.draw(|f| render_picker(f, &mounts, Path::new("/home/me/src"), 1)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let lines: Vec<String> = (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect();
+        assert!(lines.iter().any(|line| line.contains("/home/me/src")));
+        let root = lines.iter().find(|line| line.contains("/dev/nvme0n1p2")).unwrap();
+        assert!(root.contains("▶") && root.contains(" 92%"));
+        assert!(lines.iter().any(|line| line.contains("/data") && line.contains(" 25%")));
+    }
+}
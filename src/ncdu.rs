@@ -0,0 +1,306 @@
+use crate::ages::AgeBuckets;
+use crate::classifier::Classifier;
+use crate::scanner::{DirectoryEntry, EntryType};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum NcduError {
+    #[error("Not an ncdu export: {0}")]
+    Format(String),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// One directory of the export tree
+#[derive(Default)]
+struct Node {
+    file_count: u64,
+    size_bytes: u64,
+    modified: Option<SystemTime>,
+    children: BTreeSet<PathBuf>,
+}
+
+/// Write entries under `root` in ncdu's JSON export format (`ncdu -f file.json` opens it).
+///
+/// Scans don't record individual files, so each directory's own files appear as a single
+/// `<N files>` item. Directories missing from the entries (e.g. with `--temp-only`) are
+/// added empty to keep the tree connected.
+pub fn export_ncdu<W: Write>(entries: &[DirectoryEntry], root: &Path, mut writer: W) -> Result<(), NcduError> {
+    let mut nodes: BTreeMap<PathBuf, Node> = BTreeMap::new();
+    nodes.insert(root.to_path_buf(), Node::default());
+    for entry in entries.iter().filter(|e| e.path.starts_with(root)) {
+        let node = nodes.entry(entry.path.clone()).or_default();
+        node.file_count = entry.file_count;
+        node.size_bytes = entry.size_bytes;
+        node.modified = entry.modified;
+
+        // Link the entry (and any missing ancestors) into the tree
+        let mut child = entry.path.as_path();
+        while let Some(parent) = child.parent().filter(|_| child != root) {
+            let is_new = !nodes.contains_key(parent);
+            nodes.entry(parent.to_path_buf()).or_default().children.insert(child.to_path_buf());
+            if !is_new {
+                break;
+            }
+            child = parent;
+        }
+    }
+
+    let timestamp = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs();
+    let export = json!([
+        1,
+        2,
+        {"progname": "disk-cleanup-tool", "progver": env!("CARGO_PKG_VERSION"), "timestamp": timestamp},
+        directory_value(&nodes, root, true),
+    ]);
+    serde_json::to_writer(&mut writer, &export)?;
+    writer.write_all(b"\n")?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Deepest directory containing every entry, used as the export root when the entries
+/// don't come from a scan of the current root
+pub fn common_root(entries: &[DirectoryEntry]) -> Option<PathBuf> {
+    let mut paths = entries.iter().map(|e| e.path.as_path());
+    let mut root = paths.next()?.to_path_buf();
+    for path in paths {
+        while !path.starts_with(&root) {
+            if !root.pop() {
+                return None;
+            }
+        }
+    }
+    // Relative and absolute paths share only the empty path
+    (!root.as_os_str().is_empty()).then_some(root)
+}
+
+fn directory_value(nodes: &BTreeMap<PathBuf, Node>, path: &Path, is_root: bool) -> Value {
+    let node = &nodes[path];
+    // ncdu names the root by its full path and everything else by its file name
+    let name = if is_root {
+        path.display().to_string()
+    } else {
+        path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+    };
+
+    let mut info = Map::new();
+    info.insert("name".to_string(), json!(name));
+    if let Some(mtime) = node.modified.and_then(|m| m.duration_since(SystemTime::UNIX_EPOCH).ok()) {
+        info.insert("mtime".to_string(), json!(mtime.as_secs()));
+    }
+
+    let mut items = vec![Value::Object(info)];
+    if node.file_count > 0 {
+        items.push(json!({
+            "name": format!("<{} files>", node.file_count),
+            "asize": node.size_bytes,
+            "dsize": node.size_bytes,
+        }));
+    }
+    items.extend(node.children.iter().map(|child| directory_value(nodes, child, false)));
+    Value::Array(items)
+}
+
+/// Read an ncdu JSON export (`ncdu -o file.json`) into entries, classifying directories
+/// like a scan would: temp directories are sized as a whole and not descended into.
+pub fn import_ncdu<R: Read>(reader: R, classifier: &Classifier) -> Result<Vec<DirectoryEntry>, NcduError> {
+    let export: Value = serde_json::from_reader(reader)?;
+    let parts = export.as_array().ok_or_else(|| NcduError::Format("expected a JSON array".to_string()))?;
+    match parts.first().and_then(Value::as_u64) {
+        Some(1) => {}
+        Some(major) => return Err(NcduError::Format(format!("unsupported format version {}", major))),
+        None => return Err(NcduError::Format("missing format version".to_string())),
+    }
+    let root = parts.get(3).ok_or_else(|| NcduError::Format("missing directory tree".to_string()))?;
+
+    let mut entries = Vec::new();
+    import_directory(root, None, classifier, &mut entries)?;
+    entries.sort_by_key(|e| std::cmp::Reverse(e.cumulative_size_bytes));
+    Ok(entries)
+}
+
+/// Add `dir` (and, unless it is a temp directory, its subdirectories) to `entries`; returns
+/// its cumulative file count, size, allocated size, inode count and number of items ncdu
+/// couldn't read
+fn import_directory(
+    dir: &Value,
+    parent: Option<&Path>,
+    classifier: &Classifier,
+    entries: &mut Vec<DirectoryEntry>,
+) -> Result<(u64, u64, u64, u64, u64), NcduError> {
+    let items = dir.as_array().ok_or_else(|| NcduError::Format("expected a directory array".to_string()))?;
+    let info = items
+        .first()
+        .and_then(Value::as_object)
+        .ok_or_else(|| NcduError::Format("directory without info object".to_string()))?;
+    let name = info.get("name").and_then(Value::as_str).unwrap_or_default();
+    let path = match parent {
+        Some(parent) => parent.join(name),
+        None => PathBuf::from(name),
+    };
+
+    let rule_path = std::path::absolute(&path).unwrap_or_else(|_| path.clone());
+    let entry_type = classifier.classify_path(&rule_path).unwrap_or(EntryType::Normal);
+
+    let (mut file_count, mut size_bytes) = (0, 0);
+    let (mut cumulative_file_count, mut cumulative_size_bytes) = (0, 0);
+    // ncdu leaves out a zero `dsize`, so a missing one counts as nothing allocated
+    let mut allocated = 0;
+    let mut inodes = 1;
+    let read_error = |info: &Map<String, Value>| info.get("read_error").and_then(Value::as_bool) == Some(true);
+    let mut errors = u64::from(read_error(info));
+    for item in &items[1..] {
+        match item {
+            Value::Array(_) => {
+                // Subdirectories of temp directories only count towards its size
+                let (files, size, dir_allocated, dir_inodes, dir_errors) = if entry_type.is_temp() {
+                    import_directory(item, Some(&path), classifier, &mut Vec::new())?
+                } else {
+                    import_directory(item, Some(&path), classifier, entries)?
+                };
+                cumulative_file_count += files;
+                cumulative_size_bytes += size;
+                allocated += dir_allocated;
+                inodes += dir_inodes;
+                errors += dir_errors;
+            }
+            Value::Object(file) if !file.contains_key("excluded") => {
+                let size = file.get("asize").and_then(Value::as_u64).unwrap_or(0);
+                file_count += 1;
+                size_bytes += size;
+                allocated += file.get("dsize").and_then(Value::as_u64).unwrap_or(0);
+                inodes += 1;
+                errors += u64::from(read_error(file));
+            }
+            _ => {}
+        }
+    }
+    cumulative_file_count += file_count;
+    cumulative_size_bytes += size_bytes;
+
+    // Temp directories are sized as a whole, matching the scanner
+    if entry_type.is_temp() {
+        (file_count, size_bytes) = (cumulative_file_count, cumulative_size_bytes);
+    }
+    entries.push(DirectoryEntry {
+        label: entry_type.is_temp().then(|| classifier.label_path(&rule_path)).flatten(),
+        path,
+        file_count,
+        size_bytes,
+        cumulative_file_count,
+        cumulative_size_bytes,
+        cumulative_allocated_bytes: allocated,
+        entry_type,
+        active: false,
+        safety: None,
+        modified: info
+            .get("mtime")
+            .and_then(Value::as_u64)
+            .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs)),
+        source: None,
+        selected: false,
+        owners: Vec::new(),
+        ages: AgeBuckets::default(),
+        inodes,
+        incomplete: errors > 0,
+        errors,
+        alias_of: None,
+        mount: None,
+        estimated: false,
+        images: Vec::new(),
+        previous_size: None,
+        canonical_path: None,
+    });
+    Ok((cumulative_file_count, cumulative_size_bytes, allocated, inodes, errors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Trimmed `ncdu -o` output
+    const NCDU_EXPORT: &str = r#"[1,2,{"progname":"ncdu","progver":"1.19","timestamp":1700000000},
+[{"name":"/home/me/projects","asize":4096,"dsize":4096},
+ {"name":"README.md","asize":100,"dsize":4096},
+ [{"name":"app","mtime":1690000000},
+  {"name":"main.rs","asize":200,"dsize":4096},
+  [{"name":"node_modules"},
+   {"name":"index.js","asize":1000,"dsize":4096},
+   [{"name":"lodash"},{"name":"lodash.js","asize":5000,"dsize":8192}]],
+  {"name":"socket","excluded":"otherfs"}]]]"#;
+
+    #[test]
+    fn test_import_ncdu() {
+        let entries = import_ncdu(NCDU_EXPORT.as_bytes(), &Classifier::default()).unwrap();
+        let find = |p: &str| entries.iter().find(|e| e.path == Path::new(p)).unwrap();
+
+        // The dependency tree is one temp entry, not descended into
+        assert_eq!(entries.len(), 3);
+        let node_modules = find("/home/me/projects/app/node_modules");
+        assert!(node_modules.entry_type.is_temp());
+        assert_eq!((node_modules.file_count, node_modules.size_bytes), (2, 6000));
+        assert_eq!(node_modules.cumulative_allocated_bytes, 12288);
+
+        let app = find("/home/me/projects/app");
+        assert_eq!(app.entry_type, EntryType::Normal);
+        assert_eq!((app.file_count, app.size_bytes), (1, 200));
+        assert_eq!(app.cumulative_size_bytes, 6200);
+        assert_eq!(app.modified, Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_690_000_000)));
+
+        let root = find("/home/me/projects");
+        assert_eq!((root.cumulative_file_count, root.cumulative_size_bytes), (4, 6300));
+        assert_eq!(root.cumulative_allocated_bytes, 3 * 4096 + 8192);
+    }
+
+    #[test]
+    fn test_export_roundtrip() {
+        let imported = import_ncdu(NCDU_EXPORT.as_bytes(), &Classifier::default()).unwrap();
+        // A filtered scan: the "app" directory in between is missing
+        let temp_only: Vec<DirectoryEntry> = imported.iter().filter(|e| e.entry_type.is_temp()).cloned().collect();
+
+        let mut export = Vec::new();
+        export_ncdu(&temp_only, Path::new("/home/me/projects"), &mut export).unwrap();
+        let value: Value = serde_json::from_slice(&export).unwrap();
+        assert_eq!(value[0], 1);
+        assert_eq!(value[2]["progname"], "disk-cleanup-tool");
+        assert_eq!(value[3][0]["name"], "/home/me/projects");
+        assert_eq!(value[3][1][0]["name"], "app");
+        assert_eq!(value[3][1][1][1]["name"], "<2 files>");
+
+        let reimported = import_ncdu(export.as_slice(), &Classifier::default()).unwrap();
+        let node_modules = reimported.iter().find(|e| e.path.ends_with("node_modules")).unwrap();
+        assert_eq!(node_modules.cumulative_size_bytes, 6000);
+        assert_eq!(reimported.iter().find(|e| e.path.ends_with("projects")).unwrap().cumulative_size_bytes, 6000);
+    }
+
+    #[test]
+    fn test_common_root() {
+        let entry = |p: &str| DirectoryEntry {
+            path: PathBuf::from(p),
+            ..Default::default()
+        };
+        assert_eq!(
+            common_root(&[entry("/home/me/a/target"), entry("/home/me/b/node_modules")]),
+            Some(PathBuf::from("/home/me"))
+        );
+        assert_eq!(common_root(&[entry("/srv/x")]), Some(PathBuf::from("/srv/x")));
+        assert_eq!(common_root(&[entry("rel/a"), entry("/abs")]), None);
+        assert_eq!(common_root(&[]), None);
+    }
+
+    #[test]
+    fn test_import_rejects_other_json() {
+        assert!(matches!(import_ncdu(r#"{"entries": []}"#.as_bytes(), &Classifier::default()), Err(NcduError::Format(_))));
+        assert!(matches!(import_ncdu("[2,0,{},[{}]]".as_bytes(), &Classifier::default()), Err(NcduError::Format(_))));
+    }
+}
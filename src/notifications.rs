@@ -0,0 +1,53 @@
+use crate::i18n::t;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+const FOCUS_UNKNOWN: u8 = 0;
+const FOCUS_GAINED: u8 = 1;
+const FOCUS_LOST: u8 = 2;
+
+/// Last focus change the terminal reported; terminals that don't report focus stay unknown
+static FOCUS: AtomicU8 = AtomicU8::new(FOCUS_UNKNOWN);
+
+/// Record a focus event from the terminal
+pub fn set_focused(focused: bool) {
+    FOCUS.store(if focused { FOCUS_GAINED } else { FOCUS_LOST }, Ordering::Relaxed);
+}
+
+fn focused() -> Option<bool> {
+    match FOCUS.load(Ordering::Relaxed) {
+        FOCUS_GAINED => Some(true),
+        FOCUS_LOST => Some(false),
+        _ => None,
+    }
+}
+
+/// An operation is worth a notification when it took at least `threshold` and the terminal
+/// is not known to have focus
+fn should_notify(elapsed: Duration, threshold: Option<Duration>, focused: Option<bool>) -> bool {
+    threshold.is_some_and(|threshold| elapsed >= threshold) && focused != Some(true)
+}
+
+/// Show `body` as a desktop notification if the operation took at least `threshold`
+/// (`--notify-after`) and the user has probably switched away. Errors are ignored: there
+/// may be no notification service, e.g. over SSH.
+pub fn notify_if_long(elapsed: Duration, threshold: Option<Duration>, body: &str) {
+    if should_notify(elapsed, threshold, focused()) {
+        let _ = notify_rust::Notification::new().summary(&t!("app-name")).body(body).show();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_notify() {
+        let minute = Duration::from_secs(60);
+        assert!(should_notify(minute, Some(Duration::from_secs(30)), None));
+        assert!(should_notify(minute, Some(Duration::from_secs(30)), Some(false)));
+        assert!(!should_notify(minute, Some(Duration::from_secs(30)), Some(true)));
+        assert!(!should_notify(Duration::from_secs(10), Some(Duration::from_secs(30)), None));
+        assert!(!should_notify(minute, None, None));
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::BTreeSet;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A process holding files open (or mapped, or as its working directory) under a
+/// directory selected for deletion
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct OpenFileHolder {
+    /// Selected directory the open file lives under
+    pub path: PathBuf,
+    pub pid: u32,
+    pub process_name: String,
+}
+
+/// Find processes using files under any of `paths`.
+///
+/// Scans `/proc` on Linux and falls back to `lsof` elsewhere; returns an error if
+/// neither is available so callers can warn that the check was skipped.
+pub fn find_holders(paths: &[PathBuf]) -> io::Result<Vec<OpenFileHolder>> {
+    // Open-file targets are reported as canonical paths
+    let roots: Vec<(PathBuf, PathBuf)> = paths
+        .iter()
+        .map(|p| (p.clone(), p.canonicalize().unwrap_or_else(|_| p.clone())))
+        .collect();
+
+    let mut holders = BTreeSet::new();
+    for (pid, process_name, file) in open_files()? {
+        for (path, canonical) in &roots {
+            if file.starts_with(canonical) {
+                holders.insert(OpenFileHolder {
+                    path: path.clone(),
+                    pid,
+                    process_name: process_name.clone(),
+                });
+            }
+        }
+    }
+    Ok(holders.into_iter().collect())
+}
+
+/// Every (pid, process name, file) the current user can see
+#[cfg(target_os = "linux")]
+fn open_files() -> io::Result<Vec<(u32, String, PathBuf)>> {
+    use std::fs;
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir("/proc")?.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+            continue;
+        };
+        let proc_dir = entry.path();
+        let name = fs::read_to_string(proc_dir.join("comm"))
+            .map(|s| s.trim_end().to_string())
+            .unwrap_or_default();
+
+        // Processes of other users are unreadable without privileges; skip them
+        let mut targets: Vec<PathBuf> = fs::read_link(proc_dir.join("cwd")).into_iter().collect();
+        if let Ok(fds) = fs::read_dir(proc_dir.join("fd")) {
+            targets.extend(fds.flatten().filter_map(|fd| fs::read_link(fd.path()).ok()));
+        }
+        // Memory-mapped files cover loaded shared libraries, e.g. a venv's site-packages
+        if let Ok(maps) = fs::read_to_string(proc_dir.join("maps")) {
+            targets.extend(parse_maps(&maps));
+        }
+
+        files.extend(targets.into_iter().map(|target| (pid, name.clone(), target)));
+    }
+    Ok(files)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_files() -> io::Result<Vec<(u32, String, PathBuf)>> {
+    let output = std::process::Command::new("lsof").args(["-n", "-P", "-F", "pcn"]).output()?;
+    Ok(parse_lsof(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// File paths from `/proc/<pid>/maps` (the sixth column, when it is an absolute path)
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_maps(maps: &str) -> BTreeSet<PathBuf> {
+    maps.lines()
+        .filter_map(|line| line.split_whitespace().nth(5))
+        .filter(|path| path.starts_with('/'))
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Parse `lsof -F pcn` output: `p<pid>` and `c<command>` start a process, `n<name>` lines follow
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+fn parse_lsof(output: &str) -> Vec<(u32, String, PathBuf)> {
+    let mut files = Vec::new();
+    let mut pid = 0;
+    let mut name = String::new();
+    for line in output.lines() {
+        let mut chars = line.chars();
+        let field = chars.next();
+        let value = chars.as_str();
+        match field {
+            Some('p') => pid = value.parse().unwrap_or(0),
+            Some('c') => name = value.to_string(),
+            Some('n') if Path::new(value).is_absolute() => files.push((pid, name.clone(), PathBuf::from(value))),
+            _ => {}
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_maps() {
+        let maps = "\
+7f1c2a000000-7f1c2a021000 r--p 00000000 fd:01 1234 /home/me/.venv/lib/python3.12/site-packages/numpy/core/_multiarray_umath.so
+7f1c2b000000-7f1c2b001000 rw-p 00000000 00:00 0
+7ffd5a000000-7ffd5a021000 rw-p 00000000 00:00 0 [stack]
+";
+        let paths = parse_maps(maps);
+        assert_eq!(paths.len(), 1);
+        assert!(paths.iter().next().unwrap().starts_with("/home/me/.venv"));
+    }
+
+    #[test]
+    fn test_parse_lsof() {
+        let output = "p123\ncjupyter\nf4\nn/home/me/.venv/bin/python\nnlocalhost:8888\np456\ncvim\nn/tmp/notes.md\n";
+        let files = parse_lsof(output);
+        assert_eq!(
+            files,
+            vec![
+                (123, "jupyter".to_string(), PathBuf::from("/home/me/.venv/bin/python")),
+                (456, "vim".to_string(), PathBuf::from("/tmp/notes.md")),
+            ]
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_finds_own_open_file() {
+        use std::fs::{self, File};
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let busy = temp_dir.path().join("venv");
+        let idle = temp_dir.path().join("node_modules");
+        fs::create_dir_all(&busy).unwrap();
+        fs::create_dir_all(&idle).unwrap();
+        let _handle = File::create(busy.join("kernel.log")).unwrap();
+
+        let holders = find_holders(&[busy.clone(), idle]).unwrap();
+        assert!(holders
+            .iter()
+            .any(|h| h.path == busy && h.pid == std::process::id()));
+        assert!(holders.iter().all(|h| h.path == busy));
+    }
+}
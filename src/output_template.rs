@@ -0,0 +1,104 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    #[error("Unknown placeholder {{{placeholder}}} in {path}; use {{date}}, {{time}}, {{hostname}} or {{root}}")]
+    Unknown { placeholder: String, path: PathBuf },
+}
+
+/// What the placeholders in output paths stand for during one run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateValues {
+    /// "2026-10-17" (UTC)
+    pub date: String,
+    /// "143005" (UTC)
+    pub time: String,
+    pub hostname: String,
+    /// Last component of the scan root, "root" for `/`
+    pub root: String,
+}
+
+impl TemplateValues {
+    pub fn new(root: &Path, hostname: Option<&str>, now: SystemTime) -> Self {
+        // "2026-10-17T14:30:05Z"
+        let stamp = humantime::format_rfc3339_seconds(now).to_string();
+        let root = std::path::absolute(root).unwrap_or_else(|_| root.to_path_buf());
+        Self {
+            date: stamp[..10].to_string(),
+            time: stamp[11..19].replace(':', ""),
+            hostname: file_name_safe(hostname.unwrap_or("unknown")),
+            root: root.file_name().map(|name| file_name_safe(&name.to_string_lossy())).unwrap_or_else(|| "root".to_string()),
+        }
+    }
+}
+
+/// Separators would turn a value into extra directories
+fn file_name_safe(value: &str) -> String {
+    value.replace(['/', '\\', ':'], "-")
+}
+
+/// `path` with `{date}`, `{time}`, `{hostname}` and `{root}` filled in, so runs on a schedule
+/// each write their own file. Other text in braces is kept as it is; unknown lowercase
+/// names are refused, since they are most likely typos.
+pub fn expand(path: &Path, values: &TemplateValues) -> Result<PathBuf, TemplateError> {
+    let Some(text) = path.to_str().filter(|text| text.contains('{')) else {
+        return Ok(path.to_path_buf());
+    };
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let name = after.find('}').map(|end| &after[..end]);
+        let value = match name {
+            Some("date") => Some(values.date.as_str()),
+            Some("time") => Some(values.time.as_str()),
+            Some("hostname") => Some(values.hostname.as_str()),
+            Some("root") => Some(values.root.as_str()),
+            Some(name) if !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c == '_') => {
+                return Err(TemplateError::Unknown { placeholder: name.to_string(), path: path.to_path_buf() });
+            }
+            _ => None,
+        };
+        match (name, value) {
+            (Some(name), Some(value)) => {
+                expanded.push_str(value);
+                rest = &after[name.len() + 1..];
+            }
+            _ => {
+                expanded.push('{');
+                rest = after;
+            }
+        }
+    }
+    expanded.push_str(rest);
+    Ok(PathBuf::from(expanded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_expand() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_792_247_405); // 2026-10-17T14:30:05Z
+        let values = TemplateValues::new(Path::new("/home/me/projects"), Some("build-01"), now);
+        assert_eq!(values.date, "2026-10-17");
+        assert_eq!(values.time, "143005");
+        let expand = |path: &str| expand(Path::new(path), &values).map(|p| p.display().to_string());
+
+        assert_eq!(expand("scan-{hostname}-{date}.csv").unwrap(), "scan-build-01-2026-10-17.csv");
+        assert_eq!(expand("scans/{root}/{date}T{time}.json.gz").unwrap(), "scans/projects/2026-10-17T143005.json.gz");
+        assert_eq!(expand("plain.csv").unwrap(), "plain.csv");
+        // Braces that aren't placeholders stay
+        assert_eq!(expand("{}-{A}-{date").unwrap(), "{}-{A}-{date");
+        assert!(matches!(expand("scan-{host}.csv"), Err(TemplateError::Unknown { placeholder, .. }) if placeholder == "host"));
+
+        let values = TemplateValues::new(Path::new("/"), Some("a/b"), now);
+        assert_eq!((values.root.as_str(), values.hostname.as_str()), ("root", "a-b"));
+        assert_eq!(TemplateValues::new(Path::new("/"), None, now).hostname, "unknown");
+    }
+}
@@ -0,0 +1,115 @@
+use crate::config::ConfigError;
+use crate::scanner::EntryType;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Per-path classification overrides made in interactive mode.
+///
+/// Stored separately from `config.toml` because the tool rewrites this file:
+///
+/// ```toml
+/// [paths]
+/// "/home/me/photos/build" = "normal"
+/// "/home/me/scratch" = "temp"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Overrides {
+    /// Absolute directory path -> category
+    pub paths: BTreeMap<PathBuf, EntryType>,
+}
+
+impl Overrides {
+    /// Default location: `<config dir>/disk-cleanup-tool/overrides.toml`
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("disk-cleanup-tool").join("overrides.toml"))
+    }
+
+    pub fn load(path: &Path) -> Result<Overrides, ConfigError> {
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::IoError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        toml::from_str(&content).map_err(|e| ConfigError::ParseError {
+            path: path.to_path_buf(),
+            message: e.message().to_string(),
+        })
+    }
+
+    /// Load the overrides file if it exists
+    pub fn load_or_default(path: Option<&Path>) -> Result<Overrides, ConfigError> {
+        match path {
+            Some(path) if path.exists() => Overrides::load(path),
+            _ => Ok(Overrides::default()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ConfigError> {
+        let io_error = |source| ConfigError::IoError {
+            path: path.to_path_buf(),
+            source,
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let content = toml::to_string(self).map_err(|e| ConfigError::ParseError {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        fs::write(path, content).map_err(io_error)
+    }
+
+    pub fn get(&self, path: &Path) -> Option<EntryType> {
+        self.paths.get(path).copied()
+    }
+
+    pub fn set(&mut self, path: PathBuf, entry_type: EntryType) {
+        self.paths.insert(path, entry_type);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("nested/overrides.toml");
+
+        let mut overrides = Overrides::default();
+        overrides.set(PathBuf::from("/home/me/photos/build"), EntryType::Normal);
+        overrides.set(PathBuf::from("/home/me/scratch"), EntryType::Temp);
+        overrides.set(PathBuf::from("/home/me/scratch"), EntryType::BuildOutput);
+        overrides.save(&path).unwrap();
+
+        let loaded = Overrides::load(&path).unwrap();
+        assert_eq!(loaded, overrides);
+        assert_eq!(loaded.get(Path::new("/home/me/photos/build")), Some(EntryType::Normal));
+        assert_eq!(loaded.get(Path::new("/home/me/scratch")), Some(EntryType::BuildOutput));
+        assert_eq!(loaded.get(Path::new("/home/me")), None);
+    }
+
+    #[test]
+    fn test_missing_file_is_empty() {
+        let temp_dir = TempDir::new().unwrap();
+        let overrides = Overrides::load_or_default(Some(&temp_dir.path().join("overrides.toml"))).unwrap();
+        assert!(overrides.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_category() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("overrides.toml");
+        fs::write(&path, "[paths]\n\"/a\" = \"junk\"\n").unwrap();
+
+        assert!(matches!(Overrides::load(&path), Err(ConfigError::ParseError { .. })));
+    }
+}
@@ -0,0 +1,279 @@
+use crate::i18n::t;
+use crate::scanner::DirectoryEntry;
+use crate::utils::format_size;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs::Metadata;
+use std::path::Path;
+
+/// Files of one user/group pair counted directly in a directory (a temp directory counts
+/// everything inside it)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct OwnerUsage {
+    pub uid: u32,
+    pub gid: u32,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+/// Add one file of `bytes` (its size as counted by the scan) to a directory's per-owner usage
+pub fn record(owners: &mut Vec<OwnerUsage>, metadata: &Metadata, bytes: u64) {
+    if let Some((uid, gid)) = file_owner(metadata) {
+        record_owner(owners, uid, gid, bytes);
+    }
+}
+
+/// [`record`] for a file whose owner is already known
+pub fn record_owner(owners: &mut Vec<OwnerUsage>, uid: u32, gid: u32, bytes: u64) {
+    match owners.iter_mut().find(|o| o.uid == uid && o.gid == gid) {
+        Some(usage) => {
+            usage.files += 1;
+            usage.bytes += bytes;
+        }
+        None => owners.push(OwnerUsage {
+            uid,
+            gid,
+            files: 1,
+            bytes,
+        }),
+    }
+}
+
+#[cfg(unix)]
+fn file_owner(metadata: &Metadata) -> Option<(u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.uid(), metadata.gid()))
+}
+
+#[cfg(not(unix))]
+fn file_owner(_metadata: &Metadata) -> Option<(u32, u32)> {
+    None
+}
+
+/// Bytes and files owned by one user or group across a scan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnerTotal {
+    /// Account name, or the numeric id if it has no entry in the local database
+    pub name: String,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+/// Totals per user and per group, largest first
+pub fn owner_totals(entries: &[DirectoryEntry]) -> (Vec<OwnerTotal>, Vec<OwnerTotal>) {
+    let mut users: HashMap<u32, (u64, u64)> = HashMap::new();
+    let mut groups: HashMap<u32, (u64, u64)> = HashMap::new();
+    for usage in entries.iter().flat_map(|e| &e.owners) {
+        for (totals, id) in [(&mut users, usage.uid), (&mut groups, usage.gid)] {
+            let total = totals.entry(id).or_default();
+            total.0 += usage.files;
+            total.1 += usage.bytes;
+        }
+    }
+
+    let named = |totals: HashMap<u32, (u64, u64)>, names: HashMap<u32, String>| {
+        let mut totals: Vec<OwnerTotal> = totals
+            .into_iter()
+            .map(|(id, (files, bytes))| OwnerTotal {
+                name: names.get(&id).cloned().unwrap_or_else(|| id.to_string()),
+                files,
+                bytes,
+            })
+            .collect();
+        totals.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+        totals
+    };
+    let names = IdNames::load();
+    (named(users, names.users), named(groups, names.groups))
+}
+
+/// Account names by numeric id from the local user and group databases
+#[derive(Debug, Default)]
+pub struct IdNames {
+    users: HashMap<u32, String>,
+    groups: HashMap<u32, String>,
+}
+
+impl IdNames {
+    pub fn load() -> Self {
+        Self {
+            users: id_names(Path::new("/etc/passwd")),
+            groups: id_names(Path::new("/etc/group")),
+        }
+    }
+
+    /// "alice:staff", with numeric ids where there is no name
+    pub fn describe(&self, uid: u32, gid: u32) -> String {
+        let name = |names: &HashMap<u32, String>, id: u32| names.get(&id).cloned().unwrap_or_else(|| id.to_string());
+        format!("{}:{}", name(&self.users, uid), name(&self.groups, gid))
+    }
+}
+
+/// Who owns a directory and whether the current user may remove it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Access {
+    pub uid: u32,
+    pub gid: u32,
+    /// Removing an entry takes write permission on its parent and, if the parent is sticky
+    /// (like /tmp), owning the entry or the parent. Contents owned by others can still fail.
+    pub deletable: bool,
+}
+
+/// Owner of `path` and whether the current user can delete it; `None` if it or its parent
+/// can't be examined, or off Unix
+#[cfg(unix)]
+pub fn access(path: &Path) -> Option<Access> {
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::MetadataExt;
+    let metadata = std::fs::symlink_metadata(path).ok()?;
+    let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let parent_metadata = std::fs::metadata(parent).ok()?;
+    let parent_name = std::ffi::CString::new(parent.as_os_str().as_bytes()).ok()?;
+    // SAFETY: `parent_name` is a valid NUL-terminated string
+    let writable = unsafe { libc::faccessat(libc::AT_FDCWD, parent_name.as_ptr(), libc::W_OK | libc::X_OK, libc::AT_EACCESS) } == 0;
+    // SAFETY: geteuid has no preconditions and can't fail
+    let euid = unsafe { libc::geteuid() };
+    // S_ISVTX, whose type differs between platforms
+    let sticky = parent_metadata.mode() & 0o1000 != 0;
+    let owns = euid == 0 || euid == metadata.uid() || euid == parent_metadata.uid();
+    Some(Access {
+        uid: metadata.uid(),
+        gid: metadata.gid(),
+        deletable: writable && (!sticky || owns),
+    })
+}
+
+#[cfg(not(unix))]
+pub fn access(_path: &Path) -> Option<Access> {
+    None
+}
+
+/// Whether deleting `path` is known to fail for lack of permissions
+pub fn needs_elevation(path: &Path) -> bool {
+    access(path).is_some_and(|access| !access.deletable)
+}
+
+/// Names by numeric id from a passwd or group file (`name:x:id:...`)
+fn id_names(path: &Path) -> HashMap<u32, String> {
+    std::fs::read_to_string(path)
+        .map(|content| parse_id_names(&content))
+        .unwrap_or_default()
+}
+
+fn parse_id_names(content: &str) -> HashMap<u32, String> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let id = fields.nth(1)?.parse().ok()?;
+            Some((id, name.to_string()))
+        })
+        .collect()
+}
+
+/// "By user" / "By group" sections for the report
+pub fn format_owners(users: &[OwnerTotal], groups: &[OwnerTotal]) -> String {
+    let total: u64 = users.iter().map(|u| u.bytes).sum();
+    let mut out = String::new();
+    if users.is_empty() {
+        let _ = writeln!(out, "\n{}", t!("report-no-owners"));
+        return out;
+    }
+    for (title, totals) in [(t!("report-by-user"), users), (t!("report-by-group"), groups)] {
+        let _ = writeln!(out, "\n{}:", title);
+        for owner in totals {
+            let share = if total > 0 { owner.bytes as f64 * 100.0 / total as f64 } else { 0.0 };
+            let _ = writeln!(
+                out,
+                "  {:<16} {:>10}  {:>5.1}%  {}",
+                owner.name,
+                format_size(owner.bytes),
+                share,
+                t!("count-files", count = owner.files)
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_id_names() {
+        let passwd = "# comment\nroot:x:0:0:root:/root:/bin/bash\nalice:x:1000:1000::/home/alice:/bin/sh\nbroken\n";
+        let names = parse_id_names(passwd);
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[&0], "root");
+        assert_eq!(names[&1000], "alice");
+    }
+
+    #[test]
+    fn test_owner_totals() {
+        let usage = |uid, gid, bytes| OwnerUsage { uid, gid, files: 1, bytes };
+        let entries = vec![
+            DirectoryEntry {
+                path: PathBuf::from("/scratch/a"),
+                owners: vec![usage(4_000_001, 4_000_100, 300), usage(4_000_002, 4_000_100, 100)],
+                ..Default::default()
+            },
+            DirectoryEntry {
+                path: PathBuf::from("/scratch/b"),
+                owners: vec![usage(4_000_002, 4_000_200, 500)],
+                ..Default::default()
+            },
+        ];
+
+        let (users, groups) = owner_totals(&entries);
+        // Ids without an account are shown numerically
+        assert_eq!(users[0], OwnerTotal { name: "4000002".to_string(), files: 2, bytes: 600 });
+        assert_eq!(users[1], OwnerTotal { name: "4000001".to_string(), files: 1, bytes: 300 });
+        assert_eq!(groups[0].bytes, 500);
+        assert_eq!(groups[1].bytes, 400);
+
+        let text = format_owners(&users, &groups);
+        assert!(text.contains("By user:\n  4000002"));
+        assert!(text.contains(" 66.7%  2 files"));
+        assert!(text.contains("By group:"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_record() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), "abc").unwrap();
+        let metadata = temp_file.path().metadata().unwrap();
+
+        let mut owners = Vec::new();
+        record(&mut owners, &metadata, 3);
+        record(&mut owners, &metadata, 3);
+        assert_eq!(owners.len(), 1);
+        assert_eq!((owners[0].files, owners[0].bytes), (2, 6));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_access() {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let parent = temp_dir.path().join("parent");
+        std::fs::create_dir_all(parent.join("cache")).unwrap();
+
+        let access = access(&parent.join("cache")).unwrap();
+        assert_eq!(access.uid, parent.metadata().unwrap().uid());
+        assert!(access.deletable);
+        assert!(super::access(&parent.join("missing")).is_none());
+        assert_eq!(IdNames::default().describe(4_000_001, 4_000_100), "4000001:4000100");
+
+        // Root may remove anything, so only an ordinary user sees the parent's permissions
+        std::fs::set_permissions(&parent, std::fs::Permissions::from_mode(0o555)).unwrap();
+        // SAFETY: geteuid has no preconditions
+        let root = unsafe { libc::geteuid() } == 0;
+        assert_eq!(needs_elevation(&parent.join("cache")), !root);
+        std::fs::set_permissions(&parent, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+}
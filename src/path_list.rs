@@ -0,0 +1,159 @@
+use crate::i18n::t;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PathListError {
+    #[error("Cannot read path list {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// System directories nothing inside of may be deleted from a path list
+const PROTECTED_TREES: &[&str] = &[
+    "/bin", "/boot", "/dev", "/etc", "/lib", "/lib32", "/lib64", "/proc", "/sbin", "/sys", "/usr", "/System",
+    r"C:\Windows", r"C:\Program Files", r"C:\Program Files (x86)",
+];
+
+/// Directories that hold other people's or the system's data, which may not be deleted
+/// themselves, though what is inside them may
+const PROTECTED_DIRS: &[&str] = &[
+    "/Applications", "/Library", "/Users", "/Volumes", "/home", "/media", "/mnt", "/opt", "/private", "/root",
+    "/srv", "/tmp", "/var", r"C:\Users",
+];
+
+/// A listed path that won't be deleted, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejected {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// Paths from a file with one per line, as written by the command palette's export or
+/// another tool. Blank lines and lines starting with '#' are skipped, and so are repeats.
+pub fn read(file: &Path) -> Result<Vec<PathBuf>, PathListError> {
+    let text = fs::read_to_string(file).map_err(|source| PathListError::Io { path: file.to_path_buf(), source })?;
+    Ok(parse(&text))
+}
+
+fn parse(text: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for line in text.lines().map(|line| line.trim_end_matches('\r')) {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let path = PathBuf::from(line);
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// Split `paths` into the directories that may be deleted and those that may not: relative
+/// paths, anything that isn't a directory, symlinks, filesystem roots, system directories,
+/// `home` and the directories above it. Paths inside another listed path go with it.
+pub fn validate(paths: &[PathBuf], home: Option<&Path>) -> (Vec<PathBuf>, Vec<Rejected>) {
+    let mut accepted = Vec::new();
+    let mut rejected = Vec::new();
+    for path in paths {
+        match protected_reason(path, home) {
+            Some(reason) => rejected.push(Rejected { path: path.clone(), reason }),
+            None => accepted.push(path.clone()),
+        }
+    }
+    let nested: Vec<PathBuf> = accepted
+        .iter()
+        .filter(|path| accepted.iter().any(|other| other != *path && path.starts_with(other)))
+        .cloned()
+        .collect();
+    accepted.retain(|path| !nested.contains(path));
+    (accepted, rejected)
+}
+
+/// Why `path` may not be deleted from a path list, if it may not
+fn protected_reason(path: &Path, home: Option<&Path>) -> Option<String> {
+    if !path.is_absolute() {
+        return Some(t!("path-list-relative"));
+    }
+    match fs::symlink_metadata(path) {
+        Err(_) => return Some(t!("path-list-missing")),
+        Ok(metadata) if metadata.file_type().is_symlink() => return Some(t!("path-list-symlink")),
+        Ok(metadata) if !metadata.is_dir() => return Some(t!("path-list-not-directory")),
+        Ok(_) => {}
+    }
+    // `..` could otherwise climb out of an allowed directory
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if path.parent().is_none() {
+        return Some(t!("path-list-root"));
+    }
+    if PROTECTED_TREES.iter().any(|tree| path.starts_with(tree)) || PROTECTED_DIRS.iter().any(|dir| path == Path::new(dir)) {
+        return Some(t!("path-list-system"));
+    }
+    if home.is_some_and(|home| home.starts_with(&path)) {
+        return Some(t!("path-list-home"));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse() {
+        let text = "/a/node_modules\r\n\n# from last week\n/b/target\n/a/node_modules\n  \n";
+        assert_eq!(parse(text), [PathBuf::from("/a/node_modules"), PathBuf::from("/b/target")]);
+    }
+
+    #[test]
+    fn test_validate() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = fs::canonicalize(temp_dir.path()).unwrap();
+        let home = root.join("home");
+        let cache = home.join("project/node_modules");
+        fs::create_dir_all(cache.join("left-pad")).unwrap();
+        fs::write(root.join("file.txt"), "").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&cache, root.join("link")).unwrap();
+
+        let paths = vec![
+            cache.clone(),
+            cache.join("left-pad"),
+            home.join("project/node_modules/../.."),
+            home.clone(),
+            root.clone(),
+            root.join("file.txt"),
+            root.join("missing"),
+            PathBuf::from("relative/target"),
+            PathBuf::from("/"),
+            PathBuf::from("/usr/lib"),
+        ];
+        let (accepted, rejected) = validate(&paths, Some(&home));
+        assert_eq!(accepted, [cache]);
+        let reasons: Vec<&str> = rejected.iter().map(|r| r.reason.as_str()).collect();
+        assert_eq!(
+            reasons,
+            [
+                "it is your home directory or contains it",
+                "it is your home directory or contains it",
+                "it is your home directory or contains it",
+                "not a directory",
+                "does not exist",
+                "not an absolute path",
+                "a filesystem root",
+                "a system directory",
+            ]
+        );
+        #[cfg(unix)]
+        {
+            let (accepted, rejected) = validate(&[root.join("link")], Some(&home));
+            assert!(accepted.is_empty());
+            assert_eq!(rejected[0].reason, "a symbolic link");
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use crate::deletion::DeletionReport;
+use crate::i18n::t;
+use crate::utils::format_size;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+/// Between a directory's name and the date from which `purge-pending` removes it
+const MARKER: &str = ".pending-delete-";
+
+const DAY: Duration = Duration::from_secs(86_400);
+
+/// A directory renamed by `--grace-period`, waiting for `purge-pending`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingItem {
+    pub path: PathBuf,
+    /// Date in its name; it is removed once that day (UTC) is over
+    pub due: SystemTime,
+    pub size: u64,
+}
+
+/// "2026-10-24", the UTC day `time` falls on
+pub fn format_date(time: SystemTime) -> String {
+    humantime::format_rfc3339_seconds(time).to_string()[..10].to_string()
+}
+
+/// `node_modules.pending-delete-2026-10-24` for `node_modules` due on that day
+pub fn pending_path(path: &Path, due: SystemTime) -> Option<PathBuf> {
+    let mut name = path.file_name()?.to_os_string();
+    name.push(MARKER);
+    name.push(format_date(due));
+    Some(path.with_file_name(name))
+}
+
+/// The day in a pending directory's name, if `path` is one
+pub fn due_date(path: &Path) -> Option<SystemTime> {
+    let name = path.file_name()?.to_str()?;
+    let (original, date) = name.rsplit_once(MARKER)?;
+    if original.is_empty() || date.len() != 10 {
+        return None;
+    }
+    humantime::parse_rfc3339(&format!("{}T00:00:00Z", date)).ok()
+}
+
+/// Rename each of `paths` in place to its pending name with the date `due`, instead of
+/// deleting it: teammates on a shared volume see what is going and until when, and renaming
+/// it back undoes it. Nothing is freed until `purge-pending` runs.
+pub fn mark(paths: &[PathBuf], due: SystemTime, cancel: &AtomicBool) -> DeletionReport {
+    let mut report = DeletionReport {
+        successful: Vec::new(),
+        failed: Vec::new(),
+        cancelled: Vec::new(),
+        total_freed_bytes: 0,
+        deleted_files: 0,
+        retryable: Vec::new(),
+    };
+    for (idx, path) in paths.iter().enumerate() {
+        if cancel.load(Ordering::SeqCst) {
+            report.cancelled = paths[idx..].to_vec();
+            println!("{}", t!("deletion-left-untouched", count = report.cancelled.len()));
+            break;
+        }
+        match rename_pending(path, due) {
+            Ok(renamed) => {
+                println!("{}", t!("pending-marked", path = path.display().to_string(), pending = renamed.display().to_string()));
+                report.successful.push(path.clone());
+            }
+            Err(e) => {
+                eprintln!("{}", t!("pending-mark-failed", path = path.display().to_string(), reason = e.to_string()));
+                report.failed.push((path.clone(), e.to_string()));
+            }
+        }
+    }
+    report
+}
+
+fn rename_pending(path: &Path, due: SystemTime) -> io::Result<PathBuf> {
+    let renamed = pending_path(path, due).ok_or_else(|| io::Error::other("no file name"))?;
+    // A rename would silently replace an empty directory of that name
+    if fs::symlink_metadata(&renamed).is_ok() {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} already exists", renamed.display())));
+    }
+    fs::rename(path, &renamed)?;
+    Ok(renamed)
+}
+
+/// Pending directories under `roots`, oldest due date first; nothing inside them is visited
+pub fn find_pending(roots: &[PathBuf]) -> Vec<PendingItem> {
+    let mut found = Vec::new();
+    for root in roots {
+        let mut walker = WalkDir::new(root).sort_by_file_name().into_iter();
+        while let Some(entry) = walker.next() {
+            let Ok(entry) = entry else { continue };
+            let Some(due) = due_date(entry.path()) else { continue };
+            if entry.file_type().is_dir() {
+                walker.skip_current_dir();
+            }
+            let size = WalkDir::new(entry.path())
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+                .filter_map(|e| e.metadata().ok())
+                .map(|m| m.len())
+                .sum();
+            found.push(PendingItem { path: entry.into_path(), due, size });
+        }
+    }
+    found.sort_by(|a, b| (a.due, &a.path).cmp(&(b.due, &b.path)));
+    found
+}
+
+impl PendingItem {
+    /// Whether its grace period is over at `now`
+    pub fn is_due(&self, now: SystemTime) -> bool {
+        self.due + DAY <= now
+    }
+
+    /// "… (1.20 GB, due 2026-10-24)" line for the purge listing
+    pub fn describe(&self) -> String {
+        t!(
+            "pending-item",
+            path = self.path.display().to_string(),
+            size = format_size(self.size),
+            date = format_date(self.due)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_pending_names() {
+        let due = humantime::parse_rfc3339("2026-10-24T15:30:00Z").unwrap();
+        let pending = pending_path(Path::new("/srv/app/node_modules"), due).unwrap();
+        assert_eq!(pending, Path::new("/srv/app/node_modules.pending-delete-2026-10-24"));
+        assert_eq!(due_date(&pending), Some(humantime::parse_rfc3339("2026-10-24T00:00:00Z").unwrap()));
+
+        assert_eq!(due_date(Path::new("/srv/app/node_modules")), None);
+        assert_eq!(due_date(Path::new("/srv/app/.pending-delete-2026-10-24")), None);
+        assert_eq!(due_date(Path::new("/srv/app/x.pending-delete-soon")), None);
+    }
+
+    #[test]
+    fn test_mark_and_find() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let cache = root.join("app/cache");
+        fs::create_dir_all(cache.join("nested")).unwrap();
+        fs::write(cache.join("nested/blob"), "x".repeat(300)).unwrap();
+        let missing = root.join("gone");
+        let now = humantime::parse_rfc3339("2026-10-17T12:00:00Z").unwrap();
+        let due = now + 7 * DAY;
+
+        let report = mark(&[cache.clone(), missing], due, &AtomicBool::new(false));
+        assert_eq!(report.successful, std::slice::from_ref(&cache));
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.total_freed_bytes, 0);
+        let renamed = root.join("app/cache.pending-delete-2026-10-24");
+        assert!(!cache.exists() && renamed.join("nested/blob").exists());
+
+        // A directory of the same name marked again that day isn't merged into the first
+        fs::create_dir_all(&cache).unwrap();
+        assert_eq!(mark(std::slice::from_ref(&cache), due, &AtomicBool::new(false)).failed.len(), 1);
+
+        let found = find_pending(std::slice::from_ref(&root));
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].path.as_path(), found[0].size), (renamed.as_path(), 300));
+        // Due on the 24th: kept through that day, removable from the next
+        assert!(!found[0].is_due(now + 7 * DAY));
+        assert!(found[0].is_due(now + 8 * DAY));
+    }
+}
@@ -0,0 +1,233 @@
+use crate::classifier::{ClassifierError, MatchOptions};
+use crate::config::ConfigError;
+use crate::profiles::Profile;
+use crate::scanner::{DirectoryEntry, EntryType};
+use crate::utils::expand_home;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PolicyError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+
+    #[error("{0} has no policies; add a [[policies]] table")]
+    Empty(PathBuf),
+
+    #[error("Policy '{0}' has no paths; add `paths = [...]` to it")]
+    NoPaths(String),
+}
+
+/// What a policy does with the directories it matches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyAction {
+    /// List them with their sizes
+    #[default]
+    Report,
+    /// Delete them
+    Delete,
+    /// Move them to the trash, as with `--trash`
+    Trash,
+}
+
+/// A retention rule for `apply-policy`: which directories may not outlive a certain age
+/// or size on the given paths, and what happens to them when they do.
+///
+/// ```toml
+/// [[policies]]
+/// name = "stale-build-outputs"
+/// paths = ["/builds"]
+/// categories = ["build_output"]
+/// older_than = "14days"
+/// min_size = "500 MB"
+/// action = "delete"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    pub name: String,
+
+    /// Directories to scan; `~/` expands to the home directory
+    pub paths: Vec<PathBuf>,
+
+    /// Directory names or globs the policy applies to, e.g. "node_modules" or "*"
+    #[serde(default)]
+    pub names: Vec<String>,
+
+    /// Categories the policy applies to. With neither `names` nor `categories`, it applies
+    /// to every temp directory.
+    #[serde(default)]
+    pub categories: Vec<EntryType>,
+
+    /// Only directories that haven't been modified within this window, e.g. "30days"
+    #[serde(default, deserialize_with = "crate::config::deserialize_duration")]
+    pub older_than: Option<Duration>,
+
+    /// Only directories at least this large, e.g. "1 GB"
+    #[serde(default, deserialize_with = "crate::config::deserialize_size")]
+    pub min_size: u64,
+
+    #[serde(default)]
+    pub action: PolicyAction,
+}
+
+/// A directory a policy applies to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyMatch {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+impl Policy {
+    /// Scan roots, with `~/` expanded
+    pub fn roots(&self) -> Vec<PathBuf> {
+        self.paths.iter().map(|path| expand_home(path)).collect()
+    }
+
+    /// The topmost directories of a scan of the policy's paths that it applies to, sorted
+    /// by path. The roots themselves never match.
+    pub fn evaluate(
+        &self,
+        entries: &[DirectoryEntry],
+        options: MatchOptions,
+        now: SystemTime,
+    ) -> Result<Vec<PolicyMatch>, ClassifierError> {
+        // Same matching as a cleanup profile; a directory inside a small one is smaller still
+        let profile = Profile {
+            paths: self.paths.clone(),
+            names: self.names.clone(),
+            categories: self.categories.clone(),
+            older_than: self.older_than,
+            ..Default::default()
+        };
+        let matched = profile.matches(entries, &self.roots(), options, now)?;
+        Ok(matched
+            .into_iter()
+            .filter_map(|path| {
+                let size = entries.iter().find(|e| e.path == path)?.cumulative_size_bytes;
+                (size >= self.min_size).then_some(PolicyMatch { path, size })
+            })
+            .collect())
+    }
+}
+
+/// A policy file: one or more `[[policies]]`, applied in order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicySet {
+    #[serde(default)]
+    pub policies: Vec<Policy>,
+}
+
+impl PolicySet {
+    pub fn load(path: &Path) -> Result<PolicySet, PolicyError> {
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::IoError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let set: PolicySet = toml::from_str(&content).map_err(|e| ConfigError::ParseError {
+            path: path.to_path_buf(),
+            message: e.message().to_string(),
+        })?;
+        if set.policies.is_empty() {
+            return Err(PolicyError::Empty(path.to_path_buf()));
+        }
+        // Never fall back to the current directory, which could be anywhere on a server
+        if let Some(policy) = set.policies.iter().find(|p| p.paths.is_empty()) {
+            return Err(PolicyError::NoPaths(policy.name.clone()));
+        }
+        Ok(set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    fn entry(path: &str, entry_type: EntryType, size: u64, age_days: u32, now: SystemTime) -> DirectoryEntry {
+        DirectoryEntry {
+            path: PathBuf::from(path),
+            entry_type,
+            cumulative_size_bytes: size,
+            modified: Some(now - DAY * age_days),
+            ..Default::default()
+        }
+    }
+
+    fn load(content: &str) -> Result<PolicySet, PolicyError> {
+        let file = NamedTempFile::new().unwrap();
+        fs::write(file.path(), content).unwrap();
+        PolicySet::load(file.path())
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let set = load(
+            r#"
+[[policies]]
+name = "stale-builds"
+paths = ["/builds"]
+categories = ["build_output"]
+older_than = "14days"
+min_size = "1 GB"
+action = "delete"
+
+[[policies]]
+name = "caches"
+paths = ["/builds"]
+"#,
+        )
+        .unwrap();
+        let now = SystemTime::now();
+        let entries = vec![
+            entry("/builds", EntryType::Normal, 20 * GB, 1, now),
+            entry("/builds/a/target", EntryType::BuildOutput, 5 * GB, 30, now),
+            entry("/builds/a/target/debug", EntryType::BuildOutput, 4 * GB, 30, now),
+            entry("/builds/b/target", EntryType::BuildOutput, 5 * GB, 2, now),
+            entry("/builds/c/target", EntryType::BuildOutput, GB / 2, 30, now),
+            entry("/builds/c/node_modules", EntryType::DependencyCache, GB / 2, 30, now),
+        ];
+
+        let stale = &set.policies[0];
+        assert_eq!(stale.action, PolicyAction::Delete);
+        let matched = stale.evaluate(&entries, MatchOptions::default(), now).unwrap();
+        assert_eq!(
+            matched,
+            vec![PolicyMatch {
+                path: PathBuf::from("/builds/a/target"),
+                size: 5 * GB
+            }]
+        );
+
+        // No conditions: every temp directory, reported only
+        let caches = &set.policies[1];
+        assert_eq!(caches.action, PolicyAction::Report);
+        let matched = caches.evaluate(&entries, MatchOptions::default(), now).unwrap();
+        assert_eq!(matched.len(), 4);
+    }
+
+    #[test]
+    fn test_invalid_policies() {
+        assert!(matches!(load(""), Err(PolicyError::Empty(_))));
+        assert!(matches!(
+            load("[[policies]]\nname = \"anywhere\"\npaths = []\n"),
+            Err(PolicyError::NoPaths(name)) if name == "anywhere"
+        ));
+        assert!(matches!(
+            load("[[policies]]\nname = \"x\"\npaths = [\"/tmp\"]\naction = \"shred\"\n"),
+            Err(PolicyError::Config(ConfigError::ParseError { .. }))
+        ));
+        assert!(matches!(
+            PolicySet::load(Path::new("/nonexistent/policies.toml")),
+            Err(PolicyError::Config(ConfigError::IoError { .. }))
+        ));
+    }
+}
@@ -0,0 +1,264 @@
+use crate::classifier::{build_glob_set, ClassifierError, MatchOptions};
+use crate::cli::CliArgs;
+use crate::scanner::{self, DirectoryEntry, EntryType};
+use crate::utils::expand_home;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    #[error("No profiles configured; add a [profiles.NAME] table to the config")]
+    NoneConfigured,
+
+    #[error("Unknown profile '{0}' (available: {1})")]
+    Unknown(String, String),
+
+    #[error("Profile has no paths; add `paths = [...]` to it or pass --path")]
+    NoPaths,
+}
+
+/// What a profile does with the directories it selects
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileAction {
+    /// Open interactive mode with them selected, or delete them with `--yes`
+    #[default]
+    Review,
+    /// Delete them without asking, as with `--yes`, unless `--interactive` is given
+    Delete,
+    /// Only list what would be deleted, as with `--dry-run`
+    DryRun,
+}
+
+/// A named cleanup recipe from the `[profiles]` table of the config: where to look, which
+/// directories to clean there, and what to do with them.
+///
+/// ```toml
+/// [profiles.node-dev]
+/// paths = ["~/code"]
+/// names = ["node_modules", ".next"]
+/// older_than = "60days"
+///
+/// [profiles.ci-agent]
+/// paths = ["/builds"]
+/// names = ["*"]
+/// action = "delete"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    /// Directories to scan when no `--path` is given; `~/` expands to the home directory
+    pub paths: Vec<PathBuf>,
+
+    /// Directory names or globs to clean, e.g. "node_modules" or "*"
+    pub names: Vec<String>,
+
+    /// Categories to clean, e.g. "build_output". With neither `names` nor `categories`,
+    /// every temp directory is cleaned.
+    pub categories: Vec<EntryType>,
+
+    /// Only directories that themselves haven't been modified within this window, e.g. "60days"
+    #[serde(deserialize_with = "crate::config::deserialize_duration")]
+    pub older_than: Option<Duration>,
+
+    pub action: ProfileAction,
+}
+
+impl Profile {
+    /// Scan roots, with `~/` expanded
+    pub fn roots(&self) -> Vec<PathBuf> {
+        self.paths.iter().map(|path| expand_home(path)).collect()
+    }
+
+    /// Scan the profile's paths unless `--path` was given, and set up its action: review
+    /// opens interactive mode, delete runs headless, dry run only lists. A profile never
+    /// falls back to the current directory, so one without paths needs `--path`.
+    pub fn apply(&self, args: &mut CliArgs) -> Result<(), ProfileError> {
+        if args.path.is_empty() {
+            if self.paths.is_empty() {
+                return Err(ProfileError::NoPaths);
+            }
+            args.path = self.roots();
+        }
+        match self.action {
+            ProfileAction::Review => args.interactive |= !args.yes,
+            ProfileAction::Delete => args.yes |= !args.interactive,
+            ProfileAction::DryRun => {
+                args.dry_run = true;
+                args.yes |= !args.interactive;
+            }
+        }
+        Ok(())
+    }
+
+    /// Mark the directories this profile cleans as selected, replacing any saved selection.
+    /// Scan roots and aliases are never selected, and directories inside a selected one go
+    /// with it rather than being listed separately. Returns how many were selected.
+    pub fn select(
+        &self,
+        entries: &mut [DirectoryEntry],
+        roots: &[PathBuf],
+        options: MatchOptions,
+        now: SystemTime,
+    ) -> Result<usize, ClassifierError> {
+        let topmost: HashSet<PathBuf> = self.matches(entries, roots, options, now)?.into_iter().collect();
+        for entry in entries {
+            entry.selected = topmost.contains(&entry.path);
+        }
+        Ok(topmost.len())
+    }
+
+    /// The topmost directories this profile cleans, sorted by path; scan roots and aliases
+    /// never match
+    pub fn matches(
+        &self,
+        entries: &[DirectoryEntry],
+        roots: &[PathBuf],
+        options: MatchOptions,
+        now: SystemTime,
+    ) -> Result<Vec<PathBuf>, ClassifierError> {
+        let names = build_glob_set(&self.names, options)?;
+        let cutoff = self.older_than.and_then(|window| now.checked_sub(window));
+        let is_root = scanner::is_root_of(roots);
+        let wanted = |entry: &DirectoryEntry| {
+            let wanted = if self.names.is_empty() && self.categories.is_empty() {
+                entry.entry_type.is_temp()
+            } else {
+                let name = entry.path.file_name().and_then(|n| n.to_str());
+                name.is_some_and(|name| names.is_match(options.normalize(name).as_ref()))
+                    || self.categories.contains(&entry.entry_type)
+            };
+            // Without a modification time the age is unknown, so the directory stays
+            let old = cutoff.is_none_or(|cutoff| entry.modified.is_some_and(|modified| modified < cutoff));
+            wanted && old && entry.alias_of.is_none() && !is_root(entry)
+        };
+
+        let mut matched: Vec<PathBuf> = entries.iter().filter(|e| wanted(e)).map(|e| e.path.clone()).collect();
+        // Sorted by component, each directory comes right before the ones inside it
+        matched.sort();
+        let mut topmost: Vec<PathBuf> = Vec::new();
+        for path in matched {
+            if !topmost.last().is_some_and(|top| path.starts_with(top)) {
+                topmost.push(path);
+            }
+        }
+        Ok(topmost)
+    }
+}
+
+/// The profile called `name`
+pub fn find<'a>(profiles: &'a BTreeMap<String, Profile>, name: &str) -> Result<&'a Profile, ProfileError> {
+    if profiles.is_empty() {
+        return Err(ProfileError::NoneConfigured);
+    }
+    profiles.get(name).ok_or_else(|| {
+        let available = profiles.keys().map(String::as_str).collect::<Vec<_>>().join(", ");
+        ProfileError::Unknown(name.to_string(), available)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn entry(path: &str, entry_type: EntryType, age_days: u64, now: SystemTime) -> DirectoryEntry {
+        DirectoryEntry {
+            path: PathBuf::from(path),
+            entry_type,
+            modified: Some(now - DAY * age_days as u32),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_select() {
+        let profiles: BTreeMap<String, Profile> = toml::from_str(
+            r#"
+[node-dev]
+paths = ["/code"]
+names = ["node_modules", ".next"]
+older_than = "60days"
+
+[ci-agent]
+paths = ["/builds"]
+names = ["*"]
+action = "delete"
+
+[builds]
+categories = ["build_output"]
+"#,
+        )
+        .unwrap();
+        let now = SystemTime::now();
+        let mut entries = vec![
+            entry("/code", EntryType::Normal, 1, now),
+            entry("/code/old/node_modules", EntryType::DependencyCache, 90, now),
+            entry("/code/old/node_modules/left-pad/node_modules", EntryType::DependencyCache, 90, now),
+            entry("/code/old/.next", EntryType::BuildOutput, 61, now),
+            entry("/code/new/node_modules", EntryType::DependencyCache, 3, now),
+            entry("/code/new/target", EntryType::BuildOutput, 90, now),
+            DirectoryEntry { path: PathBuf::from("/code/undated/node_modules"), ..Default::default() },
+        ];
+
+        let node_dev = find(&profiles, "node-dev").unwrap();
+        let count = node_dev.select(&mut entries, &node_dev.roots(), MatchOptions::exact(), now).unwrap();
+        assert_eq!(count, 2);
+        let selected: Vec<&str> = entries.iter().filter(|e| e.selected).map(|e| e.path.to_str().unwrap()).collect();
+        assert_eq!(selected, vec!["/code/old/node_modules", "/code/old/.next"]);
+
+        // Everything under the root goes, but only the topmost directories are listed
+        let ci_agent = find(&profiles, "ci-agent").unwrap();
+        let count = ci_agent.select(&mut entries, &[PathBuf::from("/code")], MatchOptions::exact(), now).unwrap();
+        assert_eq!(count, 5);
+        assert!(!entries[0].selected);
+
+        let builds = find(&profiles, "builds").unwrap();
+        assert_eq!(builds.select(&mut entries, &[], MatchOptions::exact(), now).unwrap(), 2);
+        assert!(entries[5].selected);
+
+        // Neither names nor categories: every temp directory
+        let everything = Profile::default();
+        assert_eq!(everything.select(&mut entries, &[], MatchOptions::exact(), now).unwrap(), 4);
+
+        assert!(matches!(find(&profiles, "nope"), Err(ProfileError::Unknown(..))));
+    }
+
+    #[test]
+    fn test_apply_action() {
+        let review = Profile { paths: vec![PathBuf::from("/code")], ..Default::default() };
+        let mut args = CliArgs::parse_from(["disk-cleanup-tool"]);
+        review.apply(&mut args).unwrap();
+        assert_eq!(args.path, vec![PathBuf::from("/code")]);
+        assert!(args.interactive && !args.yes);
+
+        // Headless when asked
+        let mut args = CliArgs::parse_from(["disk-cleanup-tool", "--path", "/srv", "--yes"]);
+        review.apply(&mut args).unwrap();
+        assert_eq!(args.path, vec![PathBuf::from("/srv")]);
+        assert!(!args.interactive && args.yes);
+
+        // Without paths of its own a profile needs --path, never the current directory
+        let delete = Profile { action: ProfileAction::Delete, ..Default::default() };
+        let mut args = CliArgs::parse_from(["disk-cleanup-tool"]);
+        assert!(matches!(delete.apply(&mut args), Err(ProfileError::NoPaths)));
+        assert!(args.path.is_empty() && !args.yes);
+
+        let mut args = CliArgs::parse_from(["disk-cleanup-tool", "--path", "/builds"]);
+        delete.apply(&mut args).unwrap();
+        assert!(args.yes && !args.dry_run);
+        let mut args = CliArgs::parse_from(["disk-cleanup-tool", "--path", "/builds", "--interactive"]);
+        delete.apply(&mut args).unwrap();
+        assert!(!args.yes);
+
+        let dry_run = Profile { action: ProfileAction::DryRun, ..Default::default() };
+        let mut args = CliArgs::parse_from(["disk-cleanup-tool", "--path", "/builds"]);
+        dry_run.apply(&mut args).unwrap();
+        assert!(args.yes && args.dry_run);
+    }
+}
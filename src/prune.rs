@@ -0,0 +1,205 @@
+use crate::i18n::t;
+use crate::utils::format_size;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+/// Version-control metadata is never aged out file by file
+const VCS_DIRS: [&str; 3] = [".git", ".hg", ".svn"];
+
+/// Files inside one directory that were last modified before the cutoff
+#[derive(Debug, PartialEq, Eq)]
+pub struct PrunePlan {
+    pub dir: PathBuf,
+    pub cutoff: SystemTime,
+    /// Files and symlinks to remove with their sizes, in walk order
+    pub files: Vec<(PathBuf, u64)>,
+    pub bytes: u64,
+}
+
+/// Find the files under `dir` not modified since `cutoff`. Symlinks are never followed and
+/// count as files of their own; unreadable entries are left alone.
+pub fn plan(dir: &Path, cutoff: SystemTime) -> io::Result<PrunePlan> {
+    if !fs::metadata(dir)?.is_dir() {
+        return Err(io::Error::other(format!("{} is not a directory", dir.display())));
+    }
+    let mut plan = PrunePlan {
+        dir: dir.to_path_buf(),
+        cutoff,
+        files: Vec::new(),
+        bytes: 0,
+    };
+    let walker = WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| !(e.file_type().is_dir() && VCS_DIRS.iter().any(|v| e.file_name() == *v)));
+    for entry in walker.filter_map(|e| e.ok()) {
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.modified().is_ok_and(|modified| modified < cutoff) {
+            plan.bytes += metadata.len();
+            plan.files.push((entry.into_path(), metadata.len()));
+        }
+    }
+    Ok(plan)
+}
+
+/// What [`prune`] removed from one directory
+#[derive(Debug, Default)]
+pub struct PruneReport {
+    pub dir: PathBuf,
+    pub removed: usize,
+    pub bytes: u64,
+    /// Subdirectories left empty by the pruning, which are removed as well
+    pub removed_dirs: usize,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl PruneReport {
+    pub fn print_summary(&self) {
+        println!(
+            "{}",
+            t!(
+                "prune-done",
+                dir = self.dir.display().to_string(),
+                count = self.removed,
+                size = format_size(self.bytes),
+                dirs = self.removed_dirs
+            )
+        );
+        for (path, reason) in &self.failed {
+            println!("  ✗ {}: {}", path.display(), reason);
+        }
+    }
+}
+
+/// Remove the planned files, re-checking each one first: a file modified since the plan
+/// was made is kept. Subdirectories emptied this way are removed too; the directory itself
+/// always stays.
+pub fn prune(plan: &PrunePlan) -> PruneReport {
+    let mut report = PruneReport {
+        dir: plan.dir.clone(),
+        ..Default::default()
+    };
+    let mut emptied = Vec::new();
+    for (path, size) in &plan.files {
+        let still_old = fs::symlink_metadata(path)
+            .and_then(|m| m.modified())
+            .is_ok_and(|modified| modified < plan.cutoff);
+        if !still_old {
+            report.failed.push((path.clone(), "modified since it was listed".to_string()));
+            continue;
+        }
+        match fs::remove_file(path) {
+            Ok(()) => {
+                report.removed += 1;
+                report.bytes += size;
+                if let Some(parent) = path.parent() {
+                    emptied.push(parent.to_path_buf());
+                }
+            }
+            Err(e) => report.failed.push((path.clone(), e.to_string())),
+        }
+    }
+
+    // Deepest first, so a parent is only tried once its subdirectories are gone;
+    // `remove_dir` keeps every directory that still has content
+    emptied.sort_by(|a, b| b.components().count().cmp(&a.components().count()).then_with(|| a.cmp(b)));
+    emptied.dedup();
+    for dir in emptied {
+        for dir in dir.ancestors().take_while(|d| *d != plan.dir && d.starts_with(&plan.dir)) {
+            if fs::remove_dir(dir).is_err() {
+                break;
+            }
+            report.removed_dirs += 1;
+        }
+    }
+    report
+}
+
+/// One line per directory; `verbose` lists every file as well
+pub fn print_plan(plan: &PrunePlan, age: &str, verbose: bool) {
+    println!(
+        "{}",
+        t!(
+            "prune-found",
+            dir = plan.dir.display().to_string(),
+            count = plan.files.len(),
+            age = age,
+            size = format_size(plan.bytes)
+        )
+    );
+    if verbose {
+        for (path, size) in &plan.files {
+            println!("  {} ({})", path.display(), format_size(*size));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn age(path: &Path, days: u64) {
+        let modified = SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60);
+        fs::File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+    }
+
+    #[test]
+    fn test_prune_old_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = temp_dir.path().join("cache");
+        fs::create_dir_all(cache.join("old/nested")).unwrap();
+        fs::create_dir_all(cache.join("mixed")).unwrap();
+        fs::create_dir_all(cache.join(".git")).unwrap();
+        for (file, days) in [
+            ("old/nested/a.bin", 90),
+            ("old/b.bin", 60),
+            ("mixed/c.bin", 90),
+            ("mixed/fresh.bin", 1),
+            ("index", 1),
+            (".git/HEAD", 365),
+        ] {
+            fs::write(cache.join(file), "data").unwrap();
+            age(&cache.join(file), days);
+        }
+
+        let cutoff = SystemTime::now() - Duration::from_secs(30 * 24 * 60 * 60);
+        let plan = plan(&cache, cutoff).unwrap();
+        let files: Vec<&Path> = plan.files.iter().map(|(path, _)| path.strip_prefix(&cache).unwrap()).collect();
+        assert_eq!(files, vec![Path::new("mixed/c.bin"), Path::new("old/b.bin"), Path::new("old/nested/a.bin")]);
+        assert_eq!(plan.bytes, 12);
+
+        // A file touched after planning is kept
+        age(&cache.join("old/b.bin"), 0);
+        let report = prune(&plan);
+        assert_eq!(report.removed, 2);
+        assert_eq!(report.bytes, 8);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.removed_dirs, 1);
+        assert!(!cache.join("old/nested").exists());
+        assert!(cache.join("old/b.bin").exists() && cache.join("mixed/fresh.bin").exists());
+        assert!(cache.join(".git/HEAD").exists() && cache.join("index").exists());
+
+        // The directory itself is never removed, even when everything in it is old
+        let plan = super::plan(&cache.join("mixed"), SystemTime::now() + Duration::from_secs(60)).unwrap();
+        assert_eq!(prune(&plan).removed, 1);
+        assert!(cache.join("mixed").is_dir());
+    }
+
+    #[test]
+    fn test_plan_needs_a_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("file"), "data").unwrap();
+        assert!(plan(&temp_dir.path().join("file"), SystemTime::now()).is_err());
+        assert!(plan(&temp_dir.path().join("missing"), SystemTime::now()).is_err());
+    }
+}
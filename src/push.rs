@@ -0,0 +1,163 @@
+use crate::csv_handler::ScanMetadata;
+use crate::report::RunSummary;
+use crate::scanner::DirectoryEntry;
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error;
+
+/// How long to wait for the endpoint before giving up
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Error)]
+pub enum PushError {
+    #[error("Invalid header '{0}', expected 'Name: value'")]
+    InvalidHeader(String),
+
+    #[error("Cannot post results to {url}: {message}")]
+    Request { url: String, message: String },
+}
+
+/// Body of `--post-results`: where and when the scan ran, the run summary, and with
+/// `--post-entries` every directory in the JSON scan file layout
+#[derive(Debug, Serialize)]
+pub struct ResultsPayload<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<&'a Path>,
+    /// RFC 3339 timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scanned_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_version: Option<&'a str>,
+    pub summary: &'a RunSummary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entries: Option<&'a [DirectoryEntry]>,
+}
+
+impl<'a> ResultsPayload<'a> {
+    pub fn new(metadata: &'a ScanMetadata, summary: &'a RunSummary, entries: Option<&'a [DirectoryEntry]>) -> Self {
+        Self {
+            hostname: metadata.hostname.as_deref(),
+            root: metadata.root.as_deref(),
+            scanned_at: metadata.scanned_at.map(|t| humantime::format_rfc3339_seconds(t).to_string()),
+            tool_version: metadata.tool_version.as_deref(),
+            summary,
+            entries,
+        }
+    }
+}
+
+/// Parse `--post-header 'Authorization: Bearer ...'`
+pub fn parse_header(text: &str) -> Result<(String, String), PushError> {
+    match text.split_once(':') {
+        Some((name, value)) if !name.trim().is_empty() && !name.contains(char::is_whitespace) => {
+            Ok((name.to_string(), value.trim().to_string()))
+        }
+        _ => Err(PushError::InvalidHeader(text.to_string())),
+    }
+}
+
+/// POST `payload` as JSON to `url` with the extra `headers`; any non-2xx answer is an error
+pub fn post_results(url: &str, headers: &[(String, String)], payload: &ResultsPayload) -> Result<(), PushError> {
+    let request_error = |message: String| PushError::Request { url: url.to_string(), message };
+    let body = serde_json::to_string(payload).map_err(|e| request_error(e.to_string()))?;
+
+    let agent = ureq::AgentBuilder::new().timeout(TIMEOUT).build();
+    let mut request = agent.post(url).set("Content-Type", "application/json");
+    for (name, value) in headers {
+        request = request.set(name, value);
+    }
+    match request.send_string(&body) {
+        Ok(_) => Ok(()),
+        Err(ureq::Error::Status(code, response)) => {
+            Err(request_error(format!("HTTP {} {}", code, response.status_text())))
+        }
+        Err(ureq::Error::Transport(transport)) => {
+            // The transport error's own text repeats the URL
+            let message = match std::error::Error::source(&transport) {
+                Some(source) => format!("{}: {}", transport.kind(), source),
+                None => transport.kind().to_string(),
+            };
+            Err(request_error(message))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::thread;
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(
+            parse_header("Authorization: Bearer abc:def").unwrap(),
+            ("Authorization".to_string(), "Bearer abc:def".to_string())
+        );
+        assert!(parse_header("no colon").is_err());
+        assert!(parse_header(": value").is_err());
+        assert!(parse_header("Bad Name: value").is_err());
+    }
+
+    /// Serve one request and hand back its head and body
+    fn serve_once(status: &'static str) -> (String, thread::JoinHandle<(String, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/results", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut head = String::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                head.push_str(&line);
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let response = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            (head, String::from_utf8(body).unwrap())
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_post_results() {
+        let metadata = ScanMetadata {
+            hostname: Some("build-01".to_string()),
+            ..ScanMetadata::new(Some(Path::new("/srv")))
+        };
+        let summary = RunSummary { directories: 2, temp_bytes: 1500, ..Default::default() };
+        let entries = vec![DirectoryEntry { path: PathBuf::from("/srv/node_modules"), ..Default::default() }];
+
+        let (url, server) = serve_once("200 OK");
+        let headers = vec![("X-Token".to_string(), "secret".to_string())];
+        post_results(&url, &headers, &ResultsPayload::new(&metadata, &summary, Some(&entries))).unwrap();
+        let (head, body) = server.join().unwrap();
+        assert!(head.starts_with("POST /results HTTP/1.1"));
+        assert!(head.contains("X-Token: secret"));
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["hostname"], "build-01");
+        assert_eq!(json["root"], "/srv");
+        assert_eq!(json["summary"]["temp_bytes"], 1500);
+        assert_eq!(json["entries"][0]["path"], "/srv/node_modules");
+
+        let (url, server) = serve_once("503 Service Unavailable");
+        let result = post_results(&url, &[], &ResultsPayload::new(&metadata, &summary, None));
+        let (_, body) = server.join().unwrap();
+        assert!(!body.contains("entries"));
+        assert!(matches!(result, Err(PushError::Request { message, .. }) if message.contains("503")));
+    }
+}
@@ -0,0 +1,315 @@
+use crate::deletion::{DeletionReport, FailedPath};
+use crate::i18n::t;
+use crate::scanner::{DirectoryEntry, EntryType};
+use crate::telemetry::ScanTelemetry;
+use crate::utils::{format_size, inode_usage};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Temp directories of one kind, e.g. every `node_modules` or every "Unity Library"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportGroup {
+    /// Path rule label, or the directory name for name-based matches
+    pub name: String,
+    pub entry_type: EntryType,
+    pub total_bytes: u64,
+    pub count: usize,
+    /// Largest directories in the group, biggest first
+    pub largest: Vec<(PathBuf, u64)>,
+}
+
+/// Group temp directories by kind, largest groups first, keeping `examples` paths per group
+pub fn build_report(entries: &[DirectoryEntry], examples: usize) -> Vec<ReportGroup> {
+    let mut groups: HashMap<(EntryType, String), ReportGroup> = HashMap::new();
+    for entry in entries.iter().filter(|e| e.entry_type.is_temp()) {
+        let name = entry.label.clone().unwrap_or_else(|| {
+            entry
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| entry.path.display().to_string())
+        });
+        let group = groups
+            .entry((entry.entry_type, name.clone()))
+            .or_insert_with(|| ReportGroup {
+                name,
+                entry_type: entry.entry_type,
+                total_bytes: 0,
+                count: 0,
+                largest: Vec::new(),
+            });
+        group.total_bytes += entry.shown_size();
+        group.count += 1;
+        group.largest.push((entry.path.clone(), entry.shown_size()));
+    }
+
+    let mut groups: Vec<ReportGroup> = groups.into_values().collect();
+    for group in &mut groups {
+        group.largest.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+        group.largest.truncate(examples);
+    }
+    groups.sort_by(|a, b| b.total_bytes.cmp(&a.total_bytes).then_with(|| a.name.cmp(&b.name)));
+    groups
+}
+
+/// Plain-text report, suitable for a terminal or a cron email
+pub fn format_report(groups: &[ReportGroup], root: &Path) -> String {
+    let total: u64 = groups.iter().map(|g| g.total_bytes).sum();
+    let count: usize = groups.iter().map(|g| g.count).sum();
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{}",
+        t!("report-reclaimable", root = root.display().to_string(), size = format_size(total), count = count)
+    );
+    if groups.is_empty() {
+        return out;
+    }
+
+    let _ = writeln!(out, "\n{}", t!("report-by-category"));
+    for entry_type in EntryType::ALL.iter().filter(|t| t.is_temp()) {
+        let in_category = groups.iter().filter(|g| g.entry_type == *entry_type);
+        let (bytes, dirs) = in_category.fold((0, 0), |(b, c), g| (b + g.total_bytes, c + g.count));
+        if dirs > 0 {
+            let _ = writeln!(
+                out,
+                "  {:<14} {:>10}  {}",
+                entry_type.display_name(),
+                format_size(bytes),
+                t!("count-dirs", count = dirs)
+            );
+        }
+    }
+
+    let _ = writeln!(out, "\n{}", t!("report-by-type"));
+    for group in groups {
+        let _ = writeln!(
+            out,
+            "  {}",
+            t!(
+                "report-type-line",
+                name = group.name.as_str(),
+                category = group.entry_type.display_name(),
+                size = format_size(group.total_bytes),
+                count = group.count
+            )
+        );
+        for (path, size) in &group.largest {
+            let _ = writeln!(out, "    {:>10}  {}", format_size(*size), path.display());
+        }
+    }
+    out
+}
+
+/// "Most files" section for `--sort files`: the `top` directories with the most files and
+/// the filesystem's inode usage
+pub fn format_file_counts(entries: &[DirectoryEntry], root: &Path, top: usize) -> String {
+    let mut by_files: Vec<&DirectoryEntry> = entries.iter().filter(|e| !e.is_path(root)).collect();
+    by_files.sort_by_key(|e| std::cmp::Reverse((e.cumulative_file_count, e.inodes)));
+
+    let mut out = String::new();
+    let _ = writeln!(out, "\n{}", t!("report-most-files"));
+    if let Some(usage) = inode_usage(root) {
+        let _ = writeln!(out, "  {}: {}", t!("summary-filesystem"), usage.describe());
+    }
+    let _ = writeln!(
+        out,
+        "  {:>10} {:>10} {:>10}  {}",
+        t!("column-files"),
+        t!("column-inodes"),
+        t!("column-size"),
+        t!("report-directory")
+    );
+    for entry in by_files.iter().take(top) {
+        let _ = writeln!(
+            out,
+            "  {:>10} {:>10} {:>10}  {}",
+            entry.cumulative_file_count,
+            entry.inodes,
+            format_size(entry.shown_size()),
+            entry.path.display()
+        );
+    }
+    out
+}
+
+/// Final numbers of a run, printed as one JSON object by `--summary-json`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RunSummary {
+    pub directories: usize,
+    /// Size of the top-level directories (the scan roots, or each source of a merged scan)
+    pub total_bytes: u64,
+    pub files: u64,
+    pub temp_directories: usize,
+    pub temp_bytes: u64,
+    /// Unreadable directories and files; when non-zero the sizes are lower bounds
+    pub scan_errors: u64,
+    pub deleted: usize,
+    /// How many of `deleted` were files rather than directories
+    pub deleted_files: usize,
+    pub freed_bytes: u64,
+    pub failed: Vec<FailedPath>,
+    /// Directories left untouched because the deletion was cancelled
+    pub cancelled: usize,
+    pub dry_run: bool,
+    /// Time, memory and IO the scan took; absent for scans loaded from a file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scan: Option<ScanTelemetry>,
+}
+
+/// Entries with no ancestor among `entries`: the scan roots, or each source of a merged scan
+pub fn top_level(entries: &[DirectoryEntry]) -> Vec<&DirectoryEntry> {
+    let paths: HashSet<_> = entries.iter().map(|e| e.path.as_path()).collect();
+    entries
+        .iter()
+        .filter(|e| !e.path.ancestors().skip(1).any(|ancestor| paths.contains(ancestor)))
+        .collect()
+}
+
+impl RunSummary {
+    pub fn new(entries: &[DirectoryEntry], dry_run: bool) -> Self {
+        let top_level = top_level(entries);
+        let temp = entries.iter().filter(|e| e.entry_type.is_temp());
+        Self {
+            directories: entries.len(),
+            total_bytes: top_level.iter().map(|e| e.cumulative_size_bytes).sum(),
+            files: top_level.iter().map(|e| e.cumulative_file_count).sum(),
+            scan_errors: top_level.iter().map(|e| e.errors).sum(),
+            temp_directories: temp.clone().count(),
+            temp_bytes: temp.map(|e| e.cumulative_size_bytes).sum(),
+            dry_run,
+            ..Default::default()
+        }
+    }
+
+    pub fn add_deletion(&mut self, report: &DeletionReport) {
+        self.deleted += report.successful.len();
+        self.deleted_files += report.deleted_files;
+        self.freed_bytes += report.total_freed_bytes;
+        self.failed.extend(report.failures());
+        self.cancelled += report.cancelled.len();
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, size: u64, entry_type: EntryType) -> DirectoryEntry {
+        DirectoryEntry {
+            path: PathBuf::from(path),
+            cumulative_size_bytes: size,
+            entry_type,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_build_report_groups_by_kind() {
+        let mut unity = entry("/p/game/Library", 500, EntryType::BuildOutput);
+        unity.label = Some("Unity Library".to_string());
+        let entries = vec![
+            entry("/p", 10_000, EntryType::Normal),
+            entry("/p/a/target", 3000, EntryType::BuildOutput),
+            entry("/p/b/target", 1000, EntryType::BuildOutput),
+            entry("/p/c/target", 2000, EntryType::BuildOutput),
+            entry("/p/web/node_modules", 1500, EntryType::DependencyCache),
+            unity,
+        ];
+
+        let groups = build_report(&entries, 2);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].name, "target");
+        assert_eq!(groups[0].total_bytes, 6000);
+        assert_eq!(groups[0].count, 3);
+        assert_eq!(
+            groups[0].largest,
+            vec![(PathBuf::from("/p/a/target"), 3000), (PathBuf::from("/p/c/target"), 2000)]
+        );
+        assert_eq!(groups[1].name, "node_modules");
+        assert_eq!(groups[2].name, "Unity Library");
+    }
+
+    #[test]
+    fn test_format_report() {
+        let entries = vec![
+            entry("/p/a/target", 2 * 1024 * 1024, EntryType::BuildOutput),
+            entry("/p/b/target", 1024 * 1024, EntryType::BuildOutput),
+            entry("/p/.idea", 1024, EntryType::IdeMetadata),
+        ];
+
+        let report = format_report(&build_report(&entries, 3), Path::new("/p"));
+        assert!(report.starts_with("Reclaimable space under /p: 3.00 MB in 3 directories\n"));
+        assert!(report.contains("  target (Build output): 3.00 MB across 2 directories\n"));
+        assert!(report.contains("  .idea (IDE metadata): 1.00 KB across 1 directory\n"));
+        assert!(report.contains("/p/a/target"));
+        assert!(report.contains("By category:\n  Build output"));
+    }
+
+    #[test]
+    fn test_format_file_counts() {
+        let entry = |path: &str, files, inodes| DirectoryEntry {
+            path: PathBuf::from(path),
+            cumulative_file_count: files,
+            inodes,
+            ..Default::default()
+        };
+        let entries = vec![
+            entry("/nonexistent-root", 100_000, 120_000),
+            entry("/nonexistent-root/web/node_modules", 90_000, 110_000),
+            entry("/nonexistent-root/docs", 12, 14),
+        ];
+
+        let text = format_file_counts(&entries, Path::new("/nonexistent-root"), 1);
+        assert!(text.starts_with("\nMost files:\n"));
+        assert!(!text.contains("Filesystem:"));
+        assert!(text.contains("      90000     110000        0 B  /nonexistent-root/web/node_modules\n"));
+        assert!(!text.contains("docs"));
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let report = format_report(&build_report(&[], 3), Path::new("/p"));
+        assert_eq!(report, "Reclaimable space under /p: 0 B in 0 directories\n");
+    }
+
+    #[test]
+    fn test_run_summary() {
+        let mut root = entry("/p", 10_000, EntryType::Normal);
+        root.cumulative_file_count = 40;
+        let entries = vec![
+            root,
+            entry("/p/a", 4000, EntryType::Normal),
+            entry("/p/a/target", 3000, EntryType::BuildOutput),
+            entry("/p/web/node_modules", 1500, EntryType::DependencyCache),
+        ];
+        let mut summary = RunSummary::new(&entries, false);
+        assert_eq!(summary.directories, 4);
+        assert_eq!(summary.total_bytes, 10_000);
+        assert_eq!(summary.files, 40);
+        assert_eq!(summary.temp_directories, 2);
+        assert_eq!(summary.temp_bytes, 4500);
+
+        summary.add_deletion(&DeletionReport {
+            successful: vec![PathBuf::from("/p/a/target")],
+            failed: vec![(PathBuf::from("/p/web/node_modules"), "Permission denied".to_string())],
+            cancelled: Vec::new(),
+            total_freed_bytes: 3000,
+            deleted_files: 1,
+            retryable: Vec::new(),
+        });
+        let json: serde_json::Value = serde_json::from_str(&summary.to_json()).unwrap();
+        assert_eq!(json["deleted"], 1);
+        assert_eq!(json["deleted_files"], 1);
+        assert_eq!(json["freed_bytes"], 3000);
+        assert_eq!(json["failed"][0]["path"], "/p/web/node_modules");
+        assert_eq!(json["dry_run"], false);
+    }
+}
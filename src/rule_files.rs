@@ -0,0 +1,169 @@
+use crate::classifier::{ClassifierBackend, ClassifierError, MatchOptions, RuleSetBackend};
+use crate::config::{ConfigError, PathRule};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Path rules for one ecosystem, kept in a file of their own so they can be shared without
+/// touching the config. TOML, or JSON with a `.json` extension:
+///
+/// ```toml
+/// name = "OCaml"
+///
+/// [[rules]]
+/// path_pattern = "**/_opam"
+/// category = "dependency_cache"
+/// sibling = "dune-project"
+/// label = "opam local switch"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleFile {
+    /// Shown by `explain`; defaults to the file name
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Same fields as the config's `[[rules]]`
+    #[serde(default)]
+    pub rules: Vec<PathRule>,
+}
+
+impl RuleFile {
+    /// Default directory, loaded on every run: `<config dir>/disk-cleanup-tool/rules`
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("disk-cleanup-tool").join("rules"))
+    }
+
+    pub fn load(path: &Path) -> Result<RuleFile, ConfigError> {
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::IoError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let parse_error = |message: String| ConfigError::ParseError {
+            path: path.to_path_buf(),
+            message,
+        };
+        let mut file: RuleFile = if is_json(path) {
+            serde_json::from_str(&content).map_err(|e| parse_error(e.to_string()))?
+        } else {
+            toml::from_str(&content).map_err(|e| parse_error(e.message().to_string()))?
+        };
+        if file.name.is_none() {
+            file.name = Some(path.file_name().unwrap_or_default().to_string_lossy().into_owned());
+        }
+        Ok(file)
+    }
+
+    /// Every `.toml` and `.json` file in `dir`, in file name order; a missing directory has none
+    pub fn load_dir(dir: &Path) -> Result<Vec<RuleFile>, ConfigError> {
+        let read_dir = match fs::read_dir(dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(source) => {
+                return Err(ConfigError::IoError {
+                    path: dir.to_path_buf(),
+                    source,
+                })
+            }
+        };
+        let mut paths: Vec<PathBuf> = read_dir
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file())
+            .filter(|path| matches!(path.extension().and_then(|e| e.to_str()), Some("toml" | "json")))
+            .collect();
+        paths.sort();
+        paths.iter().map(|path| RuleFile::load(path)).collect()
+    }
+
+    /// The rules as a classifier backend
+    pub fn backend(&self, options: MatchOptions) -> Result<Arc<dyn ClassifierBackend>, ClassifierError> {
+        let name = self.name.clone().unwrap_or_default();
+        Ok(Arc::new(RuleSetBackend::new(name, &self.rules, options)?))
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classifier::{Classifier, MatchReason};
+    use crate::scanner::EntryType;
+    use tempfile::TempDir;
+
+    const OCAML_TOML: &str = r#"
+name = "OCaml"
+
+[[rules]]
+path_pattern = "**/_opam"
+category = "dependency_cache"
+sibling = "dune-project"
+label = "opam local switch"
+
+[[rules]]
+path_pattern = "**/_esy"
+category = "dependency_cache"
+"#;
+
+    #[test]
+    fn test_load_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("ocaml.toml"), OCAML_TOML).unwrap();
+        fs::write(
+            temp_dir.path().join("bazel.json"),
+            r#"{"rules": [{"path_pattern": "**/bazel-out", "category": "build_output"}]}"#,
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("notes.txt"), "not a rule file").unwrap();
+
+        let files = RuleFile::load_dir(temp_dir.path()).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].name.as_deref(), Some("bazel.json"));
+        assert_eq!(files[0].rules[0].category, EntryType::BuildOutput);
+        assert_eq!(files[1].name.as_deref(), Some("OCaml"));
+        assert_eq!(files[1].rules.len(), 2);
+
+        assert!(RuleFile::load_dir(&temp_dir.path().join("missing")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_rule_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("broken.toml");
+        fs::write(&path, "[[rules]]\ncategory = \"temp\"\n").unwrap();
+        assert!(matches!(RuleFile::load(&path), Err(ConfigError::ParseError { .. })));
+
+        let path = temp_dir.path().join("broken.json");
+        fs::write(&path, "{\"rules\": [").unwrap();
+        assert!(matches!(RuleFile::load(&path), Err(ConfigError::ParseError { .. })));
+    }
+
+    #[test]
+    fn test_rule_file_backend() {
+        let temp_dir = TempDir::new().unwrap();
+        let app = temp_dir.path().join("app");
+        fs::create_dir_all(app.join("_opam")).unwrap();
+        fs::write(app.join("dune-project"), "").unwrap();
+        let rule_path = temp_dir.path().join("ocaml.toml");
+        fs::write(&rule_path, OCAML_TOML).unwrap();
+
+        let file = RuleFile::load(&rule_path).unwrap();
+        let classifier = Classifier::default()
+            .with_backend(file.backend(MatchOptions::default()).unwrap())
+            .and_then(|c| c.with_match_options(MatchOptions::exact()))
+            .unwrap();
+        assert!(classifier.has_overrides());
+
+        assert_eq!(classifier.classify_path(&app.join("_opam")), Some(EntryType::DependencyCache));
+        assert_eq!(classifier.label_path(&app.join("_opam")).as_deref(), Some("opam local switch"));
+        // Without dune-project next to it, `_opam` is just a directory
+        assert_eq!(classifier.classify_path(&temp_dir.path().join("_opam")), None);
+
+        let explanation = classifier.explain(&app.join("_opam"));
+        assert!(matches!(&explanation.reason, MatchReason::Backend { backend, .. } if backend == "OCaml"));
+        assert!(explanation.reason.to_string().starts_with("rule '**/_opam' from OCaml"));
+    }
+}
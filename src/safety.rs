@@ -0,0 +1,261 @@
+use crate::scanner::{self, DirectoryEntry};
+use crate::utils::{classify_directory, RebuildCost};
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Files that make a directory the root of a project its dependencies and build outputs
+/// can be regenerated from
+const PROJECT_MANIFESTS: &[&str] = &[
+    "package.json", "Cargo.toml", "pyproject.toml", "setup.py", "requirements.txt", "Pipfile", "environment.yml",
+    "go.mod", "pom.xml", "build.gradle", "build.gradle.kts", "Gemfile", "composer.json", "pubspec.yaml", "mix.exs",
+    "Package.swift", "CMakeLists.txt", "Makefile",
+];
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// Safety score a suggested deletion has at least
+const SUGGEST_MIN_SCORE: u8 = 70;
+
+/// How long a suggested deletion has been untouched at least
+const SUGGEST_MIN_AGE: Duration = Duration::from_secs(30 * DAY);
+
+/// What the directory a temp directory sits in says about deleting it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProjectContext {
+    /// A project manifest is next to it, so it can be rebuilt from there
+    pub has_manifest: bool,
+    /// Something else in the project changed after it did, so it is out of date anyway
+    pub sources_newer: bool,
+}
+
+impl ProjectContext {
+    /// Look at what is next to `path`, which was last modified at `modified`. Other temp
+    /// directories don't count as sources.
+    pub fn of(path: &Path, modified: Option<SystemTime>) -> Self {
+        let mut context = Self::default();
+        let Some(items) = path.parent().and_then(|project| fs::read_dir(project).ok()) else {
+            return context;
+        };
+        for item in items.flatten() {
+            let name = item.file_name();
+            let name = name.to_string_lossy();
+            if item.path() == path || classify_directory(&name).is_some() {
+                continue;
+            }
+            context.has_manifest |= PROJECT_MANIFESTS.contains(&name.as_ref());
+            let changed = item.metadata().and_then(|metadata| metadata.modified()).ok();
+            context.sources_newer |= matches!((changed, modified), (Some(changed), Some(built)) if changed > built);
+        }
+        context
+    }
+}
+
+/// How safe deleting the temp directory `entry` is, from 0 to 100: cheap to rebuild (35),
+/// untouched for long (up to 30), next to a project manifest it can be rebuilt from (20),
+/// and older than the project's sources (15). In-use and irreplaceable directories score
+/// 0; other directories have no score.
+pub fn score(entry: &DirectoryEntry, context: ProjectContext, now: SystemTime) -> Option<u8> {
+    if !entry.entry_type.is_temp() {
+        return None;
+    }
+    let cost = match entry.rebuild_cost() {
+        _ if entry.active => return Some(0),
+        RebuildCost::Irreplaceable => return Some(0),
+        RebuildCost::Slow => 15,
+        RebuildCost::Cheap => 35,
+    };
+    let days = entry.modified.and_then(|modified| now.duration_since(modified).ok()).map_or(0, |age| age.as_secs() / DAY);
+    let age = match days {
+        0..7 => 0,
+        7..30 => 10,
+        30..182 => 20,
+        _ => 30,
+    };
+    Some(cost + age + if context.has_manifest { 20 } else { 0 } + if context.sources_newer { 15 } else { 0 })
+}
+
+/// Whether to suggest deleting `entry`, by conservative criteria: a temp directory that is
+/// not in use, untouched for 30 days, scoring at least 70 and next to a project manifest
+pub fn is_suggested(entry: &DirectoryEntry, now: SystemTime) -> bool {
+    entry.entry_type.is_temp()
+        && !entry.active
+        && entry.safety.is_some_and(|score| score >= SUGGEST_MIN_SCORE)
+        && entry.modified.and_then(|modified| now.duration_since(modified).ok()).is_some_and(|age| age >= SUGGEST_MIN_AGE)
+        && ProjectContext::of(&entry.path, entry.modified).has_manifest
+}
+
+/// The temp directories to delete to free `needed` bytes, for `--target-free`: those not in
+/// use and scoring at least 70, safest first and, at the same score, largest first, leaving
+/// out any the others reach the target without. Only directories `on_volume` accepts count,
+/// since deleting anything on another filesystem frees nothing where space is needed. Scan
+/// roots, aliases and directories inside another candidate are never picked. Returns the
+/// picked paths and their total size, which falls short of `needed` when all candidates
+/// together do.
+pub fn pick_for_target(
+    entries: &[DirectoryEntry],
+    roots: &[PathBuf],
+    needed: u64,
+    on_volume: impl Fn(&Path) -> bool,
+) -> (Vec<PathBuf>, u64) {
+    let is_root = scanner::is_root_of(roots);
+    let eligible: Vec<&DirectoryEntry> = entries
+        .iter()
+        .filter(|e| e.entry_type.is_temp() && !e.active && e.alias_of.is_none() && !is_root(e))
+        .filter(|e| on_volume(&e.path))
+        .filter(|e| e.safety.is_some_and(|score| score >= SUGGEST_MIN_SCORE))
+        .collect();
+    let paths: HashSet<&Path> = eligible.iter().map(|e| e.path.as_path()).collect();
+    let mut candidates: Vec<&DirectoryEntry> =
+        eligible.into_iter().filter(|e| !e.path.ancestors().skip(1).any(|ancestor| paths.contains(ancestor))).collect();
+    candidates.sort_by(|a, b| (b.safety, b.cumulative_size_bytes, &a.path).cmp(&(a.safety, a.cumulative_size_bytes, &b.path)));
+
+    let mut picked = Vec::new();
+    let mut total = 0;
+    for candidate in candidates {
+        if total >= needed {
+            break;
+        }
+        total += candidate.cumulative_size_bytes;
+        picked.push(candidate);
+    }
+    // A large directory picked late can make smaller ones before it unnecessary
+    if total >= needed {
+        for idx in (0..picked.len()).rev() {
+            let size = picked[idx].cumulative_size_bytes;
+            if total - size >= needed {
+                total -= size;
+                picked.remove(idx);
+            }
+        }
+    }
+    (picked.into_iter().map(|e| e.path.clone()).collect(), total)
+}
+
+/// Score every temp directory in `entries`, looking at the project each is in
+pub fn score_entries(entries: &mut [DirectoryEntry], now: SystemTime) {
+    entries.par_iter_mut().filter(|entry| entry.entry_type.is_temp()).for_each(|entry| {
+        let context = ProjectContext::of(&entry.path, entry.modified);
+        entry.safety = score(entry, context, now);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::EntryType;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_score() {
+        let now = SystemTime::now();
+        let entry = |name: &str, entry_type, days: u64| DirectoryEntry {
+            path: PathBuf::from("/p").join(name),
+            entry_type,
+            modified: now.checked_sub(Duration::from_secs(days * DAY)),
+            ..Default::default()
+        };
+        let project = ProjectContext { has_manifest: true, sources_newer: true };
+        let stray = ProjectContext::default();
+
+        assert_eq!(score(&entry("node_modules", EntryType::DependencyCache, 400), project, now), Some(100));
+        assert_eq!(score(&entry("node_modules", EntryType::DependencyCache, 1), stray, now), Some(35));
+        assert_eq!(score(&entry("target", EntryType::BuildOutput, 40), project, now), Some(70));
+        assert_eq!(score(&entry(".venv", EntryType::VirtualEnv, 10), stray, now), Some(25));
+        assert_eq!(score(&entry(".Trash", EntryType::OsCache, 400), project, now), Some(0));
+        assert_eq!(score(&entry("src", EntryType::Normal, 400), project, now), None);
+        let active = DirectoryEntry { active: true, ..entry("node_modules", EntryType::DependencyCache, 400) };
+        assert_eq!(score(&active, project, now), Some(0));
+    }
+
+    #[test]
+    fn test_project_context() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path();
+        let node_modules = project.join("node_modules");
+        fs::create_dir(&node_modules).unwrap();
+        fs::create_dir(project.join("dist")).unwrap();
+        let built = fs::metadata(&node_modules).unwrap().modified().unwrap();
+
+        // Only another temp directory next to it, which doesn't count
+        let later = built + Duration::from_secs(60);
+        assert_eq!(ProjectContext::of(&node_modules, Some(built - Duration::from_secs(60))), ProjectContext::default());
+
+        fs::write(project.join("package.json"), "{}").unwrap();
+        let context = ProjectContext::of(&node_modules, Some(later));
+        assert!(context.has_manifest && !context.sources_newer);
+        let context = ProjectContext::of(&node_modules, Some(built - Duration::from_secs(60)));
+        assert!(context.has_manifest && context.sources_newer);
+        assert_eq!(ProjectContext::of(&project.join("missing/node_modules"), None), ProjectContext::default());
+    }
+
+    #[test]
+    fn test_is_suggested() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path();
+        fs::create_dir(project.join("node_modules")).unwrap();
+        let now = SystemTime::now();
+        let entry = |days: u64, safety| DirectoryEntry {
+            path: project.join("node_modules"),
+            entry_type: EntryType::DependencyCache,
+            modified: now.checked_sub(Duration::from_secs(days * DAY)),
+            safety: Some(safety),
+            ..Default::default()
+        };
+
+        // Not in a project yet
+        assert!(!is_suggested(&entry(60, 90), now));
+        fs::write(project.join("package.json"), "{}").unwrap();
+        assert!(is_suggested(&entry(60, 90), now));
+        assert!(!is_suggested(&entry(10, 90), now));
+        assert!(!is_suggested(&entry(60, 60), now));
+        assert!(!is_suggested(&DirectoryEntry { active: true, ..entry(60, 90) }, now));
+        assert!(!is_suggested(&DirectoryEntry { entry_type: EntryType::Normal, ..entry(60, 90) }, now));
+    }
+
+    #[test]
+    fn test_pick_for_target() {
+        let entry = |path: &str, size: u64, safety| DirectoryEntry {
+            path: PathBuf::from(path),
+            cumulative_size_bytes: size,
+            entry_type: EntryType::DependencyCache,
+            safety: Some(safety),
+            ..Default::default()
+        };
+        let entries = vec![
+            entry("/p", 10_000, 100),
+            entry("/p/a/node_modules", 300, 100),
+            entry("/p/a/node_modules/x/node_modules", 200, 100),
+            entry("/p/b/node_modules", 2000, 90),
+            entry("/p/c/node_modules", 900, 90),
+            entry("/p/d/node_modules", 5000, 40),
+            DirectoryEntry { active: true, ..entry("/p/e/node_modules", 8000, 100) },
+        ];
+        let roots = [PathBuf::from("/p")];
+        let anywhere = |_: &Path| true;
+
+        // The safest one falls short, and the next one alone is enough
+        let (picked, total) = pick_for_target(&entries, &roots, 1000, anywhere);
+        assert_eq!(picked, [PathBuf::from("/p/b/node_modules")]);
+        assert_eq!(total, 2000);
+
+        // Safest first, then the largest at the next score
+        let (picked, total) = pick_for_target(&entries, &roots, 2100, anywhere);
+        assert_eq!(picked, [PathBuf::from("/p/a/node_modules"), PathBuf::from("/p/b/node_modules")]);
+        assert_eq!(total, 2300);
+
+        // Everything safe together isn't enough: low scores and directories in use stay
+        let (picked, total) = pick_for_target(&entries, &roots, 10_000, anywhere);
+        assert_eq!(picked.len(), 3);
+        assert_eq!(total, 3200);
+
+        // A directory on another device frees nothing on the one that is short of space
+        let on_volume = |path: &Path| !path.starts_with("/p/b");
+        let (picked, total) = pick_for_target(&entries, &roots, 1000, on_volume);
+        assert_eq!(picked, [PathBuf::from("/p/a/node_modules"), PathBuf::from("/p/c/node_modules")]);
+        assert_eq!(total, 1200);
+    }
+}
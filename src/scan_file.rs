@@ -0,0 +1,305 @@
+use crate::csv_handler::{self, CsvError, ScanMetadata, CSV_SCHEMA_VERSION};
+use crate::scanner::DirectoryEntry;
+use crate::yaml;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression as GzLevel;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ScanFileError {
+    #[error(transparent)]
+    Csv(#[from] CsvError),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("TOML error: {0}")]
+    TomlRead(#[from] toml::de::Error),
+
+    #[error("TOML error: {0}")]
+    TomlWrite(#[from] toml::ser::Error),
+
+    #[error("YAML scans can only be written; load the scan from a CSV, JSON or TOML copy")]
+    YamlInput,
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// On-disk format of a saved scan, chosen by file extension or `--format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ScanFormat {
+    Csv,
+    Json,
+    Toml,
+    /// Written only; strings are always quoted so paths need no escaping downstream
+    Yaml,
+}
+
+/// Compression of a saved scan, from a trailing `.gz` or `.zst` extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl ScanFormat {
+    /// Format and compression of `path`: `scan.json.zst` is zstd-compressed JSON; anything
+    /// that isn't JSON, TOML or YAML (`.yaml` or `.yml`) is CSV
+    pub fn from_path(path: &Path) -> (Self, Compression) {
+        let extension = |p: &Path| p.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+        let (compression, inner) = match extension(path).as_deref() {
+            Some("gz") => (Compression::Gzip, path.with_extension("")),
+            Some("zst") => (Compression::Zstd, path.with_extension("")),
+            _ => (Compression::None, path.to_path_buf()),
+        };
+        let format = match extension(&inner).as_deref() {
+            Some("json") => ScanFormat::Json,
+            Some("toml") => ScanFormat::Toml,
+            Some("yaml" | "yml") => ScanFormat::Yaml,
+            _ => ScanFormat::Csv,
+        };
+        (format, compression)
+    }
+}
+
+/// JSON, TOML and YAML layout: the CSV metadata block as fields, plus the entries
+#[derive(Serialize, Deserialize)]
+struct JsonScan {
+    schema_version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    root: Option<PathBuf>,
+    /// RFC 3339 timestamp
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scanned_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hostname: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tool_version: Option<String>,
+    entries: Vec<DirectoryEntry>,
+}
+
+/// Save a scan as CSV, JSON, TOML or YAML, optionally compressed, depending on the extension
+/// of `path`
+pub fn write_scan(entries: &[DirectoryEntry], path: &Path, metadata: &ScanMetadata) -> Result<(), ScanFileError> {
+    write_scan_as(entries, path, metadata, None)
+}
+
+/// `write_scan` in `format` (`--format`) whatever the extension; compression still follows it
+pub fn write_scan_as(
+    entries: &[DirectoryEntry],
+    path: &Path,
+    metadata: &ScanMetadata,
+    format: Option<ScanFormat>,
+) -> Result<(), ScanFileError> {
+    let (from_path, compression) = ScanFormat::from_path(path);
+    let format = format.unwrap_or(from_path);
+    let file = BufWriter::new(File::create(path)?);
+    let mut file = match compression {
+        Compression::None => write_to(file, format, entries, metadata)?,
+        Compression::Gzip => write_to(GzEncoder::new(file, GzLevel::default()), format, entries, metadata)?.finish()?,
+        Compression::Zstd => write_to(zstd::Encoder::new(file, 0)?, format, entries, metadata)?.finish()?,
+    };
+    file.flush()?;
+    Ok(())
+}
+
+fn write_to<W: Write>(
+    mut writer: W,
+    format: ScanFormat,
+    entries: &[DirectoryEntry],
+    metadata: &ScanMetadata,
+) -> Result<W, ScanFileError> {
+    match format {
+        ScanFormat::Csv => Ok(csv_handler::write_csv_to(writer, entries, metadata)?),
+        format => {
+            let scan = JsonScan {
+                schema_version: metadata.schema_version,
+                root: metadata.root.clone(),
+                scanned_at: metadata.scanned_at.map(|t| humantime::format_rfc3339_seconds(t).to_string()),
+                hostname: metadata.hostname.clone(),
+                tool_version: metadata.tool_version.clone(),
+                entries: entries.to_vec(),
+            };
+            writer.write_all(to_string(&scan, format)?.as_bytes())?;
+            Ok(writer)
+        }
+    }
+}
+
+/// `value` as pretty JSON, TOML or YAML text ending in a newline; CSV has no layout for
+/// arbitrary values, so it gives JSON
+pub fn to_string<T: Serialize>(value: &T, format: ScanFormat) -> Result<String, ScanFileError> {
+    Ok(match format {
+        ScanFormat::Csv | ScanFormat::Json => serde_json::to_string_pretty(value)? + "\n",
+        ScanFormat::Toml => toml::to_string_pretty(value)?,
+        ScanFormat::Yaml => yaml::to_string(value)?,
+    })
+}
+
+/// Load a scan saved by `write_scan` (or by an older version as CSV)
+pub fn read_scan(path: &Path) -> Result<(Vec<DirectoryEntry>, ScanMetadata), ScanFileError> {
+    let (format, compression) = ScanFormat::from_path(path);
+    let file = File::open(path)?;
+    let reader: Box<dyn BufRead> = match compression {
+        Compression::None => Box::new(BufReader::new(file)),
+        // Multi-member aware, so concatenated .gz files (e.g. from `cat a.gz b.gz`) read fully
+        Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(file))),
+        Compression::Zstd => Box::new(BufReader::new(zstd::Decoder::new(file)?)),
+    };
+
+    match format {
+        ScanFormat::Csv => Ok(csv_handler::read_csv_from(reader)?),
+        ScanFormat::Yaml => Err(ScanFileError::YamlInput),
+        format => {
+            let scan: JsonScan = match format {
+                ScanFormat::Toml => toml::from_str(&std::io::read_to_string(reader)?)?,
+                _ => serde_json::from_reader(reader)?,
+            };
+            if scan.schema_version > CSV_SCHEMA_VERSION {
+                return Err(CsvError::UnsupportedVersion {
+                    found: scan.schema_version,
+                    supported: CSV_SCHEMA_VERSION,
+                }
+                .into());
+            }
+            let scanned_at = match scan.scanned_at {
+                Some(value) => Some(humantime::parse_rfc3339(&value).map_err(|_| CsvError::ParseError {
+                    line: 0,
+                    message: format!("Invalid timestamp: {}", value),
+                })?),
+                None => None,
+            };
+            let metadata = ScanMetadata {
+                schema_version: scan.schema_version,
+                root: scan.root,
+                scanned_at,
+                hostname: scan.hostname,
+                tool_version: scan.tool_version,
+            };
+            Ok((scan.entries, metadata))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::EntryType;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_json_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("scan.JSON");
+        assert_eq!(ScanFormat::from_path(&path), (ScanFormat::Json, Compression::None));
+        assert_eq!(ScanFormat::from_path(Path::new("scan.csv")), (ScanFormat::Csv, Compression::None));
+
+        let mut metadata = ScanMetadata::new(Some(Path::new("/home/user/projects")));
+        metadata.scanned_at = Some(humantime::parse_rfc3339("2026-01-02T03:04:05Z").unwrap());
+        let entries = vec![DirectoryEntry {
+            path: PathBuf::from("/home/user/projects/app/node_modules"),
+            cumulative_size_bytes: 4096,
+            entry_type: EntryType::DependencyCache,
+            source: Some("laptop".to_string()),
+            ..Default::default()
+        }];
+        write_scan(&entries, &path, &metadata).unwrap();
+
+        let (loaded, loaded_metadata) = read_scan(&path).unwrap();
+        assert_eq!(loaded_metadata, metadata);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].path, entries[0].path);
+        assert_eq!(loaded[0].entry_type, EntryType::DependencyCache);
+        assert_eq!(loaded[0].source.as_deref(), Some("laptop"));
+
+        std::fs::write(&path, r#"{"schema_version": 9, "entries": []}"#).unwrap();
+        assert!(matches!(
+            read_scan(&path),
+            Err(ScanFileError::Csv(CsvError::UnsupportedVersion { found: 9, .. }))
+        ));
+    }
+
+    #[test]
+    fn test_compressed_scans() {
+        assert_eq!(ScanFormat::from_path(Path::new("nas.csv.gz")), (ScanFormat::Csv, Compression::Gzip));
+        assert_eq!(ScanFormat::from_path(Path::new("nas.json.ZST")), (ScanFormat::Json, Compression::Zstd));
+        assert_eq!(ScanFormat::from_path(Path::new("nas.gz")), (ScanFormat::Csv, Compression::Gzip));
+
+        let temp_dir = TempDir::new().unwrap();
+        let metadata = ScanMetadata::new(Some(Path::new("/srv/nas")));
+        let entries: Vec<DirectoryEntry> = (0..200)
+            .map(|i| DirectoryEntry {
+                path: PathBuf::from(format!("/srv/nas/share/project{}/node_modules", i)),
+                cumulative_size_bytes: i * 1000,
+                entry_type: EntryType::DependencyCache,
+                ..Default::default()
+            })
+            .collect();
+
+        let plain = temp_dir.path().join("scan.csv");
+        write_scan(&entries, &plain, &metadata).unwrap();
+        for name in ["scan.csv.gz", "scan.csv.zst", "scan.json.gz", "scan.json.zst"] {
+            let path = temp_dir.path().join(name);
+            write_scan(&entries, &path, &metadata).unwrap();
+            assert!(std::fs::metadata(&path).unwrap().len() < std::fs::metadata(&plain).unwrap().len());
+
+            let (loaded, loaded_metadata) = read_scan(&path).unwrap();
+            assert_eq!(loaded_metadata.root, metadata.root);
+            assert_eq!(loaded.len(), entries.len());
+            assert_eq!(loaded[199].path, entries[199].path);
+            assert_eq!(loaded[199].cumulative_size_bytes, 199_000);
+        }
+    }
+
+    #[test]
+    fn test_toml_and_yaml() {
+        assert_eq!(ScanFormat::from_path(Path::new("scan.toml")), (ScanFormat::Toml, Compression::None));
+        assert_eq!(ScanFormat::from_path(Path::new("scan.yml.gz")), (ScanFormat::Yaml, Compression::Gzip));
+
+        let temp_dir = TempDir::new().unwrap();
+        let metadata = ScanMetadata::new(Some(Path::new("/srv/share")));
+        let entries = vec![
+            DirectoryEntry {
+                path: PathBuf::from("/srv/share"),
+                cumulative_size_bytes: 9000,
+                ..Default::default()
+            },
+            DirectoryEntry {
+                path: PathBuf::from("/srv/share/team: \"ops\"/- build"),
+                cumulative_size_bytes: 4096,
+                entry_type: EntryType::BuildOutput,
+                ..Default::default()
+            },
+        ];
+
+        let path = temp_dir.path().join("scan.toml");
+        write_scan(&entries, &path, &metadata).unwrap();
+        let (loaded, loaded_metadata) = read_scan(&path).unwrap();
+        assert_eq!(loaded_metadata.root, metadata.root);
+        assert_eq!(loaded[1].path, entries[1].path);
+        assert_eq!(loaded[1].entry_type, EntryType::BuildOutput);
+
+        // --format wins over the extension
+        let path = temp_dir.path().join("scan.out");
+        write_scan_as(&entries, &path, &metadata, Some(ScanFormat::Yaml)).unwrap();
+        let yaml = std::fs::read_to_string(&path).unwrap();
+        assert!(yaml.starts_with("---\n"));
+        assert!(yaml.contains("    path: \"/srv/share/team: \\\"ops\\\"/- build\"\n"));
+        assert!(yaml.contains("    cumulative_size_bytes: 4096\n"));
+        assert!(matches!(read_scan(&temp_dir.path().join("x.yaml")), Err(ScanFileError::Io(_))));
+        std::fs::rename(&path, temp_dir.path().join("scan.yaml")).unwrap();
+        assert!(matches!(read_scan(&temp_dir.path().join("scan.yaml")), Err(ScanFileError::YamlInput)));
+
+        let summary = crate::report::RunSummary::new(&entries, true);
+        let toml = to_string(&summary, ScanFormat::Toml).unwrap();
+        assert!(toml.contains("total_bytes = 9000\n") && toml.contains("dry_run = true\n"));
+        assert!(to_string(&summary, ScanFormat::Yaml).unwrap().contains("temp_directories: 1\n"));
+    }
+}
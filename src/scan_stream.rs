@@ -0,0 +1,210 @@
+use crate::scanner::{self, DirectoryEntry, ScanConfig, ScanError};
+use futures_core::Stream;
+use std::collections::VecDeque;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+/// Stops a scan from any thread or task; clones stop the same scan
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Stop the scan; it then ends with [`ScanError::Cancelled`]
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// What the scan thread has handed over that wasn't taken yet
+#[derive(Default)]
+struct Results {
+    entries: VecDeque<DirectoryEntry>,
+    error: Option<ScanError>,
+    done: bool,
+    /// Task waiting in `poll_next`, woken once the scan is done
+    waker: Option<Waker>,
+}
+
+impl Results {
+    /// The next item, `Some(None)` once everything was taken, or `None` while the scan runs
+    fn take(&mut self) -> Option<Option<Result<DirectoryEntry, ScanError>>> {
+        if let Some(entry) = self.entries.pop_front() {
+            return Some(Some(Ok(entry)));
+        }
+        if let Some(error) = self.error.take() {
+            return Some(Some(Err(error)));
+        }
+        self.done.then_some(None)
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    results: Mutex<Results>,
+    /// Signalled with the waker, for callers blocked in `next`
+    done: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Results> {
+        self.results.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Entries of a scan running on a background thread, as a [`Stream`] for async callers
+/// (any runtime) or a blocking [`Iterator`]. A failed or cancelled scan ends with its
+/// error. Dropping it cancels the scan.
+///
+/// A directory's cumulative size is only known once everything below it was counted, so
+/// the entries all become ready when the scan finishes; until then the stream is pending
+/// and the iterator blocks.
+pub struct ScanStream {
+    shared: Arc<Shared>,
+    cancel: CancelToken,
+}
+
+/// Start scanning `config.root_path` on a background thread. A `config.cancel` flag that
+/// is already set up keeps working alongside the stream's [`CancelToken`].
+pub fn scan_stream(mut config: ScanConfig) -> ScanStream {
+    let cancel = CancelToken(config.cancel.get_or_insert_with(Arc::default).clone());
+    let shared = Arc::new(Shared::default());
+
+    let worker = Arc::clone(&shared);
+    thread::spawn(move || {
+        let root = config.root_path.clone();
+        // A crashed scan ends the stream with an error instead of leaving it pending forever
+        let result = panic::catch_unwind(AssertUnwindSafe(|| scanner::scan_directory(config))).unwrap_or_else(|_| {
+            Err(ScanError::IoError { path: root, source: io::Error::other("the scan crashed") })
+        });
+
+        let mut results = worker.lock();
+        match result {
+            Ok(entries) => results.entries.extend(entries),
+            Err(e) => results.error = Some(e),
+        }
+        results.done = true;
+        if let Some(waker) = results.waker.take() {
+            waker.wake();
+        }
+        worker.done.notify_all();
+    });
+
+    ScanStream { shared, cancel }
+}
+
+impl ScanStream {
+    /// Token that stops this scan, to hand to whatever decides to stop it
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+}
+
+impl Stream for ScanStream {
+    type Item = Result<DirectoryEntry, ScanError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut results = self.shared.lock();
+        match results.take() {
+            Some(item) => Poll::Ready(item),
+            None => {
+                results.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Iterator for ScanStream {
+    type Item = Result<DirectoryEntry, ScanError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut results = self.shared.lock();
+        loop {
+            if let Some(item) = results.take() {
+                return item;
+            }
+            results = self.shared.done.wait(results).unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+}
+
+impl Drop for ScanStream {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_scan_stream() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("node_modules/pkg")).unwrap();
+        fs::write(temp_dir.path().join("node_modules/pkg/index.js"), "x").unwrap();
+        let config = ScanConfig { root_path: temp_dir.path().to_path_buf(), ..Default::default() };
+
+        // Blocking; the temp directory is listed as a whole
+        let entries: Vec<DirectoryEntry> = scan_stream(config.clone()).collect::<Result<_, _>>().unwrap();
+        assert_eq!(entries.len(), 2);
+
+        // Polled the way an async runtime would
+        let mut stream = scan_stream(config);
+        let mut cx = Context::from_waker(Waker::noop());
+        let started = Instant::now();
+        let mut polled = Vec::new();
+        loop {
+            match Pin::new(&mut stream).poll_next(&mut cx) {
+                Poll::Ready(Some(entry)) => polled.push(entry.unwrap()),
+                Poll::Ready(None) => break,
+                Poll::Pending => {
+                    assert!(started.elapsed() < Duration::from_secs(10));
+                    thread::sleep(Duration::from_millis(10));
+                }
+            }
+        }
+        // Parallel scans list the same entries, not always in the same order
+        let paths = |entries: &[DirectoryEntry]| {
+            let mut paths: Vec<PathBuf> = entries.iter().map(|e| e.path.clone()).collect();
+            paths.sort();
+            paths
+        };
+        assert_eq!(paths(&polled), paths(&entries));
+    }
+
+    #[test]
+    fn test_cancelled_stream() {
+        let temp_dir = TempDir::new().unwrap();
+        let cancel = Arc::new(AtomicBool::new(true));
+        let config = ScanConfig {
+            root_path: temp_dir.path().to_path_buf(),
+            cancel: Some(Arc::clone(&cancel)),
+            ..Default::default()
+        };
+
+        let mut stream = scan_stream(config);
+        assert!(stream.cancel_token().is_cancelled());
+        assert!(matches!(stream.next(), Some(Err(ScanError::Cancelled))));
+        assert!(stream.next().is_none());
+
+        // Dropping the stream stops the scan
+        cancel.store(false, Ordering::Relaxed);
+        let stream = scan_stream(ScanConfig { root_path: temp_dir.path().to_path_buf(), cancel: Some(Arc::clone(&cancel)), ..Default::default() });
+        drop(stream);
+        assert!(cancel.load(Ordering::Relaxed));
+    }
+}
@@ -1,6 +1,11 @@
+use crate::error::AppError;
+use crate::i18n::t;
+use crate::notifications;
 use crate::scanner::{DirectoryEntry, ScanConfig};
+use crate::telemetry::{self, ResourceUsage};
+use crate::utils::format_size;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyModifiers},
+    event::{self, DisableFocusChange, EnableFocusChange, Event, KeyCode, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -15,8 +20,9 @@ use ratatui::{
 use std::io;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+#[derive(Default)]
 pub struct ScanProgress {
     pub files_scanned: u64,
     pub dirs_scanned: u64,
@@ -25,28 +31,26 @@ pub struct ScanProgress {
 
 impl ScanProgress {
     pub fn new() -> Self {
-        Self {
-            files_scanned: 0,
-            dirs_scanned: 0,
-            current_path: String::new(),
-        }
+        Self::default()
     }
 }
 
-pub fn scan_with_progress(config: ScanConfig) -> Result<Vec<DirectoryEntry>, Box<dyn std::error::Error>> {
+pub fn scan_with_progress(config: ScanConfig) -> Result<Vec<DirectoryEntry>, AppError> {
     let progress = Arc::new(Mutex::new(ScanProgress::new()));
     let progress_clone = Arc::clone(&progress);
     let progress_for_scan = Arc::clone(&progress);
+    let started = Instant::now();
+    let baseline = ResourceUsage::sample();
 
     // Spawn scanning thread
     let scan_handle = thread::spawn(move || {
         crate::scanner::scan_directory_with_progress(config, Some(progress_for_scan))
     });
 
-    // Setup terminal for progress display
+    // Setup terminal for progress display; focus changes tell whether a notification is needed
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableFocusChange)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
@@ -57,16 +61,19 @@ pub fn scan_with_progress(config: ScanConfig) -> Result<Vec<DirectoryEntry>, Box
     loop {
         // Check for keyboard events (Ctrl-C or 'q' to quit)
         if event::poll(Duration::from_millis(80))? {
-            if let Event::Key(key) = event::read()? {
-                if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) 
-                    || key.code == KeyCode::Char('q') {
+            match event::read()? {
+                Event::Key(key) if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) 
+                    || key.code == KeyCode::Char('q') => {
                     // Restore terminal before exiting
                     disable_raw_mode()?;
-                    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+                    execute!(terminal.backend_mut(), DisableFocusChange, LeaveAlternateScreen)?;
                     terminal.show_cursor()?;
-                    println!("\nScan cancelled by user.");
+                    println!("\n{}", t!("scan-cancelled"));
                     std::process::exit(130); // Standard exit code for Ctrl-C
                 }
+                Event::FocusGained => notifications::set_focused(true),
+                Event::FocusLost => notifications::set_focused(false),
+                _ => {}
             }
         }
 
@@ -75,7 +82,7 @@ pub fn scan_with_progress(config: ScanConfig) -> Result<Vec<DirectoryEntry>, Box
         }
 
         terminal.draw(|f| {
-            render_scan_progress(f, &progress_clone, spinner_frames[frame_idx]);
+            render_scan_progress(f, &progress_clone, spinner_frames[frame_idx], started, &baseline);
         })?;
 
         frame_idx = (frame_idx + 1) % spinner_frames.len();
@@ -83,24 +90,25 @@ pub fn scan_with_progress(config: ScanConfig) -> Result<Vec<DirectoryEntry>, Box
 
     // Restore terminal
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(terminal.backend_mut(), DisableFocusChange, LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
     // Get scan result
-    let result = scan_handle.join().map_err(|_| "Scan thread panicked")??;
+    let result = scan_handle.join().map_err(|_| AppError::Message(t!("error-scan-panicked")))??;
     
     Ok(result)
 }
 
-fn render_scan_progress(f: &mut Frame, progress: &Arc<Mutex<ScanProgress>>, spinner: &str) {
+fn render_scan_progress(f: &mut Frame, progress: &Arc<Mutex<ScanProgress>>, spinner: &str, started: Instant, baseline: &ResourceUsage) {
     let prog = progress.lock().unwrap();
+    let usage = ResourceUsage::sample();
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),
             Constraint::Length(3),
-            Constraint::Length(3),
+            Constraint::Length(4),
             Constraint::Length(5),
             Constraint::Length(3),
             Constraint::Min(0),
@@ -110,7 +118,7 @@ fn render_scan_progress(f: &mut Frame, progress: &Arc<Mutex<ScanProgress>>, spin
     // Title
     let title = Paragraph::new(vec![
         Line::from(vec![
-            Span::styled("🔍 Scanning Filesystem", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::styled(format!("🔍 {}", t!("scan-title")), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
         ]),
     ])
     .alignment(Alignment::Center)
@@ -121,7 +129,7 @@ fn render_scan_progress(f: &mut Frame, progress: &Arc<Mutex<ScanProgress>>, spin
     let status = Paragraph::new(vec![
         Line::from(vec![
             Span::styled(spinner, Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-            Span::raw("  Scanning directories..."),
+            Span::raw(format!("  {}", t!("scan-scanning"))),
         ]),
     ])
     .alignment(Alignment::Center)
@@ -131,11 +139,12 @@ fn render_scan_progress(f: &mut Frame, progress: &Arc<Mutex<ScanProgress>>, spin
     // Stats
     let stats = Paragraph::new(vec![
         Line::from(vec![
-            Span::raw("Directories: "),
+            Span::raw(format!("{}: ", t!("list-directories"))),
             Span::styled(format!("{}", prog.dirs_scanned), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            Span::raw("  |  Files: "),
+            Span::raw(format!("  |  {}: ", t!("column-files"))),
             Span::styled(format!("{}", prog.files_scanned), Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD)),
         ]),
+        resource_line(&prog, started.elapsed(), baseline, &usage),
     ])
     .alignment(Alignment::Center)
     .block(Block::default().borders(Borders::ALL));
@@ -150,27 +159,47 @@ fn render_scan_progress(f: &mut Frame, progress: &Arc<Mutex<ScanProgress>>, spin
 
     let current = Paragraph::new(vec![
         Line::from(vec![
-            Span::styled("Current: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(format!("{}: ", t!("scan-current")), Style::default().fg(Color::DarkGray)),
         ]),
         Line::from(vec![
             Span::styled(path_display, Style::default().fg(Color::Gray)),
         ]),
     ])
     .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL).title(" Current Path "));
+    .block(Block::default().borders(Borders::ALL).title(format!(" {} ", t!("scan-current-path"))));
     f.render_widget(current, chunks[3]);
 
     // Help text
     let help = Paragraph::new(vec![
         Line::from(vec![
-            Span::styled("Press ", Style::default().fg(Color::DarkGray)),
+            Span::styled(format!("{} ", t!("scan-press")), Style::default().fg(Color::DarkGray)),
             Span::styled("Ctrl-C", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-            Span::styled(" or ", Style::default().fg(Color::DarkGray)),
+            Span::styled(format!(" {} ", t!("key-or")), Style::default().fg(Color::DarkGray)),
             Span::styled("q", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-            Span::styled(" to cancel", Style::default().fg(Color::DarkGray)),
+            Span::styled(format!(" {}", t!("scan-to-cancel")), Style::default().fg(Color::DarkGray)),
         ]),
     ])
     .alignment(Alignment::Center)
     .block(Block::default().borders(Borders::ALL));
     f.render_widget(help, chunks[4]);
 }
+
+/// Elapsed time, memory, storage reads and rate so far, as far as the platform tells
+fn resource_line(progress: &ScanProgress, elapsed: Duration, baseline: &ResourceUsage, usage: &ResourceUsage) -> Line<'static> {
+    let secs = elapsed.as_secs();
+    let mut spans = vec![
+        Span::styled(format!("{}: ", t!("telemetry-progress-elapsed")), Style::default().fg(Color::DarkGray)),
+        Span::raw(format!("{}:{:02}", secs / 60, secs % 60)),
+    ];
+    if let Some(memory) = usage.memory_bytes.or(usage.peak_memory_bytes) {
+        spans.push(Span::styled(format!("  |  {}: ", t!("telemetry-progress-memory")), Style::default().fg(Color::DarkGray)));
+        spans.push(Span::raw(format_size(memory)));
+    }
+    if let (Some(start), Some(now)) = (baseline.read_bytes, usage.read_bytes) {
+        spans.push(Span::styled(format!("  |  {}: ", t!("telemetry-progress-read")), Style::default().fg(Color::DarkGray)));
+        spans.push(Span::raw(format_size(now.saturating_sub(start))));
+    }
+    spans.push(Span::styled("  |  ", Style::default().fg(Color::DarkGray)));
+    spans.push(Span::raw(t!("telemetry-rate", rate = telemetry::rate(progress.dirs_scanned, elapsed))));
+    Line::from(spans)
+}
@@ -0,0 +1,291 @@
+use clap::ValueEnum;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ScheduleError {
+    #[error("Cannot write {path}: {source}")]
+    IoError { path: PathBuf, source: io::Error },
+
+    #[error("'{command}' failed: {message}")]
+    CommandFailed { command: String, message: String },
+}
+
+/// How often a scheduled cleanup runs
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Frequency {
+    Hourly,
+    Daily,
+    /// Mondays
+    Weekly,
+    /// The first of the month
+    Monthly,
+}
+
+/// What `schedule` sets up
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScheduleFormat {
+    /// A systemd user service and timer
+    Systemd,
+    /// A line of the user's crontab
+    Cron,
+}
+
+/// Parse a time of day such as "03:00" for `--at`
+pub fn parse_time(text: &str) -> Result<(u8, u8), String> {
+    let (hour, minute) = text.split_once(':').ok_or_else(|| format!("expected HH:MM, got '{}'", text))?;
+    match (hour.parse::<u8>(), minute.parse::<u8>()) {
+        (Ok(hour), Ok(minute)) if hour < 24 && minute < 60 => Ok((hour, minute)),
+        _ => Err(format!("'{}' is not a time of day", text)),
+    }
+}
+
+/// A headless cleanup run at regular times, with its output appended to a log file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    /// Unit name suffix and crontab marker, e.g. the profile name
+    pub name: String,
+    /// Program and arguments to run
+    pub command: Vec<String>,
+    pub frequency: Frequency,
+    /// Hour and minute; hourly runs only use the minute
+    pub at: (u8, u8),
+    pub log: PathBuf,
+}
+
+impl Schedule {
+    /// `name` is reduced to characters that are safe in unit names
+    pub fn new(name: &str, command: Vec<String>, frequency: Frequency, at: (u8, u8), log: PathBuf) -> Self {
+        let name = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+            .collect();
+        Self { name, command, frequency, at, log }
+    }
+
+    /// Default log: `<data dir>/disk-cleanup-tool/logs/<name>.log`
+    pub fn default_log(name: &str) -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("disk-cleanup-tool").join("logs").join(format!("{}.log", name)))
+    }
+
+    /// Where systemd looks for user units: `<config dir>/systemd/user`
+    pub fn systemd_user_dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("systemd").join("user"))
+    }
+
+    pub fn unit_name(&self) -> String {
+        format!("disk-cleanup-{}", self.name)
+    }
+
+    fn on_calendar(&self) -> String {
+        let (hour, minute) = self.at;
+        match self.frequency {
+            Frequency::Hourly => format!("*-*-* *:{:02}:00", minute),
+            Frequency::Daily => format!("*-*-* {:02}:{:02}:00", hour, minute),
+            Frequency::Weekly => format!("Mon *-*-* {:02}:{:02}:00", hour, minute),
+            Frequency::Monthly => format!("*-*-01 {:02}:{:02}:00", hour, minute),
+        }
+    }
+
+    fn cron_times(&self) -> String {
+        let (hour, minute) = self.at;
+        match self.frequency {
+            Frequency::Hourly => format!("{} * * * *", minute),
+            Frequency::Daily => format!("{} {} * * *", minute, hour),
+            Frequency::Weekly => format!("{} {} * * 1", minute, hour),
+            Frequency::Monthly => format!("{} {} 1 * *", minute, hour),
+        }
+    }
+
+    /// The service and timer unit files
+    pub fn systemd_units(&self) -> (String, String) {
+        let exec = self.command.iter().map(|arg| systemd_quote(arg)).collect::<Vec<_>>().join(" ");
+        let log_dir = self.log.parent().unwrap_or(Path::new("/"));
+        let service = format!(
+            "[Unit]\n\
+             Description=Disk cleanup ({name})\n\
+             \n\
+             [Service]\n\
+             Type=oneshot\n\
+             ExecStartPre=mkdir -p {log_dir}\n\
+             ExecStart={exec}\n\
+             StandardOutput=append:{log}\n\
+             StandardError=append:{log}\n\
+             Nice=10\n\
+             IOSchedulingClass=idle\n",
+            name = self.name,
+            log_dir = systemd_quote(&log_dir.to_string_lossy()),
+            exec = exec,
+            log = self.log.to_string_lossy().replace('%', "%%"),
+        );
+        let timer = format!(
+            "[Unit]\n\
+             Description=Run disk cleanup ({name}) {frequency}\n\
+             \n\
+             [Timer]\n\
+             OnCalendar={calendar}\n\
+             Persistent=true\n\
+             \n\
+             [Install]\n\
+             WantedBy=timers.target\n",
+            name = self.name,
+            frequency = format!("{:?}", self.frequency).to_lowercase(),
+            calendar = self.on_calendar(),
+        );
+        (service, timer)
+    }
+
+    /// Marker comment that identifies this schedule's line in a crontab
+    fn cron_marker(&self) -> String {
+        format!("# disk-cleanup-tool:{}", self.name)
+    }
+
+    /// The crontab line, ending with a marker so installing again replaces it
+    pub fn crontab_line(&self) -> String {
+        let command = self.command.iter().map(|arg| shell_quote(arg)).collect::<Vec<_>>().join(" ");
+        let log = shell_quote(&self.log.to_string_lossy());
+        let log_dir = shell_quote(&self.log.parent().unwrap_or(Path::new("/")).to_string_lossy());
+        // cron turns unescaped % into newlines
+        let line = format!("mkdir -p {} && {} >> {} 2>&1", log_dir, command, log).replace('%', "\\%");
+        format!("{} {} {}", self.cron_times(), line, self.cron_marker())
+    }
+
+    /// Write the units to `dir` and enable the timer with `systemctl --user`
+    pub fn install_systemd(&self, dir: &Path) -> Result<Vec<PathBuf>, ScheduleError> {
+        let (service, timer) = self.systemd_units();
+        fs::create_dir_all(dir).map_err(|source| ScheduleError::IoError { path: dir.to_path_buf(), source })?;
+        let mut written = Vec::new();
+        for (extension, content) in [("service", service), ("timer", timer)] {
+            let path = dir.join(format!("{}.{}", self.unit_name(), extension));
+            fs::write(&path, content).map_err(|source| ScheduleError::IoError { path: path.clone(), source })?;
+            written.push(path);
+        }
+        run(Command::new("systemctl").args(["--user", "daemon-reload"]), None)?;
+        run(
+            Command::new("systemctl").args(["--user", "enable", "--now", &format!("{}.timer", self.unit_name())]),
+            None,
+        )?;
+        Ok(written)
+    }
+
+    /// Add the line to the user's crontab, replacing an earlier line of this schedule
+    pub fn install_cron(&self) -> Result<(), ScheduleError> {
+        // `crontab -l` fails when the user has no crontab yet
+        let current = run(Command::new("crontab").arg("-l"), None).unwrap_or_default();
+        let updated = self.updated_crontab(&current);
+        run(Command::new("crontab").arg("-"), Some(&updated)).map(|_| ())
+    }
+
+    fn updated_crontab(&self, current: &str) -> String {
+        let marker = self.cron_marker();
+        let mut lines: Vec<&str> = current.lines().filter(|line| !line.ends_with(&marker)).collect();
+        let line = self.crontab_line();
+        lines.push(&line);
+        lines.join("\n") + "\n"
+    }
+}
+
+/// Run `command`, feeding it `input`, and return its stdout
+fn run(command: &mut Command, input: Option<&str>) -> Result<String, ScheduleError> {
+    let program = format!("{:?}", command);
+    let failed = |message: String| ScheduleError::CommandFailed {
+        command: program.clone(),
+        message,
+    };
+    let mut child = command
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| failed(e.to_string()))?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input.as_bytes()).map_err(|e| failed(e.to_string()))?;
+    }
+    let output = child.wait_with_output().map_err(|e| failed(e.to_string()))?;
+    if !output.status.success() {
+        return Err(failed(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn is_plain(arg: &str) -> bool {
+    !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:=@+,".contains(c))
+}
+
+/// Quote an argument for a systemd `ExecStart=` line
+fn systemd_quote(arg: &str) -> String {
+    // systemd expands specifiers (%h) and environment variables ($HOME) in command lines
+    let escaped = arg.replace('%', "%%").replace('$', "$$");
+    if is_plain(arg) {
+        escaped
+    } else {
+        format!("\"{}\"", escaped.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/// Quote an argument for `sh`
+fn shell_quote(arg: &str) -> String {
+    if is_plain(arg) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(frequency: Frequency) -> Schedule {
+        Schedule::new(
+            "node dev",
+            vec!["/usr/bin/disk-cleanup-tool".into(), "--profile".into(), "node dev".into(), "--yes".into()],
+            frequency,
+            (3, 5),
+            PathBuf::from("/home/me/.local/share/disk-cleanup-tool/logs/node-dev.log"),
+        )
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("03:00"), Ok((3, 0)));
+        assert_eq!(parse_time("23:59"), Ok((23, 59)));
+        assert!(parse_time("24:00").is_err());
+        assert!(parse_time("3").is_err());
+    }
+
+    #[test]
+    fn test_systemd_units() {
+        let (service, timer) = schedule(Frequency::Weekly).systemd_units();
+        assert!(service.contains("ExecStart=/usr/bin/disk-cleanup-tool --profile \"node dev\" --yes\n"));
+        assert!(service.contains("StandardOutput=append:/home/me/.local/share/disk-cleanup-tool/logs/node-dev.log\n"));
+        assert!(service.contains("Type=oneshot\n"));
+        assert!(timer.contains("OnCalendar=Mon *-*-* 03:05:00\n"));
+        assert!(timer.contains("Persistent=true\n"));
+        assert_eq!(schedule(Frequency::Hourly).on_calendar(), "*-*-* *:05:00");
+        assert_eq!(schedule(Frequency::Monthly).on_calendar(), "*-*-01 03:05:00");
+        assert_eq!(schedule(Frequency::Daily).unit_name(), "disk-cleanup-node-dev");
+        assert_eq!(systemd_quote("50%$HOME"), "\"50%%$$HOME\"");
+    }
+
+    #[test]
+    fn test_crontab() {
+        let line = schedule(Frequency::Daily).crontab_line();
+        assert_eq!(
+            line,
+            "5 3 * * * mkdir -p /home/me/.local/share/disk-cleanup-tool/logs && \
+             /usr/bin/disk-cleanup-tool --profile 'node dev' --yes \
+             >> /home/me/.local/share/disk-cleanup-tool/logs/node-dev.log 2>&1 # disk-cleanup-tool:node-dev"
+        );
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+
+        // Installing again replaces the line and keeps everything else
+        let weekly = schedule(Frequency::Weekly);
+        let crontab = weekly.updated_crontab(&format!("MAILTO=me\n{}\n0 * * * * backup\n", line));
+        assert_eq!(crontab, format!("MAILTO=me\n0 * * * * backup\n{}\n", weekly.crontab_line()));
+        assert_eq!(weekly.updated_crontab(""), format!("{}\n", weekly.crontab_line()));
+    }
+}
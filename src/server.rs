@@ -0,0 +1,685 @@
+use crate::app::{self, Recheck};
+use crate::cli::OpenFilesCheck;
+use crate::csv_handler::ScanMetadata;
+use crate::deletion::{delete_directories_cancellable, FailedPath};
+use crate::push::ResultsPayload;
+use crate::report::RunSummary;
+use crate::scanner::{self, DirectoryEntry, ScanConfig};
+use crate::trash::Trash;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+
+/// Environment variable holding the token that enables `POST /api/delete`; it isn't a flag
+/// so it never shows up in `ps`
+pub const TOKEN_VARIABLE: &str = "DISK_CLEANUP_SERVE_TOKEN";
+
+/// Largest request body accepted
+const MAX_BODY: usize = 1024 * 1024;
+
+/// Largest request line and headers accepted, together
+const MAX_HEAD: u64 = 16 * 1024;
+
+/// Clients served at once; more are turned away with 503
+const MAX_CONNECTIONS: usize = 32;
+
+/// A client that stops sending is dropped after this long
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum ServeError {
+    #[error("Cannot listen on {addr}: {source}")]
+    Bind { addr: String, source: io::Error },
+}
+
+/// What `--serve` may do besides scanning and reporting
+#[derive(Debug, Clone, Default)]
+pub struct ServerOptions {
+    /// Scanned on `POST /api/scan`; clients can't choose other paths
+    pub roots: Vec<PathBuf>,
+    /// Classifier and scan switches used for every root
+    pub scan: ScanConfig,
+    /// Bearer token for `POST /api/delete`; without one, deleting is disabled
+    pub token: Option<String>,
+    pub read_only: bool,
+    pub dry_run: bool,
+    pub allow_tracked: bool,
+    /// Leave out directories modified this recently (`--skip-active`)
+    pub skip_active: Option<Duration>,
+    pub open_files: Option<OpenFilesCheck>,
+    /// Move deleted directories here instead of removing them (`--trash`)
+    pub trash: Option<Trash>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+enum ScanState {
+    Idle,
+    Running { started_at: String },
+    Finished { started_at: String, finished_at: String },
+    Failed { started_at: String, error: String },
+    Cancelled { started_at: String, cancelled_at: String },
+}
+
+struct LatestScan {
+    metadata: ScanMetadata,
+    summary: RunSummary,
+    entries: Vec<DirectoryEntry>,
+}
+
+struct State {
+    scan: ScanState,
+    latest: Option<LatestScan>,
+    /// Stops the running scan when set
+    cancel: Option<Arc<AtomicBool>>,
+    /// A deletion is running; scans and other deletions wait for it
+    deleting: bool,
+}
+
+/// Clears `State::deleting` however the deletion ends
+struct Deleting<'a>(&'a Mutex<State>);
+
+impl Drop for Deleting<'_> {
+    fn drop(&mut self) {
+        lock(self.0).deleting = false;
+    }
+}
+
+/// A panic while the lock was held leaves every field assigned whole, so the state is
+/// still usable
+fn lock(state: &Mutex<State>) -> MutexGuard<'_, State> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A parsed HTTP request
+#[derive(Debug, Default)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+
+    fn query_flag(&self, name: &str) -> bool {
+        self.query.split('&').any(|pair| pair == name || pair == format!("{}=true", name) || pair == format!("{}=1", name))
+    }
+}
+
+/// A JSON response
+#[derive(Debug)]
+pub struct Response {
+    pub status: u16,
+    pub body: serde_json::Value,
+}
+
+impl Response {
+    fn ok(body: serde_json::Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self { status, body: json!({ "error": message }) }
+    }
+}
+
+#[derive(Deserialize)]
+struct DeleteRequest {
+    paths: Vec<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct DeleteResponse {
+    deleted: Vec<PathBuf>,
+    failed: Vec<FailedPath>,
+    cancelled: Vec<PathBuf>,
+    freed_bytes: u64,
+}
+
+/// JSON API for driving scans from other machines or a small web UI:
+///
+/// ```text
+/// GET  /api/status       idle, running, finished, failed or cancelled, with timestamps
+/// POST /api/scan         start scanning the configured roots (202; 409 while one runs)
+/// POST /api/scan/cancel  stop the running scan (202; 409 when none runs)
+/// GET  /api/results      summary and entries of the latest scan (?temp_only=true)
+/// POST /api/delete       {"paths": [...]} from the latest results; needs the bearer token
+/// ```
+pub struct Server {
+    options: ServerOptions,
+    state: Arc<Mutex<State>>,
+    connections: AtomicUsize,
+}
+
+impl Server {
+    pub fn new(options: ServerOptions) -> Self {
+        Self {
+            options,
+            state: Arc::new(Mutex::new(State { scan: ScanState::Idle, latest: None, cancel: None, deleting: false })),
+            connections: AtomicUsize::new(0),
+        }
+    }
+
+    /// Answer requests on `addr` until the process is stopped
+    pub fn serve(&self, addr: &str) -> Result<(), ServeError> {
+        let listener = TcpListener::bind(addr).map_err(|source| ServeError::Bind {
+            addr: addr.to_string(),
+            source,
+        })?;
+        self.serve_on(listener);
+        Ok(())
+    }
+
+    fn serve_on(&self, listener: TcpListener) {
+        thread::scope(|scope| {
+            for mut stream in listener.incoming().flatten() {
+                if self.connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                    self.connections.fetch_sub(1, Ordering::SeqCst);
+                    let _ = write_response(&mut stream, &Response::error(503, "too many connections"));
+                    continue;
+                }
+                // A slow deletion or client doesn't hold up the others, and a broken
+                // connection only affects its own client
+                scope.spawn(move || {
+                    let _ = self.handle_connection(stream);
+                    self.connections.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+    }
+
+    fn handle_connection(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = BufReader::new(stream);
+        let response = match read_request(&mut reader) {
+            Ok(request) => self.handle(&request),
+            Err(e) => Response::error(400, &e.to_string()),
+        };
+        write_response(reader.get_mut(), &response)
+    }
+
+    pub fn handle(&self, request: &Request) -> Response {
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/api/status") => Response::ok(json!(lock(&self.state).scan)),
+            ("POST", "/api/scan") => self.start_scan(),
+            ("POST", "/api/scan/cancel") => self.cancel_scan(),
+            ("GET", "/api/results") => self.results(request.query_flag("temp_only")),
+            ("POST", "/api/delete") => self.delete(request),
+            (_, "/api/status" | "/api/scan" | "/api/scan/cancel" | "/api/results" | "/api/delete") => {
+                Response::error(405, "method not allowed")
+            }
+            _ => Response::error(404, "not found"),
+        }
+    }
+
+    fn start_scan(&self) -> Response {
+        let mut state = lock(&self.state);
+        if matches!(state.scan, ScanState::Running { .. }) {
+            return Response::error(409, "a scan is already running");
+        }
+        if state.deleting {
+            return Response::error(409, "a deletion is running; scan once it has finished");
+        }
+        let started = SystemTime::now();
+        let started_at = rfc3339(started);
+        state.scan = ScanState::Running { started_at: started_at.clone() };
+        let cancel = Arc::new(AtomicBool::new(false));
+        state.cancel = Some(Arc::clone(&cancel));
+        drop(state);
+
+        let options = self.options.clone();
+        let state = Arc::clone(&self.state);
+        thread::spawn(move || {
+            let metadata = ScanMetadata::new(options.roots.first().map(|r| r.as_path()));
+            // A crashed scan is reported as failed instead of leaving it running forever
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| scan_roots(&options, &cancel)))
+                .unwrap_or_else(|_| Outcome::Failed("the scan crashed".to_string()));
+
+            // The previous results stay available after a cancelled scan
+            let mut state = lock(&state);
+            state.cancel = None;
+            state.scan = match outcome {
+                Outcome::Cancelled => ScanState::Cancelled { started_at, cancelled_at: rfc3339(SystemTime::now()) },
+                Outcome::Failed(error) => ScanState::Failed { started_at, error },
+                Outcome::Finished(entries) => {
+                    state.latest = Some(LatestScan {
+                        metadata,
+                        summary: RunSummary::new(&entries, options.dry_run),
+                        entries,
+                    });
+                    ScanState::Finished { started_at, finished_at: rfc3339(SystemTime::now()) }
+                }
+            };
+        });
+        Response { status: 202, body: json!({ "state": "running", "started_at": rfc3339(started) }) }
+    }
+
+    fn cancel_scan(&self) -> Response {
+        let state = lock(&self.state);
+        match (&state.scan, &state.cancel) {
+            (ScanState::Running { .. }, Some(cancel)) => {
+                cancel.store(true, Ordering::Relaxed);
+                Response { status: 202, body: json!({ "state": "cancelling" }) }
+            }
+            _ => Response::error(409, "no scan is running"),
+        }
+    }
+
+    fn results(&self, temp_only: bool) -> Response {
+        let state = lock(&self.state);
+        let Some(latest) = &state.latest else {
+            return Response::error(404, "no scan has finished yet; POST /api/scan first");
+        };
+        let temp: Vec<DirectoryEntry>;
+        let entries = if temp_only {
+            temp = latest.entries.iter().filter(|e| e.entry_type.is_temp()).cloned().collect();
+            &temp
+        } else {
+            &latest.entries
+        };
+        Response::ok(json!(ResultsPayload::new(&latest.metadata, &latest.summary, Some(entries))))
+    }
+
+    fn delete(&self, request: &Request) -> Response {
+        let Some(token) = &self.options.token else {
+            return Response::error(403, &format!("deleting is disabled; set {} to enable it", TOKEN_VARIABLE));
+        };
+        let authorized = request
+            .header("Authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|given| constant_time_eq(given.trim().as_bytes(), token.as_bytes()));
+        if !authorized {
+            return Response::error(401, "missing or wrong bearer token");
+        }
+        if self.options.read_only {
+            return Response::error(403, "read-only mode: nothing can be deleted");
+        }
+        let paths = match serde_json::from_slice::<DeleteRequest>(&request.body) {
+            Ok(body) => body.paths,
+            Err(e) => return Response::error(400, &format!("expected {{\"paths\": [...]}}: {}", e)),
+        };
+
+        let mut state = lock(&self.state);
+        if matches!(state.scan, ScanState::Running { .. }) {
+            return Response::error(409, "a scan is running; delete once it has finished");
+        }
+        if state.deleting {
+            return Response::error(409, "a deletion is already running");
+        }
+        let Some(latest) = state.latest.as_ref() else {
+            return Response::error(409, "no scan results to delete from");
+        };
+        // Only directories the scan listed, and never a root, can be deleted
+        let is_root = scanner::is_root_of(&self.options.roots);
+        let unknown: Vec<&PathBuf> = paths
+            .iter()
+            .filter(|path| !latest.entries.iter().any(|e| &e.path == *path && e.alias_of.is_none() && !is_root(e)))
+            .collect();
+        if !unknown.is_empty() {
+            let list = unknown.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ");
+            return Response::error(400, &format!("not in the latest scan results: {}", list));
+        }
+        // No scan replaces the results until this deletion is over, but status and results
+        // requests are answered meanwhile
+        state.deleting = true;
+        drop(state);
+        let _deleting = Deleting(&self.state);
+
+        // The same checks as deleting from the command line
+        let mut allowed = paths;
+        let mut reasons: BTreeMap<PathBuf, Vec<String>> = BTreeMap::new();
+        let mut blocked = |path: &Path, reason: String| reasons.entry(path.to_path_buf()).or_default().push(reason);
+        let active_since = self.options.skip_active.and_then(|window| SystemTime::now().checked_sub(window));
+        let open_files = self.options.open_files;
+        app::recheck(&mut allowed, active_since, self.options.allow_tracked, open_files, |event| match event {
+            Recheck::Active(path) => blocked(path, "modified recently, may be in use".to_string()),
+            Recheck::Tracked(path, count) => blocked(path, format!("contains {} git-tracked files", count)),
+            Recheck::InUse(holder) if open_files == Some(OpenFilesCheck::Block) => blocked(
+                &holder.path,
+                format!("in use by {} (pid {})", holder.process_name, holder.pid),
+            ),
+            _ => {}
+        });
+        let mut failed: Vec<FailedPath> = reasons
+            .into_iter()
+            .map(|(path, reasons)| FailedPath { path, reason: reasons.join("; ") })
+            .collect();
+        if self.options.dry_run {
+            return Response::ok(json!({ "dry_run": true, "would_delete": allowed, "skipped": failed }));
+        }
+
+        let mut batch = self.options.trash.as_ref().map(Trash::batch);
+        let report = match delete_directories_cancellable(&allowed, &Default::default(), &AtomicBool::new(false), batch.as_mut()) {
+            Ok(report) => report,
+            Err(e) => return Response::error(500, &e.to_string()),
+        };
+        // Deleted directories and everything inside them are gone from the results
+        if let Some(latest) = lock(&self.state).latest.as_mut() {
+            latest
+                .entries
+                .retain(|e| !report.successful.iter().any(|deleted| e.path.starts_with(deleted)));
+            latest.summary.add_deletion(&report);
+        }
+        failed.extend(report.failures());
+        Response::ok(json!(DeleteResponse {
+            deleted: report.successful,
+            failed,
+            cancelled: report.cancelled,
+            freed_bytes: report.total_freed_bytes,
+        }))
+    }
+}
+
+/// How a scan of all roots ended
+enum Outcome {
+    Finished(Vec<DirectoryEntry>),
+    Failed(String),
+    Cancelled,
+}
+
+fn scan_roots(options: &ServerOptions, cancel: &Arc<AtomicBool>) -> Outcome {
+    let mut entries = Vec::new();
+    let mut error = None;
+    for root in &options.roots {
+        let config = ScanConfig {
+            root_path: root.clone(),
+            cancel: Some(Arc::clone(cancel)),
+            ..options.scan.clone()
+        };
+        match scanner::scan_directory(config) {
+            Ok(found) => entries.extend(found),
+            Err(scanner::ScanError::Cancelled) => return Outcome::Cancelled,
+            Err(e) => error = Some(format!("{}: {}", root.display(), e)),
+        }
+    }
+    if let Some(error) = error {
+        return Outcome::Failed(error);
+    }
+    let mut seen = HashSet::new();
+    entries.retain(|e: &DirectoryEntry| seen.insert(e.path.clone()));
+    Outcome::Finished(entries)
+}
+
+fn rfc3339(time: SystemTime) -> String {
+    humantime::format_rfc3339_seconds(time).to_string()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Read one line of the request head, at most `budget` bytes of what is left of it
+fn read_head_line<R: BufRead>(reader: &mut R, budget: &mut u64) -> io::Result<String> {
+    let mut line = String::new();
+    let read = reader.by_ref().take(*budget).read_line(&mut line)?;
+    *budget -= read as u64;
+    if *budget == 0 && !line.ends_with('\n') {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "request head too large"));
+    }
+    Ok(line)
+}
+
+fn read_request<R: BufRead>(reader: &mut R) -> io::Result<Request> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    let mut budget = MAX_HEAD;
+    let line = read_head_line(reader, &mut budget)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(invalid("malformed request line"));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut request = Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query.to_string(),
+        ..Default::default()
+    };
+
+    loop {
+        let line = read_head_line(reader, &mut budget)?;
+        if line.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            request.headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let length = match request.header("Content-Length") {
+        Some(value) => value.parse::<usize>().map_err(|_| invalid("invalid Content-Length"))?,
+        None => 0,
+    };
+    if length > MAX_BODY {
+        return Err(invalid("request body too large"));
+    }
+    request.body = vec![0; length];
+    reader.read_exact(&mut request.body)?;
+    Ok(request)
+}
+
+fn write_response<W: Write>(writer: &mut W, response: &Response) -> io::Result<()> {
+    let body = response.body.to_string();
+    let reason = match response.status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    };
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason,
+        body.len(),
+        body
+    )?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn request(method: &str, target: &str, body: &str, token: Option<&str>) -> Request {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: query.to_string(),
+            headers: token.map(|t| ("authorization".to_string(), format!("Bearer {}", t))).into_iter().collect(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    fn wait_for_scan(server: &Server) -> serde_json::Value {
+        for _ in 0..500 {
+            let status = server.handle(&request("GET", "/api/status", "", None)).body;
+            if status["state"] != "running" {
+                return status;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("scan did not finish");
+    }
+
+    fn project() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("app/node_modules/pkg")).unwrap();
+        fs::write(temp_dir.path().join("app/node_modules/pkg/index.js"), "x").unwrap();
+        fs::write(temp_dir.path().join("app/main.js"), "y").unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn test_scan_and_results() {
+        let temp_dir = project();
+        let root = temp_dir.path().to_path_buf();
+        let server = Server::new(ServerOptions { roots: vec![root.clone()], ..Default::default() });
+
+        assert_eq!(server.handle(&request("GET", "/api/status", "", None)).body["state"], "idle");
+        assert_eq!(server.handle(&request("GET", "/api/results", "", None)).status, 404);
+        assert_eq!(server.handle(&request("POST", "/api/scan", "", None)).status, 202);
+        assert_eq!(wait_for_scan(&server)["state"], "finished");
+
+        let results = server.handle(&request("GET", "/api/results", "", None)).body;
+        assert_eq!(results["root"], root.display().to_string());
+        assert!(results["entries"].as_array().unwrap().len() >= 3);
+        let temp = server.handle(&request("GET", "/api/results?temp_only=true", "", None)).body;
+        assert_eq!(temp["entries"].as_array().unwrap().len(), 1);
+        assert_eq!(temp["summary"]["temp_directories"], 1);
+
+        assert_eq!(server.handle(&request("GET", "/api/scan", "", None)).status, 405);
+        assert_eq!(server.handle(&request("GET", "/", "", None)).status, 404);
+    }
+
+    #[test]
+    fn test_cancel_scan() {
+        let temp_dir = project();
+        for i in 0..200 {
+            fs::create_dir_all(temp_dir.path().join(format!("dir{}/sub", i))).unwrap();
+        }
+        let server = Server::new(ServerOptions { roots: vec![temp_dir.path().to_path_buf()], ..Default::default() });
+        assert_eq!(server.handle(&request("POST", "/api/scan/cancel", "", None)).status, 409);
+
+        // The scan may finish before the request arrives; either way it ends consistently
+        server.handle(&request("POST", "/api/scan", "", None));
+        let expected = match server.handle(&request("POST", "/api/scan/cancel", "", None)).status {
+            202 => "cancelled",
+            _ => "finished",
+        };
+        assert_eq!(wait_for_scan(&server)["state"], expected);
+        assert_eq!(server.handle(&request("GET", "/api/results", "", None)).status == 404, expected == "cancelled");
+        assert_eq!(server.handle(&request("GET", "/api/scan/cancel", "", None)).status, 405);
+    }
+
+    #[test]
+    fn test_delete() {
+        let temp_dir = project();
+        let root = temp_dir.path().to_path_buf();
+        let node_modules = root.join("app/node_modules");
+        let options = ServerOptions { roots: vec![root.clone()], ..Default::default() };
+        let body = json!({ "paths": [node_modules] }).to_string();
+
+        // Without a configured token, deleting is off
+        let server = Server::new(options.clone());
+        assert_eq!(server.handle(&request("POST", "/api/delete", &body, Some("secret"))).status, 403);
+
+        let server = Server::new(ServerOptions { token: Some("secret".to_string()), ..options });
+        assert_eq!(server.handle(&request("POST", "/api/delete", &body, None)).status, 401);
+        assert_eq!(server.handle(&request("POST", "/api/delete", &body, Some("wrong"))).status, 401);
+        assert_eq!(server.handle(&request("POST", "/api/delete", &body, Some("secret"))).status, 409);
+
+        server.handle(&request("POST", "/api/scan", "", None));
+        wait_for_scan(&server);
+        // Roots and paths the scan didn't list are refused
+        for path in [root.clone(), PathBuf::from("/etc")] {
+            let body = json!({ "paths": [path] }).to_string();
+            assert_eq!(server.handle(&request("POST", "/api/delete", &body, Some("secret"))).status, 400);
+        }
+        assert!(root.exists());
+
+        let response = server.handle(&request("POST", "/api/delete", &body, Some("secret")));
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body["deleted"][0], node_modules.display().to_string());
+        assert!(!node_modules.exists());
+        let results = server.handle(&request("GET", "/api/results?temp_only", "", None)).body;
+        assert!(results["entries"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delete_rechecks() {
+        let temp_dir = project();
+        let node_modules = temp_dir.path().join("app/node_modules");
+        let server = Server::new(ServerOptions {
+            roots: vec![temp_dir.path().to_path_buf()],
+            token: Some("secret".to_string()),
+            skip_active: Some(Duration::from_secs(3600)),
+            ..Default::default()
+        });
+        server.handle(&request("POST", "/api/scan", "", None));
+        wait_for_scan(&server);
+
+        // Just created, so it counts as in use, as it would with --skip-active on the command line
+        let body = json!({ "paths": [node_modules] }).to_string();
+        let response = server.handle(&request("POST", "/api/delete", &body, Some("secret")));
+        assert_eq!(response.status, 200);
+        assert!(response.body["deleted"].as_array().unwrap().is_empty());
+        assert_eq!(response.body["failed"][0]["path"], node_modules.display().to_string());
+        assert!(node_modules.exists());
+
+        // Scans and other deletions wait while one runs
+        lock(&server.state).deleting = true;
+        assert_eq!(server.handle(&request("POST", "/api/scan", "", None)).status, 409);
+        assert_eq!(server.handle(&request("POST", "/api/delete", &body, Some("secret"))).status, 409);
+        assert_eq!(server.handle(&request("GET", "/api/results", "", None)).status, 200);
+    }
+
+    #[test]
+    fn test_poisoned_state() {
+        let server = Server::new(ServerOptions::default());
+        let state = Arc::clone(&server.state);
+        let _ = thread::spawn(move || {
+            let _state = state.lock().unwrap();
+            panic!("poison the lock");
+        })
+        .join();
+        assert!(server.state.is_poisoned());
+        assert_eq!(server.handle(&request("GET", "/api/status", "", None)).body["state"], "idle");
+    }
+
+    #[test]
+    fn test_read_request_limits() {
+        let request = read_request(&mut &b"GET /api/status HTTP/1.1\r\nHost: x\r\n\r\n"[..]).unwrap();
+        assert_eq!(request.path, "/api/status");
+        assert_eq!(request.header("host"), Some("x"));
+
+        let long_line = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_HEAD as usize));
+        assert!(read_request(&mut long_line.as_bytes()).is_err());
+        let many_headers = format!("GET / HTTP/1.1\r\n{}\r\n", "X-Pad: 0123456789\r\n".repeat(2000));
+        assert!(read_request(&mut many_headers.as_bytes()).is_err());
+        let big_body = format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY + 1);
+        assert!(read_request(&mut big_body.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_http_roundtrip() {
+        let temp_dir = project();
+        let server = Server::new(ServerOptions { roots: vec![temp_dir.path().to_path_buf()], ..Default::default() });
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || server.serve_on(listener));
+
+        let response = ureq::post(&format!("{}/api/scan", url)).call().unwrap();
+        assert_eq!(response.status(), 202);
+        assert_eq!(response.header("Content-Type"), Some("application/json"));
+        match ureq::get(&format!("{}/nowhere", url)).call() {
+            Err(ureq::Error::Status(404, response)) => {
+                let body: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+                assert_eq!(body["error"], "not found");
+            }
+            other => panic!("expected 404, got {:?}", other.map(|r| r.status())),
+        }
+    }
+}
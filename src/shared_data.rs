@@ -0,0 +1,164 @@
+use std::collections::HashSet;
+use std::fs::Metadata;
+use std::path::Path;
+
+/// Filesystem snapshot directory, skipped by scans unless `--include-snapshots` is given:
+/// snapper's `.snapshots` (btrfs), ZFS's `.zfs` control directory, and the `.snapshot` /
+/// `~snapshot` directories of NetApp and Isilon shares. Snapshots share their data with the
+/// live files, so counting them multiplies the apparent usage.
+pub fn is_snapshot_dir(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| matches!(name, ".snapshots" | ".snapshot" | "~snapshot" | ".zfs"))
+}
+
+/// Counts data shared between files (reflinks and clones on btrfs and XFS, deduplicated
+/// extents) once per scan. Linux only; elsewhere every file counts in full.
+#[derive(Debug, Default)]
+pub struct SharedExtents {
+    /// (device, physical offset) of every shared extent counted so far
+    seen: HashSet<(u64, u64)>,
+}
+
+impl SharedExtents {
+    /// `size` minus the shared extents of the file already counted for another file
+    pub fn unique_bytes(&mut self, path: &Path, metadata: &Metadata, size: u64) -> u64 {
+        if size == 0 {
+            return 0;
+        }
+        match (device(metadata), fiemap::shared_extents(path)) {
+            (Some(device), Some(shared)) => self.count(device, size, &shared),
+            _ => size,
+        }
+    }
+
+    /// Record `shared` extents as (physical offset, length) and subtract those seen before
+    fn count(&mut self, device: u64, size: u64, shared: &[(u64, u64)]) -> u64 {
+        let counted_before: u64 = shared
+            .iter()
+            .filter(|(physical, _)| !self.seen.insert((device, *physical)))
+            .map(|(_, length)| length)
+            .sum();
+        size.saturating_sub(counted_before)
+    }
+}
+
+#[cfg(unix)]
+fn device(metadata: &Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.dev())
+}
+
+#[cfg(not(unix))]
+fn device(_metadata: &Metadata) -> Option<u64> {
+    None
+}
+
+/// The `FS_IOC_FIEMAP` ioctl, which lists a file's extents and flags the shared ones
+#[cfg(target_os = "linux")]
+mod fiemap {
+    use std::fs::File;
+    use std::os::unix::io::AsRawFd;
+    use std::path::Path;
+
+    const FS_IOC_FIEMAP: u32 = 0xC020_660B;
+    const FIEMAP_EXTENT_LAST: u32 = 0x1;
+    const FIEMAP_EXTENT_SHARED: u32 = 0x2000;
+    /// Extents fetched per call
+    const BATCH: usize = 32;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Default)]
+    struct Extent {
+        logical: u64,
+        physical: u64,
+        length: u64,
+        reserved64: [u64; 2],
+        flags: u32,
+        reserved: [u32; 3],
+    }
+
+    #[repr(C)]
+    struct Request {
+        start: u64,
+        length: u64,
+        flags: u32,
+        mapped_extents: u32,
+        extent_count: u32,
+        reserved: u32,
+        extents: [Extent; BATCH],
+    }
+
+    /// Shared extents of the file as (physical offset, length); `None` when the file can't
+    /// be opened or the filesystem doesn't support FIEMAP
+    pub fn shared_extents(path: &Path) -> Option<Vec<(u64, u64)>> {
+        let file = File::open(path).ok()?;
+        let mut shared = Vec::new();
+        let mut start = 0;
+        loop {
+            let mut request = Request {
+                start,
+                length: u64::MAX - start,
+                flags: 0,
+                mapped_extents: 0,
+                extent_count: BATCH as u32,
+                reserved: 0,
+                extents: [Extent::default(); BATCH],
+            };
+            // SAFETY: `request` is a writable fiemap header followed by room for BATCH extents,
+            // as announced in `extent_count`
+            if unsafe { libc::ioctl(file.as_raw_fd(), FS_IOC_FIEMAP as _, &mut request) } != 0 {
+                return None;
+            }
+            let extents = &request.extents[..(request.mapped_extents as usize).min(BATCH)];
+            shared.extend(
+                extents
+                    .iter()
+                    .filter(|extent| extent.flags & FIEMAP_EXTENT_SHARED != 0)
+                    .map(|extent| (extent.physical, extent.length)),
+            );
+            match extents.last() {
+                Some(last) if last.flags & FIEMAP_EXTENT_LAST == 0 => start = last.logical + last.length,
+                _ => return Some(shared),
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod fiemap {
+    use std::path::Path;
+
+    pub fn shared_extents(_path: &Path) -> Option<Vec<(u64, u64)>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_snapshot_dir() {
+        assert!(is_snapshot_dir(Path::new("/home/.snapshots")));
+        assert!(is_snapshot_dir(Path::new("/tank/data/.zfs")));
+        assert!(is_snapshot_dir(Path::new("/mnt/filer/.snapshot")));
+        assert!(!is_snapshot_dir(Path::new("/home/me/snapshots")));
+    }
+
+    #[test]
+    fn test_shared_extents_counted_once() {
+        let mut extents = SharedExtents::default();
+        // A file and its reflinked copy share one 4 KB extent; the copy changed 1 KB
+        assert_eq!(extents.count(1, 4096, &[(8192, 4096)]), 4096);
+        assert_eq!(extents.count(1, 5120, &[(8192, 4096)]), 1024);
+        // The same offset on another device is different data
+        assert_eq!(extents.count(2, 4096, &[(8192, 4096)]), 4096);
+
+        // Unshared files count in full
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), "hello").unwrap();
+        let metadata = std::fs::metadata(temp_file.path()).unwrap();
+        assert_eq!(extents.unique_bytes(temp_file.path(), &metadata, 5), 5);
+    }
+}
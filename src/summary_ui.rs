@@ -1,5 +1,9 @@
-use crate::scanner::{DirectoryEntry, EntryType};
-use crate::utils::format_size;
+use crate::ages;
+use crate::i18n::t;
+use crate::report;
+use crate::columns::{format_age, format_entry_path, format_entry_size, format_share_with_bar, heat_color, parent_shares, size_width};
+use crate::scanner::{self, DirectoryEntry};
+use crate::utils::{format_size, inode_usage, InodeUsage, SortKey};
 use crossterm::{
     event::{self, Event, KeyCode},
     execute,
@@ -10,18 +14,24 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
     Frame, Terminal,
 };
+use std::collections::HashMap;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Width of the "% of parent" column: percentage plus inline bar
+const PARENT_WIDTH: u16 = 17;
 
 pub enum SummaryAction {
     Continue,
     LaunchInteractive,
 }
 
-pub fn show_summary(entries: &[DirectoryEntry], root_path: &PathBuf) -> io::Result<SummaryAction> {
+/// Show the scan summary; `entries` are expected in `sort` order
+pub fn show_summary(entries: &[DirectoryEntry], root_path: &Path, sort: SortKey) -> io::Result<SummaryAction> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -29,7 +39,7 @@ pub fn show_summary(entries: &[DirectoryEntry], root_path: &PathBuf) -> io::Resu
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let result = run_summary_ui(&mut terminal, entries, root_path);
+    let result = run_summary_ui(&mut terminal, entries, root_path, sort);
 
     // Restore terminal
     disable_raw_mode()?;
@@ -42,13 +52,17 @@ pub fn show_summary(entries: &[DirectoryEntry], root_path: &PathBuf) -> io::Resu
 fn run_summary_ui(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     entries: &[DirectoryEntry],
-    root_path: &PathBuf,
+    root_path: &Path,
+    sort: SortKey,
 ) -> io::Result<SummaryAction> {
     let mut scroll_offset = 0usize;
-    
+    let shares = parent_shares(entries);
+    // Filesystem inode usage only matters when ranking by file count
+    let inodes = (sort == SortKey::Files).then(|| inode_usage(root_path)).flatten();
+
     loop {
         terminal.draw(|f| {
-            render_summary(f, entries, root_path, scroll_offset);
+            render_summary(f, entries, &shares, root_path, scroll_offset, sort, inodes);
         })?;
 
         if event::poll(std::time::Duration::from_millis(100))? {
@@ -85,132 +99,216 @@ fn run_summary_ui(
     }
 }
 
-fn render_summary(f: &mut Frame, entries: &[DirectoryEntry], root_path: &PathBuf, scroll_offset: usize) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(7),  // Header with stats
-            Constraint::Min(0),     // Top directories list
-            Constraint::Length(3),  // Footer
-        ])
-        .split(f.area());
-
+fn render_summary(
+    f: &mut Frame,
+    entries: &[DirectoryEntry],
+    shares: &HashMap<PathBuf, f64>,
+    root_path: &Path,
+    scroll_offset: usize,
+    sort: SortKey,
+    inodes: Option<InodeUsage>,
+) {
     // Calculate stats
-    let root_entry = entries.iter().find(|e| &e.path == root_path);
-    let temp_count = entries.iter().filter(|e| matches!(e.entry_type, EntryType::Temp)).count();
+    let root_entry = scanner::find_root(entries, root_path);
+    let temp_count = entries.iter().filter(|e| e.entry_type.is_temp()).count();
     let temp_size: u64 = entries.iter()
-        .filter(|e| matches!(e.entry_type, EntryType::Temp))
-        .map(|e| e.cumulative_size_bytes)
+        .filter(|e| e.entry_type.is_temp())
+        .map(|e| e.shown_size())
         .sum();
 
+    // Dead data at a glance: bytes by last-modified age (scans that recorded file ages only)
+    let by_age = ages::age_report(entries, root_path).overall;
+    let mut age_line = vec![Span::raw(format!("{}: ", t!("summary-by-age")))];
+    for (index, label) in ages::bucket_labels().iter().enumerate() {
+        if index > 0 {
+            age_line.push(Span::raw("  |  "));
+        }
+        let color = if index == ages::BUCKET_COLUMNS.len() - 1 { Color::Red } else { Color::White };
+        age_line.push(Span::raw(format!("{} ", label)));
+        age_line.push(Span::styled(ages::format_bucket(&by_age, index), Style::default().fg(color).add_modifier(Modifier::BOLD)));
+    }
+
     // Header
-    let header_lines = if let Some(root) = root_entry {
+    let mut header_lines = if let Some(root) = root_entry {
         vec![
             Line::from(vec![
-                Span::styled("📊 Scan Summary", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::styled(format!("📊 {}", t!("summary-title")), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
             ]),
             Line::from(""),
             Line::from(vec![
-                Span::raw("Root: "),
+                Span::raw(format!("{}: ", t!("summary-root"))),
                 Span::styled(root_path.display().to_string(), Style::default().fg(Color::White)),
             ]),
             Line::from(vec![
-                Span::raw("Total directories: "),
+                Span::raw(format!("{}: ", t!("summary-total-dirs"))),
                 Span::styled(format!("{}", entries.len()), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-                Span::raw("  |  Files: "),
+                Span::raw(format!("  |  {}: ", t!("column-files"))),
                 Span::styled(format!("{}", root.cumulative_file_count), Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD)),
-                Span::raw("  |  Size: "),
-                Span::styled(format_size(root.cumulative_size_bytes), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                Span::raw(format!("  |  {}: ", t!("label-size"))),
+                Span::styled(format_entry_size(root), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
             ]),
             Line::from(vec![
-                Span::raw("Temp directories: "),
+                Span::raw(format!("{}: ", t!("summary-temp-dirs"))),
                 Span::styled(format!("{}", temp_count), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-                Span::raw("  |  Temp size: "),
+                Span::raw(format!("  |  {}: ", t!("summary-temp-size"))),
                 Span::styled(format_size(temp_size), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
             ]),
         ]
     } else {
         vec![
             Line::from(vec![
-                Span::styled("📊 Scan Summary", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::styled(format!("📊 {}", t!("summary-title")), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
             ]),
             Line::from(""),
             Line::from(vec![
-                Span::raw("Total directories: "),
+                Span::raw(format!("{}: ", t!("summary-total-dirs"))),
                 Span::styled(format!("{}", entries.len()), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
             ]),
             Line::from(vec![
-                Span::raw("Temp directories: "),
+                Span::raw(format!("{}: ", t!("summary-temp-dirs"))),
                 Span::styled(format!("{}", temp_count), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-                Span::raw("  |  Temp size: "),
+                Span::raw(format!("  |  {}: ", t!("summary-temp-size"))),
                 Span::styled(format_size(temp_size), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
             ]),
         ]
     };
 
+    if !by_age.is_empty() {
+        header_lines.push(Line::from(age_line));
+    }
+    // Unreadable directories make every size above them a lower bound
+    let errors: u64 = report::top_level(entries).iter().map(|e| e.errors).sum();
+    if errors > 0 {
+        header_lines.push(Line::from(Span::styled(
+            format!("⚠ {}", t!("summary-incomplete", count = errors)),
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+    if entries.iter().any(|e| e.estimated) {
+        header_lines.push(Line::from(Span::styled(format!("≈ {}", t!("summary-estimated")), Style::default().fg(Color::Yellow))));
+    }
+    // Single files that often dominate developer machines, listed in interactive mode
+    let images: Vec<u64> = entries.iter().flat_map(|e| e.images.iter().map(|image| image.on_disk)).collect();
+    if !images.is_empty() {
+        let size = format_size(images.iter().sum());
+        header_lines.push(Line::from(Span::styled(
+            format!("💿 {}", t!("summary-images", count = images.len(), size = size)),
+            Style::default().fg(Color::Magenta),
+        )));
+    }
+    if let Some(inodes) = inodes {
+        header_lines.push(Line::from(vec![
+            Span::raw(format!("{}: ", t!("summary-filesystem"))),
+            Span::styled(inodes.describe(), Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)),
+        ]));
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(header_lines.len() as u16 + 2),  // Header with stats
+            Constraint::Min(0),     // Top directories list
+            Constraint::Length(3),  // Footer
+        ])
+        .split(f.area());
+
     let header = Paragraph::new(header_lines)
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Cyan)));
     f.render_widget(header, chunks[0]);
 
-    // Top directories list
-    let list_height = chunks[1].height.saturating_sub(2) as usize;
+    // Top directories table; modified/parent columns are dropped on narrow terminals
+    let list_height = chunks[1].height.saturating_sub(3) as usize;
     let display_count = 20.min(entries.len());
-    
-    let items: Vec<ListItem> = entries
+    let inner_width = chunks[1].width.saturating_sub(2);
+    let show_details = inner_width >= 90;
+    let fixed = 4 + 2 + size_width() + 12 + if show_details { 9 + PARENT_WIDTH } else { 0 };
+    let column_count = if show_details { 7 } else { 5 };
+    let path_width = inner_width.saturating_sub(fixed + column_count - 1);
+
+    let mut widths = vec![
+        Constraint::Length(4),
+        Constraint::Length(2),
+        Constraint::Length(path_width),
+        Constraint::Length(size_width()),
+        Constraint::Length(12),
+    ];
+    let mut header = vec![
+        Cell::from(Line::from("#").right_aligned()),
+        Cell::from(""),
+        Cell::from(t!("column-path")),
+        Cell::from(Line::from(t!("column-size")).right_aligned()),
+        Cell::from(Line::from(t!("column-files")).right_aligned()),
+    ];
+    if show_details {
+        widths.extend([Constraint::Length(9), Constraint::Length(PARENT_WIDTH)]);
+        header.extend([Cell::from(t!("column-modified")), Cell::from(t!("column-parent-share"))]);
+    }
+
+    let now = SystemTime::now();
+    let largest = entries.iter().map(|e| e.shown_size()).max().unwrap_or(0);
+    let rows: Vec<Row> = entries
         .iter()
         .take(display_count)
         .skip(scroll_offset)
         .take(list_height)
         .enumerate()
         .map(|(idx, entry)| {
-            let type_marker = match entry.entry_type {
-                EntryType::Temp => "🗑 ",
-                EntryType::Normal => "📁 ",
-            };
+            let type_marker = if entry.entry_type.is_temp() { "🗑" } else { "📁" };
             
             let rank = scroll_offset + idx + 1;
             
-            ListItem::new(Line::from(vec![
-                Span::styled(format!("{:2}. ", rank), Style::default().fg(Color::DarkGray)),
-                Span::raw(type_marker),
-                Span::styled(
-                    entry.path.display().to_string(),
-                    if matches!(entry.entry_type, EntryType::Temp) {
+            let mut cells = vec![
+                Cell::from(Line::from(format!("{}.", rank)).right_aligned()).style(Style::default().fg(Color::DarkGray)),
+                Cell::from(type_marker),
+                Cell::from(format_entry_path(entry, path_width as usize)).style(
+                    if entry.entry_type.is_temp() {
                         Style::default().fg(Color::Red)
                     } else {
                         Style::default().fg(Color::White)
                     }
                 ),
-                Span::raw(" - "),
-                Span::styled(format_size(entry.cumulative_size_bytes), Style::default().fg(Color::Yellow)),
-                Span::raw(" ("),
-                Span::styled(format!("{} files", entry.cumulative_file_count), Style::default().fg(Color::Blue)),
-                Span::raw(")"),
-            ]))
+                Cell::from(Line::from(format_entry_size(entry)).right_aligned())
+                    .style(Style::default().fg(heat_color(entry.shown_size(), largest))),
+                Cell::from(Line::from(t!("count-files", count = entry.cumulative_file_count)).right_aligned())
+                    .style(Style::default().fg(Color::Blue)),
+            ];
+            if show_details {
+                cells.push(Cell::from(format_age(entry.modified, now)).style(Style::default().fg(Color::DarkGray)));
+                let share = shares.get(&entry.path).copied();
+                cells.push(Cell::from(format_share_with_bar(share)).style(Style::default().fg(Color::Magenta)));
+            }
+            Row::new(cells)
         })
         .collect();
 
-    let list = List::new(items)
+    let table = Table::new(rows, widths)
+        .column_spacing(1)
+        .header(Row::new(header).style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)))
         .block(Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::White))
-            .title(format!(" Top {} Largest Directories ", display_count)));
-    f.render_widget(list, chunks[1]);
+            .title(match sort {
+                SortKey::Size => format!(" {} ", t!("summary-top-size", count = display_count)),
+                SortKey::Files => format!(" {} ", t!("summary-top-files", count = display_count)),
+                SortKey::Safety => format!(" {} ", t!("summary-top-safety", count = display_count)),
+                SortKey::Growth => format!(" {} ", t!("summary-top-growth", count = display_count)),
+            }));
+    f.render_widget(table, chunks[1]);
 
     // Footer
     let footer = Paragraph::new(vec![
         Line::from(vec![
             Span::styled("↑/↓", Style::default().fg(Color::Cyan)),
-            Span::raw(" or "),
+            Span::raw(format!(" {} ", t!("key-or"))),
             Span::styled("j/k", Style::default().fg(Color::Cyan)),
-            Span::raw(": Scroll  |  "),
+            Span::raw(format!(": {}  |  ", t!("key-scroll"))),
             Span::styled("PgUp/PgDn", Style::default().fg(Color::Cyan)),
-            Span::raw(": Page  |  "),
+            Span::raw(format!(": {}  |  ", t!("key-page"))),
             Span::styled("i", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            Span::raw(": Interactive mode  |  "),
+            Span::raw(format!(": {}  |  ", t!("key-interactive"))),
             Span::styled("q", Style::default().fg(Color::Green)),
-            Span::raw(": Exit"),
+            Span::raw(format!(": {}", t!("key-exit"))),
         ]),
     ])
     .alignment(Alignment::Center)
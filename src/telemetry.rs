@@ -0,0 +1,167 @@
+use crate::i18n::t;
+use crate::utils::format_size;
+use serde::Serialize;
+use std::time::Duration;
+
+/// This process's own resource usage at one moment; what a platform can't tell is `None`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    /// Resident memory now (Linux)
+    pub memory_bytes: Option<u64>,
+    /// Most resident memory so far
+    pub peak_memory_bytes: Option<u64>,
+    /// User plus system CPU time so far
+    pub cpu: Duration,
+    /// Bytes fetched from storage so far (Linux; not counted for network filesystems)
+    pub read_bytes: Option<u64>,
+    /// Times the filesystem had to go to storage for input so far
+    pub block_reads: Option<u64>,
+}
+
+impl ResourceUsage {
+    #[cfg(unix)]
+    pub fn sample() -> Self {
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        // SAFETY: `usage` is a writable rusage
+        let rusage = (unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } == 0).then_some(usage);
+        let time = |tv: libc::timeval| Duration::new(tv.tv_sec as u64, tv.tv_usec as u32 * 1000);
+        // ru_maxrss is in kilobytes on Linux and in bytes on macOS
+        let max_rss_unit = if cfg!(target_os = "macos") { 1 } else { 1024 };
+        let (status, io) = proc_files();
+        Self {
+            memory_bytes: status.as_deref().and_then(|status| proc_field(status, "VmRSS:")).map(|kb| kb * 1024),
+            peak_memory_bytes: rusage.map(|usage| usage.ru_maxrss as u64 * max_rss_unit),
+            cpu: rusage.map(|usage| time(usage.ru_utime) + time(usage.ru_stime)).unwrap_or_default(),
+            read_bytes: io.as_deref().and_then(|io| proc_field(io, "read_bytes:")),
+            block_reads: rusage.map(|usage| usage.ru_inblock as u64),
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn sample() -> Self {
+        Self::default()
+    }
+}
+
+/// /proc/self/status and /proc/self/io, where they exist
+#[cfg(unix)]
+fn proc_files() -> (Option<String>, Option<String>) {
+    (
+        std::fs::read_to_string("/proc/self/status").ok(),
+        std::fs::read_to_string("/proc/self/io").ok(),
+    )
+}
+
+/// The number after `key` in a `/proc` file of "key: value" lines ("VmRSS:   1234 kB")
+fn proc_field(text: &str, key: &str) -> Option<u64> {
+    text.lines()
+        .find_map(|line| line.strip_prefix(key))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|value| value.parse().ok())
+}
+
+/// What a scan cost: time, memory and IO between two samples, for tuning and for telling
+/// a slow network filesystem from a slow scanner
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ScanTelemetry {
+    pub elapsed_ms: u64,
+    pub cpu_ms: u64,
+    /// The whole process's peak, which the scan usually sets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_memory_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_reads: Option<u64>,
+    pub directories_per_second: u64,
+}
+
+impl ScanTelemetry {
+    pub fn between(start: &ResourceUsage, end: &ResourceUsage, elapsed: Duration, directories: u64) -> Self {
+        let since = |start: Option<u64>, end: Option<u64>| Some(end?.saturating_sub(start?));
+        Self {
+            elapsed_ms: elapsed.as_millis() as u64,
+            cpu_ms: end.cpu.saturating_sub(start.cpu).as_millis() as u64,
+            peak_memory_bytes: end.peak_memory_bytes,
+            read_bytes: since(start.read_bytes, end.read_bytes),
+            block_reads: since(start.block_reads, end.block_reads),
+            directories_per_second: rate(directories, elapsed),
+        }
+    }
+
+    /// "⏱ 12.3s, CPU 8.1s, peak memory 85.0 MB, read 1.20 GB (3400 block reads), 2300 dirs/s"
+    pub fn describe(&self) -> String {
+        let mut parts = vec![
+            t!("telemetry-elapsed", time = format!("{:.1}s", self.elapsed_ms as f64 / 1000.0)),
+            t!("telemetry-cpu", time = format!("{:.1}s", self.cpu_ms as f64 / 1000.0)),
+        ];
+        if let Some(peak) = self.peak_memory_bytes {
+            parts.push(t!("telemetry-peak-memory", size = format_size(peak)));
+        }
+        match (self.read_bytes, self.block_reads) {
+            (Some(bytes), Some(ops)) => parts.push(t!("telemetry-read-ops", size = format_size(bytes), ops = ops)),
+            (Some(bytes), None) => parts.push(t!("telemetry-read", size = format_size(bytes))),
+            (None, Some(ops)) => parts.push(t!("telemetry-block-reads", ops = ops)),
+            (None, None) => {}
+        }
+        parts.push(t!("telemetry-rate", rate = self.directories_per_second));
+        format!("⏱ {}", parts.join(", "))
+    }
+}
+
+/// Items per second over `elapsed`, 0 for an instant
+pub fn rate(count: u64, elapsed: Duration) -> u64 {
+    let seconds = elapsed.as_secs_f64();
+    if seconds > 0.0 {
+        (count as f64 / seconds) as u64
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proc_field() {
+        let status = "Name:\tdisk-cleanup-to\nVmHWM:\t   90124 kB\nVmRSS:\t   81234 kB\n";
+        assert_eq!(proc_field(status, "VmRSS:"), Some(81234));
+        assert_eq!(proc_field(status, "VmSwap:"), None);
+        let io = "rchar: 5000\nwchar: 10\nread_bytes: 4096\n";
+        assert_eq!(proc_field(io, "read_bytes:"), Some(4096));
+    }
+
+    #[test]
+    fn test_scan_telemetry() {
+        let start = ResourceUsage {
+            cpu: Duration::from_millis(200),
+            read_bytes: Some(1000),
+            block_reads: None,
+            ..Default::default()
+        };
+        let end = ResourceUsage {
+            peak_memory_bytes: Some(80 << 20),
+            cpu: Duration::from_millis(1700),
+            read_bytes: Some(5096),
+            block_reads: Some(12),
+            ..Default::default()
+        };
+        let telemetry = ScanTelemetry::between(&start, &end, Duration::from_secs(4), 10_000);
+        assert_eq!(telemetry.cpu_ms, 1500);
+        assert_eq!(telemetry.read_bytes, Some(4096));
+        assert_eq!(telemetry.block_reads, None);
+        assert_eq!(telemetry.directories_per_second, 2500);
+        let text = telemetry.describe();
+        assert!(text.contains("4.0s") && text.contains("CPU 1.5s") && text.contains("2500 dirs/s"));
+
+        let json = serde_json::to_string(&telemetry).unwrap();
+        assert!(json.contains("\"elapsed_ms\":4000") && !json.contains("block_reads"));
+
+        // Sampling itself works wherever the tool runs
+        let now = ResourceUsage::sample();
+        if cfg!(target_os = "linux") {
+            assert!(now.memory_bytes.is_some_and(|bytes| bytes > 0));
+        }
+    }
+}
@@ -0,0 +1,546 @@
+use crate::i18n::t;
+use crate::utils::format_size;
+#[cfg(all(unix, not(target_os = "macos")))]
+use crate::xdg_trash::{self, XdgTrash};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TrashError {
+    #[error("Cannot use the deletion journal {path}: {source}")]
+    IoError { path: PathBuf, source: io::Error },
+
+    #[error("Invalid deletion journal {path} at line {line}: {source}")]
+    JsonError {
+        path: PathBuf,
+        line: usize,
+        source: serde_json::Error,
+    },
+
+    #[error("Nothing to undo: no directories are waiting in the trash")]
+    NothingToUndo,
+}
+
+/// One line of the deletion journal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum JournalEntry {
+    /// `original` was moved to `trashed` by the deletion run `batch`
+    Trash {
+        batch: String,
+        original: PathBuf,
+        trashed: PathBuf,
+        size: u64,
+    },
+    /// `original` was sent to the Windows Recycle Bin by the deletion run `batch`
+    Recycle {
+        batch: String,
+        original: PathBuf,
+        size: u64,
+    },
+    /// Everything `batch` moved to the trash has been restored
+    Undo { batch: String },
+}
+
+/// A directory waiting in the trash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashedItem {
+    pub original: PathBuf,
+    /// Where it is in the trash folder; `None` for the Windows Recycle Bin, which restores
+    /// items itself
+    pub trashed: Option<PathBuf>,
+    pub size: u64,
+}
+
+/// Where `--trash` moves directories instead of deleting them, with a journal of every
+/// move so `undo` can put the most recent batch back.
+///
+/// ```text
+/// <data dir>/disk-cleanup-tool/trash/
+///     journal.jsonl
+///     1760682600-4242/0-node_modules
+///     1760682600-4242/1-target
+/// ```
+///
+/// On Linux and other freedesktop systems, directories go to the desktop trash instead, and
+/// on Windows to the Recycle Bin, so the file manager lists and restores them too; the
+/// folder then only holds the journal.
+#[derive(Debug, Clone)]
+pub struct Trash {
+    dir: PathBuf,
+    backend: Backend,
+    /// Refuse moves that would grow a trash directory past this (`trash_max_size`)
+    max_size: Option<u64>,
+}
+
+/// Where moved directories end up
+#[derive(Debug, Clone)]
+enum Backend {
+    /// Numbered batch folders next to the journal
+    Folder,
+    #[cfg(windows)]
+    RecycleBin,
+    #[cfg(all(unix, not(target_os = "macos")))]
+    Xdg(XdgTrash),
+}
+
+impl Trash {
+    /// Default location: the desktop trash on Linux, the Recycle Bin on Windows and
+    /// `<data dir>/disk-cleanup-tool/trash` elsewhere, with the journal in the latter
+    pub fn default_location() -> Option<Trash> {
+        let dir = dirs::data_dir()?.join("disk-cleanup-tool").join("trash");
+        #[cfg(windows)]
+        let backend = Backend::RecycleBin;
+        #[cfg(all(unix, not(target_os = "macos")))]
+        let backend = Backend::Xdg(XdgTrash::home()?);
+        #[cfg(not(any(windows, all(unix, not(target_os = "macos")))))]
+        let backend = Backend::Folder;
+        Some(Trash { backend, ..Trash::new(dir) })
+    }
+
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir, backend: Backend::Folder, max_size: None }
+    }
+
+    /// Cap each trash directory at `max_size` bytes
+    pub fn with_max_size(mut self, max_size: Option<u64>) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// What a deletion run reports after moving `count` directories
+    pub fn moved_message(&self, count: usize) -> String {
+        match &self.backend {
+            Backend::Folder => t!("trash-moved", count = count, dir = self.dir.display().to_string()),
+            #[cfg(windows)]
+            Backend::RecycleBin => t!("trash-moved-recycle-bin", count = count),
+            #[cfg(all(unix, not(target_os = "macos")))]
+            Backend::Xdg(_) => t!("trash-moved-desktop", count = count),
+        }
+    }
+
+    fn journal_path(&self) -> PathBuf {
+        self.dir.join("journal.jsonl")
+    }
+
+    /// Start a batch for one deletion run; it gets its own folder in the trash
+    pub fn batch(&self) -> TrashBatch<'_> {
+        // Batches started in the same second by the same process still get their own folders
+        static BATCHES: AtomicU64 = AtomicU64::new(0);
+        let started = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let sequence = BATCHES.fetch_add(1, Ordering::Relaxed);
+        TrashBatch {
+            trash: self,
+            id: format!("{}-{}-{}", started, std::process::id(), sequence),
+            moved: 0,
+            used: HashMap::new(),
+        }
+    }
+
+    fn append(&self, entry: &JournalEntry) -> Result<(), TrashError> {
+        let path = self.journal_path();
+        let io_error = |source| TrashError::IoError { path: path.clone(), source };
+        let mut line = serde_json::to_string(entry).map_err(|source| TrashError::JsonError {
+            path: path.clone(),
+            line: 0,
+            source,
+        })?;
+        line.push('\n');
+        fs::create_dir_all(&self.dir).map_err(io_error)?;
+        let mut file = OpenOptions::new().create(true).append(true).open(&path).map_err(io_error)?;
+        // One write per line, so an interrupted run never leaves half a record behind
+        file.write_all(line.as_bytes()).map_err(io_error)?;
+        file.sync_all().map_err(io_error)
+    }
+
+    /// Every journal entry, oldest first; a missing journal is an empty one
+    pub fn entries(&self) -> Result<Vec<JournalEntry>, TrashError> {
+        let path = self.journal_path();
+        let file = match fs::File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(source) => return Err(TrashError::IoError { path, source }),
+        };
+        let mut entries = Vec::new();
+        for (idx, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|source| TrashError::IoError { path: path.clone(), source })?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_str(&line).map_err(|source| TrashError::JsonError {
+                path: path.clone(),
+                line: idx + 1,
+                source,
+            })?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
+    /// The most recent batch that has not been undone, with what it moved in the order the
+    /// directories were moved
+    pub fn last_batch(&self) -> Result<(String, Vec<TrashedItem>), TrashError> {
+        let entries = self.entries()?;
+        let undone: HashSet<&str> = entries
+            .iter()
+            .filter_map(|entry| match entry {
+                JournalEntry::Undo { batch } => Some(batch.as_str()),
+                JournalEntry::Trash { .. } | JournalEntry::Recycle { .. } => None,
+            })
+            .collect();
+        let last = entries.iter().rev().find_map(|entry| match entry {
+            JournalEntry::Trash { batch, .. } | JournalEntry::Recycle { batch, .. }
+                if !undone.contains(batch.as_str()) =>
+            {
+                Some(batch.clone())
+            }
+            _ => None,
+        });
+        let batch = last.ok_or(TrashError::NothingToUndo)?;
+        let items = entries
+            .into_iter()
+            .filter_map(|entry| match entry {
+                JournalEntry::Trash { batch: b, original, trashed, size } if b == batch => {
+                    Some(TrashedItem { original, trashed: Some(trashed), size })
+                }
+                JournalEntry::Recycle { batch: b, original, size } if b == batch => {
+                    Some(TrashedItem { original, trashed: None, size })
+                }
+                _ => None,
+            })
+            .collect();
+        Ok((batch, items))
+    }
+
+    /// Move everything of the most recent batch back where it came from. A directory whose
+    /// original path is taken again stays in the trash and is reported; running `undo` again
+    /// retries it. Once nothing of the batch is left, the next `undo` goes one batch further back.
+    /// Directories in the Recycle Bin can't be put back from here; they are listed for
+    /// restoring from Explorer, and never counted as restored.
+    pub fn undo(&self) -> Result<RestoreReport, TrashError> {
+        let (batch, items) = self.last_batch()?;
+        let mut report = RestoreReport::default();
+        for item in items {
+            let Some(trashed) = &item.trashed else {
+                report.in_recycle_bin.push(item.original);
+                continue;
+            };
+            // Restored by an earlier, partly failed undo
+            if fs::symlink_metadata(trashed).is_err() && fs::symlink_metadata(&item.original).is_ok() {
+                continue;
+            }
+            match restore(&item.original, trashed) {
+                Ok(()) => {
+                    #[cfg(all(unix, not(target_os = "macos")))]
+                    xdg_trash::forget(trashed);
+                    report.restored.push(item.original);
+                    report.restored_bytes += item.size;
+                }
+                Err(e) => report.failed.push((item.original, e.to_string())),
+            }
+        }
+        if report.failed.is_empty() {
+            self.append(&JournalEntry::Undo { batch: batch.clone() })?;
+            // Only empty folders are removed, so nothing still in the trash is lost
+            let _ = fs::remove_dir(self.dir.join(&batch));
+        }
+        Ok(report)
+    }
+}
+
+fn restore(original: &Path, trashed: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(trashed).is_err() {
+        return Err(io::Error::other(format!("{} is no longer in the trash", trashed.display())));
+    }
+    if fs::symlink_metadata(original).is_ok() {
+        return Err(io::Error::other(format!("{} already exists", original.display())));
+    }
+    if let Some(parent) = original.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(trashed, original)
+}
+
+/// The directories one deletion run moves to the trash
+pub struct TrashBatch<'a> {
+    trash: &'a Trash,
+    id: String,
+    moved: usize,
+    /// Bytes in each trash directory used so far, for the size cap
+    used: HashMap<PathBuf, u64>,
+}
+
+impl TrashBatch<'_> {
+    /// Move `path` into the batch's folder (or the desktop trash or Recycle Bin) and record
+    /// it in the journal. Moving never copies, so directories on another filesystem than
+    /// the trash are refused, and so are moves past the size cap.
+    pub fn move_in(&mut self, path: &Path, size: u64) -> io::Result<()> {
+        let trashed = match &self.trash.backend {
+            #[cfg(windows)]
+            Backend::RecycleBin => {
+                recycle(path)?;
+                let entry = JournalEntry::Recycle {
+                    batch: self.id.clone(),
+                    original: path.to_path_buf(),
+                    size,
+                };
+                // Already in the Recycle Bin, which can restore it without the journal
+                if let Err(e) = self.trash.append(&entry) {
+                    eprintln!("{}", t!("warning", message = e.to_string()));
+                }
+                self.moved += 1;
+                return Ok(());
+            }
+            #[cfg(all(unix, not(target_os = "macos")))]
+            Backend::Xdg(xdg) => {
+                let dir = xdg.trash_dir_for(path)?;
+                self.reserve(&dir.root, size, || dir.size())?;
+                dir.put(path, size, SystemTime::now())?
+            }
+            Backend::Folder => {
+                let dir = self.trash.dir.join(&self.id);
+                self.reserve(&self.trash.dir, size, || dir_size(&self.trash.dir))?;
+                fs::create_dir_all(&dir)?;
+                let name = path.file_name().ok_or_else(|| io::Error::other("no file name"))?;
+                // Numbered, so two node_modules from different projects don't collide
+                let mut trashed_name = std::ffi::OsString::from(format!("{}-", self.moved));
+                trashed_name.push(name);
+                let trashed = dir.join(trashed_name);
+
+                fs::rename(path, &trashed).map_err(|e| match e.kind() {
+                    io::ErrorKind::CrossesDevices => io::Error::other(format!(
+                        "on a different filesystem than the trash ({}); delete it without --trash",
+                        self.trash.dir.display()
+                    )),
+                    _ => e,
+                })?;
+                trashed
+            }
+        };
+        let entry = JournalEntry::Trash {
+            batch: self.id.clone(),
+            original: path.to_path_buf(),
+            trashed: trashed.clone(),
+            size,
+        };
+        if let Err(e) = self.trash.append(&entry) {
+            // A move the journal doesn't know about could never be undone
+            let _ = fs::rename(&trashed, path);
+            #[cfg(all(unix, not(target_os = "macos")))]
+            xdg_trash::forget(&trashed);
+            return Err(io::Error::other(e.to_string()));
+        }
+        self.moved += 1;
+        Ok(())
+    }
+
+    /// Count `size` more bytes against the cap of the trash directory `root`, measuring
+    /// what is in it on first use
+    fn reserve(&mut self, root: &Path, size: u64, measure: impl FnOnce() -> u64) -> io::Result<()> {
+        let Some(max_size) = self.trash.max_size else {
+            return Ok(());
+        };
+        let used = self.used.entry(root.to_path_buf()).or_insert_with(measure);
+        if *used + size > max_size {
+            return Err(io::Error::other(format!(
+                "the trash in {} would grow past trash_max_size ({}); empty it or delete without --trash",
+                root.display(),
+                format_size(max_size)
+            )));
+        }
+        *used += size;
+        Ok(())
+    }
+
+    /// How many directories this batch has moved
+    pub fn moved(&self) -> usize {
+        self.moved
+    }
+}
+
+/// Bytes of the files under `dir`
+fn dir_size(dir: &Path) -> u64 {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Send `path` to the Recycle Bin through the shell, so Explorer can list and restore it
+#[cfg(windows)]
+fn recycle(path: &Path) -> io::Result<()> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::UI::Shell::{
+        SHFileOperationW, FOF_ALLOWUNDO, FOF_NOCONFIRMATION, FOF_NOERRORUI, FOF_SILENT, FO_DELETE, SHFILEOPSTRUCTW,
+    };
+
+    // The shell wants an absolute path, terminated by two NULs (it takes a list)
+    let path = std::path::absolute(path)?;
+    let mut from: Vec<u16> = path.as_os_str().encode_wide().collect();
+    from.extend([0, 0]);
+    let mut operation = SHFILEOPSTRUCTW {
+        hwnd: std::ptr::null_mut(),
+        wFunc: FO_DELETE,
+        pFrom: from.as_ptr(),
+        pTo: std::ptr::null(),
+        fFlags: FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_NOERRORUI | FOF_SILENT,
+        fAnyOperationsAborted: 0,
+        hNameMappings: std::ptr::null_mut(),
+        lpszProgressTitle: std::ptr::null(),
+    };
+    match unsafe { SHFileOperationW(&mut operation) } {
+        0 if operation.fAnyOperationsAborted == 0 => Ok(()),
+        0 => Err(io::Error::other("moving to the Recycle Bin was aborted")),
+        code => Err(io::Error::other(format!("the Recycle Bin refused it (error {:#x})", code))),
+    }
+}
+
+/// What `undo` put back
+#[derive(Debug, Default)]
+pub struct RestoreReport {
+    pub restored: Vec<PathBuf>,
+    pub failed: Vec<(PathBuf, String)>,
+    /// Left for Explorer to restore from the Recycle Bin
+    pub in_recycle_bin: Vec<PathBuf>,
+    pub restored_bytes: u64,
+}
+
+impl RestoreReport {
+    pub fn print_summary(&self) {
+        for path in &self.restored {
+            println!("{}", t!("undo-restored", path = path.display().to_string()));
+        }
+        for (path, reason) in &self.failed {
+            eprintln!("{}", t!("undo-failed", path = path.display().to_string(), reason = reason.clone()));
+        }
+        for path in &self.in_recycle_bin {
+            eprintln!("{}", t!("undo-recycle-bin", path = path.display().to_string()));
+        }
+        println!(
+            "{}",
+            t!(
+                "undo-summary",
+                restored = self.restored.len(),
+                total = self.restored.len() + self.failed.len() + self.in_recycle_bin.len(),
+                size = format_size(self.restored_bytes)
+            )
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deletion::delete_directories_cancellable;
+    use std::sync::atomic::AtomicBool;
+    use tempfile::TempDir;
+
+    fn make_dir(path: &Path) {
+        fs::create_dir_all(path).unwrap();
+        fs::write(path.join("file"), "data").unwrap();
+    }
+
+    #[test]
+    fn test_trash_and_undo() {
+        let temp_dir = TempDir::new().unwrap();
+        let work = temp_dir.path().join("work");
+        let trash = Trash::new(temp_dir.path().join("trash"));
+        let (a, b, c) = (work.join("a/node_modules"), work.join("b/node_modules"), work.join("c/target"));
+        for dir in [&a, &b, &c] {
+            make_dir(dir);
+        }
+
+        // First run trashes c, the second a and b; same names don't collide
+        let mut first = trash.batch();
+        first.move_in(&c, 4).unwrap();
+        let mut second = trash.batch();
+        assert_ne!(first.id, second.id);
+        let report =
+            delete_directories_cancellable(&[a.clone(), b.clone()], &Default::default(), &AtomicBool::new(false), Some(&mut second)).unwrap();
+        assert_eq!(report.successful, vec![a.clone(), b.clone()]);
+        assert_eq!(second.moved(), 2);
+        assert!(!a.exists() && !b.exists() && !c.exists());
+        assert_eq!(trash.entries().unwrap().len(), 3);
+
+        // Undo restores the most recent run only
+        let report = trash.undo().unwrap();
+        assert_eq!(report.restored, vec![a.clone(), b.clone()]);
+        assert_eq!(report.restored_bytes, 8);
+        assert!(a.join("file").exists() && b.join("file").exists() && !c.exists());
+        assert!(!trash.dir.join(&second.id).exists());
+
+        // A taken path keeps the directory in the trash until it is free again
+        make_dir(&c);
+        let report = trash.undo().unwrap();
+        assert!(report.restored.is_empty());
+        assert_eq!(report.failed.len(), 1);
+        fs::remove_dir_all(&c).unwrap();
+        let report = trash.undo().unwrap();
+        assert_eq!(report.restored, vec![c.clone()]);
+
+        assert!(matches!(trash.undo(), Err(TrashError::NothingToUndo)));
+    }
+
+    #[test]
+    fn test_size_cap() {
+        let temp_dir = TempDir::new().unwrap();
+        let trash = Trash::new(temp_dir.path().join("trash")).with_max_size(Some(10));
+        let (a, b, c) = (temp_dir.path().join("a"), temp_dir.path().join("b"), temp_dir.path().join("c"));
+        for dir in [&a, &b, &c] {
+            make_dir(dir);
+        }
+
+        let mut batch = trash.batch();
+        batch.move_in(&a, 4).unwrap();
+        batch.move_in(&b, 4).unwrap();
+        assert!(batch.move_in(&c, 4).is_err());
+        assert!(c.join("file").exists());
+
+        // A later run counts what is already in the trash
+        let mut batch = trash.batch();
+        assert!(batch.move_in(&c, 4).is_err());
+        assert_eq!(batch.moved(), 0);
+    }
+
+    #[test]
+    fn test_recycle_bin_undo() {
+        let temp_dir = TempDir::new().unwrap();
+        let trash = Trash::new(temp_dir.path().join("trash"));
+        let original = temp_dir.path().join("project/node_modules");
+        trash
+            .append(&JournalEntry::Recycle { batch: "1".to_string(), original: original.clone(), size: 4 })
+            .unwrap();
+
+        // Undo can't take it out of the Recycle Bin, so it says where to restore it, even
+        // when something else has taken the original path since
+        make_dir(&original);
+        let report = trash.undo().unwrap();
+        assert!(report.restored.is_empty() && report.failed.is_empty());
+        assert_eq!(report.restored_bytes, 0);
+        assert_eq!(report.in_recycle_bin, vec![original]);
+
+        // Nothing more undo can do for the batch, so the next one goes further back
+        assert!(matches!(trash.undo(), Err(TrashError::NothingToUndo)));
+    }
+
+    #[test]
+    fn test_empty_journal() {
+        let temp_dir = TempDir::new().unwrap();
+        let trash = Trash::new(temp_dir.path().join("trash"));
+        assert!(trash.entries().unwrap().is_empty());
+        assert!(matches!(trash.undo(), Err(TrashError::NothingToUndo)));
+
+        fs::create_dir_all(&trash.dir).unwrap();
+        fs::write(trash.dir.join("journal.jsonl"), "{\"op\":\"undo\",\"batch\":\"1\"}\nnot json\n").unwrap();
+        assert!(matches!(trash.entries(), Err(TrashError::JsonError { line: 2, .. })));
+    }
+}
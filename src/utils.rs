@@ -1,156 +1,585 @@
-/// Check if a directory name indicates a temporary directory
-pub fn is_temp_directory(name: &str) -> bool {
-    matches!(
-        name,
-        // Node.js / JavaScript
-        "node_modules"
-            | ".npm"
-            | ".yarn"
-            | ".pnpm-store"
-            | ".turbo"
-            | ".parcel-cache"
-            | ".webpack"
-            | ".rollup.cache"
-            | ".vite"
-            | ".next"
-            | ".nuxt"
-            | ".output"
-            | ".vercel"
-            | ".netlify"
-            | "bower_components"
-            // Python
-            | ".venv"
-            | "venv"
-            | "env"
-            | ".env"
-            | "__pycache__"
-            | ".pytest_cache"
-            | ".mypy_cache"
-            | ".tox"
-            | ".eggs"
-            | "*.egg-info"
-            | ".ipynb_checkpoints"
-            // Rust
-            | "target"
-            | ".fingerprint"
-            | ".cargo"
-            // Build outputs
-            | "dist"
-            | "build"
-            | "out"
-            | ".build"
-            | "_build"
-            | ".gradle"
-            | ".mvn"
-            // Caches
-            | ".cache"
-            | "cache"
-            | ".tmp"
-            | "tmp"
-            | "temp"
-            | ".temp"
-            // Version managers
-            | ".nvm"
-            | ".rvm"
-            | ".rbenv"
-            | ".pyenv"
-            // IDEs and editors
-            | ".idea"
-            | ".vscode"
-            | ".vs"
-            | ".eclipse"
-            | ".settings"
-            // OS
-            | ".DS_Store"
-            | "Thumbs.db"
-            | ".Trash"
-            // Other
-            | "coverage"
-            | ".coverage"
-            | ".nyc_output"
-            | "htmlcov"
-            | ".sass-cache"
-            | ".docusaurus"
-    )
-}
-
-/// Format bytes into human-readable size (KB, MB, GB, TB)
+use crate::i18n::t;
+use crate::scanner::{DirectoryEntry, EntryType};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Glob patterns for temp directories whose names vary per project
+pub const TEMP_DIRECTORY_GLOBS: &[(&str, EntryType)] = &[
+    // Python packaging metadata
+    ("*.egg-info", EntryType::BuildOutput),
+    // Xcode debug symbols
+    ("*.dSYM", EntryType::BuildOutput),
+    // CLion / CMake build trees
+    ("cmake-build-*", EntryType::BuildOutput),
+];
+
+/// A built-in path rule; same semantics as `[[rules]]` in the config file
+pub struct BuiltinPathRule {
+    pub pattern: &'static str,
+    pub category: EntryType,
+    pub sibling: Option<&'static str>,
+    pub label: Option<&'static str>,
+}
+
+const fn rule(pattern: &'static str, category: EntryType, label: &'static str) -> BuiltinPathRule {
+    BuiltinPathRule { pattern, category, sibling: None, label: Some(label) }
+}
+
+/// Rule that only applies inside a project recognised by `sibling`
+const fn project_rule(
+    pattern: &'static str,
+    category: EntryType,
+    sibling: &'static str,
+    label: &'static str,
+) -> BuiltinPathRule {
+    BuiltinPathRule { pattern, category, sibling: Some(sibling), label: Some(label) }
+}
+
+/// Keeps a container directory normal so its children are classified individually
+const fn container(pattern: &'static str) -> BuiltinPathRule {
+    BuiltinPathRule { pattern, category: EntryType::Normal, sibling: None, label: None }
+}
+
+/// Built-in path rules for caches that are only recognisable by their location.
+/// Rules are matched in order, so more specific patterns come first.
+pub const BUILTIN_PATH_RULES: &[BuiltinPathRule] = &[
+    // Hugging Face hub: one entry per downloaded model/dataset
+    container("~/.cache/huggingface"),
+    container("~/.cache/huggingface/hub"),
+    rule("~/.cache/huggingface/hub/{models,datasets,spaces}--*", EntryType::ModelCache, "Hugging Face hub"),
+    rule("~/.cache/huggingface/*", EntryType::ModelCache, "Hugging Face"),
+    // Other ML frameworks
+    rule("~/.cache/torch", EntryType::ModelCache, "PyTorch hub"),
+    rule("~/.keras", EntryType::ModelCache, "Keras models"),
+    rule("~/tensorflow_datasets", EntryType::ModelCache, "TensorFlow datasets"),
+    // pip wheels/http cache
+    rule("~/.cache/pip", EntryType::DependencyCache, "pip cache"),
+    rule("~/Library/Caches/pip", EntryType::DependencyCache, "pip cache"),
+    // Go module and build caches (`$GOPATH/pkg/mod` is added at runtime)
+    rule("~/go/pkg/mod", EntryType::DependencyCache, "Go module cache"),
+    rule("~/.cache/go-build", EntryType::BuildOutput, "Go build cache"),
+    rule("~/Library/Caches/go-build", EntryType::BuildOutput, "Go build cache"),
+    // Conda package caches and environments
+    rule("**/{miniconda3,anaconda3,miniforge3,mambaforge}/pkgs", EntryType::DependencyCache, "Conda packages"),
+    rule("~/.conda/pkgs", EntryType::DependencyCache, "Conda packages"),
+    rule("**/{miniconda3,anaconda3,miniforge3,mambaforge}/envs/*", EntryType::VirtualEnv, "Conda environment"),
+    rule("~/.conda/envs/*", EntryType::VirtualEnv, "Conda environment"),
+    // Xcode
+    rule("~/Library/Developer/Xcode/DerivedData", EntryType::BuildOutput, "Xcode DerivedData"),
+    rule("~/Library/Developer/Xcode/iOS DeviceSupport", EntryType::Temp, "Xcode device support"),
+    rule("~/Library/Developer/CoreSimulator/Caches", EntryType::Temp, "iOS Simulator cache"),
+    // CocoaPods
+    project_rule("**/Pods", EntryType::DependencyCache, "Podfile", "CocoaPods"),
+    rule("~/Library/Caches/CocoaPods", EntryType::DependencyCache, "CocoaPods cache"),
+    // Android
+    project_rule("**/build", EntryType::BuildOutput, "build.gradle", "Gradle build"),
+    project_rule("**/build", EntryType::BuildOutput, "build.gradle.kts", "Gradle build"),
+    rule("~/.android/avd/*", EntryType::VirtualEnv, "Android emulator (AVD)"),
+    rule("~/{Library/Android/sdk,Android/Sdk}/system-images/*", EntryType::DependencyCache, "Android system image"),
+    // Unity projects are recognised by their ProjectSettings directory
+    project_rule("**/Library", EntryType::BuildOutput, "ProjectSettings", "Unity Library"),
+    project_rule("**/Temp", EntryType::Temp, "ProjectSettings", "Unity Temp"),
+    project_rule("**/Logs", EntryType::Temp, "ProjectSettings", "Unity Logs"),
+    project_rule("**/obj", EntryType::BuildOutput, "ProjectSettings", "Unity obj"),
+    // Browser caches; profiles, history and logins live elsewhere
+    rule("~/.cache/{google-chrome,google-chrome-beta,chromium,BraveSoftware,microsoft-edge,mozilla}", EntryType::AppCache, "Browser cache"),
+    rule("~/Library/Caches/{Google/Chrome,Chromium,BraveSoftware,Microsoft Edge,Firefox,com.apple.Safari}", EntryType::AppCache, "Browser cache"),
+    rule("~/Library/Containers/com.apple.Safari/Data/Library/Caches", EntryType::AppCache, "Browser cache"),
+    rule("~/AppData/Local/Mozilla/Firefox/Profiles/*/cache2", EntryType::AppCache, "Browser cache"),
+    rule("~/.config/{google-chrome,google-chrome-beta,chromium,BraveSoftware/Brave-Browser,microsoft-edge}/*/{Cache,Code Cache,GPUCache,Service Worker/CacheStorage}", EntryType::AppCache, "Browser cache"),
+    rule("~/Library/Application Support/{Google/Chrome,Chromium,BraveSoftware/Brave-Browser,Microsoft Edge}/*/{Cache,Code Cache,GPUCache,Service Worker/CacheStorage}", EntryType::AppCache, "Browser cache"),
+    rule("~/AppData/Local/{Google/Chrome,Chromium,BraveSoftware/Brave-Browser,Microsoft/Edge}/User Data/*/{Cache,Code Cache,GPUCache,Service Worker/CacheStorage}", EntryType::AppCache, "Browser cache"),
+    // Electron apps keep Chromium's caches next to their settings
+    rule("~/.config/{Slack,discord,Code,Code - Insiders,VSCodium}/{Cache,Code Cache,GPUCache,CachedData,Service Worker/CacheStorage}", EntryType::AppCache, "Electron app cache"),
+    rule("~/Library/Application Support/{Slack,discord,Code,Code - Insiders,VSCodium}/{Cache,Code Cache,GPUCache,CachedData,Service Worker/CacheStorage}", EntryType::AppCache, "Electron app cache"),
+    rule("~/Library/Containers/com.tinyspeck.slackmacgap/Data/Library/Application Support/Slack/{Cache,Code Cache,GPUCache,Service Worker/CacheStorage}", EntryType::AppCache, "Electron app cache"),
+    rule("~/AppData/Roaming/{Slack,discord,Code,Code - Insiders,VSCodium}/{Cache,Code Cache,GPUCache,CachedData,Service Worker/CacheStorage}", EntryType::AppCache, "Electron app cache"),
+    // JetBrains: per-product caches are added at runtime; Android Studio and macOS logs here
+    container("~/{.cache,Library/Caches,AppData/Local}/JetBrains"),
+    container("~/{.cache,Library/Caches,AppData/Local}/Google"),
+    rule("~/{.cache,Library/Caches,AppData/Local}/Google/AndroidStudio*", EntryType::Temp, "Android Studio caches"),
+    rule("~/Library/Logs/{JetBrains,Google}/*", EntryType::Temp, "JetBrains IDE logs"),
+    // Everything else in the XDG cache is listed per application
+    container("~/.cache"),
+    rule("~/.cache/*", EntryType::Temp, "Application cache"),
+];
+
+/// JetBrains IDEs by the name of their per-version directories (`PyCharm2024.1`), with a
+/// product label; names that are prefixes of others come last
+pub const JETBRAINS_PRODUCTS: &[(&str, &str)] = &[
+    ("IntelliJIdea", "IntelliJ IDEA"),
+    ("IdeaIC", "IntelliJ IDEA Community"),
+    ("PyCharmCE", "PyCharm Community"),
+    ("PyCharm", "PyCharm"),
+    ("WebStorm", "WebStorm"),
+    ("GoLand", "GoLand"),
+    ("CLion", "CLion"),
+    ("Rider", "Rider"),
+    ("RubyMine", "RubyMine"),
+    ("PhpStorm", "PhpStorm"),
+    ("DataGrip", "DataGrip"),
+    ("DataSpell", "DataSpell"),
+    ("RustRover", "RustRover"),
+    ("AppCode", "AppCode"),
+];
+
+/// Where JetBrains IDEs keep caches, indexes and logs, one directory per product and
+/// version. Old versions are left behind after every upgrade.
+pub const JETBRAINS_CACHE_ROOTS: &[&str] = &["~/.cache/JetBrains", "~/Library/Caches/JetBrains", "~/AppData/Local/JetBrains"];
+
+/// VS Code and its forks by the name of their settings directory, with a product label
+pub const VSCODE_PRODUCTS: &[(&str, &str)] = &[
+    ("Code", "VS Code"),
+    ("Code - Insiders", "VS Code Insiders"),
+    ("VSCodium", "VSCodium"),
+    ("Cursor", "Cursor"),
+];
+
+/// Where VS Code keeps its settings directory on Linux, macOS and Windows
+pub const VSCODE_CONFIG_ROOTS: &[&str] = &["~/.config", "~/Library/Application Support", "~/AppData/Roaming"];
+
+/// Exact directory names of temp directories, by category
+pub const TEMP_DIRECTORY_NAMES: &[(&[&str], EntryType)] = &[
+    // Node.js / JavaScript packages
+    (&["node_modules", ".npm", ".yarn", ".pnpm-store", "bower_components"], EntryType::DependencyCache),
+    // Python / Rust / JVM packages
+    (&[".eggs", ".cargo", ".gradle", ".mvn"], EntryType::DependencyCache),
+    // Framework and bundler output
+    (&[".next", ".nuxt", ".output", ".vercel", ".netlify", ".webpack", ".docusaurus"], EntryType::BuildOutput),
+    // Compiler output
+    (&["target", ".fingerprint"], EntryType::BuildOutput),
+    // Build outputs
+    (&["dist", "build", "out", ".build", "_build"], EntryType::BuildOutput),
+    // Xcode per-project build folder
+    (&["DerivedData"], EntryType::BuildOutput),
+    // Python environments
+    (&[".venv", "venv", "env", ".env", ".tox"], EntryType::VirtualEnv),
+    // Version managers
+    (&[".nvm", ".rvm", ".rbenv", ".pyenv"], EntryType::VirtualEnv),
+    // Terraform providers/modules and Terragrunt working copies
+    (&[".terraform"], EntryType::DependencyCache),
+    (&[".terragrunt-cache"], EntryType::Temp),
+    // IDEs and editors
+    (&[".idea", ".vscode", ".vs", ".eclipse", ".settings"], EntryType::IdeMetadata),
+    // OS
+    (&[".DS_Store", "Thumbs.db", ".Trash"], EntryType::OsCache),
+    // Tool caches
+    (&[".turbo", ".parcel-cache", ".rollup.cache", ".vite", ".sass-cache"], EntryType::Temp),
+    (&["__pycache__", ".pytest_cache", ".mypy_cache", ".ipynb_checkpoints"], EntryType::Temp),
+    // Caches
+    (&[".cache", "cache", ".tmp", "tmp", "temp", ".temp"], EntryType::Temp),
+    // Other
+    (&["coverage", ".coverage", ".nyc_output", "htmlcov"], EntryType::Temp),
+];
+
+/// Map a directory name to its temp category, or `None` for normal directories.
+/// Only exact names are matched here; see [`TEMP_DIRECTORY_GLOBS`] for patterns.
+pub fn classify_directory(name: &str) -> Option<EntryType> {
+    find_temp_name(|candidate| candidate == name)
+}
+
+/// Like [`classify_directory`], but `Node_Modules` and `TARGET` match too
+pub fn classify_directory_ignore_case(name: &str) -> Option<EntryType> {
+    find_temp_name(|candidate| candidate.eq_ignore_ascii_case(name))
+}
+
+fn find_temp_name(matches: impl Fn(&str) -> bool) -> Option<EntryType> {
+    TEMP_DIRECTORY_NAMES
+        .iter()
+        .find(|(names, _)| names.iter().any(|name| matches(name)))
+        .map(|&(_, entry_type)| entry_type)
+}
+
+/// How much it costs to get a directory's contents back after deleting it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RebuildCost {
+    /// Reinstalled or regenerated quickly (`node_modules`, `__pycache__`, ...)
+    Cheap,
+    /// Long compiles or large downloads (`target`, `DerivedData`, ML models, ...)
+    Slow,
+    /// Cannot be regenerated (settings, trashed files, unflagged data)
+    Irreplaceable,
+}
+
+impl RebuildCost {
+    /// "cheap to regenerate", in the UI language
+    pub fn describe(&self) -> String {
+        match self {
+            RebuildCost::Cheap => t!("rebuild-cheap"),
+            RebuildCost::Slow => t!("rebuild-slow"),
+            RebuildCost::Irreplaceable => t!("rebuild-irreplaceable"),
+        }
+    }
+
+    /// Compact form for table columns
+    pub fn short_label(&self) -> String {
+        match self {
+            RebuildCost::Cheap => t!("rebuild-cheap-short"),
+            RebuildCost::Slow => t!("rebuild-slow-short"),
+            RebuildCost::Irreplaceable => t!("rebuild-irreplaceable-short"),
+        }
+    }
+}
+
+/// Artifacts, by directory name or path rule label, that take long to rebuild or re-download
+const SLOW_TO_REGENERATE: &[&str] = &[
+    "target",
+    "DerivedData",
+    "Xcode DerivedData",
+    ".gradle",
+    "Gradle build",
+    "Unity Library",
+    ".terraform",
+    "Android system image",
+    "Xcode device support",
+];
+
+/// Contents that are gone for good once deleted
+const IRREPLACEABLE: &[&str] = &[".Trash", "Android emulator (AVD)"];
+
+/// Rebuild cost of a temp directory: specific names and labels first, then its category
+pub fn rebuild_cost(entry_type: EntryType, name: &str, label: Option<&str>) -> RebuildCost {
+    let known = |list: &[&str]| list.contains(&name) || label.is_some_and(|l| list.contains(&l));
+    if known(IRREPLACEABLE) {
+        return RebuildCost::Irreplaceable;
+    }
+    if known(SLOW_TO_REGENERATE) || name.starts_with("cmake-build-") {
+        return RebuildCost::Slow;
+    }
+    match entry_type {
+        EntryType::Normal | EntryType::IdeMetadata => RebuildCost::Irreplaceable,
+        EntryType::VirtualEnv | EntryType::ModelCache => RebuildCost::Slow,
+        EntryType::Temp
+        | EntryType::BuildOutput
+        | EntryType::DependencyCache
+        | EntryType::OsCache
+        | EntryType::AppCache => {
+            RebuildCost::Cheap
+        }
+    }
+}
+
+/// How sizes are displayed (`--units`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SizeUnits {
+    /// Powers of 1024 (1 KB = 1024 bytes)
+    #[default]
+    Binary,
+    /// Powers of 1000 (1 kB = 1000 bytes)
+    Si,
+    /// Exact byte counts
+    Bytes,
+}
+
+/// Units used by [`format_size`], set once from the command line
+static SIZE_UNITS: AtomicU8 = AtomicU8::new(SizeUnits::Binary as u8);
+
+pub fn set_size_units(units: SizeUnits) {
+    SIZE_UNITS.store(units as u8, Ordering::Relaxed);
+}
+
+pub fn size_units() -> SizeUnits {
+    match SIZE_UNITS.load(Ordering::Relaxed) {
+        x if x == SizeUnits::Si as u8 => SizeUnits::Si,
+        x if x == SizeUnits::Bytes as u8 => SizeUnits::Bytes,
+        _ => SizeUnits::Binary,
+    }
+}
+
+/// Which size of a directory is displayed (`--size-basis`, 'u' in interactive mode)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SizeBasis {
+    /// What the files hold, as `ls` and `du --apparent-size` show it
+    #[default]
+    Apparent,
+    /// Space allocated on disk, as `du` shows it
+    Allocated,
+}
+
+/// Basis used by `DirectoryEntry::shown_size`, set from the command line and toggled in
+/// interactive mode
+static SIZE_BASIS: AtomicU8 = AtomicU8::new(SizeBasis::Apparent as u8);
+
+pub fn set_size_basis(basis: SizeBasis) {
+    SIZE_BASIS.store(basis as u8, Ordering::Relaxed);
+}
+
+pub fn size_basis() -> SizeBasis {
+    match SIZE_BASIS.load(Ordering::Relaxed) {
+        x if x == SizeBasis::Allocated as u8 => SizeBasis::Allocated,
+        _ => SizeBasis::Apparent,
+    }
+}
+
+/// Format bytes into human-readable size (KB, MB, GB, TB) in the configured units
 pub fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-    const TB: u64 = GB * 1024;
-
-    if bytes >= TB {
-        format!("{:.2} TB", bytes as f64 / TB as f64)
-    } else if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
+    format_size_with(bytes, size_units())
+}
+
+pub fn format_size_with(bytes: u64, units: SizeUnits) -> String {
+    let (base, labels) = match units {
+        SizeUnits::Binary => (1024u64, ["KB", "MB", "GB", "TB"]),
+        SizeUnits::Si => (1000u64, ["kB", "MB", "GB", "TB"]),
+        SizeUnits::Bytes => return format!("{} B", bytes),
+    };
+
+    let mut unit = base.pow(4);
+    for label in labels.iter().rev() {
+        if bytes >= unit {
+            return format!("{:.2} {}", bytes as f64 / unit as f64, label);
+        }
+        unit /= base;
+    }
+    format!("{} B", bytes)
+}
+
+/// Parse a size like "50 GB", "1.5T", "500MiB" or a plain byte count. Units are powers
+/// of 1024, matching the default display.
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let exponent = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 0,
+        "k" | "kb" | "kib" => 1,
+        "m" | "mb" | "mib" => 2,
+        "g" | "gb" | "gib" => 3,
+        "t" | "tb" | "tib" => 4,
+        _ => return None,
+    };
+    Some((number * 1024f64.powi(exponent)) as u64)
+}
+
+/// [`parse_size`] for command-line arguments
+pub fn parse_size_arg(text: &str) -> Result<u64, String> {
+    parse_size(text).ok_or_else(|| format!("invalid size '{}'", text))
+}
+
+/// What directory lists are ranked by (`--sort`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortKey {
+    /// Largest cumulative size first, in the displayed size basis
+    #[default]
+    Size,
+    /// Most files first, for filesystems that run out of inodes before bytes
+    Files,
+    /// Safest temp directories to delete first, the largest first among equals; other
+    /// directories after them
+    Safety,
+    /// Largest growth since the last recorded scan of the root first, the shrunk last;
+    /// directories that scan didn't list after them
+    Growth,
+}
+
+impl SortKey {
+    pub fn sort(self, entries: &mut [DirectoryEntry]) {
+        entries.sort_by(|a, b| self.compare(a, b));
+    }
+
+    /// Whether `a` is listed before `b`
+    pub fn compare(self, a: &DirectoryEntry, b: &DirectoryEntry) -> std::cmp::Ordering {
+        match self {
+            SortKey::Size => b.shown_size().cmp(&a.shown_size()),
+            SortKey::Files => (b.cumulative_file_count, b.inodes).cmp(&(a.cumulative_file_count, a.inodes)),
+            SortKey::Safety => (b.safety, b.shown_size()).cmp(&(a.safety, a.shown_size())),
+            SortKey::Growth => (b.growth(), b.cumulative_size_bytes).cmp(&(a.growth(), a.cumulative_size_bytes)),
+        }
+    }
+}
+
+/// Used and total inodes of the filesystem containing `path`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InodeUsage {
+    pub used: u64,
+    pub total: u64,
+}
+
+impl InodeUsage {
+    /// "1234567 of 6553600 inodes used (18.8%)"
+    pub fn describe(&self) -> String {
+        let share = if self.total > 0 { self.used as f64 * 100.0 / self.total as f64 } else { 0.0 };
+        t!(
+            "inodes-used",
+            used = self.used.to_string(),
+            total = self.total.to_string(),
+            share = format!("{:.1}", share)
+        )
+    }
+}
+
+/// Inode usage of the filesystem containing `path`; `None` where the filesystem has no
+/// fixed inode table (e.g. btrfs reports 0) or on non-Unix systems
+#[cfg(unix)]
+pub fn inode_usage(path: &Path) -> Option<InodeUsage> {
+    use std::os::unix::ffi::OsStrExt;
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `path` is a valid NUL-terminated string and `stat` a writable statvfs
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    // fsfilcnt_t is 32-bit on some platforms
+    #[allow(clippy::unnecessary_cast)]
+    let (total, free) = (stat.f_files as u64, stat.f_ffree as u64);
+    (total > 0).then(|| InodeUsage {
+        used: total.saturating_sub(free),
+        total,
+    })
+}
+
+#[cfg(not(unix))]
+pub fn inode_usage(_path: &Path) -> Option<InodeUsage> {
+    None
+}
+
+/// Name of this machine, used to tell scans from different hosts apart
+pub fn hostname() -> Option<String> {
+    ["HOSTNAME", "COMPUTERNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok())
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// `path` with a leading `~` replaced by the home directory, for paths from the config file
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// `path` with symlinks and `..` resolved, e.g. `/private/tmp/x` for `/tmp/x` on macOS; the
+/// absolute path when it can't be resolved. Windows drive paths come back as `C:\dir`
+/// rather than the `\\?\C:\dir` that `fs::canonicalize` gives, so they still compare equal
+/// to what the user typed.
+pub fn canonical_path(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path)
+        .map(without_verbatim_prefix)
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// `\\?\C:\dir` as `C:\dir` and `\\?\UNC\server\share` as `\\server\share`
+fn without_verbatim_prefix(path: PathBuf) -> PathBuf {
+    let Some(text) = path.to_str() else {
+        return path;
+    };
+    if let Some(rest) = text.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{}", rest))
+    } else if let Some(rest) = text.strip_prefix(r"\\?\").filter(|rest| matches!(rest.as_bytes(), [letter, b':', ..] if letter.is_ascii_alphabetic())) {
+        PathBuf::from(rest)
     } else {
-        format!("{} B", bytes)
+        path
     }
 }
 
+/// Whether `a` and `b` are the same path, taking `C:`, `c:\` and `C:/` for the same drive root
+pub fn same_path(a: &Path, b: &Path) -> bool {
+    a == b || matches!((drive_letter(a), drive_letter(b)), (Some(x), Some(y)) if x == y)
+}
+
+/// The letter of a drive root such as `C:` or `C:\`, uppercased; `None` for other paths
+fn drive_letter(path: &Path) -> Option<char> {
+    let mut chars = path.to_str()?.chars();
+    let letter = chars.next().filter(char::is_ascii_alphabetic)?;
+    (chars.next() == Some(':') && matches!(chars.as_str(), "" | "\\" | "/")).then(|| letter.to_ascii_uppercase())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_is_temp_directory() {
+    fn test_classify_directory_known_names() {
         // Test Node.js / JavaScript temp directories
-        assert!(is_temp_directory("node_modules"));
-        assert!(is_temp_directory(".npm"));
-        assert!(is_temp_directory(".yarn"));
-        assert!(is_temp_directory(".next"));
-        assert!(is_temp_directory(".nuxt"));
-        assert!(is_temp_directory(".turbo"));
-        assert!(is_temp_directory(".vite"));
+        assert!(classify_directory("node_modules").is_some());
+        assert!(classify_directory(".npm").is_some());
+        assert!(classify_directory(".yarn").is_some());
+        assert!(classify_directory(".next").is_some());
+        assert!(classify_directory(".nuxt").is_some());
+        assert!(classify_directory(".turbo").is_some());
+        assert!(classify_directory(".vite").is_some());
         
         // Test Python temp directories
-        assert!(is_temp_directory(".venv"));
-        assert!(is_temp_directory("venv"));
-        assert!(is_temp_directory("__pycache__"));
-        assert!(is_temp_directory(".pytest_cache"));
-        assert!(is_temp_directory(".mypy_cache"));
+        assert!(classify_directory(".venv").is_some());
+        assert!(classify_directory("venv").is_some());
+        assert!(classify_directory("__pycache__").is_some());
+        assert!(classify_directory(".pytest_cache").is_some());
+        assert!(classify_directory(".mypy_cache").is_some());
         
         // Test Rust temp directories
-        assert!(is_temp_directory("target"));
-        assert!(is_temp_directory(".fingerprint"));
-        assert!(is_temp_directory(".cargo"));
+        assert!(classify_directory("target").is_some());
+        assert!(classify_directory(".fingerprint").is_some());
+        assert!(classify_directory(".cargo").is_some());
         
         // Test build outputs
-        assert!(is_temp_directory("dist"));
-        assert!(is_temp_directory("build"));
-        assert!(is_temp_directory("out"));
+        assert!(classify_directory("dist").is_some());
+        assert!(classify_directory("build").is_some());
+        assert!(classify_directory("out").is_some());
         
         // Test caches
-        assert!(is_temp_directory(".cache"));
-        assert!(is_temp_directory("cache"));
-        assert!(is_temp_directory("tmp"));
+        assert!(classify_directory(".cache").is_some());
+        assert!(classify_directory("cache").is_some());
+        assert!(classify_directory("tmp").is_some());
         
         // Test version managers
-        assert!(is_temp_directory(".nvm"));
-        assert!(is_temp_directory(".rvm"));
-        assert!(is_temp_directory(".pyenv"));
+        assert!(classify_directory(".nvm").is_some());
+        assert!(classify_directory(".rvm").is_some());
+        assert!(classify_directory(".pyenv").is_some());
         
         // Test IDEs
-        assert!(is_temp_directory(".idea"));
-        assert!(is_temp_directory(".vscode"));
+        assert!(classify_directory(".idea").is_some());
+        assert!(classify_directory(".vscode").is_some());
         
         // Test coverage
-        assert!(is_temp_directory("coverage"));
-        assert!(is_temp_directory(".nyc_output"));
+        assert!(classify_directory("coverage").is_some());
+        assert!(classify_directory(".nyc_output").is_some());
 
         // Test normal directories
-        assert!(!is_temp_directory("src"));
-        assert!(!is_temp_directory("lib"));
-        assert!(!is_temp_directory("tests"));
-        assert!(!is_temp_directory("my_project"));
-        assert!(!is_temp_directory("public"));
-        assert!(!is_temp_directory("assets"));
+        assert!(!classify_directory("src").is_some());
+        assert!(!classify_directory("lib").is_some());
+        assert!(!classify_directory("tests").is_some());
+        assert!(!classify_directory("my_project").is_some());
+        assert!(!classify_directory("public").is_some());
+        assert!(!classify_directory("assets").is_some());
+    }
+
+    #[test]
+    fn test_classify_directory_categories() {
+        assert_eq!(classify_directory("node_modules"), Some(EntryType::DependencyCache));
+        assert_eq!(classify_directory("target"), Some(EntryType::BuildOutput));
+        assert_eq!(classify_directory(".venv"), Some(EntryType::VirtualEnv));
+        assert_eq!(classify_directory(".idea"), Some(EntryType::IdeMetadata));
+        assert_eq!(classify_directory(".DS_Store"), Some(EntryType::OsCache));
+        assert_eq!(classify_directory("__pycache__"), Some(EntryType::Temp));
+        assert_eq!(classify_directory(".terraform"), Some(EntryType::DependencyCache));
+        assert_eq!(classify_directory(".terragrunt-cache"), Some(EntryType::Temp));
+        assert_eq!(classify_directory("src"), None);
+
+        assert_eq!(classify_directory("Node_Modules"), None);
+        assert_eq!(classify_directory_ignore_case("Node_Modules"), Some(EntryType::DependencyCache));
+        assert_eq!(classify_directory_ignore_case("deriveddata"), Some(EntryType::BuildOutput));
+        assert_eq!(classify_directory_ignore_case("Sources"), None);
+    }
+
+    #[test]
+    fn test_rebuild_cost() {
+        assert_eq!(rebuild_cost(EntryType::DependencyCache, "node_modules", None), RebuildCost::Cheap);
+        assert_eq!(rebuild_cost(EntryType::BuildOutput, "dist", None), RebuildCost::Cheap);
+        assert_eq!(rebuild_cost(EntryType::BuildOutput, "target", None), RebuildCost::Slow);
+        assert_eq!(rebuild_cost(EntryType::BuildOutput, "cmake-build-debug", None), RebuildCost::Slow);
+        assert_eq!(
+            rebuild_cost(EntryType::BuildOutput, "Library", Some("Unity Library")),
+            RebuildCost::Slow
+        );
+        assert_eq!(rebuild_cost(EntryType::VirtualEnv, ".venv", None), RebuildCost::Slow);
+        assert_eq!(rebuild_cost(EntryType::ModelCache, "torch", None), RebuildCost::Slow);
+        assert_eq!(rebuild_cost(EntryType::IdeMetadata, ".idea", None), RebuildCost::Irreplaceable);
+        assert_eq!(rebuild_cost(EntryType::OsCache, ".Trash", None), RebuildCost::Irreplaceable);
+        assert_eq!(rebuild_cost(EntryType::Normal, "photos", None), RebuildCost::Irreplaceable);
     }
 
     #[test]
@@ -164,6 +593,100 @@ mod tests {
         assert_eq!(format_size(1099511627776), "1.00 TB");
         assert_eq!(format_size(5368709120), "5.00 GB");
     }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("50 GB"), Some(50 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size("1.5t"), Some(3 * 1024u64.pow(4) / 2));
+        assert_eq!(parse_size("500MiB"), Some(500 * 1024 * 1024));
+        assert_eq!(parse_size("4096"), Some(4096));
+        assert_eq!(parse_size(" 2 kb "), Some(2048));
+        assert_eq!(parse_size("GB"), None);
+        assert_eq!(parse_size("10 parsecs"), None);
+    }
+
+    #[test]
+    fn test_sort_key() {
+        let entry = |path: &str, size, files| DirectoryEntry {
+            path: path.into(),
+            cumulative_size_bytes: size,
+            cumulative_file_count: files,
+            ..Default::default()
+        };
+        let mut entries = vec![entry("/big", 1000, 2), entry("/many", 10, 5000)];
+        SortKey::Files.sort(&mut entries);
+        assert_eq!(entries[0].path, Path::new("/many"));
+        SortKey::Size.sort(&mut entries);
+        assert_eq!(entries[0].path, Path::new("/big"));
+
+        // Safest first, larger first among equals, unscored last
+        let scored = |path: &str, size, safety| DirectoryEntry { safety, ..entry(path, size, 0) };
+        let mut entries = vec![scored("/src", 5000, None), scored("/a", 10, Some(50)), scored("/b", 20, Some(90)), scored("/c", 30, Some(50))];
+        SortKey::Safety.sort(&mut entries);
+        let order: Vec<&Path> = entries.iter().map(|e| e.path.as_path()).collect();
+        assert_eq!(order, [Path::new("/b"), Path::new("/c"), Path::new("/a"), Path::new("/src")]);
+
+        // Biggest growers first, then those that shrank, then those without history
+        let grown = |path: &str, size, previous_size| DirectoryEntry { previous_size, ..entry(path, size, 0) };
+        let mut entries = vec![grown("/new", 9000, None), grown("/shrunk", 10, Some(500)), grown("/grew", 800, Some(100)), grown("/same", 50, Some(50))];
+        SortKey::Growth.sort(&mut entries);
+        let order: Vec<&Path> = entries.iter().map(|e| e.path.as_path()).collect();
+        assert_eq!(order, [Path::new("/grew"), Path::new("/same"), Path::new("/shrunk"), Path::new("/new")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_inode_usage() {
+        // Some filesystems (btrfs, some overlays) don't report inodes at all
+        if let Some(usage) = inode_usage(Path::new("/")) {
+            assert!(usage.used <= usage.total);
+            assert!(usage.describe().contains(" inodes used ("));
+        }
+        assert_eq!(inode_usage(Path::new("/nonexistent/path")), None);
+    }
+
+    #[test]
+    fn test_format_size_units() {
+        assert_eq!(format_size_with(1536, SizeUnits::Binary), "1.50 KB");
+        assert_eq!(format_size_with(999, SizeUnits::Si), "999 B");
+        assert_eq!(format_size_with(1500, SizeUnits::Si), "1.50 kB");
+        assert_eq!(format_size_with(1_000_000_000, SizeUnits::Si), "1.00 GB");
+        assert_eq!(format_size_with(2_500_000_000_000, SizeUnits::Si), "2.50 TB");
+        assert_eq!(format_size_with(1_073_741_824, SizeUnits::Bytes), "1073741824 B");
+        assert_eq!(format_size_with(0, SizeUnits::Bytes), "0 B");
+    }
+
+    #[test]
+    fn test_same_path() {
+        assert!(same_path(Path::new("/home/me"), Path::new("/home/me")));
+        assert!(!same_path(Path::new("/home/me"), Path::new("/home")));
+        assert!(same_path(Path::new("C:"), Path::new(r"C:\")));
+        assert!(same_path(Path::new("d:/"), Path::new(r"D:\")));
+        assert!(!same_path(Path::new(r"C:\"), Path::new(r"D:\")));
+        assert!(!same_path(Path::new(r"C:\Users"), Path::new(r"C:\")));
+    }
+
+    #[test]
+    fn test_canonical_path() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let real = temp_dir.path().join("real");
+        std::fs::create_dir(&real).unwrap();
+        let resolved = canonical_path(&real);
+        assert_eq!(canonical_path(&real.join("..").join("real")), resolved);
+        assert_eq!(canonical_path(&temp_dir.path().join("real/")), resolved);
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&real, temp_dir.path().join("link")).unwrap();
+            assert_eq!(canonical_path(&temp_dir.path().join("link")), resolved);
+        }
+        // Missing paths are made absolute but otherwise left alone
+        assert!(canonical_path(Path::new("missing-dir")).ends_with("missing-dir"));
+
+        assert_eq!(without_verbatim_prefix(PathBuf::from(r"\\?\C:\Users\me")), PathBuf::from(r"C:\Users\me"));
+        assert_eq!(without_verbatim_prefix(PathBuf::from(r"\\?\UNC\nas\share")), PathBuf::from(r"\\nas\share"));
+        assert_eq!(without_verbatim_prefix(PathBuf::from(r"\\?\Volume{1}\dir")), PathBuf::from(r"\\?\Volume{1}\dir"));
+        assert_eq!(without_verbatim_prefix(PathBuf::from("/private/tmp")), PathBuf::from("/private/tmp"));
+    }
 }
 
 
@@ -192,16 +715,16 @@ mod proptests {
             ];
             
             for name in temp_names {
-                prop_assert!(is_temp_directory(name));
+                prop_assert!(classify_directory(name).is_some());
                 
                 // With prefix/suffix should NOT be detected (exact match only)
                 if !prefix.is_empty() {
                     let with_prefix = format!("{}{}", prefix, name);
-                    prop_assert!(!is_temp_directory(&with_prefix));
+                    prop_assert!(!classify_directory(&with_prefix).is_some());
                 }
                 if !suffix.is_empty() {
                     let with_suffix = format!("{}{}", name, suffix);
-                    prop_assert!(!is_temp_directory(&with_suffix));
+                    prop_assert!(!classify_directory(&with_suffix).is_some());
                 }
             }
         }
@@ -211,13 +734,13 @@ mod proptests {
             name in "[a-z_]{1,20}"
         ) {
             // Filter out actual temp directory names
-            let temp_names = vec![
+            let temp_names = [
                 "node_modules", "venv", "__pycache__",
                 "dist", "build", "target"
             ];
             
             if !temp_names.contains(&name.as_str()) && !name.starts_with('.') {
-                prop_assert!(!is_temp_directory(&name));
+                prop_assert!(!classify_directory(&name).is_some());
             }
         }
 
@@ -0,0 +1,80 @@
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Number of git-tracked files under `path`, or 1 when `path` is itself a tracked file.
+///
+/// Returns 0 when the directory isn't inside a git work tree, and an error when
+/// git itself can't be run.
+pub fn tracked_file_count(path: &Path) -> io::Result<usize> {
+    // git -C needs a directory; a file is looked up from the one it is in
+    let (dir, pathspec) = match (path.is_dir(), path.parent(), path.file_name()) {
+        (false, Some(parent), Some(name)) => (parent, Path::new(name)),
+        _ => (path, Path::new(".")),
+    };
+    let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["ls-files", "-z", "--"])
+        .arg(pathspec)
+        .stderr(Stdio::null())
+        .output()?;
+
+    // Not a repository (or the path vanished): nothing tracked
+    if !output.status.success() {
+        return Ok(0);
+    }
+    Ok(output.stdout.split(|&b| b == 0).filter(|f| !f.is_empty()).count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> bool {
+        Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success())
+    }
+
+    #[test]
+    fn test_tracked_file_count() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path();
+        if !git(repo, &["init", "-q"]) {
+            // git isn't installed; nothing to test
+            return;
+        }
+
+        fs::create_dir_all(repo.join("build")).unwrap();
+        fs::write(repo.join("build/export.jpg"), "photo").unwrap();
+        fs::write(repo.join("build/notes.txt"), "notes").unwrap();
+        fs::create_dir_all(repo.join("target")).unwrap();
+        fs::write(repo.join("target/app"), "binary").unwrap();
+        assert!(git(repo, &["add", "build"]));
+
+        assert_eq!(tracked_file_count(&repo.join("build")).unwrap(), 2);
+        assert_eq!(tracked_file_count(&repo.join("target")).unwrap(), 0);
+        assert_eq!(tracked_file_count(&repo.join("build/export.jpg")).unwrap(), 1);
+        assert_eq!(tracked_file_count(&repo.join("target/app")).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_outside_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("file"), "data").unwrap();
+
+        // Either git is missing (error) or the directory isn't a work tree (0)
+        if let Ok(count) = tracked_file_count(temp_dir.path()) {
+            assert_eq!(count, 0);
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use std::ffi::OsString;
+use std::fs::{self, DirBuilder, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+/// The freedesktop.org trash (https://specifications.freedesktop.org/trash-spec/) that GNOME,
+/// KDE and other desktops list and restore from. Directories on the home device go to the
+/// home trash; elsewhere they go to the trash at the top of their mount, so nothing is copied.
+///
+/// ```text
+/// ~/.local/share/Trash/
+///     files/node_modules
+///     info/node_modules.trashinfo
+///     directorysizes
+/// /mnt/data/.Trash-1000/files/target
+/// ```
+#[derive(Debug, Clone)]
+pub struct XdgTrash {
+    home: PathBuf,
+}
+
+impl XdgTrash {
+    /// Home trash: `$XDG_DATA_HOME/Trash`
+    pub fn home() -> Option<XdgTrash> {
+        dirs::data_dir().map(|dir| XdgTrash::new(dir.join("Trash")))
+    }
+
+    pub fn new(home: PathBuf) -> Self {
+        Self { home }
+    }
+
+    /// The trash directory for `path`: the home trash if they share a device, otherwise
+    /// `$topdir/.Trash/$uid` when the admin set up a sticky `.Trash`, or `$topdir/.Trash-$uid`
+    pub fn trash_dir_for(&self, path: &Path) -> io::Result<TrashDir> {
+        let path = std::path::absolute(path)?;
+        let device = fs::symlink_metadata(&path)?.dev();
+        let home = TrashDir { root: self.home.clone(), topdir: None };
+        home.create()?;
+        if fs::metadata(&self.home)?.dev() == device {
+            return Ok(home);
+        }
+
+        let topdir = path
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| fs::metadata(dir).is_ok_and(|m| m.dev() == device))
+            .last()
+            .ok_or_else(|| io::Error::other("cannot find the top of its mount"))?
+            .to_path_buf();
+        let uid = unsafe { libc::getuid() };
+        let admin = topdir.join(".Trash");
+        let shared = fs::symlink_metadata(&admin)
+            .is_ok_and(|m| m.is_dir() && !m.file_type().is_symlink() && m.permissions().mode() & 0o1000 != 0);
+        let root = if shared { admin.join(uid.to_string()) } else { topdir.join(format!(".Trash-{}", uid)) };
+        let trash = TrashDir { root, topdir: Some(topdir) };
+        trash.create()?;
+        Ok(trash)
+    }
+}
+
+/// One trash directory with its `files` and `info` folders
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashDir {
+    pub root: PathBuf,
+    /// Top of the mount for a per-mount trash, which stores paths relative to it
+    topdir: Option<PathBuf>,
+}
+
+impl TrashDir {
+    fn files(&self) -> PathBuf {
+        self.root.join("files")
+    }
+
+    fn info(&self) -> PathBuf {
+        self.root.join("info")
+    }
+
+    /// Create the trash, readable only by the user, as the spec asks
+    fn create(&self) -> io::Result<()> {
+        let mut builder = DirBuilder::new();
+        builder.recursive(true).mode(0o700);
+        builder.create(self.files())?;
+        builder.create(self.info())?;
+        let metadata = fs::symlink_metadata(&self.root)?;
+        if metadata.file_type().is_symlink() || metadata.uid() != unsafe { libc::getuid() } {
+            return Err(io::Error::other(format!("{} is not a trash directory of yours", self.root.display())));
+        }
+        Ok(())
+    }
+
+    /// Bytes of everything in the trash
+    pub fn size(&self) -> u64 {
+        WalkDir::new(self.files())
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| !e.file_type().is_dir())
+            .filter_map(|e| e.metadata().ok())
+            .map(|m| m.len())
+            .sum()
+    }
+
+    /// Move `path` into the trash with a `.trashinfo` record of where it came from and when,
+    /// and list a directory's `size` in `directorysizes`. Returns its path in the trash.
+    pub fn put(&self, path: &Path, size: u64, now: SystemTime) -> io::Result<PathBuf> {
+        let path = std::path::absolute(path)?;
+        let name = path.file_name().ok_or_else(|| io::Error::other("no file name"))?;
+        let recorded = match &self.topdir {
+            Some(topdir) => path.strip_prefix(topdir).unwrap_or(&path),
+            None => &path,
+        };
+        let record = format!(
+            "[Trash Info]\nPath={}\nDeletionDate={}\n",
+            percent_encode(recorded.as_os_str().as_bytes()),
+            deletion_date(now)
+        );
+
+        // Claiming the .trashinfo name first (O_EXCL) reserves the name in files/ too
+        let (trash_name, info_path, mut info) = (1..)
+            .find_map(|n| {
+                let mut trash_name = name.to_os_string();
+                if n > 1 {
+                    trash_name.push(format!(".{}", n));
+                }
+                let info_path = self.info().join(info_name(&trash_name));
+                if fs::symlink_metadata(self.files().join(&trash_name)).is_ok() {
+                    return None;
+                }
+                match OpenOptions::new().write(true).create_new(true).open(&info_path) {
+                    Ok(file) => Some(Ok((trash_name, info_path, file))),
+                    Err(e) if e.kind() == io::ErrorKind::AlreadyExists => None,
+                    Err(e) => Some(Err(e)),
+                }
+            })
+            .expect("an unused name")?;
+        let trashed = self.files().join(&trash_name);
+        let moved = info.write_all(record.as_bytes()).and_then(|_| info.sync_all()).and_then(|_| fs::rename(&path, &trashed));
+        if let Err(e) = moved {
+            let _ = fs::remove_file(&info_path);
+            return Err(match e.kind() {
+                io::ErrorKind::CrossesDevices => io::Error::other(format!(
+                    "on a different filesystem than the trash ({}); delete it without --trash",
+                    self.root.display()
+                )),
+                _ => e,
+            });
+        }
+
+        if fs::symlink_metadata(&trashed).is_ok_and(|m| m.is_dir()) {
+            let mtime = fs::metadata(&info_path).map(|m| m.mtime()).unwrap_or_default();
+            let line = format!("{} {} {}", size, mtime, percent_encode(trash_name.as_bytes()));
+            // The cache is optional; a desktop recomputes what it misses
+            let _ = self.update_directory_sizes(|lines| lines.push(line));
+        }
+        Ok(trashed)
+    }
+
+    /// Rewrite `directorysizes` through a temporary file, so readers never see half of it
+    fn update_directory_sizes(&self, change: impl FnOnce(&mut Vec<String>)) -> io::Result<()> {
+        let path = self.root.join("directorysizes");
+        let mut lines: Vec<String> = match fs::read_to_string(&path) {
+            Ok(text) => text.lines().map(str::to_string).collect(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        change(&mut lines);
+        let temp = self.root.join(format!("directorysizes.{}", std::process::id()));
+        fs::write(&temp, lines.iter().map(|line| format!("{}\n", line)).collect::<String>())?;
+        fs::rename(temp, path)
+    }
+}
+
+/// After `trashed` was restored from a trash's `files` folder, drop its `.trashinfo` and
+/// `directorysizes` entries; anything else is left alone
+pub fn forget(trashed: &Path) {
+    let (Some(files), Some(name)) = (trashed.parent(), trashed.file_name()) else {
+        return;
+    };
+    if files.file_name().is_none_or(|n| n != "files") {
+        return;
+    }
+    let Some(root) = files.parent() else {
+        return;
+    };
+    if fs::remove_file(root.join("info").join(info_name(name))).is_ok() {
+        let trash = TrashDir { root: root.to_path_buf(), topdir: None };
+        let suffix = format!(" {}", percent_encode(name.as_bytes()));
+        let _ = trash.update_directory_sizes(|lines| lines.retain(|line| !line.ends_with(&suffix)));
+    }
+}
+
+fn info_name(name: &std::ffi::OsStr) -> OsString {
+    let mut info = name.to_os_string();
+    info.push(".trashinfo");
+    info
+}
+
+/// Escape a path the way URIs are escaped, keeping `/`
+fn percent_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    for &b in bytes {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => encoded.push(b as char),
+            _ => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    encoded
+}
+
+/// `percent_encode` reversed, for reading `.trashinfo` paths back
+#[cfg(test)]
+fn percent_decode(text: &str) -> OsString {
+    use std::os::unix::ffi::OsStringExt;
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match (bytes[i], text.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok())) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b, _) => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+    OsString::from_vec(decoded)
+}
+
+/// `YYYY-MM-DDThh:mm:ss` in local time, as the spec asks
+fn deletion_date(now: SystemTime) -> String {
+    let secs = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&secs, &mut tm) }.is_null() {
+        return humantime::format_rfc3339_seconds(now).to_string().trim_end_matches('Z').to_string();
+    }
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_put_and_forget() {
+        let temp_dir = TempDir::new().unwrap();
+        let xdg = XdgTrash::new(temp_dir.path().join("data/Trash"));
+        let work = temp_dir.path().join("my project");
+        for dir in ["a/node_modules", "b/node_modules"] {
+            fs::create_dir_all(work.join(dir)).unwrap();
+            fs::write(work.join(dir).join("index.js"), "data").unwrap();
+        }
+
+        let trash = xdg.trash_dir_for(&work.join("a/node_modules")).unwrap();
+        assert_eq!(trash.root, temp_dir.path().join("data/Trash"));
+        assert_eq!(fs::metadata(&trash.root).unwrap().permissions().mode() & 0o777, 0o700);
+        let first = trash.put(&work.join("a/node_modules"), 4, SystemTime::now()).unwrap();
+        let second = trash.put(&work.join("b/node_modules"), 4, SystemTime::now()).unwrap();
+        assert_eq!(first, trash.files().join("node_modules"));
+        assert_eq!(second, trash.files().join("node_modules.2"));
+        assert!(second.join("index.js").exists());
+        assert_eq!(trash.size(), 8);
+
+        let info = fs::read_to_string(trash.info().join("node_modules.2.trashinfo")).unwrap();
+        let mut lines = info.lines();
+        assert_eq!(lines.next(), Some("[Trash Info]"));
+        let path = lines.next().unwrap().strip_prefix("Path=").unwrap();
+        assert!(path.contains("my%20project"));
+        assert_eq!(PathBuf::from(percent_decode(path)), std::path::absolute(work.join("b/node_modules")).unwrap());
+        let date = lines.next().unwrap().strip_prefix("DeletionDate=").unwrap();
+        assert_eq!((date.len(), &date[10..11]), (19, "T"));
+        let sizes = fs::read_to_string(trash.root.join("directorysizes")).unwrap();
+        assert_eq!(sizes.lines().count(), 2);
+        assert!(sizes.lines().all(|line| line.starts_with("4 ")));
+
+        // Restoring drops the records; other files next to the trash are never touched
+        fs::rename(&first, work.join("a/node_modules")).unwrap();
+        forget(&first);
+        forget(&work.join("a/node_modules"));
+        assert!(!trash.info().join("node_modules.trashinfo").exists());
+        assert!(trash.info().join("node_modules.2.trashinfo").exists());
+        let sizes = fs::read_to_string(trash.root.join("directorysizes")).unwrap();
+        assert_eq!(sizes.lines().collect::<Vec<_>>().len(), 1);
+        assert!(sizes.ends_with(" node_modules.2\n"));
+    }
+
+    #[test]
+    fn test_percent_encoding() {
+        assert_eq!(percent_encode(b"/home/me/caf\xc3\xa9 & co/x"), "/home/me/caf%C3%A9%20%26%20co/x");
+        assert_eq!(percent_decode("/home/me/caf%C3%A9%20%26%20co/x"), OsString::from("/home/me/café & co/x"));
+    }
+}
@@ -0,0 +1,132 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// `value` as a YAML document. Every string is written double-quoted with JSON escapes, which
+/// YAML reads the same way, so paths with colons, quotes, leading dashes or newlines come
+/// through unchanged.
+pub fn to_string<T: Serialize>(value: &T) -> Result<String, serde_json::Error> {
+    let mut out = String::from("---\n");
+    write_block(&mut out, &serde_json::to_value(value)?, 0);
+    Ok(out)
+}
+
+fn write_block(out: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                pad(out, indent);
+                out.push_str(&key_text(key));
+                out.push(':');
+                write_child(out, value, indent + 2);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for item in items {
+                pad(out, indent);
+                out.push('-');
+                if is_block(item) {
+                    // The item's first line goes on the dash's line: "- key: value"
+                    let mut nested = String::new();
+                    write_block(&mut nested, item, indent + 2);
+                    out.push(' ');
+                    out.push_str(&nested[indent + 2..]);
+                } else {
+                    out.push(' ');
+                    out.push_str(&scalar(item));
+                    out.push('\n');
+                }
+            }
+        }
+        _ => {
+            pad(out, indent);
+            out.push_str(&scalar(value));
+            out.push('\n');
+        }
+    }
+}
+
+/// After "key:", either the value on the same line or its block indented below
+fn write_child(out: &mut String, value: &Value, indent: usize) {
+    if is_block(value) {
+        out.push('\n');
+        write_block(out, value, indent);
+    } else {
+        out.push(' ');
+        out.push_str(&scalar(value));
+        out.push('\n');
+    }
+}
+
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => !map.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => Value::String(s.clone()).to_string(),
+        Value::Array(_) => "[]".to_string(),
+        Value::Object(_) => "{}".to_string(),
+    }
+}
+
+/// Field names are left bare; anything else is quoted
+fn key_text(key: &str) -> String {
+    let plain = !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') && !key.starts_with('-');
+    if plain {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+fn pad(out: &mut String, indent: usize) {
+    out.extend(std::iter::repeat_n(' ', indent));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_yaml_layout() {
+        let value = json!({
+            "root": "/srv/data: \"shared\"\n- old",
+            "count": 2,
+            "empty": [],
+            "entries": [
+                {"path": "/srv/a", "sizes": [1, 2]},
+                {"path": "-b", "tags": {}}
+            ],
+            "nested": [[1], null],
+            "weird key": true
+        });
+        assert_eq!(
+            to_string(&value).unwrap(),
+            concat!(
+                "---\n",
+                "count: 2\n",
+                "empty: []\n",
+                "entries:\n",
+                "  - path: \"/srv/a\"\n",
+                "    sizes:\n",
+                "      - 1\n",
+                "      - 2\n",
+                "  - path: \"-b\"\n",
+                "    tags: {}\n",
+                "nested:\n",
+                "  - - 1\n",
+                "  - null\n",
+                "root: \"/srv/data: \\\"shared\\\"\\n- old\"\n",
+                "\"weird key\": true\n",
+            )
+        );
+    }
+}